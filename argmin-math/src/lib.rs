@@ -230,6 +230,7 @@ mod vec;
 pub use crate::vec::*;
 
 use anyhow::Error;
+use rand::Rng;
 
 /// Dot/scalar product of `T` and `self`
 pub trait ArgminDot<T, U> {
@@ -335,8 +336,8 @@ pub trait ArgminInv<T> {
 
 /// Create a random number
 pub trait ArgminRandom {
-    /// Get a random element between min and max,
-    fn rand_from_range(min: &Self, max: &Self) -> Self;
+    /// Get a random element between min and max, drawn from the random number generator `rng`
+    fn rand_from_range<R: Rng>(min: &Self, max: &Self, rng: &mut R) -> Self;
 }
 
 /// Minimum and Maximum of type `T`
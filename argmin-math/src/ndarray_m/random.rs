@@ -12,12 +12,10 @@ use crate::ArgminRandom;
 macro_rules! make_random {
     ($t:ty) => {
         impl ArgminRandom for ndarray::Array1<$t> {
-            fn rand_from_range(min: &Self, max: &Self) -> ndarray::Array1<$t> {
+            fn rand_from_range<R: Rng>(min: &Self, max: &Self, rng: &mut R) -> ndarray::Array1<$t> {
                 assert!(!min.is_empty());
                 assert_eq!(min.len(), max.len());
 
-                let mut rng = rand::thread_rng();
-
                 ndarray::Array1::from_iter(min.iter().zip(max.iter()).map(|(a, b)| {
                     // Do not require a < b:
 
@@ -35,12 +33,10 @@ macro_rules! make_random {
         }
 
         impl ArgminRandom for ndarray::Array2<$t> {
-            fn rand_from_range(min: &Self, max: &Self) -> ndarray::Array2<$t> {
+            fn rand_from_range<R: Rng>(min: &Self, max: &Self, rng: &mut R) -> ndarray::Array2<$t> {
                 assert!(!min.is_empty());
                 assert_eq!(min.raw_dim(), max.raw_dim());
 
-                let mut rng = rand::thread_rng();
-
                 ndarray::Array2::from_shape_fn(min.raw_dim(), |(i, j)| {
                     let a = min.get((i, j)).unwrap();
                     let b = max.get((i, j)).unwrap();
@@ -86,7 +82,7 @@ mod tests {
                 fn [<test_random_vec_ $t>]() {
                     let a = array![1 as $t, 2 as $t, 4 as $t];
                     let b = array![2 as $t, 3 as $t, 5 as $t];
-                    let random = Array1::<$t>::rand_from_range(&a, &b);
+                    let random = Array1::<$t>::rand_from_range(&a, &b, &mut rand::thread_rng());
                     for i in 0..3usize {
                         assert!(random[i] >= a[i]);
                         assert!(random[i] <= b[i]);
@@ -105,7 +101,7 @@ mod tests {
                         [2 as $t, 3 as $t, 5 as $t],
                         [3 as $t, 4 as $t, 6 as $t]
                     ];
-                    let random = Array2::<$t>::rand_from_range(&a, &b);
+                    let random = Array2::<$t>::rand_from_range(&a, &b, &mut rand::thread_rng());
                     for i in 0..3 {
                         for j in 0..2 {
                             assert!(random[(j, i)] >= a[(j, i)]);
@@ -0,0 +1,45 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use argmin::core::{CostFunction, Error, Executor};
+use argmin::solver::differentialevolution::{DifferentialEvolution, Strategy};
+use argmin_testfunctions::himmelblau;
+
+struct Himmelblau {}
+
+impl CostFunction for Himmelblau {
+    type Param = Vec<f64>;
+    type Output = f64;
+
+    fn cost(&self, param: &Self::Param) -> Result<Self::Output, Error> {
+        Ok(himmelblau(param))
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let cost_function = Himmelblau {};
+
+    let solver = DifferentialEvolution::new((vec![-4.0, -4.0], vec![4.0, 4.0]), 40)
+        .with_mutation_factor(0.7)?
+        .with_crossover_probability(0.8)?
+        .with_strategy(Strategy::CurrentToBest1Bin);
+
+    let res = Executor::new(cost_function, solver)
+        .configure(|state| state.max_iters(100))
+        .run()?;
+
+    // Print Result
+    println!("{res}");
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(ref e) = run() {
+        println!("{e}");
+    }
+}
@@ -6,11 +6,10 @@
 // copied, modified, or distributed except according to those terms.
 
 use argmin::core::observers::{ObserverMode, SlogLogger};
-use argmin::core::{CostFunction, Error, Executor, Gradient};
+use argmin::core::{CostFunction, Error, Executor, FiniteDiff};
 use argmin::solver::linesearch::MoreThuenteLineSearch;
 use argmin::solver::quasinewton::LBFGS;
 use argmin_testfunctions::rosenbrock;
-use finitediff::FiniteDiff;
 use ndarray::{array, Array1};
 
 struct Rosenbrock {
@@ -26,19 +25,14 @@ impl CostFunction for Rosenbrock {
         Ok(rosenbrock(&p.to_vec(), self.a, self.b))
     }
 }
-impl Gradient for Rosenbrock {
-    type Param = Array1<f64>;
-    type Gradient = Array1<f64>;
-
-    fn gradient(&self, p: &Self::Param) -> Result<Self::Gradient, Error> {
-        Ok((*p).forward_diff(&|x| rosenbrock(&x.to_vec(), self.a, self.b)))
-    }
-}
 
 fn run() -> Result<(), Error> {
     // Define cost function
     let cost = Rosenbrock { a: 1.0, b: 100.0 };
 
+    // Approximate the gradient via forward differences
+    let problem = FiniteDiff::new(cost).forward();
+
     // Define initial parameter vector
     let init_param: Array1<f64> = array![-1.2, 1.0];
     // let init_param: Array1<f64> = array![-1.2, 1.0, -10.0, 2.0, 3.0, 2.0, 4.0, 10.0];
@@ -50,7 +44,7 @@ fn run() -> Result<(), Error> {
     let solver = LBFGS::new(linesearch, 7);
 
     // Run solver
-    let res = Executor::new(cost, solver)
+    let res = Executor::new(problem, solver)
         .configure(|state| state.param(init_param).max_iters(100))
         .add_observer(SlogLogger::term(), ObserverMode::Always)
         .run()?;
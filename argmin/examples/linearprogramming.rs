@@ -0,0 +1,63 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use argmin::core::observers::{ObserverMode, SlogLogger};
+use argmin::core::{Error, Executor, LinearProgram};
+use argmin::solver::linearprogramming::{InteriorPoint, Simplex};
+
+/// Maximize `x1 + 2 x2` subject to `x1 + x2 <= 4` and `x1 + 3 x2 <= 6`.
+///
+/// In standard form, the objective is negated and the inequalities are turned into equalities by
+/// adding the slack variables `s1` and `s2`.
+struct Production {}
+
+impl LinearProgram for Production {
+    type Param = Vec<f64>;
+    type Float = f64;
+
+    fn c(&self) -> Result<Vec<f64>, Error> {
+        Ok(vec![-1.0, -2.0, 0.0, 0.0])
+    }
+
+    fn b(&self) -> Result<Vec<f64>, Error> {
+        Ok(vec![4.0, 6.0])
+    }
+
+    fn A(&self) -> Result<Vec<Vec<f64>>, Error> {
+        Ok(vec![vec![1.0, 1.0, 1.0, 0.0], vec![1.0, 3.0, 0.0, 1.0]])
+    }
+}
+
+fn run() -> Result<(), Error> {
+    let res = Executor::new(Production {}, Simplex::new())
+        .configure(|state| state.max_iters(100))
+        .add_observer(SlogLogger::term(), ObserverMode::Always)
+        .run()?;
+
+    // Wait a second (lets the logger flush everything before printing again)
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    println!("Result of simplex method:\n{res}");
+
+    let res = Executor::new(Production {}, InteriorPoint::new())
+        .configure(|state| state.max_iters(100))
+        .add_observer(SlogLogger::term(), ObserverMode::Always)
+        .run()?;
+
+    // Wait a second (lets the logger flush everything before printing again)
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    println!("Result of interior point method:\n{res}");
+    Ok(())
+}
+
+fn main() {
+    if let Err(ref e) = run() {
+        println!("{e}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,66 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use argmin::core::observers::{ObserverMode, SlogLogger};
+use argmin::core::{CostFunction, Error, Executor};
+use argmin::solver::multistart::MultiStart;
+use argmin::solver::neldermead::NelderMead;
+use argmin_testfunctions::rastrigin;
+
+struct Rastrigin {}
+
+impl CostFunction for Rastrigin {
+    type Param = Vec<f64>;
+    type Output = f64;
+
+    fn cost(&self, param: &Self::Param) -> Result<Self::Output, Error> {
+        Ok(rastrigin(param))
+    }
+}
+
+fn run() -> Result<(), Error> {
+    // Define cost function
+    let cost_function = Rastrigin {};
+
+    // Set up inner solver. The simplex is moved to each starting point.
+    let inner = NelderMead::new(vec![vec![0.0, 0.0], vec![0.2, 0.0], vec![0.0, 0.2]])
+        .with_sd_tolerance(1e-10)?;
+
+    // Set up multi-start method
+    let bounds = (vec![-5.12, -5.12], vec![5.12, 5.12]);
+    let solver = MultiStart::new(inner, bounds)
+        .with_inner_max_iters(200)?
+        .with_minima_tolerance(1e-3)?;
+
+    // Run solver
+    let res = Executor::new(cost_function, solver)
+        .configure(|state| state.max_iters(500))
+        .add_observer(SlogLogger::term(), ObserverMode::Always)
+        .run()?;
+
+    // Wait a second (lets the logger flush everything before printing again)
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    // Print result
+    println!("{res}");
+
+    // Print the best distinct local minima found
+    for minimum in res.solver.minima() {
+        println!(
+            "cost: {:.6}, param: {:?}, hits: {}",
+            minimum.cost, minimum.param, minimum.hits
+        );
+    }
+    Ok(())
+}
+
+fn main() {
+    if let Err(ref e) = run() {
+        println!("{e}");
+        std::process::exit(1);
+    }
+}
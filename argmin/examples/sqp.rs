@@ -0,0 +1,110 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use argmin::core::observers::{ObserverMode, SlogLogger};
+use argmin::core::{
+    CostFunction, EqualityConstraints, Error, Executor, Gradient, InequalityConstraints,
+};
+use argmin::solver::linesearch::MoreThuenteLineSearch;
+use argmin::solver::sqp::SQP;
+use argmin_testfunctions::{rosenbrock_2d, rosenbrock_2d_derivative};
+
+/// Rosenbrock function constrained to the disk `x^2 + y^2 <= 0.5` and the line `y = x - 0.2`
+struct ConstrainedRosenbrock {}
+
+impl CostFunction for ConstrainedRosenbrock {
+    type Param = Vec<f64>;
+    type Output = f64;
+
+    fn cost(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+        Ok(rosenbrock_2d(p, 1.0, 100.0))
+    }
+}
+
+impl Gradient for ConstrainedRosenbrock {
+    type Param = Vec<f64>;
+    type Gradient = Vec<f64>;
+
+    fn gradient(&self, p: &Self::Param) -> Result<Self::Gradient, Error> {
+        Ok(rosenbrock_2d_derivative(p, 1.0, 100.0))
+    }
+}
+
+impl EqualityConstraints for ConstrainedRosenbrock {
+    type Param = Vec<f64>;
+    type Gradient = Vec<f64>;
+    type Float = f64;
+
+    fn equality_constraints(&self, p: &Self::Param) -> Result<Vec<f64>, Error> {
+        Ok(vec![p[1] - p[0] + 0.2])
+    }
+
+    fn equality_jacobian(&self, _p: &Self::Param) -> Result<Vec<Self::Gradient>, Error> {
+        Ok(vec![vec![-1.0, 1.0]])
+    }
+}
+
+impl InequalityConstraints for ConstrainedRosenbrock {
+    type Param = Vec<f64>;
+    type Gradient = Vec<f64>;
+    type Float = f64;
+
+    fn inequality_constraints(&self, p: &Self::Param) -> Result<Vec<f64>, Error> {
+        Ok(vec![0.5 - p[0].powi(2) - p[1].powi(2)])
+    }
+
+    fn inequality_jacobian(&self, p: &Self::Param) -> Result<Vec<Self::Gradient>, Error> {
+        Ok(vec![vec![-2.0 * p[0], -2.0 * p[1]]])
+    }
+}
+
+fn run() -> Result<(), Error> {
+    // Define problem
+    let problem = ConstrainedRosenbrock {};
+
+    // Define initial parameter vector
+    let init_param: Vec<f64> = vec![-1.2, 1.0];
+
+    // Initial guess of the inverse Hessian of the Lagrangian
+    let init_inv_hessian: Vec<Vec<f64>> = vec![vec![1.0, 0.0], vec![0.0, 1.0]];
+
+    // Set up line search on the merit function
+    let linesearch = MoreThuenteLineSearch::new();
+
+    // Set up solver
+    let solver = SQP::new(linesearch);
+
+    // Run solver
+    let res = Executor::new(problem, solver)
+        .configure(|state| {
+            state
+                .param(init_param)
+                .inv_hessian(init_inv_hessian)
+                .max_iters(100)
+        })
+        .add_observer(SlogLogger::term(), ObserverMode::Always)
+        .run()?;
+
+    // Wait a second (lets the logger flush everything before printing again)
+    std::thread::sleep(std::time::Duration::from_secs(1));
+
+    // Print result
+    println!("{res}");
+    println!(
+        "Lagrange multipliers: {:?} {:?}",
+        res.state.get_equality_multipliers().unwrap(),
+        res.state.get_inequality_multipliers().unwrap()
+    );
+    Ok(())
+}
+
+fn main() {
+    if let Err(ref e) = run() {
+        println!("{e}");
+        std::process::exit(1);
+    }
+}
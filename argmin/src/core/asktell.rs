@@ -0,0 +1,632 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Ask-and-tell interface
+//!
+//! [`Executor`](`crate::core::Executor`) owns the optimization loop and calls the cost function
+//! whenever the solver needs a cost function value. If cost functions are evaluated outside of
+//! the program, for instance by batch jobs on a cluster or by lab experiments, [`AskTell`] inverts
+//! this control flow: [`ask`](`AskTell::ask`) returns the parameter vectors which need to be
+//! evaluated and [`tell`](`AskTell::tell`) hands the cost function values back to the solver.
+//!
+//! Internally, every iteration is replayed from the solver and the state at the beginning of the
+//! iteration, with all cost function values told so far, until the solver requests a value which
+//! is not known yet. This way any solver which only depends on cost function values can be used
+//! without modification, as long as all its randomness is drawn from a random number generator
+//! owned by the solver (as in [`ParticleSwarm`](`crate::solver::particleswarm::ParticleSwarm`)
+//! and [`SimulatedAnnealing`](`crate::solver::simulatedannealing::SimulatedAnnealing`)). Solvers
+//! which evaluate the cost function sequentially ask for a single parameter vector at a time,
+//! while bulk evaluations (for instance of all particles of a swarm) are asked for at once.
+//!
+//! Operations which are computed locally, such as
+//! [`Anneal`](`crate::solver::simulatedannealing::Anneal`), are provided by the problem passed
+//! to [`AskTell::new`]. Their results are recorded such that replays see the same values. If the
+//! solver only needs the cost function, `()` can be used as problem.
+//!
+//! With the `serde1` feature, `AskTell` implements `Serialize` and `Deserialize` and can therefore
+//! be stored between a call to `ask` and the corresponding call to `tell`.
+//!
+//! # Example
+//!
+//! ```
+//! use argmin::core::asktell::AskTell;
+//! use argmin::core::{Error, State};
+//! use argmin::solver::neldermead::NelderMead;
+//! use argmin_testfunctions::rosenbrock_2d;
+//!
+//! # fn main() -> Result<(), Error> {
+//! let solver = NelderMead::new(vec![vec![-1.0, 3.0], vec![2.0, 1.5], vec![2.0, -1.0]]);
+//!
+//! let mut driver = AskTell::new((), solver).configure(|state| state.max_iters(100));
+//!
+//! loop {
+//!     let params = driver.ask()?;
+//!     if params.is_empty() {
+//!         break;
+//!     }
+//!     // The parameter vectors would typically be evaluated by an external scheduler
+//!     let costs = params.iter().map(|p| rosenbrock_2d(p, 1.0, 100.0)).collect();
+//!     driver.tell(costs)?;
+//! }
+//!
+//! println!("Best parameter vector: {:?}", driver.state().get_best_param());
+//! # assert!(driver.state().get_best_cost() < 1e-2);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::core::{
+    CostFunction, Error, Problem, SendAlias, Solver, State, SyncAlias, TerminationStatus,
+};
+use crate::solver::simulatedannealing::Anneal;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+use std::sync::Mutex;
+
+/// Error which aborts a replay when the solver requests a cost function value which has not been
+/// told yet
+#[derive(Debug)]
+struct Pending;
+
+impl std::fmt::Display for Pending {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cost function value has not been told yet.")
+    }
+}
+
+impl std::error::Error for Pending {}
+
+/// Values recorded during the current iteration
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+struct Record<P, F> {
+    /// Parameter vectors which need to be evaluated
+    asked: Vec<P>,
+    /// Cost function values told so far
+    costs: Vec<F>,
+    /// Results of `anneal`
+    anneals: Vec<P>,
+}
+
+impl<P, F> Record<P, F> {
+    fn new() -> Self {
+        Record {
+            asked: vec![],
+            costs: vec![],
+            anneals: vec![],
+        }
+    }
+}
+
+/// Position of a replay in the recorded values
+struct Replay<P, F> {
+    /// Recorded values
+    record: Record<P, F>,
+    /// Number of cost function values used so far
+    costs_used: usize,
+    /// Number of results of `anneal` used so far
+    anneals_used: usize,
+}
+
+impl<P: Clone, F: Clone> Replay<P, F> {
+    /// Returns the recorded cost function values of `params` or records all parameter vectors
+    /// whose values are not known yet as asked.
+    fn costs<Q: Borrow<P>>(&mut self, params: &[Q]) -> Result<Vec<F>, Error> {
+        let start = self.costs_used;
+        let available = self.record.costs.len() - start;
+        if params.len() > available {
+            self.record.asked = params[available..]
+                .iter()
+                .map(|p| p.borrow().clone())
+                .collect();
+            return Err(Pending.into());
+        }
+        self.costs_used += params.len();
+        Ok(self.record.costs[start..self.costs_used].to_vec())
+    }
+}
+
+/// Problem seen by solvers run by [`AskTell`].
+///
+/// Cost function values are taken from the values told to [`AskTell`], while all other operations
+/// are forwarded to the problem passed to [`AskTell::new`]. This type cannot be constructed by
+/// users; it only appears in the trait bounds of [`AskTell`].
+pub struct AskTellProblem<O, P, F> {
+    /// Problem providing operations which are computed locally
+    problem: O,
+    /// Replay of the current iteration
+    replay: Mutex<Replay<P, F>>,
+}
+
+impl<O, P, F> CostFunction for AskTellProblem<O, P, F>
+where
+    P: Clone,
+    F: Clone,
+{
+    type Param = P;
+    type Output = F;
+
+    fn cost(&self, param: &P) -> Result<F, Error> {
+        let mut costs = self
+            .replay
+            .lock()
+            .unwrap()
+            .costs(std::slice::from_ref(param))?;
+        Ok(costs.remove(0))
+    }
+
+    fn bulk_cost<Q>(&self, params: &[Q]) -> Result<Vec<F>, Error>
+    where
+        Q: Borrow<P> + SyncAlias,
+        F: SendAlias,
+        Self: SyncAlias,
+    {
+        self.replay.lock().unwrap().costs(params)
+    }
+}
+
+impl<O, P, F> Anneal for AskTellProblem<O, P, F>
+where
+    O: Anneal<Param = P, Output = P, Float = F>,
+    P: Clone,
+{
+    type Param = P;
+    type Output = P;
+    type Float = F;
+
+    fn anneal(&self, param: &P, extent: F) -> Result<P, Error> {
+        let mut replay = self.replay.lock().unwrap();
+        let idx = replay.anneals_used;
+        replay.anneals_used += 1;
+        if let Some(new_param) = replay.record.anneals.get(idx) {
+            return Ok(new_param.clone());
+        }
+        let new_param = self.problem.anneal(param, extent)?;
+        replay.record.anneals.push(new_param.clone());
+        Ok(new_param)
+    }
+}
+
+/// Drives a solver by asking for parameter vectors and being told their cost function values
+///
+/// See the [module documentation](`crate::core::asktell`) for details.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct AskTell<O, S, I: State, P> {
+    /// Problem providing operations which are computed locally
+    problem: Option<O>,
+    /// Solver at the beginning of the current iteration
+    solver: S,
+    /// State at the beginning of the current iteration
+    state: Option<I>,
+    /// Indicates whether `init` of the solver has completed
+    initialized: bool,
+    /// Values recorded during the current iteration
+    record: Record<P, I::Float>,
+}
+
+impl<O, S, I, P> AskTell<O, S, I, P>
+where
+    S: Solver<AskTellProblem<O, P, I::Float>, I> + Clone,
+    I: State + Clone,
+    P: Clone,
+{
+    /// Constructs an `AskTell` driver from a problem and a solver.
+    ///
+    /// `problem` only needs to implement the operations which are computed locally, such as
+    /// [`Anneal`]; use `()` if the solver only needs the cost function.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::asktell::AskTell;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// let simplex = vec![vec![-1.0, 3.0], vec![2.0, 1.5], vec![2.0, -1.0]];
+    /// let solver: NelderMead<_, f64> = NelderMead::new(simplex);
+    /// let driver = AskTell::new((), solver);
+    /// # let _ = driver.state();
+    /// ```
+    pub fn new(problem: O, solver: S) -> Self {
+        AskTell {
+            problem: Some(problem),
+            solver,
+            state: Some(I::new()),
+            initialized: false,
+            record: Record::new(),
+        }
+    }
+
+    /// This method gives mutable access to the internal state of the solver. This allows for
+    /// initializing the state before running the driver, in the same way as
+    /// [`Executor::configure`](`crate::core::Executor::configure`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::asktell::AskTell;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// # let simplex = vec![vec![-1.0, 3.0], vec![2.0, 1.5], vec![2.0, -1.0]];
+    /// # let solver: NelderMead<_, f64> = NelderMead::new(simplex);
+    /// let driver = AskTell::new((), solver).configure(|state| state.max_iters(10));
+    /// ```
+    #[must_use]
+    pub fn configure<G: FnOnce(I) -> I>(mut self, init: G) -> Self {
+        let state = self.state.take().unwrap();
+        self.state = Some(init(state));
+        self
+    }
+
+    /// Returns the parameter vectors which need to be evaluated.
+    ///
+    /// Repeated calls without a call to [`tell`](`AskTell::tell`) in between return the same
+    /// parameter vectors. An empty vector is returned once the solver has terminated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::asktell::AskTell;
+    /// # use argmin::core::Error;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// # fn main() -> Result<(), Error> {
+    /// # let simplex = vec![vec![-1.0, 3.0], vec![2.0, 1.5], vec![2.0, -1.0]];
+    /// # let solver: NelderMead<_, f64> = NelderMead::new(simplex);
+    /// let mut driver = AskTell::new((), solver).configure(|state| state.max_iters(10));
+    /// let params = driver.ask()?;
+    /// # assert_eq!(params, vec![vec![-1.0, 3.0]]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ask(&mut self) -> Result<Vec<P>, Error> {
+        while self.record.asked.is_empty() {
+            if self.initialized {
+                let mut state = self.state.take().unwrap();
+                if !state.terminated() {
+                    let term = self.solver.terminate_internal(&state);
+                    if let TerminationStatus::Terminated(reason) = term {
+                        state = state.terminate_with(reason);
+                    }
+                }
+                let terminated = state.terminated();
+                self.state = Some(state);
+                if terminated {
+                    return Ok(vec![]);
+                }
+            }
+            self.replay()?;
+        }
+        Ok(self.record.asked.clone())
+    }
+
+    /// Hands the cost function values of the parameter vectors returned by the last call to
+    /// [`ask`](`AskTell::ask`) to the solver.
+    ///
+    /// `costs` must be in the order of the asked parameter vectors.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::asktell::AskTell;
+    /// # use argmin::core::Error;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// # use argmin_testfunctions::rosenbrock_2d;
+    /// # fn main() -> Result<(), Error> {
+    /// # let solver = NelderMead::new(vec![vec![-1.0, 3.0], vec![2.0, 1.5], vec![2.0, -1.0]]);
+    /// # let mut driver = AskTell::new((), solver).configure(|state| state.max_iters(10));
+    /// let params = driver.ask()?;
+    /// let costs = params.iter().map(|p| rosenbrock_2d(p, 1.0, 100.0)).collect();
+    /// driver.tell(costs)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn tell(&mut self, costs: Vec<I::Float>) -> Result<(), Error> {
+        if self.record.asked.is_empty() {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`AskTell`: No parameter vectors have been asked for."
+            ));
+        }
+        if costs.len() != self.record.asked.len() {
+            return Err(argmin_error!(
+                InvalidParameter,
+                format!(
+                    "`AskTell`: Expected {} cost function values, got {}.",
+                    self.record.asked.len(),
+                    costs.len()
+                )
+            ));
+        }
+        self.record.asked.clear();
+        self.record.costs.extend(costs);
+        self.replay()
+    }
+
+    /// Returns the state at the beginning of the current iteration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::asktell::AskTell;
+    /// # use argmin::core::State;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// # let simplex = vec![vec![-1.0, 3.0], vec![2.0, 1.5], vec![2.0, -1.0]];
+    /// # let solver: NelderMead<_, f64> = NelderMead::new(simplex);
+    /// # let driver = AskTell::new((), solver);
+    /// let best_cost = driver.state().get_best_cost();
+    /// # assert_eq!(best_cost.to_ne_bytes(), f64::INFINITY.to_ne_bytes());
+    /// ```
+    pub fn state(&self) -> &I {
+        self.state.as_ref().unwrap()
+    }
+
+    /// Replays the current iteration with all values recorded so far.
+    ///
+    /// If the iteration completes, the solver and the state are advanced to the next iteration.
+    /// Otherwise the parameter vectors which need to be evaluated are recorded as asked.
+    fn replay(&mut self) -> Result<(), Error> {
+        let mut solver = self.solver.clone();
+        let state = self.state.clone().unwrap();
+        let record = std::mem::replace(&mut self.record, Record::new());
+        let mut problem = Problem::new(AskTellProblem {
+            problem: self.problem.take().unwrap(),
+            replay: Mutex::new(Replay {
+                record,
+                costs_used: 0,
+                anneals_used: 0,
+            }),
+        });
+
+        let result = if self.initialized {
+            solver.next_iter(&mut problem, state)
+        } else {
+            solver.init(&mut problem, state)
+        };
+
+        let AskTellProblem {
+            problem: local,
+            replay,
+        } = problem.take_problem().unwrap();
+        self.problem = Some(local);
+        let record = replay.into_inner().unwrap().record;
+
+        match result {
+            Ok((mut state, _)) => {
+                // The counts of `problem` only cover this iteration.
+                for (k, v) in problem.counts.iter_mut() {
+                    *v += state.get_func_counts().get(*k).copied().unwrap_or(0);
+                }
+                state.func_counts(&problem);
+                state.update();
+                if self.initialized {
+                    state.increment_iter();
+                }
+                self.initialized = true;
+                self.solver = solver;
+                self.state = Some(state);
+                Ok(())
+            }
+            Err(e) => {
+                self.record = record;
+                if e.is::<Pending>() {
+                    Ok(())
+                } else {
+                    Err(e)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ArgminError, Executor, IterState, PopulationState};
+    use crate::solver::neldermead::NelderMead;
+    use crate::solver::particleswarm::{Particle, ParticleSwarm};
+    use crate::solver::simulatedannealing::SimulatedAnnealing;
+    use argmin_testfunctions::rosenbrock_2d;
+    use rand::{Rng, SeedableRng};
+    use rand_xoshiro::Xoshiro256PlusPlus;
+
+    type NelderMeadDriver =
+        AskTell<(), NelderMead<Vec<f64>, f64>, IterState<Vec<f64>, (), (), (), f64>, Vec<f64>>;
+
+    test_trait_impl!(asktell, NelderMeadDriver);
+
+    #[derive(Clone)]
+    #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+    struct Rosenbrock {}
+
+    impl CostFunction for Rosenbrock {
+        type Param = Vec<f64>;
+        type Output = f64;
+
+        fn cost(&self, param: &Self::Param) -> Result<Self::Output, Error> {
+            Ok(rosenbrock_2d(param, 1.0, 100.0))
+        }
+    }
+
+    impl Anneal for Rosenbrock {
+        type Param = Vec<f64>;
+        type Output = Vec<f64>;
+        type Float = f64;
+
+        fn anneal(&self, param: &Vec<f64>, extent: f64) -> Result<Vec<f64>, Error> {
+            // Deliberately not reproducible
+            let mut rng = rand::thread_rng();
+            Ok(param
+                .iter()
+                .map(|x| x + rng.gen_range(-1.0..1.0) * extent)
+                .collect())
+        }
+    }
+
+    fn nelder_mead() -> NelderMead<Vec<f64>, f64> {
+        NelderMead::new(vec![vec![-1.0, 3.0], vec![2.0, 1.5], vec![2.0, -1.0]])
+    }
+
+    fn particle_swarm() -> ParticleSwarm<Vec<f64>, f64> {
+        ParticleSwarm::new_with_rng(
+            (vec![-2.0, -2.0], vec![2.0, 2.0]),
+            10,
+            Xoshiro256PlusPlus::seed_from_u64(42),
+        )
+    }
+
+    /// Runs the driver to completion and returns the number of calls to `ask`
+    fn drive<O, S, I>(driver: &mut AskTell<O, S, I, Vec<f64>>) -> Result<u64, Error>
+    where
+        S: Solver<AskTellProblem<O, Vec<f64>, f64>, I> + Clone,
+        I: State<Float = f64> + Clone,
+    {
+        let mut asks = 0;
+        loop {
+            let params = driver.ask()?;
+            if params.is_empty() {
+                return Ok(asks);
+            }
+            asks += 1;
+            driver.tell(
+                params
+                    .iter()
+                    .map(|p| rosenbrock_2d(p, 1.0, 100.0))
+                    .collect(),
+            )?;
+        }
+    }
+
+    #[test]
+    fn test_nelder_mead_matches_executor() {
+        let mut driver = AskTell::new((), nelder_mead()).configure(|state| state.max_iters(50));
+        drive(&mut driver).unwrap();
+
+        let res = Executor::new(Rosenbrock {}, nelder_mead())
+            .configure(|state| state.max_iters(50))
+            .ctrlc(false)
+            .run()
+            .unwrap();
+
+        let state = driver.state();
+        assert_eq!(state.get_iter(), 50);
+        assert_eq!(
+            state.get_best_param().unwrap(),
+            res.state.get_best_param().unwrap()
+        );
+        assert_eq!(
+            state.get_best_cost().to_ne_bytes(),
+            res.state.get_best_cost().to_ne_bytes()
+        );
+        assert_eq!(
+            state.get_func_counts()["cost_count"],
+            res.state.get_func_counts()["cost_count"]
+        );
+    }
+
+    #[test]
+    fn test_particle_swarm_matches_executor() {
+        let mut driver = AskTell::new((), particle_swarm()).configure(|state| state.max_iters(20));
+        let asks = drive(&mut driver).unwrap();
+        // Initialization and every iteration evaluate the whole swarm at once.
+        assert_eq!(asks, 21);
+
+        let res = Executor::new(Rosenbrock {}, particle_swarm())
+            .configure(|state| state.max_iters(20))
+            .ctrlc(false)
+            .run()
+            .unwrap();
+
+        let state: &PopulationState<Particle<Vec<f64>, f64>, f64> = driver.state();
+        assert_eq!(
+            state.get_best_param().unwrap().position,
+            res.state.get_best_param().unwrap().position
+        );
+        assert_eq!(
+            state.get_best_cost().to_ne_bytes(),
+            res.state.get_best_cost().to_ne_bytes()
+        );
+        assert_eq!(state.get_func_counts()["cost_count"], 210);
+    }
+
+    #[test]
+    fn test_simulated_annealing() {
+        let solver =
+            SimulatedAnnealing::new_with_rng(10.0, Xoshiro256PlusPlus::seed_from_u64(1)).unwrap();
+        let mut driver = AskTell::new(Rosenbrock {}, solver)
+            .configure(|state| state.param(vec![1.5, 1.5]).max_iters(100));
+        let asks = drive(&mut driver).unwrap();
+        assert_eq!(asks, 101);
+
+        // Every told cost function value must belong to the annealed parameter vector which was
+        // asked for, even though `anneal` is not reproducible.
+        let state = driver.state();
+        assert_eq!(
+            state.get_best_cost().to_ne_bytes(),
+            rosenbrock_2d(state.get_best_param().unwrap(), 1.0, 100.0).to_ne_bytes()
+        );
+        assert_eq!(state.get_func_counts()["cost_count"], 101);
+        assert_eq!(state.get_func_counts()["anneal_count"], 100);
+    }
+
+    #[test]
+    fn test_ask_repeated() {
+        let mut driver = AskTell::new((), nelder_mead()).configure(|state| state.max_iters(5));
+        let params = driver.ask().unwrap();
+        assert_eq!(params, vec![vec![-1.0, 3.0]]);
+        assert_eq!(driver.ask().unwrap(), params);
+        driver.tell(vec![1.0]).unwrap();
+        assert_eq!(driver.ask().unwrap(), vec![vec![2.0, 1.5]]);
+    }
+
+    #[test]
+    fn test_tell_errors() {
+        let mut driver = AskTell::new((), nelder_mead()).configure(|state| state.max_iters(5));
+        assert_error!(
+            driver.tell(vec![1.0]),
+            ArgminError,
+            "Invalid parameter: \"`AskTell`: No parameter vectors have been asked for.\""
+        );
+        driver.ask().unwrap();
+        assert_error!(
+            driver.tell(vec![1.0, 2.0]),
+            ArgminError,
+            "Invalid parameter: \"`AskTell`: Expected 1 cost function values, got 2.\""
+        );
+        driver.tell(vec![1.0]).unwrap();
+    }
+
+    #[cfg(feature = "serde1")]
+    #[test]
+    fn test_serialize_between_ask_and_tell() {
+        let mut reference =
+            AskTell::new((), particle_swarm()).configure(|state| state.max_iters(10));
+        drive(&mut reference).unwrap();
+
+        let mut driver = AskTell::new((), particle_swarm()).configure(|state| state.max_iters(10));
+        loop {
+            let params = driver.ask().unwrap();
+            if params.is_empty() {
+                break;
+            }
+            let serialized = bincode::serialize(&driver).unwrap();
+            driver = bincode::deserialize(&serialized).unwrap();
+            driver
+                .tell(
+                    params
+                        .iter()
+                        .map(|p| rosenbrock_2d(p, 1.0, 100.0))
+                        .collect(),
+                )
+                .unwrap();
+        }
+
+        assert_eq!(driver.state().get_iter(), 10);
+        assert_eq!(
+            driver.state().get_best_cost().to_ne_bytes(),
+            reference.state().get_best_cost().to_ne_bytes()
+        );
+    }
+}
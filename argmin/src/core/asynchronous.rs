@@ -0,0 +1,956 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Asynchronous cost functions and gradients
+//!
+//! Cost functions which wait for I/O, for instance because they call out to a simulation service
+//! via RPC, can implement [`AsyncCostFunction`] and [`AsyncGradient`] instead of
+//! [`CostFunction`] and [`Gradient`]. Both traits return boxed futures and do not depend on any
+//! particular async runtime.
+//!
+//! [`AsyncExecutor`] solves such problems with any of the existing solvers. The solver runs on a
+//! dedicated thread, and every function evaluation requested by the solver is forwarded to the
+//! future returned by [`AsyncExecutor::run`], which awaits the evaluation on the runtime the
+//! future is polled on. Therefore only a single thread is blocked for the whole optimization
+//! instead of one thread per function evaluation. Bulk evaluations (for instance the cost function
+//! values of a population in [`ParticleSwarm`](`crate::solver::particleswarm::ParticleSwarm`))
+//! are evaluated concurrently, with the number of evaluations in flight at any time limited by
+//! [`max_in_flight`](`AsyncExecutor::max_in_flight`).
+//!
+//! # Example
+//!
+//! ```
+//! use argmin::core::asynchronous::{AsyncCostFunction, AsyncExecutor, AsyncGradient, BoxFuture};
+//! use argmin::core::{Error, State};
+//! use argmin::solver::linesearch::MoreThuenteLineSearch;
+//! use argmin::solver::quasinewton::LBFGS;
+//! # use argmin::core::test_utils::block_on;
+//!
+//! struct Sphere {}
+//!
+//! impl AsyncCostFunction for Sphere {
+//!     type Param = Vec<f64>;
+//!     type Output = f64;
+//!
+//!     fn cost<'a>(&'a self, param: &'a Self::Param) -> BoxFuture<'a, Result<Self::Output, Error>> {
+//!         // The body would typically await a request to a remote service
+//!         Box::pin(async move { Ok(param.iter().map(|x| x.powi(2)).sum()) })
+//!     }
+//! }
+//!
+//! impl AsyncGradient for Sphere {
+//!     type Param = Vec<f64>;
+//!     type Gradient = Vec<f64>;
+//!
+//!     fn gradient<'a>(
+//!         &'a self,
+//!         param: &'a Self::Param,
+//!     ) -> BoxFuture<'a, Result<Self::Gradient, Error>> {
+//!         Box::pin(async move { Ok(param.iter().map(|x| 2.0 * x).collect()) })
+//!     }
+//! }
+//!
+//! # fn main() -> Result<(), Error> {
+//! # block_on(async {
+//! let solver = LBFGS::new(MoreThuenteLineSearch::new(), 7);
+//!
+//! let res = AsyncExecutor::new(Sphere {}, solver)
+//!     .configure(|state| state.param(vec![1.0, -2.0]).max_iters(10))
+//!     .run()
+//!     .await?;
+//! # assert!(res.state.get_best_cost() < 1e-8);
+//! # Ok(())
+//! # })
+//! # }
+//! ```
+
+use crate::core::checkpointing::Checkpoint;
+use crate::core::observers::{Observe, ObserverMode};
+use crate::core::{
+    CostFunction, DeserializeOwnedAlias, Error, Executor, Gradient, OptimizationResult, Problem,
+    SendAlias, SerializeAlias, Solver, State, SyncAlias,
+};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{mpsc, Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// Boxed future returned by [`AsyncCostFunction`] and [`AsyncGradient`]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Defines an asynchronous cost function.
+///
+/// This is the asynchronous equivalent of [`CostFunction`]. Implementations typically return
+/// `Box::pin(async move { ... })`.
+///
+/// # Example
+///
+/// ```
+/// use argmin::core::asynchronous::{AsyncCostFunction, BoxFuture};
+/// use argmin::core::Error;
+/// use argmin_testfunctions::rosenbrock_2d;
+///
+/// struct Rosenbrock {}
+///
+/// impl AsyncCostFunction for Rosenbrock {
+///     type Param = Vec<f64>;
+///     type Output = f64;
+///
+///     fn cost<'a>(&'a self, param: &'a Self::Param) -> BoxFuture<'a, Result<Self::Output, Error>> {
+///         Box::pin(async move { Ok(rosenbrock_2d(param, 1.0, 100.0)) })
+///     }
+/// }
+/// ```
+pub trait AsyncCostFunction {
+    /// Type of the parameter vector
+    type Param;
+    /// Type of the return value of the cost function
+    type Output;
+
+    /// Compute cost function
+    fn cost<'a>(&'a self, param: &'a Self::Param) -> BoxFuture<'a, Result<Self::Output, Error>>;
+}
+
+/// Defines the asynchronous computation of the gradient.
+///
+/// This is the asynchronous equivalent of [`Gradient`].
+///
+/// # Example
+///
+/// ```
+/// use argmin::core::asynchronous::{AsyncGradient, BoxFuture};
+/// use argmin::core::Error;
+/// use argmin_testfunctions::rosenbrock_2d_derivative;
+///
+/// struct Rosenbrock {}
+///
+/// impl AsyncGradient for Rosenbrock {
+///     type Param = Vec<f64>;
+///     type Gradient = Vec<f64>;
+///
+///     fn gradient<'a>(
+///         &'a self,
+///         param: &'a Self::Param,
+///     ) -> BoxFuture<'a, Result<Self::Gradient, Error>> {
+///         Box::pin(async move { Ok(rosenbrock_2d_derivative(param, 1.0, 100.0)) })
+///     }
+/// }
+/// ```
+pub trait AsyncGradient {
+    /// Type of the parameter vector
+    type Param;
+    /// Type of the gradient
+    type Gradient;
+
+    /// Compute gradient
+    fn gradient<'a>(
+        &'a self,
+        param: &'a Self::Param,
+    ) -> BoxFuture<'a, Result<Self::Gradient, Error>>;
+}
+
+/// Wrapper around asynchronous problems defined by users.
+///
+/// This is the asynchronous equivalent of [`Problem`]. It keeps track of how many times `cost`
+/// and `gradient` are called and evaluates bulk requests concurrently, with at most
+/// [`max_in_flight`](`AsyncProblem::with_max_in_flight`) evaluations in flight at any time.
+#[derive(Clone, Debug)]
+pub struct AsyncProblem<O> {
+    /// Problem defined by user
+    pub problem: Option<O>,
+    /// Keeps track of how often methods of `problem` have been called.
+    pub counts: HashMap<&'static str, u64>,
+    /// Maximum number of concurrent evaluations in bulk requests
+    max_in_flight: usize,
+}
+
+impl<O> Default for AsyncProblem<O> {
+    fn default() -> Self {
+        AsyncProblem {
+            problem: None,
+            counts: HashMap::new(),
+            max_in_flight: 8,
+        }
+    }
+}
+
+impl<O> AsyncProblem<O> {
+    /// Wraps a problem into an instance of `AsyncProblem`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::asynchronous::AsyncProblem;
+    /// # #[derive(Eq, PartialEq, Debug)]
+    /// # struct UserDefinedProblem {};
+    /// let wrapped_problem = AsyncProblem::new(UserDefinedProblem {});
+    /// # assert_eq!(wrapped_problem.problem.unwrap(), UserDefinedProblem {});
+    /// ```
+    pub fn new(problem: O) -> Self {
+        AsyncProblem {
+            problem: Some(problem),
+            counts: HashMap::new(),
+            max_in_flight: 8,
+        }
+    }
+
+    /// Set the maximum number of evaluations in flight in bulk requests
+    ///
+    /// Defaults to `8`. Must be larger than 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::asynchronous::AsyncProblem;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// # struct UserDefinedProblem {};
+    /// let wrapped_problem = AsyncProblem::new(UserDefinedProblem {}).with_max_in_flight(32)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Result<Self, Error> {
+        if max_in_flight == 0 {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`AsyncProblem`: max in flight must be > 0."
+            ));
+        }
+        self.max_in_flight = max_in_flight;
+        Ok(self)
+    }
+
+    fn count(&mut self, counts_string: &'static str, num: usize) {
+        let count = self.counts.entry(counts_string).or_insert(0);
+        *count += num as u64;
+    }
+}
+
+impl<O: AsyncCostFunction> AsyncProblem<O> {
+    /// Calls `cost` defined by the `AsyncCostFunction` trait and keeps track of the number of
+    /// evaluations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::asynchronous::AsyncProblem;
+    /// # use argmin::core::test_utils::{block_on, TestProblem};
+    /// # let mut problem = AsyncProblem::new(TestProblem::new());
+    /// let cost = block_on(problem.cost(&vec![1.0f64, 2.0]));
+    /// # assert_eq!(problem.counts["cost_count"], 1);
+    /// ```
+    pub async fn cost(&mut self, param: &O::Param) -> Result<O::Output, Error> {
+        self.count("cost_count", 1);
+        self.problem.as_ref().unwrap().cost(param).await
+    }
+
+    /// Evaluates the cost function for all `params` concurrently, with at most `max_in_flight`
+    /// evaluations in flight at any time. The results are in the order of `params`. If an
+    /// evaluation fails, the remaining evaluations are canceled and the error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::asynchronous::AsyncProblem;
+    /// # use argmin::core::test_utils::{block_on, TestProblem};
+    /// # let mut problem = AsyncProblem::new(TestProblem::new());
+    /// let params = vec![vec![1.0f64, 2.0], vec![3.0, 4.0]];
+    /// let costs = block_on(problem.bulk_cost(&params));
+    /// # assert_eq!(problem.counts["cost_count"], 2);
+    /// ```
+    pub async fn bulk_cost(&mut self, params: &[O::Param]) -> Result<Vec<O::Output>, Error> {
+        self.count("cost_count", params.len());
+        let problem = self.problem.as_ref().unwrap();
+        let futures = params.iter().map(|p| problem.cost(p)).collect();
+        Buffered::new(futures, self.max_in_flight).await
+    }
+}
+
+impl<O: AsyncGradient> AsyncProblem<O> {
+    /// Calls `gradient` defined by the `AsyncGradient` trait and keeps track of the number of
+    /// evaluations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::asynchronous::AsyncProblem;
+    /// # use argmin::core::test_utils::{block_on, TestProblem};
+    /// # let mut problem = AsyncProblem::new(TestProblem::new());
+    /// let gradient = block_on(problem.gradient(&vec![1.0f64, 2.0]));
+    /// # assert_eq!(problem.counts["gradient_count"], 1);
+    /// ```
+    pub async fn gradient(&mut self, param: &O::Param) -> Result<O::Gradient, Error> {
+        self.count("gradient_count", 1);
+        self.problem.as_ref().unwrap().gradient(param).await
+    }
+
+    /// Evaluates the gradient for all `params` concurrently, with at most `max_in_flight`
+    /// evaluations in flight at any time. The results are in the order of `params`. If an
+    /// evaluation fails, the remaining evaluations are canceled and the error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::asynchronous::AsyncProblem;
+    /// # use argmin::core::test_utils::{block_on, TestProblem};
+    /// # let mut problem = AsyncProblem::new(TestProblem::new());
+    /// let params = vec![vec![1.0f64, 2.0], vec![3.0, 4.0]];
+    /// let gradients = block_on(problem.bulk_gradient(&params));
+    /// # assert_eq!(problem.counts["gradient_count"], 2);
+    /// ```
+    pub async fn bulk_gradient(&mut self, params: &[O::Param]) -> Result<Vec<O::Gradient>, Error> {
+        self.count("gradient_count", params.len());
+        let problem = self.problem.as_ref().unwrap();
+        let futures = params.iter().map(|p| problem.gradient(p)).collect();
+        Buffered::new(futures, self.max_in_flight).await
+    }
+}
+
+/// Future which polls a number of futures concurrently, with at most `limit` of them started but
+/// not yet completed at any time.
+struct Buffered<'a, T> {
+    /// Futures which were not started yet
+    queue: VecDeque<(usize, BoxFuture<'a, Result<T, Error>>)>,
+    /// Started futures
+    in_flight: Vec<(usize, BoxFuture<'a, Result<T, Error>>)>,
+    /// Results in the order of the futures
+    results: Vec<Option<T>>,
+    /// Maximum number of futures in flight
+    limit: usize,
+}
+
+impl<'a, T> Buffered<'a, T> {
+    fn new(futures: Vec<BoxFuture<'a, Result<T, Error>>>, limit: usize) -> Self {
+        Buffered {
+            results: futures.iter().map(|_| None).collect(),
+            queue: futures.into_iter().enumerate().collect(),
+            in_flight: vec![],
+            limit,
+        }
+    }
+}
+
+// No field is ever pinned: The futures are boxed.
+impl<T> Unpin for Buffered<'_, T> {}
+
+impl<T> Future for Buffered<'_, T> {
+    type Output = Result<Vec<T>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        loop {
+            while this.in_flight.len() < this.limit {
+                match this.queue.pop_front() {
+                    Some(future) => this.in_flight.push(future),
+                    None => break,
+                }
+            }
+            let mut completed = false;
+            let mut idx = 0;
+            while idx < this.in_flight.len() {
+                match this.in_flight[idx].1.as_mut().poll(cx) {
+                    Poll::Ready(result) => {
+                        let (i, _) = this.in_flight.swap_remove(idx);
+                        this.results[i] = Some(result?);
+                        completed = true;
+                    }
+                    Poll::Pending => idx += 1,
+                }
+            }
+            if this.in_flight.is_empty() && this.queue.is_empty() {
+                return Poll::Ready(Ok(this
+                    .results
+                    .iter_mut()
+                    .map(|r| r.take().unwrap())
+                    .collect()));
+            }
+            // Start further futures if slots became available, otherwise wait to be woken up.
+            if !completed || this.queue.is_empty() {
+                return Poll::Pending;
+            }
+        }
+    }
+}
+
+/// Function evaluation requested by the solver thread
+type Job<O> = Box<dyn for<'a> FnOnce(&'a mut AsyncProblem<O>) -> BoxFuture<'a, ()> + Send>;
+
+/// Queue of function evaluations shared between the solver thread and [`AsyncExecutor::run`]
+struct Channel<O> {
+    /// Requested function evaluations
+    jobs: VecDeque<Job<O>>,
+    /// Waker of the future returned by `AsyncExecutor::run`
+    waker: Option<Waker>,
+    /// Set when the solver thread has finished
+    solver_done: bool,
+    /// Set when the future returned by `AsyncExecutor::run` was dropped
+    closed: bool,
+}
+
+type SharedChannel<O> = Arc<Mutex<Channel<O>>>;
+
+/// Future resolving to the next function evaluation requested by the solver thread, or to `None`
+/// once the solver thread has finished.
+struct NextJob<'a, O> {
+    channel: &'a SharedChannel<O>,
+}
+
+impl<O> Future for NextJob<'_, O> {
+    type Output = Option<Job<O>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut channel = self.channel.lock().unwrap();
+        if let Some(job) = channel.jobs.pop_front() {
+            Poll::Ready(Some(job))
+        } else if channel.solver_done {
+            Poll::Ready(None)
+        } else {
+            channel.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+/// Notifies the other side of the channel when the solver thread finishes or the future returned
+/// by `AsyncExecutor::run` is dropped, also in case of a panic.
+struct ChannelGuard<O> {
+    channel: SharedChannel<O>,
+    solver: bool,
+}
+
+impl<O> Drop for ChannelGuard<O> {
+    fn drop(&mut self) {
+        let mut channel = match self.channel.lock() {
+            Ok(channel) => channel,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if self.solver {
+            channel.solver_done = true;
+            if let Some(waker) = channel.waker.take() {
+                waker.wake();
+            }
+        } else {
+            // Dropping the pending jobs disconnects the solver thread waiting for their results.
+            channel.closed = true;
+            channel.jobs.clear();
+        }
+    }
+}
+
+/// Synchronous view on an asynchronous problem, used by [`AsyncExecutor`].
+///
+/// Implements [`CostFunction`] if the problem implements [`AsyncCostFunction`] and [`Gradient`]
+/// if the problem implements [`AsyncGradient`]. Every call blocks until the evaluation was
+/// awaited by the future returned by [`AsyncExecutor::run`]. Solvers used with an
+/// `AsyncExecutor` need to implement `Solver<AsyncBridge<O>, I>`, which is the case for all
+/// solvers which only require the problem to implement `CostFunction` and/or `Gradient`.
+pub struct AsyncBridge<O> {
+    channel: SharedChannel<O>,
+}
+
+impl<O: Send> AsyncBridge<O> {
+    /// Sends `func` to the future returned by `AsyncExecutor::run` and waits for the result.
+    fn request<T, F>(&self, func: F) -> Result<T, Error>
+    where
+        T: Send + 'static,
+        F: for<'a> FnOnce(&'a mut AsyncProblem<O>) -> BoxFuture<'a, Result<T, Error>>
+            + Send
+            + 'static,
+    {
+        let dropped = argmin_error_closure!(
+            NotInitialized,
+            "`AsyncExecutor` was dropped before the optimization finished."
+        );
+        let (sender, receiver) = mpsc::channel();
+        let job: Job<O> = Box::new(move |problem: &mut AsyncProblem<O>| -> BoxFuture<'_, ()> {
+            Box::pin(async move {
+                // The solver thread only stops waiting for the result if it panicked.
+                let _ = sender.send(func(problem).await);
+            })
+        });
+        {
+            let mut channel = self.channel.lock().unwrap();
+            if channel.closed {
+                return Err(dropped());
+            }
+            channel.jobs.push_back(job);
+            if let Some(waker) = channel.waker.take() {
+                waker.wake();
+            }
+        }
+        receiver.recv().map_err(|_| dropped())?
+    }
+}
+
+impl<O> CostFunction for AsyncBridge<O>
+where
+    O: AsyncCostFunction + Send + Sync + 'static,
+    O::Param: Clone + Send + Sync + 'static,
+    O::Output: Send + 'static,
+{
+    type Param = O::Param;
+    type Output = O::Output;
+
+    fn cost(&self, param: &Self::Param) -> Result<Self::Output, Error> {
+        let param = param.clone();
+        self.request(move |problem| Box::pin(async move { problem.cost(&param).await }))
+    }
+
+    fn bulk_cost<P>(&self, params: &[P]) -> Result<Vec<Self::Output>, Error>
+    where
+        P: std::borrow::Borrow<Self::Param> + SyncAlias,
+        Self::Output: SendAlias,
+        Self: SyncAlias,
+    {
+        let params: Vec<O::Param> = params.iter().map(|p| p.borrow().clone()).collect();
+        self.request(move |problem| Box::pin(async move { problem.bulk_cost(&params).await }))
+    }
+}
+
+impl<O> Gradient for AsyncBridge<O>
+where
+    O: AsyncGradient + Send + Sync + 'static,
+    O::Param: Clone + Send + Sync + 'static,
+    O::Gradient: Send + 'static,
+{
+    type Param = O::Param;
+    type Gradient = O::Gradient;
+
+    fn gradient(&self, param: &Self::Param) -> Result<Self::Gradient, Error> {
+        let param = param.clone();
+        self.request(move |problem| Box::pin(async move { problem.gradient(&param).await }))
+    }
+
+    fn bulk_gradient<P>(&self, params: &[P]) -> Result<Vec<Self::Gradient>, Error>
+    where
+        P: std::borrow::Borrow<Self::Param> + SyncAlias,
+        Self::Gradient: SendAlias,
+        Self: SyncAlias,
+    {
+        let params: Vec<O::Param> = params.iter().map(|p| p.borrow().clone()).collect();
+        self.request(move |problem| Box::pin(async move { problem.bulk_gradient(&params).await }))
+    }
+}
+
+/// Deferred configuration of the [`Executor`] running on the solver thread
+type Setup<O, S, I> =
+    Box<dyn FnOnce(Executor<AsyncBridge<O>, S, I>) -> Executor<AsyncBridge<O>, S, I> + Send>;
+
+/// Solves an optimization problem with an asynchronous cost function and/or gradient
+///
+/// See the [module documentation](`crate::core::asynchronous`) for details.
+pub struct AsyncExecutor<O, S, I> {
+    /// Problem
+    problem: AsyncProblem<O>,
+    /// Solver
+    solver: S,
+    /// State
+    state: I,
+    /// Configuration of the `Executor` running on the solver thread
+    setup: Vec<Setup<O, S, I>>,
+}
+
+impl<O, S, I> AsyncExecutor<O, S, I>
+where
+    O: Send + Sync + 'static,
+    S: Solver<AsyncBridge<O>, I> + Send + 'static,
+    I: State + SerializeAlias + DeserializeOwnedAlias + Send + 'static,
+{
+    /// Constructs an `AsyncExecutor` from a user defined problem and a solver.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::asynchronous::AsyncExecutor;
+    /// # use argmin::core::test_utils::{TestSolver, TestProblem};
+    /// let executor = AsyncExecutor::new(TestProblem::new(), TestSolver::new());
+    /// ```
+    pub fn new(problem: O, solver: S) -> Self {
+        AsyncExecutor {
+            problem: AsyncProblem::new(problem),
+            solver,
+            state: I::new(),
+            setup: vec![],
+        }
+    }
+
+    /// Gives access to the internal state of the solver before running the `AsyncExecutor`
+    /// (see [`Executor::configure`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::asynchronous::AsyncExecutor;
+    /// # use argmin::core::test_utils::{TestSolver, TestProblem};
+    /// let executor = AsyncExecutor::new(TestProblem::new(), TestSolver::new())
+    ///     .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(10));
+    /// ```
+    #[must_use]
+    pub fn configure<F: FnOnce(I) -> I>(mut self, init: F) -> Self {
+        self.state = init(self.state);
+        self
+    }
+
+    /// Set the maximum number of function evaluations in flight in bulk requests
+    ///
+    /// Defaults to `8`. Must be larger than 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::asynchronous::AsyncExecutor;
+    /// # use argmin::core::test_utils::{TestSolver, TestProblem};
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// let executor =
+    ///     AsyncExecutor::new(TestProblem::new(), TestSolver::new()).max_in_flight(32)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn max_in_flight(mut self, max_in_flight: usize) -> Result<Self, Error> {
+        self.problem = self.problem.with_max_in_flight(max_in_flight)?;
+        Ok(self)
+    }
+
+    /// Adds an observer (see [`Executor::add_observer`]). In contrast to `Executor`, the observer
+    /// needs to be `Send` because it is called from the solver thread.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::asynchronous::AsyncExecutor;
+    /// # use argmin::core::observers::ObserverMode;
+    /// # use argmin::core::test_utils::{TestSolver, TestProblem};
+    /// # #[cfg(feature = "slog-logger")]
+    /// # use argmin::core::observers::SlogLogger;
+    /// # #[cfg(feature = "slog-logger")]
+    /// let executor = AsyncExecutor::new(TestProblem::new(), TestSolver::new())
+    ///     .add_observer(SlogLogger::term(), ObserverMode::Always);
+    /// ```
+    #[must_use]
+    pub fn add_observer<OBS: Observe<I> + Send + 'static>(
+        mut self,
+        observer: OBS,
+        mode: ObserverMode,
+    ) -> Self {
+        self.setup.push(Box::new(move |executor| {
+            executor.add_observer(observer, mode)
+        }));
+        self
+    }
+
+    /// Configures checkpointing (see [`Executor::checkpointing`]). In contrast to `Executor`, the
+    /// checkpoint needs to be `Send` because it is used on the solver thread.
+    #[must_use]
+    pub fn checkpointing<C: Checkpoint<S, I> + Send + 'static>(mut self, checkpoint: C) -> Self {
+        self.setup
+            .push(Box::new(move |executor| executor.checkpointing(checkpoint)));
+        self
+    }
+
+    /// Enables or disables CTRL-C handling (see [`Executor::ctrlc`]).
+    #[must_use]
+    pub fn ctrlc(mut self, ctrlc: bool) -> Self {
+        self.setup
+            .push(Box::new(move |executor| executor.ctrlc(ctrlc)));
+        self
+    }
+
+    /// Enables or disables timing of individual iterations (see [`Executor::timer`]).
+    #[must_use]
+    pub fn timer(mut self, timer: bool) -> Self {
+        self.setup
+            .push(Box::new(move |executor| executor.timer(timer)));
+        self
+    }
+
+    /// Runs the solver on a dedicated thread and awaits all function evaluations it requests.
+    ///
+    /// The returned future can be polled by any async runtime. The function evaluation counts of
+    /// the returned problem are those observed by the solver. If the future is dropped before it
+    /// completes, all further function evaluations of the solver fail and the solver thread
+    /// terminates.
+    pub async fn run(self) -> Result<OptimizationResult<O, S, I>, Error> {
+        let AsyncExecutor {
+            mut problem,
+            solver,
+            state,
+            setup,
+        } = self;
+        let channel: SharedChannel<O> = Arc::new(Mutex::new(Channel {
+            jobs: VecDeque::new(),
+            waker: None,
+            solver_done: false,
+            closed: false,
+        }));
+        let _guard = ChannelGuard {
+            channel: Arc::clone(&channel),
+            solver: false,
+        };
+
+        let bridge = AsyncBridge {
+            channel: Arc::clone(&channel),
+        };
+        let solver_guard = ChannelGuard {
+            channel: Arc::clone(&channel),
+            solver: true,
+        };
+        let handle = std::thread::spawn(move || {
+            let _guard = solver_guard;
+            setup
+                .into_iter()
+                .fold(
+                    Executor::new(bridge, solver).configure(|_| state),
+                    |executor, setup| setup(executor),
+                )
+                .run()
+        });
+
+        while let Some(job) = (NextJob { channel: &channel }).await {
+            job(&mut problem).await;
+        }
+
+        let OptimizationResult {
+            problem: bridge,
+            solver,
+            state,
+        } = handle.join().map_err(|_| -> Error {
+            argmin_error!(PotentialBug, "`AsyncExecutor`: Solver thread panicked.")
+        })??;
+        let problem = Problem {
+            problem: problem.problem.take(),
+            counts: bridge.counts,
+        };
+        Ok(OptimizationResult::new(problem, solver, state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_utils::block_on;
+    use crate::core::{ArgminError, IterState, PopulationState};
+    use crate::solver::linesearch::MoreThuenteLineSearch;
+    use crate::solver::particleswarm::ParticleSwarm;
+    use crate::solver::quasinewton::LBFGS;
+    use approx::assert_relative_eq;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// Shared slot for the answer of the mock server and the waker of the pending response
+    type Slot = Arc<Mutex<(Option<Vec<f64>>, Option<Waker>)>>;
+
+    /// Response of the mock server which completes once the server answered
+    struct Response {
+        slot: Slot,
+    }
+
+    impl Future for Response {
+        type Output = Vec<f64>;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let mut slot = self.slot.lock().unwrap();
+            match slot.0.take() {
+                Some(value) => Poll::Ready(value),
+                None => {
+                    slot.1 = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        }
+    }
+
+    type Request = (bool, Vec<f64>, Slot);
+
+    /// Local mock of a simulation service: Answers each request on its own thread after a short
+    /// delay with the value (`true`) or the gradient (`false`) of the sphere function.
+    struct MockServer {
+        sender: Mutex<mpsc::Sender<Request>>,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+        fail: bool,
+    }
+
+    impl MockServer {
+        fn new() -> Self {
+            let (sender, receiver) = mpsc::channel::<Request>();
+            std::thread::spawn(move || {
+                for (value, param, slot) in receiver {
+                    std::thread::spawn(move || {
+                        std::thread::sleep(Duration::from_millis(1));
+                        let response = if value {
+                            vec![param.iter().map(|x| x.powi(2)).sum()]
+                        } else {
+                            param.iter().map(|x| 2.0 * x).collect()
+                        };
+                        let mut slot = slot.lock().unwrap();
+                        slot.0 = Some(response);
+                        if let Some(waker) = slot.1.take() {
+                            waker.wake();
+                        }
+                    });
+                }
+            });
+            MockServer {
+                sender: Mutex::new(sender),
+                in_flight: AtomicUsize::new(0),
+                max_in_flight: AtomicUsize::new(0),
+                fail: false,
+            }
+        }
+
+        async fn call(&self, value: bool, param: &[f64]) -> Result<Vec<f64>, Error> {
+            if self.fail {
+                return Err(Error::msg("service unavailable"));
+            }
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            let slot = Arc::new(Mutex::new((None, None)));
+            self.sender
+                .lock()
+                .unwrap()
+                .send((value, param.to_vec(), Arc::clone(&slot)))
+                .unwrap();
+            let response = Response { slot }.await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(response)
+        }
+    }
+
+    impl AsyncCostFunction for MockServer {
+        type Param = Vec<f64>;
+        type Output = f64;
+
+        fn cost<'a>(&'a self, p: &'a Self::Param) -> BoxFuture<'a, Result<Self::Output, Error>> {
+            Box::pin(async move { Ok(self.call(true, p).await?[0]) })
+        }
+    }
+
+    impl AsyncGradient for MockServer {
+        type Param = Vec<f64>;
+        type Gradient = Vec<f64>;
+
+        fn gradient<'a>(
+            &'a self,
+            p: &'a Self::Param,
+        ) -> BoxFuture<'a, Result<Self::Gradient, Error>> {
+            Box::pin(self.call(false, p))
+        }
+    }
+
+    #[test]
+    fn test_default() {
+        let problem: AsyncProblem<MockServer> = AsyncProblem::default();
+        assert!(problem.problem.is_none());
+        assert!(problem.counts.is_empty());
+        assert_eq!(problem.max_in_flight, 8);
+    }
+
+    #[test]
+    fn test_max_in_flight() {
+        let problem = AsyncProblem::new(MockServer::new());
+        assert_eq!(problem.max_in_flight, 8);
+        let problem = problem.with_max_in_flight(3).unwrap();
+        assert_eq!(problem.max_in_flight, 3);
+        assert_error!(
+            AsyncProblem::new(MockServer::new()).with_max_in_flight(0),
+            ArgminError,
+            "Invalid parameter: \"`AsyncProblem`: max in flight must be > 0.\""
+        );
+    }
+
+    #[test]
+    fn test_cost_gradient() {
+        let mut problem = AsyncProblem::new(MockServer::new());
+        let cost = block_on(problem.cost(&vec![1.0, 2.0])).unwrap();
+        let gradient = block_on(problem.gradient(&vec![1.0, 2.0])).unwrap();
+        assert_relative_eq!(cost, 5.0);
+        assert_eq!(gradient, vec![2.0, 4.0]);
+        assert_eq!(problem.counts["cost_count"], 1);
+        assert_eq!(problem.counts["gradient_count"], 1);
+    }
+
+    #[test]
+    fn test_bulk_concurrency() {
+        let mut problem = AsyncProblem::new(MockServer::new())
+            .with_max_in_flight(3)
+            .unwrap();
+        let params: Vec<Vec<f64>> = (0..10).map(|i| vec![f64::from(i), 1.0]).collect();
+        let costs = block_on(problem.bulk_cost(&params)).unwrap();
+        for (i, c) in costs.iter().enumerate() {
+            assert_relative_eq!(*c, (i * i) as f64 + 1.0);
+        }
+        let gradients = block_on(problem.bulk_gradient(&params)).unwrap();
+        for (i, g) in gradients.iter().enumerate() {
+            assert_eq!(*g, vec![2.0 * i as f64, 2.0]);
+        }
+        let server = problem.problem.as_ref().unwrap();
+        assert_eq!(server.max_in_flight.load(Ordering::SeqCst), 3);
+        assert_eq!(server.in_flight.load(Ordering::SeqCst), 0);
+        assert_eq!(problem.counts["cost_count"], 10);
+        assert_eq!(problem.counts["gradient_count"], 10);
+    }
+
+    #[test]
+    fn test_bulk_error() {
+        let mut server = MockServer::new();
+        server.fail = true;
+        let mut problem = AsyncProblem::new(server);
+        let res = block_on(problem.bulk_cost(&[vec![1.0], vec![2.0]]));
+        assert_eq!(res.unwrap_err().to_string(), "service unavailable");
+    }
+
+    #[test]
+    fn test_executor_gradient() {
+        let solver = LBFGS::new(MoreThuenteLineSearch::new(), 7);
+        let res = block_on(
+            AsyncExecutor::new(MockServer::new(), solver)
+                .configure(|state| state.param(vec![1.0, -2.0]).max_iters(10))
+                .run(),
+        )
+        .unwrap();
+        let state: IterState<Vec<f64>, Vec<f64>, (), (), f64> = res.state;
+        assert!(state.get_best_cost() < 1e-8);
+        assert!(res.problem.counts["cost_count"] > 0);
+        assert!(res.problem.counts["gradient_count"] > 0);
+        assert!(res.problem.problem.is_some());
+    }
+
+    #[test]
+    fn test_executor_bulk() {
+        let solver = ParticleSwarm::new((vec![-1.0, -1.0], vec![1.0, 1.0]), 20);
+        let res = block_on(
+            AsyncExecutor::new(MockServer::new(), solver)
+                .configure(|state| state.max_iters(5))
+                .max_in_flight(4)
+                .unwrap()
+                .run(),
+        )
+        .unwrap();
+        let state: PopulationState<_, f64> = res.state;
+        assert_eq!(state.get_iter(), 5);
+        // Initial population plus one bulk evaluation per iteration
+        assert_eq!(res.problem.counts["cost_count"], 6 * 20);
+        let server = res.problem.problem.unwrap();
+        assert_eq!(server.max_in_flight.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_executor_error() {
+        let mut server = MockServer::new();
+        server.fail = true;
+        let solver = LBFGS::new(MoreThuenteLineSearch::new(), 7);
+        type State = IterState<Vec<f64>, Vec<f64>, (), (), f64>;
+        let res: Result<OptimizationResult<_, _, State>, _> = block_on(
+            AsyncExecutor::new(server, solver)
+                .configure(|state| state.param(vec![1.0, -2.0]).max_iters(10))
+                .run(),
+        );
+        assert_eq!(res.err().unwrap().to_string(), "service unavailable");
+    }
+}
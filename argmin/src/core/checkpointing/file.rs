@@ -5,22 +5,62 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use crate::core::checkpointing::{Checkpoint, CheckpointingFrequency};
+use crate::core::checkpointing::{
+    Checkpoint, CheckpointFormat, CheckpointSerializer, CheckpointingFrequency, Snapshots,
+};
 use crate::core::{DeserializeOwnedAlias, Error, SerializeAlias};
 use std::default::Default;
 use std::fs::File;
-use std::io::{BufReader, BufWriter};
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
-/// Handles saving a checkpoint to disk as a binary file.
+/// Identifies files written by `FileCheckpoint`
+const MAGIC: &[u8] = b"ARGMIN-CHECKPOINT ";
+/// Version of the checkpoint file format. Version 1 contains `(solver, state)`, version 2
+/// `(solver, state, snapshots)`.
+const FORMAT_VERSION: u32 = 2;
+/// Maximum length of the header line
+const MAX_HEADER_LEN: usize = 256;
+
+/// Handles saving a checkpoint to disk.
+///
+/// The format of the checkpoints is defined by a [`CheckpointSerializer`]. By default,
+/// [`CheckpointFormat::Bincode`] is used, which writes compact binary files. With
+/// [`with_serializer`](`FileCheckpoint::with_serializer`), [`CheckpointFormat::JSON`] or a custom
+/// serializer can be chosen instead.
+///
+/// Checkpoints are written atomically: The data is first written to a temporary file in the same
+/// directory, which is synced to disk and then renamed to the final name. A crash during saving
+/// therefore never leaves a partially written checkpoint behind.
+///
+/// Each file starts with a single header line of the form
+/// `ARGMIN-CHECKPOINT <format version> <serializer> <payload length> <CRC-32 of payload>`,
+/// followed by the serialized `(solver, state, snapshots)` tuple, where `snapshots` are the
+/// [`Snapshots`] of the problem and the observers. For JSON checkpoints, the payload can be
+/// inspected with `tail -n +2 <file>`. Loading a truncated, corrupt or incompatible checkpoint
+/// returns an [`ArgminError::InvalidCheckpoint`](`crate::core::ArgminError::InvalidCheckpoint`).
+/// Files written by earlier versions of argmin (bincode without header) can still be loaded.
+///
+/// By default only the most recent checkpoint is kept in `<directory>/<name>.<ext>`, where
+/// `<ext>` is the [`extension`](`CheckpointSerializer::extension`) of the serializer (`arg` for
+/// bincode, `json` for JSON). With [`with_keep_last`](`FileCheckpoint::with_keep_last`) the last N
+/// checkpoints are kept instead, named `<directory>/<name>.<iter>.<ext>` where `<iter>` is the
+/// iteration number. Together with [`with_fallback`](`FileCheckpoint::with_fallback`), loading
+/// falls back to the previous good checkpoint if the most recent one is invalid.
 #[derive(Clone, Eq, PartialEq, Debug, Hash)]
-pub struct FileCheckpoint {
+pub struct FileCheckpoint<Z = CheckpointFormat> {
     /// Indicates how often a checkpoint is created
     pub frequency: CheckpointingFrequency,
     /// Directory where the checkpoints are saved to
     pub directory: PathBuf,
     /// Name of the checkpoint files
     pub filename: PathBuf,
+    /// Number of checkpoints to keep
+    pub keep_last: usize,
+    /// Whether to fall back to older checkpoints if the most recent one is invalid
+    pub fallback: bool,
+    /// Serialization format of the checkpoints
+    pub serializer: Z,
 }
 
 impl Default for FileCheckpoint {
@@ -32,19 +72,25 @@ impl Default for FileCheckpoint {
     ///
     /// ```
     /// use argmin::core::checkpointing::FileCheckpoint;
-    /// # use argmin::core::checkpointing::CheckpointingFrequency;
+    /// # use argmin::core::checkpointing::{CheckpointFormat, CheckpointingFrequency};
     /// # use std::path::PathBuf;
     ///
     /// let checkpoint = FileCheckpoint::default();
     /// # assert_eq!(checkpoint.frequency, CheckpointingFrequency::default());
     /// # assert_eq!(checkpoint.directory, PathBuf::from(".checkpoints"));
     /// # assert_eq!(checkpoint.filename, PathBuf::from("checkpoint.arg"));
+    /// # assert_eq!(checkpoint.keep_last, 1);
+    /// # assert!(!checkpoint.fallback);
+    /// # assert_eq!(checkpoint.serializer, CheckpointFormat::Bincode);
     /// ```
     fn default() -> FileCheckpoint {
         FileCheckpoint {
             frequency: CheckpointingFrequency::default(),
             directory: PathBuf::from(".checkpoints"),
             filename: PathBuf::from("checkpoint.arg"),
+            keep_last: 1,
+            fallback: false,
+            serializer: CheckpointFormat::default(),
         }
     }
 }
@@ -73,55 +119,394 @@ impl FileCheckpoint {
             frequency,
             directory: PathBuf::from(directory.as_ref()),
             filename: PathBuf::from(format!("{}.arg", name.as_ref())),
+            keep_last: 1,
+            fallback: false,
+            serializer: CheckpointFormat::default(),
         }
     }
 }
 
-impl<S, I> Checkpoint<S, I> for FileCheckpoint
+impl<Z: CheckpointSerializer> FileCheckpoint<Z> {
+    /// Set the serialization format of the checkpoints.
+    ///
+    /// Either one of the formats of [`CheckpointFormat`] or a custom implementation of
+    /// [`CheckpointSerializer`]. The extension of the file name is changed to the
+    /// [`extension`](`CheckpointSerializer::extension`) of the serializer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use argmin::core::checkpointing::{CheckpointFormat, CheckpointingFrequency, FileCheckpoint};
+    /// # use std::path::PathBuf;
+    ///
+    /// // Saves human-readable checkpoints to `checkpoints/optimization.json`
+    /// let checkpoint = FileCheckpoint::new("checkpoints", "optimization", CheckpointingFrequency::Always)
+    ///     .with_serializer(CheckpointFormat::JSON);
+    /// # assert_eq!(checkpoint.filename, PathBuf::from("optimization.json"));
+    /// # assert_eq!(checkpoint.serializer, CheckpointFormat::JSON);
+    /// ```
+    #[must_use]
+    pub fn with_serializer<Z2: CheckpointSerializer>(self, serializer: Z2) -> FileCheckpoint<Z2> {
+        let filename = PathBuf::from(format!("{}.{}", self.stem(), serializer.extension()));
+        FileCheckpoint {
+            frequency: self.frequency,
+            directory: self.directory,
+            filename,
+            keep_last: self.keep_last,
+            fallback: self.fallback,
+            serializer,
+        }
+    }
+
+    /// Keep the last `n` checkpoints instead of only the most recent one.
+    ///
+    /// For `n > 1`, checkpoints are saved to `<directory>/<name>.<iter>.<ext>`, where `<iter>` is
+    /// the iteration number, and older checkpoints are removed. `n` must be at least 1.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use argmin::core::checkpointing::{FileCheckpoint, CheckpointingFrequency};
+    /// # use argmin::core::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// // Keeps `checkpoints/optimization.<iter>.arg` for the last 3 checkpoints
+    /// let checkpoint = FileCheckpoint::new("checkpoints", "optimization", CheckpointingFrequency::Every(10))
+    ///     .with_keep_last(3)?;
+    /// # assert_eq!(checkpoint.keep_last, 3);
+    /// # assert!(FileCheckpoint::default().with_keep_last(0).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_keep_last(mut self, n: usize) -> Result<Self, Error> {
+        if n < 1 {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`FileCheckpoint`: Number of checkpoints to keep must be at least 1."
+            ));
+        }
+        self.keep_last = n;
+        Ok(self)
+    }
+
+    /// Fall back to the previous good checkpoint if the most recent one is invalid.
+    ///
+    /// This only has an effect when more than one checkpoint is kept (see
+    /// [`with_keep_last`](`FileCheckpoint::with_keep_last`)). If all checkpoints are invalid, the
+    /// error of the most recent one is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use argmin::core::checkpointing::{FileCheckpoint, CheckpointingFrequency};
+    /// # use argmin::core::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let checkpoint = FileCheckpoint::new("checkpoints", "optimization", CheckpointingFrequency::Always)
+    ///     .with_keep_last(2)?
+    ///     .with_fallback(true);
+    /// # assert!(checkpoint.fallback);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_fallback(mut self, fallback: bool) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Returns the stem of the file names (`<name>` of `<name>.<ext>`)
+    fn stem(&self) -> String {
+        self.filename
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    /// Returns all rotated checkpoints together with their iteration numbers, oldest first
+    fn rotated(&self) -> Result<Vec<(u64, PathBuf)>, Error> {
+        if !self.directory.exists() {
+            return Ok(vec![]);
+        }
+        let prefix = format!("{}.", self.stem());
+        let suffix = format!(".{}", self.serializer.extension());
+        let mut checkpoints = vec![];
+        for entry in std::fs::read_dir(&self.directory)? {
+            let path = entry?.path();
+            let iter = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix(&prefix))
+                .and_then(|name| name.strip_suffix(&suffix))
+                .and_then(|iter| iter.parse::<u64>().ok());
+            if let Some(iter) = iter {
+                checkpoints.push((iter, path));
+            }
+        }
+        checkpoints.sort();
+        Ok(checkpoints)
+    }
+
+    /// Writes a checkpoint for iteration `iter` (or following the most recent one if `None`)
+    fn write<S: SerializeAlias, I: SerializeAlias>(
+        &self,
+        solver: &S,
+        state: &I,
+        snapshots: &Snapshots,
+        iter: Option<u64>,
+    ) -> Result<(), Error> {
+        let name = self.serializer.name();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                format!(
+                    "`FileCheckpoint`: Serializer name `{name}` must not be empty or contain whitespace."
+                )
+            ));
+        }
+        if !self.directory.exists() {
+            std::fs::create_dir_all(&self.directory)?
+        }
+        let path = if self.keep_last > 1 {
+            let iter = match iter {
+                Some(iter) => iter,
+                None => self.rotated()?.last().map_or(0, |(iter, _)| iter + 1),
+            };
+            self.directory.join(format!(
+                "{}.{}.{}",
+                self.stem(),
+                iter,
+                self.serializer.extension()
+            ))
+        } else {
+            self.directory.join(&self.filename)
+        };
+        let payload = self.serializer.serialize(&(solver, state, snapshots))?;
+        write_atomic(&path, &encode(&payload, name))?;
+
+        if self.keep_last > 1 {
+            let checkpoints = self.rotated()?;
+            let num_old = checkpoints.len().saturating_sub(self.keep_last);
+            for (_, old) in checkpoints.into_iter().take(num_old) {
+                std::fs::remove_file(old)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads a single checkpoint file
+    fn read<S: DeserializeOwnedAlias, I: DeserializeOwnedAlias>(
+        &self,
+        path: &Path,
+    ) -> Result<(S, I, Snapshots), Error> {
+        let data = std::fs::read(path)?;
+        let (version, payload) = if data.starts_with(MAGIC) {
+            decode(&data, path, self.serializer.name())?
+        } else {
+            // Checkpoints written before the header was introduced
+            (0, &data[..])
+        };
+        let legacy = version == 0;
+        let checkpoint = if version < 2 {
+            self.serializer
+                .deserialize(payload)
+                .map(|(solver, state)| (solver, state, Snapshots::default()))
+        } else {
+            self.serializer.deserialize(payload)
+        };
+        checkpoint.map_err(|e| {
+            let reason = if legacy {
+                "has no valid header and cannot be read as a checkpoint without header"
+            } else {
+                "cannot be deserialized (incompatible solver or state)"
+            };
+            argmin_error!(
+                InvalidCheckpoint,
+                format!("`{}` {}: {}.", path.display(), reason, e)
+            )
+        })
+    }
+}
+
+/// Writes `data` to a temporary file, syncs it to disk and renames it to `path`
+fn write_atomic(path: &Path, data: &[u8]) -> Result<(), Error> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    {
+        let mut file = File::create(&tmp)?;
+        file.write_all(data)?;
+        file.sync_all()?;
+    }
+    std::fs::rename(&tmp, path)?;
+    // Persist the rename itself. Directories cannot be opened as files on all platforms.
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Prepends the header line to `payload` written by the serializer `name`
+fn encode(payload: &[u8], name: &str) -> Vec<u8> {
+    let header = format!(
+        "{} {} {} {:08x}\n",
+        FORMAT_VERSION,
+        name,
+        payload.len(),
+        crc32(payload)
+    );
+    let mut data = Vec::with_capacity(MAGIC.len() + header.len() + payload.len());
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(header.as_bytes());
+    data.extend_from_slice(payload);
+    data
+}
+
+/// Checks the header of `data` against the serializer `name` and returns the format version and
+/// the payload
+fn decode<'a>(data: &'a [u8], path: &Path, name: &str) -> Result<(u32, &'a [u8]), Error> {
+    let invalid = |reason: String| -> Error {
+        argmin_error!(
+            InvalidCheckpoint,
+            format!("`{}` {}.", path.display(), reason)
+        )
+    };
+    let header_len = match data.iter().take(MAX_HEADER_LEN).position(|&b| b == b'\n') {
+        Some(pos) => pos + 1,
+        None if data.len() < MAX_HEADER_LEN => return Err(invalid("is truncated".to_string())),
+        None => return Err(invalid("has an invalid header".to_string())),
+    };
+    let header = std::str::from_utf8(&data[MAGIC.len()..header_len - 1])
+        .map_err(|_| invalid("has an invalid header".to_string()))?;
+    let fields: Vec<&str> = header.split(' ').collect();
+    let version = match fields[0].parse::<u32>() {
+        Ok(version @ 1..=FORMAT_VERSION) => version,
+        Ok(version) => {
+            return Err(invalid(format!(
+            "has format version {version}, but only versions 1 to {FORMAT_VERSION} are supported"
+        )))
+        }
+        Err(_) => return Err(invalid("has an invalid header".to_string())),
+    };
+    let (written_by, len, checksum) = match fields[..] {
+        [_, written_by, len, checksum] => {
+            match (len.parse::<usize>(), u32::from_str_radix(checksum, 16)) {
+                (Ok(len), Ok(checksum)) => (written_by, len, checksum),
+                _ => return Err(invalid("has an invalid header".to_string())),
+            }
+        }
+        _ => return Err(invalid("has an invalid header".to_string())),
+    };
+    if written_by != name {
+        return Err(invalid(format!(
+            "was written with serializer `{written_by}`, but serializer `{name}` is used"
+        )));
+    }
+    let payload = &data[header_len..];
+    if payload.len() != len {
+        return Err(invalid(format!(
+            "is truncated (expected {len} bytes of data, found {})",
+            payload.len()
+        )));
+    }
+    if crc32(payload) != checksum {
+        return Err(invalid("is corrupt (checksum mismatch)".to_string()));
+    }
+    Ok((version, payload))
+}
+
+/// Lookup table of the CRC-32 (IEEE 802.3) checksum
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Computes the CRC-32 (IEEE 802.3) checksum of `data`
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &byte| {
+        CRC32_TABLE[((crc ^ u32::from(byte)) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+impl<S, I, Z> Checkpoint<S, I> for FileCheckpoint<Z>
 where
     S: SerializeAlias + DeserializeOwnedAlias,
     I: SerializeAlias + DeserializeOwnedAlias,
+    Z: CheckpointSerializer,
 {
     /// Writes checkpoint to disk.
     ///
-    /// If the directory does not exist already, it will be created. It uses `bincode` to serialize
-    /// the data.
+    /// If the directory does not exist already, it will be created. The data is serialized with
+    /// the configured [`CheckpointSerializer`] (`bincode` by default).
     /// It will return an error if creating the directory or file or serialization failed.
     ///
+    /// When more than one checkpoint is kept, the checkpoint is numbered following the most
+    /// recent one. The [`Executor`](`crate::core::Executor`) calls
+    /// [`save_iter`](`Checkpoint::save_iter`) instead, which uses the iteration number.
+    ///
     /// # Example
     ///
     /// ```
     /// use argmin::core::checkpointing::{FileCheckpoint, CheckpointingFrequency, Checkpoint};
     ///
-    /// # use std::fs::File;
-    /// # use std::io::BufReader;
     /// # let checkpoint = FileCheckpoint::new(".checkpoints", "save_test" , CheckpointingFrequency::Always);
     /// # let solver: u64 = 12;
     /// # let state: u64 = 21;
     /// # let _ = std::fs::remove_file(".checkpoints/save_test.arg");
     /// checkpoint.save(&solver, &state);
-    /// # let (f_solver, f_state): (u64, u64) = bincode::deserialize_from(
-    /// #     BufReader::new(File::open(".checkpoints/save_test.arg").unwrap())
-    /// # ).unwrap();
+    /// # let (f_solver, f_state): (u64, u64) = checkpoint.load().unwrap().unwrap();
     /// # assert_eq!(solver, f_solver);
     /// # assert_eq!(state, f_state);
     /// # let _ = std::fs::remove_file(".checkpoints/save_test.arg");
     /// ```
     fn save(&self, solver: &S, state: &I) -> Result<(), Error> {
-        if !self.directory.exists() {
-            std::fs::create_dir_all(&self.directory)?
-        }
-        let fname = self.directory.join(&self.filename);
-        let f = BufWriter::new(File::create(fname)?);
-        bincode::serialize_into(f, &(solver, state))?;
-        Ok(())
+        self.write(solver, state, &Snapshots::default(), None)
+    }
+
+    /// Saves a checkpoint of iteration `iter`.
+    ///
+    /// When more than one checkpoint is kept, the iteration number is part of the file name.
+    fn save_iter(&self, solver: &S, state: &I, iter: u64) -> Result<(), Error> {
+        self.write(solver, state, &Snapshots::default(), Some(iter))
+    }
+
+    /// Saves a checkpoint of iteration `iter` including the snapshots of the problem and the
+    /// observers.
+    fn save_with_snapshots(
+        &self,
+        solver: &S,
+        state: &I,
+        snapshots: &Snapshots,
+        iter: u64,
+    ) -> Result<(), Error> {
+        self.write(solver, state, snapshots, Some(iter))
     }
 
     /// Load a checkpoint from disk.
     ///
+    /// If more than one checkpoint is kept, the most recent one is loaded. If it is invalid and
+    /// `fallback` is set, the previous ones are tried in turn.
     ///
     /// If there is no checkpoint on disk, it will return `Ok(None)`.
-    /// Returns an error if opening the file or deserialization failed.
+    /// Returns an error if opening the file or deserialization failed, or an
+    /// [`ArgminError::InvalidCheckpoint`](`crate::core::ArgminError::InvalidCheckpoint`) if the
+    /// checkpoint is truncated, corrupt or incompatible.
     ///
     /// # Example
     ///
@@ -132,6 +517,7 @@ where
     /// # use std::fs::File;
     /// # use std::io::BufWriter;
     /// # fn main() -> Result<(), Error> {
+    /// # std::fs::create_dir_all(".checkpoints")?;
     /// # let f = BufWriter::new(File::create(".checkpoints/load_test.arg")?);
     /// # let f_solver: u64 = 12;
     /// # let f_state: u64 = 21;
@@ -153,13 +539,45 @@ where
     /// # }
     /// ```
     fn load(&self) -> Result<Option<(S, I)>, Error> {
-        let path = &self.directory.join(&self.filename);
-        if !path.exists() {
-            return Ok(None);
+        Ok(self
+            .load_with_snapshots()?
+            .map(|(solver, state, _)| (solver, state)))
+    }
+
+    /// Load a checkpoint including the snapshots of the problem and the observers from disk.
+    ///
+    /// Checkpoints written by earlier versions of argmin contain no snapshots, in which case empty
+    /// snapshots are returned.
+    fn load_with_snapshots(&self) -> Result<Option<(S, I, Snapshots)>, Error> {
+        let candidates: Vec<PathBuf> = if self.keep_last > 1 {
+            self.rotated()?
+                .into_iter()
+                .rev()
+                .map(|(_, path)| path)
+                .collect()
+        } else {
+            let path = self.directory.join(&self.filename);
+            if path.exists() {
+                vec![path]
+            } else {
+                vec![]
+            }
+        };
+
+        let mut first_error = None;
+        for path in candidates {
+            match self.read(&path) {
+                Ok(checkpoint) => return Ok(Some(checkpoint)),
+                Err(e) if self.fallback => {
+                    first_error.get_or_insert(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(None),
         }
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
-        Ok(Some(bincode::deserialize_from(reader)?))
     }
 
     /// Returns the how often a checkpoint is to be saved.
@@ -174,8 +592,11 @@ where
 #[cfg(feature = "serde1")]
 mod tests {
     use super::*;
+    use crate::core::checkpointing::StateSnapshot;
     use crate::core::test_utils::TestSolver;
-    use crate::core::{IterState, State};
+    use crate::core::{ArgminError, IterState, State};
+
+    type TState = IterState<Vec<f64>, (), (), (), f64>;
 
     #[test]
     #[allow(clippy::type_complexity)]
@@ -188,4 +609,381 @@ mod tests {
         let _loaded: Option<(TestSolver, IterState<Vec<f64>, (), (), (), f64>)> =
             check.load().unwrap();
     }
+
+    /// Returns a fresh checkpoint directory in the temporary directory
+    fn directory(name: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("argmin_test_checkpoint_{name}"));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_atomic_write() {
+        let dir = directory("atomic");
+        let check = FileCheckpoint::new(dir.as_str(), "solver", CheckpointingFrequency::Always);
+        let state: TState = IterState::new().param(vec![1.0f64, 0.0]);
+        Checkpoint::<TestSolver, TState>::save(&check, &TestSolver::new(), &state).unwrap();
+
+        let files: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        assert_eq!(files, vec!["solver.arg"]);
+        let data = std::fs::read(check.directory.join("solver.arg")).unwrap();
+        assert!(data.starts_with(MAGIC));
+
+        let (_, loaded): (TestSolver, TState) = check.load().unwrap().unwrap();
+        assert_eq!(loaded.get_param(), Some(&vec![1.0f64, 0.0]));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_invalid() {
+        let dir = directory("invalid");
+        let check = FileCheckpoint::new(dir.as_str(), "solver", CheckpointingFrequency::Always);
+        let path = check.directory.join("solver.arg");
+        let state: TState = IterState::new();
+        Checkpoint::<TestSolver, TState>::save(&check, &TestSolver::new(), &state).unwrap();
+        let data = std::fs::read(&path).unwrap();
+        let load = || -> Result<Option<(TestSolver, TState)>, Error> { check.load() };
+        let name = path.display();
+
+        let header_len = data.iter().position(|&b| b == b'\n').unwrap() + 1;
+
+        // Truncated
+        std::fs::write(&path, &data[..data.len() - 1]).unwrap();
+        assert_error!(
+            load(),
+            ArgminError,
+            format!(
+                "Invalid checkpoint: \"`{name}` is truncated (expected {} bytes of data, found {}).\"",
+                data.len() - header_len,
+                data.len() - header_len - 1
+            )
+        );
+
+        // Corrupt
+        let mut corrupt = data.clone();
+        *corrupt.last_mut().unwrap() ^= 0xFF;
+        std::fs::write(&path, &corrupt).unwrap();
+        assert_error!(
+            load(),
+            ArgminError,
+            format!("Invalid checkpoint: \"`{name}` is corrupt (checksum mismatch).\"")
+        );
+
+        // Unsupported version
+        let mut version = data.clone();
+        version[MAGIC.len()] = b'3';
+        std::fs::write(&path, &version).unwrap();
+        assert_error!(
+            load(),
+            ArgminError,
+            format!(
+                "Invalid checkpoint: \"`{name}` has format version 3, but only versions 1 to 2 are supported.\""
+            )
+        );
+
+        // Invalid header
+        let mut header = MAGIC.to_vec();
+        header.extend_from_slice(b"1 bincode\n");
+        std::fs::write(&path, &header).unwrap();
+        assert_error!(
+            load(),
+            ArgminError,
+            format!("Invalid checkpoint: \"`{name}` has an invalid header.\"")
+        );
+
+        // Written with a different serializer
+        let json = check.clone().with_serializer(CheckpointFormat::JSON);
+        Checkpoint::<TestSolver, TState>::save(&json, &TestSolver::new(), &state).unwrap();
+        std::fs::copy(json.directory.join("solver.json"), &path).unwrap();
+        assert_error!(
+            load(),
+            ArgminError,
+            format!(
+                "Invalid checkpoint: \"`{name}` was written with serializer `json`, but serializer `bincode` is used.\""
+            )
+        );
+
+        // Neither a checkpoint with header nor a legacy checkpoint
+        std::fs::write(&path, b"garbage").unwrap();
+        let err = load().unwrap_err().downcast::<ArgminError>().unwrap();
+        assert!(matches!(err, ArgminError::InvalidCheckpoint { .. }));
+
+        // Checkpoint of format version 1 without snapshots
+        let state: TState = IterState::new().param(vec![2.0]);
+        let payload = bincode::serialize(&(TestSolver::new(), &state)).unwrap();
+        let mut version_1 = MAGIC.to_vec();
+        version_1.extend_from_slice(
+            format!("1 bincode {} {:08x}\n", payload.len(), crc32(&payload)).as_bytes(),
+        );
+        version_1.extend_from_slice(&payload);
+        std::fs::write(&path, &version_1).unwrap();
+        let (_, loaded, snapshots): (TestSolver, TState, _) =
+            check.load_with_snapshots().unwrap().unwrap();
+        assert_eq!(loaded.get_param(), Some(&vec![2.0]));
+        assert_eq!(snapshots, Snapshots::default());
+
+        // Legacy checkpoint without header
+        let state: TState = IterState::new().param(vec![3.0]);
+        std::fs::write(
+            &path,
+            bincode::serialize(&(TestSolver::new(), &state)).unwrap(),
+        )
+        .unwrap();
+        let (_, loaded) = load().unwrap().unwrap();
+        assert_eq!(loaded.get_param(), Some(&vec![3.0]));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_keep_last() {
+        assert_error!(
+            FileCheckpoint::default().with_keep_last(0),
+            ArgminError,
+            "Invalid parameter: \"`FileCheckpoint`: Number of checkpoints to keep must be at least 1.\""
+        );
+
+        let dir = directory("keep_last");
+        let check = FileCheckpoint::new(dir.as_str(), "solver", CheckpointingFrequency::Every(2))
+            .with_keep_last(2)
+            .unwrap();
+        for iter in 0..7 {
+            let state: TState = IterState::new().param(vec![iter as f64]);
+            check.save_cond(&TestSolver::new(), &state, iter).unwrap();
+        }
+        let mut files: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        assert_eq!(files, vec!["solver.4.arg", "solver.6.arg"]);
+
+        let (_, loaded): (TestSolver, TState) = check.load().unwrap().unwrap();
+        assert_eq!(loaded.get_param(), Some(&vec![6.0]));
+
+        // `save` continues the numbering
+        Checkpoint::<TestSolver, TState>::save(&check, &TestSolver::new(), &IterState::new())
+            .unwrap();
+        assert!(check.directory.join("solver.7.arg").exists());
+        assert!(!check.directory.join("solver.4.arg").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_fallback() {
+        let dir = directory("fallback");
+        let check = FileCheckpoint::new(dir.as_str(), "solver", CheckpointingFrequency::Always)
+            .with_keep_last(3)
+            .unwrap();
+        for iter in 1..=3 {
+            let state: TState = IterState::new().param(vec![iter as f64]);
+            check.save_cond(&TestSolver::new(), &state, iter).unwrap();
+        }
+        std::fs::write(check.directory.join("solver.3.arg"), MAGIC).unwrap();
+
+        // Without fallback, the invalid checkpoint results in an error
+        let loaded: Result<Option<(TestSolver, TState)>, Error> = check.load();
+        let err = loaded.unwrap_err().downcast::<ArgminError>().unwrap();
+        assert!(matches!(err, ArgminError::InvalidCheckpoint { .. }));
+
+        // With fallback, the previous checkpoint is loaded
+        let check = check.with_fallback(true);
+        let (_, loaded): (TestSolver, TState) = check.load().unwrap().unwrap();
+        assert_eq!(loaded.get_param(), Some(&vec![2.0]));
+
+        // If all checkpoints are invalid, the error of the most recent one is returned
+        for iter in 1..=2 {
+            std::fs::write(check.directory.join(format!("solver.{iter}.arg")), b"").unwrap();
+        }
+        let loaded: Result<Option<(TestSolver, TState)>, Error> = check.load();
+        assert_error!(
+            loaded,
+            ArgminError,
+            format!(
+                "Invalid checkpoint: \"`{}` is truncated.\"",
+                check.directory.join("solver.3.arg").display()
+            )
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_json() {
+        let dir = directory("json");
+        let check = FileCheckpoint::new(dir.as_str(), "solver", CheckpointingFrequency::Always)
+            .with_keep_last(2)
+            .unwrap()
+            .with_serializer(CheckpointFormat::JSON);
+        assert_eq!(check.filename, PathBuf::from("solver.json"));
+        assert_eq!(check.keep_last, 2);
+        for iter in 1..=3 {
+            let state: TState = IterState::new().param(vec![iter as f64]);
+            check.save_cond(&TestSolver::new(), &state, iter).unwrap();
+        }
+        let mut files: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        assert_eq!(files, vec!["solver.2.json", "solver.3.json"]);
+
+        // The payload is plain JSON following the header line
+        let data = std::fs::read_to_string(check.directory.join("solver.3.json")).unwrap();
+        let (header, payload) = data.split_once('\n').unwrap();
+        assert!(header.starts_with("ARGMIN-CHECKPOINT 2 json "));
+        let value: serde_json::Value = serde_json::from_str(payload).unwrap();
+        assert_eq!(value[1]["param"], serde_json::json!([3.0]));
+        assert_eq!(value[1]["best_cost"], serde_json::json!("inf"));
+
+        let (_, loaded): (TestSolver, TState) = check.load().unwrap().unwrap();
+        assert_eq!(loaded.get_param(), Some(&vec![3.0]));
+        assert_eq!(
+            loaded.get_best_cost().to_ne_bytes(),
+            f64::INFINITY.to_ne_bytes()
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_json_lbfgs() {
+        use crate::core::{CostFunction, Executor, Gradient};
+        use crate::solver::linesearch::MoreThuenteLineSearch;
+        use crate::solver::quasinewton::LBFGS;
+        use argmin_testfunctions::{rosenbrock_2d, rosenbrock_2d_derivative};
+
+        struct Rosenbrock {}
+
+        impl CostFunction for Rosenbrock {
+            type Param = Vec<f64>;
+            type Output = f64;
+
+            fn cost(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+                Ok(rosenbrock_2d(p, 1.0, 100.0))
+            }
+        }
+
+        impl Gradient for Rosenbrock {
+            type Param = Vec<f64>;
+            type Gradient = Vec<f64>;
+
+            fn gradient(&self, p: &Self::Param) -> Result<Self::Gradient, Error> {
+                Ok(rosenbrock_2d_derivative(p, 1.0, 100.0))
+            }
+        }
+
+        type Solver =
+            LBFGS<MoreThuenteLineSearch<Vec<f64>, Vec<f64>, f64>, Vec<f64>, Vec<f64>, f64>;
+        type State = IterState<Vec<f64>, Vec<f64>, (), (), f64>;
+
+        let dir = directory("json_lbfgs");
+        let check = FileCheckpoint::new(dir.as_str(), "lbfgs", CheckpointingFrequency::Always)
+            .with_serializer(CheckpointFormat::JSON);
+        let solver: Solver = LBFGS::new(MoreThuenteLineSearch::new(), 7);
+        let res = Executor::new(Rosenbrock {}, solver)
+            .configure(|state| state.param(vec![-1.2, 1.0]).max_iters(5))
+            .checkpointing(check.clone())
+            .ctrlc(false)
+            .run()
+            .unwrap();
+
+        // The infinite upper bound of the step length of the line search is written as a string
+        let path = check.directory.join("lbfgs.json");
+        let data = std::fs::read_to_string(&path).unwrap();
+        let payload = data.split_once('\n').unwrap().1;
+        assert!(payload.contains("\"stpmax\": \"inf\""));
+
+        let (solver, state): (Solver, State) = check.load().unwrap().unwrap();
+        assert_eq!(state.get_iter(), 5);
+        assert_eq!(state.get_best_param(), res.state().get_best_param());
+        // Saving the loaded solver and state again yields the same JSON
+        let saved: serde_json::Value = serde_json::from_str(payload).unwrap();
+        let resaved: serde_json::Value =
+            serde_json::from_slice(&CheckpointFormat::JSON.serialize(&(solver, state)).unwrap())
+                .unwrap();
+        assert_eq!(resaved[0], saved[0]);
+        assert_eq!(resaved[1], saved[1]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_serializer_name() {
+        struct Named(&'static str);
+
+        impl CheckpointSerializer for Named {
+            fn name(&self) -> &str {
+                self.0
+            }
+
+            fn extension(&self) -> &str {
+                "json"
+            }
+
+            fn serialize<T: serde::Serialize>(&self, value: &T) -> Result<Vec<u8>, Error> {
+                Ok(serde_json::to_vec(value)?)
+            }
+
+            fn deserialize<T: serde::de::DeserializeOwned>(&self, data: &[u8]) -> Result<T, Error> {
+                Ok(serde_json::from_slice(data)?)
+            }
+        }
+
+        let dir = directory("serializer_name");
+        let state: TState = IterState::new().param(vec![1.0f64, 0.0]);
+        for name in ["pretty json", " json", ""] {
+            let check = FileCheckpoint::new(dir.as_str(), "solver", CheckpointingFrequency::Always)
+                .with_serializer(Named(name));
+            assert_error!(
+                Checkpoint::<TestSolver, TState>::save(&check, &TestSolver::new(), &state),
+                ArgminError,
+                format!(
+                    "Invalid parameter: \"`FileCheckpoint`: Serializer name `{name}` must not be empty or contain whitespace.\""
+                )
+            );
+        }
+        assert!(!PathBuf::from(&dir).exists());
+
+        let check = FileCheckpoint::new(dir.as_str(), "solver", CheckpointingFrequency::Always)
+            .with_serializer(Named("pretty-json"));
+        Checkpoint::<TestSolver, TState>::save(&check, &TestSolver::new(), &state).unwrap();
+        let data = std::fs::read_to_string(check.directory.join("solver.json")).unwrap();
+        assert!(data.starts_with("ARGMIN-CHECKPOINT 2 pretty-json "));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_snapshots() {
+        let dir = directory("snapshots");
+        let check = FileCheckpoint::new(dir.as_str(), "solver", CheckpointingFrequency::Always);
+        let state: TState = IterState::new().param(vec![1.0]);
+        let snapshots = Snapshots {
+            problem: Some(StateSnapshot::new(&5u64).unwrap()),
+            observers: vec![None, Some(StateSnapshot::from_bytes(vec![1, 2]))],
+        };
+        for format in [CheckpointFormat::Bincode, CheckpointFormat::JSON] {
+            let check = check.clone().with_serializer(format);
+            check
+                .save_with_snapshots(&TestSolver::new(), &state, &snapshots, 1)
+                .unwrap();
+            let (_, loaded, loaded_snapshots): (TestSolver, TState, _) =
+                check.load_with_snapshots().unwrap().unwrap();
+            assert_eq!(loaded.get_param(), Some(&vec![1.0]));
+            assert_eq!(loaded_snapshots, snapshots);
+
+            // Checkpoints without snapshots
+            Checkpoint::<TestSolver, TState>::save(&check, &TestSolver::new(), &state).unwrap();
+            let (_, _, loaded_snapshots): (TestSolver, TState, _) =
+                check.load_with_snapshots().unwrap().unwrap();
+            assert_eq!(loaded_snapshots, Snapshots::default());
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
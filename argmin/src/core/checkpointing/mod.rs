@@ -12,11 +12,21 @@
 //! with a user-chosen frequency. Optimizations can then be resumed from a given checkpoint after a
 //! crash.
 //!
-//! For saving checkpoints to disk, `FileCheckpoint` is provided.
+//! For saving checkpoints to disk, `FileCheckpoint` is provided. By default it writes compact
+//! binary files; `CheckpointFormat::JSON` writes human-readable files instead, and other formats
+//! can be added by implementing `CheckpointSerializer`.
 //! Via the `Checkpoint` trait other checkpointing approaches can be implemented.
 //!
 //! The `CheckpointingFrequency` defines how often checkpoints are saved and can be chosen to be
-//! either `Always` (every iteration), `Every(u64)` (every Nth iteration) or `Never`.
+//! either `Always` (every iteration), `Every(u64)` (every Nth iteration),
+//! `EveryDuration(Duration)` (at most once per given time span), `OnNewBest` (whenever a new best
+//! parameter vector was found) or `Never`. Unless the frequency is `Never`, a final checkpoint is
+//! saved when the optimization terminates, including when it is interrupted via Ctrl-C.
+//!
+//! Checkpoints contain the solver and the state. Problems with internal state (such as caches or
+//! random number generators) can implement `Stateful` and be included via
+//! `Executor::checkpoint_problem`, and observers can provide snapshots via `Observe::snapshot`.
+//! Resuming then continues the run exactly as if it had not been interrupted.
 //!
 //! The following example shows how the `checkpointing` method is used to activate checkpointing.
 //! If no checkpoint is available on disk, an optimization will be started from scratch. If the run
@@ -101,13 +111,20 @@
 
 #[cfg(feature = "serde1")]
 mod file;
+#[cfg(feature = "serde1")]
+mod serializer;
+mod snapshot;
 
 #[cfg(feature = "serde1")]
 pub use crate::core::checkpointing::file::FileCheckpoint;
+#[cfg(feature = "serde1")]
+pub use crate::core::checkpointing::serializer::{CheckpointFormat, CheckpointSerializer};
+pub use crate::core::checkpointing::snapshot::{Snapshots, StateSnapshot, Stateful};
 
 use crate::core::Error;
 use std::default::Default;
 use std::fmt::Display;
+use std::time::Duration;
 
 /// An interface for checkpointing methods
 ///
@@ -161,30 +178,109 @@ pub trait Checkpoint<S, I> {
     /// Save a checkpoint
     ///
     /// Gets a reference to the current `solver` of type `S` and to the current `state` of type
-    /// `I`. Both solver and state can maintain state. Optimization problems itself are not allowed
-    /// to have state which changes during an optimization (at least not in the context of
-    /// checkpointing).
+    /// `I`. The internal state of the optimization problem and the observers is only saved via
+    /// [`save_with_snapshots`](`Checkpoint::save_with_snapshots`).
     fn save(&self, solver: &S, state: &I) -> Result<(), Error>;
 
+    /// Save a checkpoint of iteration `iter`
+    ///
+    /// Calls [`save`](`Checkpoint::save`) by default. Implementations which keep more than one
+    /// checkpoint can use `iter` to tell them apart.
+    fn save_iter(&self, solver: &S, state: &I, _iter: u64) -> Result<(), Error> {
+        self.save(solver, state)
+    }
+
+    /// Save a checkpoint of iteration `iter` together with snapshots of the problem and the
+    /// observers
+    ///
+    /// Called by the [`Executor`](`crate::core::Executor`). By default, the snapshots are
+    /// discarded and [`save_iter`](`Checkpoint::save_iter`) is called. Implementations which store
+    /// the snapshots need to return them in
+    /// [`load_with_snapshots`](`Checkpoint::load_with_snapshots`).
+    fn save_with_snapshots(
+        &self,
+        solver: &S,
+        state: &I,
+        _snapshots: &Snapshots,
+        iter: u64,
+    ) -> Result<(), Error> {
+        self.save_iter(solver, state, iter)
+    }
+
     /// Saves a checkpoint when the checkpointing condition is met.
     ///
-    /// Calls [`save`](`Checkpoint::save`) in each iteration (`CheckpointingFrequency::Always`),
-    /// every X iterations (`CheckpointingFrequency::Every(X)`) or never
-    /// (`CheckpointingFrequency::Never`).
+    /// Calls [`save_iter`](`Checkpoint::save_iter`) in each iteration
+    /// (`CheckpointingFrequency::Always`), every X iterations (`CheckpointingFrequency::Every(X)`)
+    /// or never (`CheckpointingFrequency::Never`).
+    ///
+    /// `CheckpointingFrequency::EveryDuration` and `CheckpointingFrequency::OnNewBest` depend on
+    /// the timing and the state of the optimization run and are ignored here. The
+    /// [`Executor`](`crate::core::Executor`) calls
+    /// [`save_cond_with_snapshots`](`Checkpoint::save_cond_with_snapshots`) instead, which also
+    /// covers these.
     fn save_cond(&self, solver: &S, state: &I, iter: u64) -> Result<(), Error> {
         match self.frequency() {
-            CheckpointingFrequency::Always => self.save(solver, state)?,
-            CheckpointingFrequency::Every(it) if iter % it == 0 => self.save(solver, state)?,
-            CheckpointingFrequency::Never | CheckpointingFrequency::Every(_) => {}
+            CheckpointingFrequency::Always => self.save_iter(solver, state, iter)?,
+            CheckpointingFrequency::Every(it) if iter.checked_rem(it) == Some(0) => {
+                self.save_iter(solver, state, iter)?
+            }
+            CheckpointingFrequency::Never
+            | CheckpointingFrequency::Every(_)
+            | CheckpointingFrequency::EveryDuration(_)
+            | CheckpointingFrequency::OnNewBest => {}
         };
         Ok(())
     }
 
+    /// Saves a checkpoint together with snapshots of the problem and the observers when the
+    /// checkpointing condition is met.
+    ///
+    /// Called by the [`Executor`](`crate::core::Executor`) after each iteration. `since_last` is
+    /// the time since the last checkpoint was saved and `new_best` indicates whether a new best
+    /// parameter vector was found in this iteration. The snapshots are only taken when requested
+    /// via `snapshots`. Returns whether a checkpoint was saved.
+    ///
+    /// By default, [`save_with_snapshots`](`Checkpoint::save_with_snapshots`) is called under the
+    /// same conditions as in [`save_cond`](`Checkpoint::save_cond`), at most once per given time
+    /// span (`CheckpointingFrequency::EveryDuration(_)`) and whenever a new best parameter vector
+    /// was found (`CheckpointingFrequency::OnNewBest`).
+    fn save_cond_with_snapshots(
+        &self,
+        solver: &S,
+        state: &I,
+        snapshots: &dyn Fn() -> Result<Snapshots, Error>,
+        iter: u64,
+        since_last: Duration,
+        new_best: bool,
+    ) -> Result<bool, Error> {
+        let due = match self.frequency() {
+            CheckpointingFrequency::Always => true,
+            CheckpointingFrequency::Every(it) => iter.checked_rem(it) == Some(0),
+            CheckpointingFrequency::EveryDuration(interval) => since_last >= interval,
+            CheckpointingFrequency::OnNewBest => new_best,
+            CheckpointingFrequency::Never => false,
+        };
+        if due {
+            self.save_with_snapshots(solver, state, &snapshots()?, iter)?;
+        }
+        Ok(due)
+    }
+
     /// Loads a saved checkpoint
     ///
     /// Returns the solver of type `S` and the `state` of type `I`.
     fn load(&self) -> Result<Option<(S, I)>, Error>;
 
+    /// Loads a saved checkpoint together with the snapshots of the problem and the observers
+    ///
+    /// Called by the [`Executor`](`crate::core::Executor`). By default,
+    /// [`load`](`Checkpoint::load`) is called and empty snapshots are returned.
+    fn load_with_snapshots(&self) -> Result<Option<(S, I, Snapshots)>, Error> {
+        Ok(self
+            .load()?
+            .map(|(solver, state)| (solver, state, Snapshots::default())))
+    }
+
     /// Indicates how often checkpoints should be saved
     ///
     /// Returns enum `CheckpointingFrequency`.
@@ -197,6 +293,7 @@ pub trait Checkpoint<S, I> {
 ///
 /// ```
 /// use argmin::core::checkpointing::CheckpointingFrequency;
+/// use std::time::Duration;
 ///
 /// // A checkpoint every 10 iterations
 /// let every_10 = CheckpointingFrequency::Every(10);
@@ -204,6 +301,12 @@ pub trait Checkpoint<S, I> {
 /// // A checkpoint in each iteration
 /// let always = CheckpointingFrequency::Always;
 ///
+/// // At most one checkpoint every 5 minutes
+/// let every_5_min = CheckpointingFrequency::EveryDuration(Duration::from_secs(300));
+///
+/// // A checkpoint whenever a new best parameter vector was found
+/// let on_new_best = CheckpointingFrequency::OnNewBest;
+///
 /// // The default is `CheckpointingFrequency::Always`
 /// assert_eq!(CheckpointingFrequency::default(), CheckpointingFrequency::Always);
 /// ```
@@ -213,6 +316,11 @@ pub enum CheckpointingFrequency {
     Never,
     /// Create checkpoint every N iterations
     Every(u64),
+    /// Create checkpoint in the first iteration after the given time has passed since the last
+    /// checkpoint (or the start of the run)
+    EveryDuration(Duration),
+    /// Create checkpoint in every iteration in which a new best parameter vector was found
+    OnNewBest,
     /// Create checkpoint in every iteration
     #[default]
     Always,
@@ -223,6 +331,8 @@ impl Display for CheckpointingFrequency {
         match *self {
             CheckpointingFrequency::Never => write!(f, "Never"),
             CheckpointingFrequency::Every(i) => write!(f, "Every({i})"),
+            CheckpointingFrequency::EveryDuration(d) => write!(f, "EveryDuration({d:?})"),
+            CheckpointingFrequency::OnNewBest => write!(f, "OnNewBest"),
             CheckpointingFrequency::Always => write!(f, "Always"),
         }
     }
@@ -0,0 +1,575 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::core::Error;
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::ser::{self, Serialize, Serializer};
+use serde::Deserializer;
+use serde_json::Value;
+
+/// An interface for the serialization format of checkpoints
+///
+/// [`CheckpointFormat`] offers [`bincode`](https://crates.io/crates/bincode) and JSON. Other
+/// formats such as MessagePack or CBOR can be used by implementing this trait and passing it to
+/// [`FileCheckpoint::with_serializer`](`crate::core::checkpointing::FileCheckpoint::with_serializer`).
+///
+/// # Example
+///
+/// ```
+/// use argmin::core::checkpointing::CheckpointSerializer;
+/// use argmin::core::Error;
+/// use serde::{de::DeserializeOwned, Serialize};
+///
+/// /// JSON without indentation
+/// struct CompactJson;
+///
+/// impl CheckpointSerializer for CompactJson {
+///     fn name(&self) -> &str {
+///         "compact-json"
+///     }
+///
+///     fn extension(&self) -> &str {
+///         "json"
+///     }
+///
+///     fn serialize<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, Error> {
+///         Ok(serde_json::to_vec(value)?)
+///     }
+///
+///     fn deserialize<T: DeserializeOwned>(&self, data: &[u8]) -> Result<T, Error> {
+///         Ok(serde_json::from_slice(data)?)
+///     }
+/// }
+/// ```
+pub trait CheckpointSerializer {
+    /// Name of the format, which is stored in the header of each checkpoint file and checked when
+    /// loading. Must not be empty or contain whitespace, otherwise saving a checkpoint fails.
+    fn name(&self) -> &str;
+
+    /// File extension of the checkpoint files (without leading dot)
+    fn extension(&self) -> &str;
+
+    /// Serializes `value`
+    fn serialize<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, Error>;
+
+    /// Deserializes `data`
+    fn deserialize<T: DeserializeOwned>(&self, data: &[u8]) -> Result<T, Error>;
+}
+
+/// Serialization formats of checkpoints offered by argmin.
+///
+/// # Example
+///
+/// ```
+/// use argmin::core::checkpointing::CheckpointFormat;
+///
+/// let bincode = CheckpointFormat::Bincode;
+/// let json = CheckpointFormat::JSON;
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Default)]
+pub enum CheckpointFormat {
+    /// Use [`bincode`](https://crates.io/crates/bincode) for compact binary files (extension
+    /// `arg`)
+    #[default]
+    Bincode,
+    /// Use [`serde_json`](https://crates.io/crates/serde_json) for human-readable files (extension
+    /// `json`).
+    ///
+    /// Since JSON cannot represent infinite and NaN floats, these are written as the strings
+    /// `"inf"`, `"-inf"` and `"NaN"`.
+    JSON,
+}
+
+impl CheckpointSerializer for CheckpointFormat {
+    fn name(&self) -> &str {
+        match self {
+            CheckpointFormat::Bincode => "bincode",
+            CheckpointFormat::JSON => "json",
+        }
+    }
+
+    fn extension(&self) -> &str {
+        match self {
+            CheckpointFormat::Bincode => "arg",
+            CheckpointFormat::JSON => "json",
+        }
+    }
+
+    fn serialize<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, Error> {
+        Ok(match self {
+            CheckpointFormat::Bincode => bincode::serialize(value)?,
+            CheckpointFormat::JSON => serde_json::to_vec_pretty(&NonFinite(value))?,
+        })
+    }
+
+    fn deserialize<T: DeserializeOwned>(&self, data: &[u8]) -> Result<T, Error> {
+        Ok(match self {
+            CheckpointFormat::Bincode => bincode::deserialize(data)?,
+            CheckpointFormat::JSON => T::deserialize(JsonValue(serde_json::from_slice(data)?))?,
+        })
+    }
+}
+
+/// Returns the string representation of a non-finite float
+fn non_finite(value: f64) -> &'static str {
+    if value.is_nan() {
+        "NaN"
+    } else if value > 0.0 {
+        "inf"
+    } else {
+        "-inf"
+    }
+}
+
+/// Parses the string representation of a non-finite float
+fn parse_non_finite(value: &str) -> Option<f64> {
+    match value {
+        "NaN" => Some(f64::NAN),
+        "inf" => Some(f64::INFINITY),
+        "-inf" => Some(f64::NEG_INFINITY),
+        _ => None,
+    }
+}
+
+/// Serializes the wrapped value with non-finite floats written as strings
+struct NonFinite<'a, T: ?Sized>(&'a T);
+
+impl<T: Serialize + ?Sized> Serialize for NonFinite<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(NonFiniteSerializer(serializer))
+    }
+}
+
+/// Serializer which writes non-finite floats as strings and forwards everything else
+struct NonFiniteSerializer<S>(S);
+
+/// Forwards the compound serializers of [`NonFiniteSerializer`], wrapping all values
+struct Compound<C>(C);
+
+macro_rules! forward {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method(self, $($arg: $ty),*) -> Result<S::Ok, S::Error> {
+                self.0.$method($($arg),*)
+            }
+        )*
+    };
+}
+
+macro_rules! forward_compound {
+    ($($method:ident($($arg:ident: $ty:ty),*) -> $compound:ident;)*) => {
+        $(
+            fn $method(self, $($arg: $ty),*) -> Result<Self::$compound, S::Error> {
+                self.0.$method($($arg),*).map(Compound)
+            }
+        )*
+    };
+}
+
+impl<S: Serializer> Serializer for NonFiniteSerializer<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Compound<S::SerializeSeq>;
+    type SerializeTuple = Compound<S::SerializeTuple>;
+    type SerializeTupleStruct = Compound<S::SerializeTupleStruct>;
+    type SerializeTupleVariant = Compound<S::SerializeTupleVariant>;
+    type SerializeMap = Compound<S::SerializeMap>;
+    type SerializeStruct = Compound<S::SerializeStruct>;
+    type SerializeStructVariant = Compound<S::SerializeStructVariant>;
+
+    forward! {
+        serialize_bool(v: bool);
+        serialize_i8(v: i8);
+        serialize_i16(v: i16);
+        serialize_i32(v: i32);
+        serialize_i64(v: i64);
+        serialize_i128(v: i128);
+        serialize_u8(v: u8);
+        serialize_u16(v: u16);
+        serialize_u32(v: u32);
+        serialize_u64(v: u64);
+        serialize_u128(v: u128);
+        serialize_char(v: char);
+        serialize_str(v: &str);
+        serialize_bytes(v: &[u8]);
+        serialize_none();
+        serialize_unit();
+        serialize_unit_struct(name: &'static str);
+        serialize_unit_variant(name: &'static str, index: u32, variant: &'static str);
+    }
+
+    forward_compound! {
+        serialize_seq(len: Option<usize>) -> SerializeSeq;
+        serialize_tuple(len: usize) -> SerializeTuple;
+        serialize_tuple_struct(name: &'static str, len: usize) -> SerializeTupleStruct;
+        serialize_tuple_variant(
+            name: &'static str,
+            index: u32,
+            variant: &'static str,
+            len: usize
+        ) -> SerializeTupleVariant;
+        serialize_map(len: Option<usize>) -> SerializeMap;
+        serialize_struct(name: &'static str, len: usize) -> SerializeStruct;
+        serialize_struct_variant(
+            name: &'static str,
+            index: u32,
+            variant: &'static str,
+            len: usize
+        ) -> SerializeStructVariant;
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<S::Ok, S::Error> {
+        if v.is_finite() {
+            self.0.serialize_f32(v)
+        } else {
+            self.0.serialize_str(non_finite(f64::from(v)))
+        }
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<S::Ok, S::Error> {
+        if v.is_finite() {
+            self.0.serialize_f64(v)
+        } else {
+            self.0.serialize_str(non_finite(v))
+        }
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<S::Ok, S::Error> {
+        self.0.serialize_some(&NonFinite(value))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.0.serialize_newtype_struct(name, &NonFinite(value))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.0
+            .serialize_newtype_variant(name, index, variant, &NonFinite(value))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+}
+
+macro_rules! compound {
+    ($trait:ident, $method:ident($($key:ident: $ty:ty)?)) => {
+        impl<C: ser::$trait> ser::$trait for Compound<C> {
+            type Ok = C::Ok;
+            type Error = C::Error;
+
+            fn $method<T: Serialize + ?Sized>(
+                &mut self,
+                $($key: $ty,)?
+                value: &T,
+            ) -> Result<(), C::Error> {
+                self.0.$method($($key,)? &NonFinite(value))
+            }
+
+            fn end(self) -> Result<C::Ok, C::Error> {
+                self.0.end()
+            }
+        }
+    };
+}
+
+compound!(SerializeSeq, serialize_element());
+compound!(SerializeTuple, serialize_element());
+compound!(SerializeTupleStruct, serialize_field());
+compound!(SerializeTupleVariant, serialize_field());
+compound!(SerializeStruct, serialize_field(key: &'static str));
+compound!(SerializeStructVariant, serialize_field(key: &'static str));
+
+impl<C: ser::SerializeMap> ser::SerializeMap for Compound<C> {
+    type Ok = C::Ok;
+    type Error = C::Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), C::Error> {
+        self.0.serialize_key(key)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), C::Error> {
+        self.0.serialize_value(&NonFinite(value))
+    }
+
+    fn end(self) -> Result<C::Ok, C::Error> {
+        self.0.end()
+    }
+}
+
+/// Deserializer for a JSON value which reads the non-finite floats written by
+/// [`NonFiniteSerializer`] as well as integers from strings (as written for map keys)
+struct JsonValue(Value);
+
+macro_rules! deserialize_int {
+    ($($method:ident => $visit:ident($ty:ty),)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                match &self.0 {
+                    Value::String(s) => match s.parse::<$ty>() {
+                        Ok(v) => visitor.$visit(v),
+                        Err(_) => self.deserialize_any(visitor),
+                    },
+                    _ => self.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for JsonValue {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(v) => visitor.visit_bool(v),
+            Value::Number(v) => v.deserialize_any(visitor),
+            Value::String(v) => visitor.visit_string(v),
+            Value::Array(v) => de::value::SeqDeserializer::new(v.into_iter().map(JsonValue))
+                .deserialize_any(visitor),
+            Value::Object(v) => de::value::MapDeserializer::new(
+                v.into_iter()
+                    .map(|(key, value)| (JsonValue(Value::String(key)), JsonValue(value))),
+            )
+            .deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_f64(visitor)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match &self.0 {
+            Value::String(s) => match parse_non_finite(s) {
+                Some(v) => visitor.visit_f64(v),
+                None => self.deserialize_any(visitor),
+            },
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    deserialize_int! {
+        deserialize_i8 => visit_i8(i8),
+        deserialize_i16 => visit_i16(i16),
+        deserialize_i32 => visit_i32(i32),
+        deserialize_i64 => visit_i64(i64),
+        deserialize_i128 => visit_i128(i128),
+        deserialize_u8 => visit_u8(u8),
+        deserialize_u16 => visit_u16(u16),
+        deserialize_u32 => visit_u32(u32),
+        deserialize_u64 => visit_u64(u64),
+        deserialize_u128 => visit_u128(u128),
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Value::String(variant) => visitor.visit_enum(Variant(variant, Value::Null)),
+            Value::Object(map) if map.len() == 1 => {
+                let (variant, value) = map.into_iter().next().unwrap();
+                visitor.visit_enum(Variant(variant, value))
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool char str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, serde_json::Error> for JsonValue {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// An enum variant with its name and content
+struct Variant(String, Value);
+
+impl<'de> de::EnumAccess<'de> for Variant {
+    type Error = serde_json::Error;
+    type Variant = JsonValue;
+
+    fn variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<(T::Value, JsonValue), Self::Error> {
+        let variant = seed.deserialize(self.0.into_deserializer())?;
+        Ok((variant, JsonValue(self.1)))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for JsonValue {
+    type Error = serde_json::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_any(visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_any(visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_utils::TestSolver;
+    use crate::core::{IterState, State, TerminationReason};
+    use std::collections::HashMap;
+
+    send_sync_test!(checkpoint_format, CheckpointFormat);
+
+    #[test]
+    fn test_json_non_finite() {
+        type TState = IterState<Vec<f64>, (), (), (), f64>;
+        let json = CheckpointFormat::JSON;
+        let state: TState = IterState::new().param(vec![1.0]).cost(f64::NAN);
+        let serialized = json.serialize(&state).unwrap();
+        let text = String::from_utf8(serialized.clone()).unwrap();
+        assert!(text.contains("\"cost\": \"NaN\""));
+        assert!(text.contains("\"best_cost\": \"inf\""));
+        assert!(text.contains("\"target_cost\": \"-inf\""));
+        let loaded: TState = json.deserialize(&serialized).unwrap();
+        assert!(loaded.get_cost().is_nan());
+
+        // Finite values are written as numbers, other strings are rejected
+        let state: TState = IterState::new().cost(1.5);
+        let text = String::from_utf8(json.serialize(&state).unwrap()).unwrap();
+        assert!(text.contains("\"cost\": 1.5"));
+        let invalid = text.replace("\"cost\": 1.5", "\"cost\": \"infinity\"");
+        assert!(json.deserialize::<TState>(invalid.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_json_non_finite_nested() {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        enum Nested {
+            Unit,
+            Newtype(f32),
+            Tuple(f64, f64),
+            Struct { x: Option<f64> },
+        }
+
+        type Value = (Vec<f64>, Option<f32>, HashMap<u64, f64>, Vec<Nested>);
+        let json = CheckpointFormat::JSON;
+        let value: Value = (
+            vec![f64::INFINITY, 1.5, f64::NEG_INFINITY],
+            Some(f32::INFINITY),
+            HashMap::from([(3, f64::NEG_INFINITY)]),
+            vec![
+                Nested::Unit,
+                Nested::Newtype(f32::NEG_INFINITY),
+                Nested::Tuple(f64::INFINITY, 2.0),
+                Nested::Struct {
+                    x: Some(f64::INFINITY),
+                },
+            ],
+        );
+        let serialized = json.serialize(&value).unwrap();
+        let text = String::from_utf8(serialized.clone()).unwrap();
+        assert!(!text.contains("null"));
+        assert_eq!(json.deserialize::<Value>(&serialized).unwrap(), value);
+
+        let serialized = json.serialize(&vec![f64::NAN]).unwrap();
+        let loaded: Vec<f64> = json.deserialize(&serialized).unwrap();
+        assert!(loaded[0].is_nan());
+
+        // `None` is still written as `null`, other strings are not read as floats
+        let serialized = json.serialize(&Option::<f64>::None).unwrap();
+        assert_eq!(serialized, b"null");
+        assert!(json.deserialize::<f64>(b"\"infinity\"").is_err());
+    }
+
+    #[test]
+    fn test_state_roundtrip() {
+        type TState = IterState<Vec<f64>, (), (), (), f64>;
+        for format in [CheckpointFormat::Bincode, CheckpointFormat::JSON] {
+            let state: TState = IterState::new()
+                .param(vec![1.0, 2.0])
+                .terminate_with(TerminationReason::MaxItersReached);
+            let serialized = format.serialize(&(TestSolver::new(), &state)).unwrap();
+            let (_, loaded): (TestSolver, TState) = format.deserialize(&serialized).unwrap();
+            assert_eq!(loaded.get_param(), state.get_param());
+            assert_eq!(
+                loaded.get_best_cost().to_ne_bytes(),
+                f64::INFINITY.to_ne_bytes()
+            );
+            assert_eq!(
+                loaded.target_cost.to_ne_bytes(),
+                f64::NEG_INFINITY.to_ne_bytes()
+            );
+            assert_eq!(
+                loaded.get_termination_reason(),
+                Some(&TerminationReason::MaxItersReached)
+            );
+        }
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(CheckpointFormat::default(), CheckpointFormat::Bincode);
+        assert_eq!(CheckpointFormat::Bincode.name(), "bincode");
+        assert_eq!(CheckpointFormat::Bincode.extension(), "arg");
+        assert_eq!(CheckpointFormat::JSON.name(), "json");
+        assert_eq!(CheckpointFormat::JSON.extension(), "json");
+    }
+}
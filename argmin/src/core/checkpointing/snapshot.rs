@@ -0,0 +1,158 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::core::Error;
+#[cfg(feature = "serde1")]
+use crate::core::{DeserializeOwnedAlias, SerializeAlias};
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// Serialized internal state of a problem or an observer, stored in checkpoints
+///
+/// With the `serde1` feature, a `StateSnapshot` can be created from any serializable value via
+/// [`StateSnapshot::new`] and turned back into it via [`StateSnapshot::restore`]. Otherwise, the raw bytes
+/// can be used via [`StateSnapshot::from_bytes`] and [`StateSnapshot::as_bytes`].
+///
+/// # Example
+///
+/// ```
+/// use argmin::core::checkpointing::StateSnapshot;
+/// # use argmin::core::Error;
+///
+/// # fn main() -> Result<(), Error> {
+/// let history: Vec<f64> = vec![3.0, 2.0, 1.5];
+/// # #[cfg(feature = "serde1")]
+/// # {
+/// let snapshot = StateSnapshot::new(&history)?;
+/// let restored: Vec<f64> = snapshot.restore()?;
+/// # assert_eq!(restored, history);
+/// # }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct StateSnapshot(Vec<u8>);
+
+impl StateSnapshot {
+    /// Create a snapshot of `value` (serialized with `bincode`)
+    #[cfg(feature = "serde1")]
+    pub fn new<T: SerializeAlias>(value: &T) -> Result<Self, Error> {
+        Ok(StateSnapshot(bincode::serialize(value)?))
+    }
+
+    /// Deserialize the value stored via [`StateSnapshot::new`]
+    #[cfg(feature = "serde1")]
+    pub fn restore<T: DeserializeOwnedAlias>(&self) -> Result<T, Error> {
+        Ok(bincode::deserialize(&self.0)?)
+    }
+
+    /// Create a snapshot from raw bytes
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use argmin::core::checkpointing::StateSnapshot;
+    ///
+    /// let snapshot = StateSnapshot::from_bytes(vec![1, 2, 3]);
+    /// assert_eq!(snapshot.as_bytes(), &[1, 2, 3]);
+    /// ```
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        StateSnapshot(bytes)
+    }
+
+    /// Returns the raw bytes of the snapshot
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Snapshots of the problem and the observers, stored in a checkpoint in addition to solver and
+/// state.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct Snapshots {
+    /// Snapshot of the problem (see
+    /// [`Executor::checkpoint_problem`](`crate::core::Executor::checkpoint_problem`))
+    pub problem: Option<StateSnapshot>,
+    /// Snapshots of the observers, in the order in which the observers were added. `None` for
+    /// observers which do not provide a snapshot.
+    pub observers: Vec<Option<StateSnapshot>>,
+}
+
+/// Problems with internal state which should be stored in checkpoints
+///
+/// Examples are caches or random number generators of stochastic problems. Without snapshot, such
+/// problems restart from scratch when an optimization is resumed from a checkpoint, and the
+/// resumed run differs from an uninterrupted one. Snapshots of the problem are only taken if
+/// enabled via [`Executor::checkpoint_problem`](`crate::core::Executor::checkpoint_problem`).
+///
+/// # Example
+///
+/// ```
+/// use argmin::core::checkpointing::{StateSnapshot, Stateful};
+/// use argmin::core::{CostFunction, Error};
+/// use std::cell::Cell;
+///
+/// struct NoisySphere {
+///     seed: Cell<u64>,
+/// }
+///
+/// impl CostFunction for NoisySphere {
+///     type Param = Vec<f64>;
+///     type Output = f64;
+///
+///     fn cost(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+///         // Simple linear congruential generator
+///         let seed = self.seed.get().wrapping_mul(6364136223846793005).wrapping_add(1);
+///         self.seed.set(seed);
+///         let noise = (seed >> 11) as f64 / (1u64 << 53) as f64;
+///         Ok(p.iter().map(|x| x.powi(2)).sum::<f64>() + 1e-3 * noise)
+///     }
+/// }
+///
+/// impl Stateful for NoisySphere {
+///     fn snapshot(&self) -> Result<StateSnapshot, Error> {
+///         Ok(StateSnapshot::from_bytes(self.seed.get().to_le_bytes().to_vec()))
+///     }
+///
+///     fn restore(&mut self, snapshot: &StateSnapshot) -> Result<(), Error> {
+///         self.seed.set(u64::from_le_bytes(snapshot.as_bytes().try_into()?));
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait Stateful {
+    /// Returns a snapshot of the internal state
+    fn snapshot(&self) -> Result<StateSnapshot, Error>;
+
+    /// Restores the internal state from a snapshot created by [`snapshot`](`Stateful::snapshot`)
+    fn restore(&mut self, snapshot: &StateSnapshot) -> Result<(), Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    send_sync_test!(snapshot, StateSnapshot);
+    send_sync_test!(snapshots, Snapshots);
+
+    #[test]
+    #[cfg(feature = "serde1")]
+    fn test_snapshot() {
+        let value = (vec![1.0f64, f64::INFINITY], "abc".to_string(), 7u64);
+        let snapshot = StateSnapshot::new(&value).unwrap();
+        let restored: (Vec<f64>, String, u64) = snapshot.restore().unwrap();
+        assert_eq!(restored.0[0].to_ne_bytes(), 1.0f64.to_ne_bytes());
+        assert_eq!(restored.0[1].to_ne_bytes(), f64::INFINITY.to_ne_bytes());
+        assert_eq!(restored.1, "abc");
+        assert_eq!(restored.2, 7);
+        assert!(StateSnapshot::from_bytes(vec![])
+            .restore::<String>()
+            .is_err());
+    }
+}
@@ -0,0 +1,646 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Derivative checks
+//!
+//! Compares user defined implementations of [`Gradient`], [`Hessian`] and [`Jacobian`] to central
+//! finite difference approximations (see [`finitediff`](`crate::core::finitediff`)) at a set of
+//! test points. This helps to find bugs in hand-written derivatives, which otherwise may lead to
+//! poor convergence of derivative based solvers without any obvious reason.
+//!
+//! The result is a [`DerivativeReport`] containing the worst relative error of each component
+//! over all test points. The relative error of a component is computed as
+//! `|analytic - approximation| / max(1, |analytic|, |approximation|)`, i.e. it reduces to the
+//! absolute error for small values. Because the finite difference approximations themselves are
+//! not exact, errors in the order of `1e-6` (gradients and Jacobians) and `1e-4` (Hessians) are to
+//! be expected for correct implementations.
+//!
+//! The checks can also be run automatically before a solver starts via
+//! [`Executor::check_gradient`](`crate::core::Executor::check_gradient`),
+//! [`Executor::check_hessian`](`crate::core::Executor::check_hessian`) and
+//! [`Executor::check_jacobian`](`crate::core::Executor::check_jacobian`).
+//!
+//! # Example
+//!
+//! ```
+//! use argmin::core::{checks::check_gradient, CostFunction, Error, Gradient, Problem};
+//!
+//! struct Paraboloid {}
+//!
+//! impl CostFunction for Paraboloid {
+//!     type Param = Vec<f64>;
+//!     type Output = f64;
+//!
+//!     fn cost(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+//!         Ok(p[0].powi(2) + 2.0 * p[1].powi(2))
+//!     }
+//! }
+//!
+//! impl Gradient for Paraboloid {
+//!     type Param = Vec<f64>;
+//!     type Gradient = Vec<f64>;
+//!
+//!     fn gradient(&self, p: &Self::Param) -> Result<Self::Gradient, Error> {
+//!         // Bug: second component should be `4.0 * p[1]`
+//!         Ok(vec![2.0 * p[0], 2.0 * p[1]])
+//!     }
+//! }
+//!
+//! let mut problem = Problem::new(Paraboloid {});
+//! let report = check_gradient(&mut problem, &[vec![1.0, 1.0], vec![-2.0, 3.0]])?;
+//!
+//! assert!(!report.passes(1e-6));
+//!
+//! let worst = report.worst().unwrap();
+//! assert_eq!(worst.index, vec![1]);
+//! assert_eq!(worst.point, 1);
+//! # Ok::<(), Error>(())
+//! ```
+
+use crate::core::finitediff::{
+    gradient_real, hessian_real, jacobian_columns_real, Central, FiniteDiffMode,
+};
+use crate::core::{
+    ArgminFloat, CostFunction, Error, Gradient, Hessian, Jacobian, Operator, Problem, State,
+};
+use argmin_math::{ArgminDot, ArgminMul, ArgminSub};
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Worst deviation of a single component of a derivative from its finite difference
+/// approximation
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct ComponentError<F> {
+    /// Index of the component (`[i]` for gradients, `[row, column]` for Hessians and Jacobians)
+    pub index: Vec<usize>,
+    /// Worst relative error over all test points
+    pub error: F,
+    /// Index of the test point at which the worst error occurred
+    pub point: usize,
+    /// Analytic derivative at this test point
+    pub analytic: F,
+    /// Finite difference approximation at this test point
+    pub approximation: F,
+}
+
+/// Result of a derivative check
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct DerivativeReport<F> {
+    /// Checked derivative (`"gradient"`, `"hessian"` or `"jacobian"`)
+    pub derivative: String,
+    /// Worst relative error per component
+    pub components: Vec<ComponentError<F>>,
+}
+
+impl<F: ArgminFloat> DerivativeReport<F> {
+    fn new(derivative: &str) -> Self {
+        DerivativeReport {
+            derivative: derivative.to_string(),
+            components: vec![],
+        }
+    }
+
+    /// Records the comparison of `analytic` and `approximation` of component `k` at test point
+    /// `point`.
+    fn record(&mut self, k: usize, index: Vec<usize>, point: usize, analytic: F, approximation: F) {
+        let scale = F::one().max(analytic.abs()).max(approximation.abs());
+        let error = (analytic - approximation).abs() / scale;
+        let component = ComponentError {
+            index,
+            error,
+            point,
+            analytic,
+            approximation,
+        };
+        match self.components.get_mut(k) {
+            Some(c) => {
+                // NaN errors always count as worst
+                if error.is_nan() || (!c.error.is_nan() && error > c.error) {
+                    *c = component;
+                }
+            }
+            None => self.components.push(component),
+        }
+    }
+
+    /// Returns the component with the largest relative error
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::checks::{ComponentError, DerivativeReport};
+    /// # let report = DerivativeReport {
+    /// #     derivative: "gradient".to_string(),
+    /// #     components: vec![
+    /// #         ComponentError { index: vec![0], error: 1e-3, point: 0, analytic: 1.0, approximation: 1.001 },
+    /// #         ComponentError { index: vec![1], error: 1e-9, point: 0, analytic: 1.0, approximation: 1.0 },
+    /// #     ],
+    /// # };
+    /// let worst = report.worst().unwrap();
+    /// # assert_eq!(worst.index, vec![0]);
+    /// ```
+    pub fn worst(&self) -> Option<&ComponentError<F>> {
+        self.components.iter().fold(None, |worst, c| match worst {
+            Some(w) if w.error.is_nan() || w.error >= c.error => Some(w),
+            _ => Some(c),
+        })
+    }
+
+    /// Returns the largest relative error of all components (zero if there are no components)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::checks::{ComponentError, DerivativeReport};
+    /// # let report = DerivativeReport {
+    /// #     derivative: "gradient".to_string(),
+    /// #     components: vec![
+    /// #         ComponentError { index: vec![0], error: 1e-3f64, point: 0, analytic: 1.0, approximation: 1.001 },
+    /// #     ],
+    /// # };
+    /// let max_error = report.max_error();
+    /// # assert_eq!(max_error.to_ne_bytes(), 1e-3f64.to_ne_bytes());
+    /// ```
+    pub fn max_error(&self) -> F {
+        self.worst().map(|c| c.error).unwrap_or_else(F::zero)
+    }
+
+    /// Returns `true` if the relative errors of all components are below `tol`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::checks::{ComponentError, DerivativeReport};
+    /// # let report = DerivativeReport {
+    /// #     derivative: "gradient".to_string(),
+    /// #     components: vec![
+    /// #         ComponentError { index: vec![0], error: 1e-3, point: 0, analytic: 1.0, approximation: 1.001 },
+    /// #     ],
+    /// # };
+    /// assert!(report.passes(1e-2));
+    /// assert!(!report.passes(1e-4));
+    /// ```
+    pub fn passes(&self, tol: F) -> bool {
+        self.components.iter().all(|c| c.error < tol)
+    }
+}
+
+impl<F: ArgminFloat> fmt::Display for DerivativeReport<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Derivative check: {}", self.derivative)?;
+        for c in self.components.iter() {
+            writeln!(
+                f,
+                "    {:?}: relative error {} at point {} (analytic: {}, approximation: {})",
+                c.index, c.error, c.point, c.analytic, c.approximation
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn check_points<P>(points: &[P]) -> Result<(), Error> {
+    if points.is_empty() {
+        return Err(argmin_error!(
+            InvalidParameter,
+            "Derivative check: at least one test point is required."
+        ));
+    }
+    Ok(())
+}
+
+fn check_len(derivative: &str, found: usize, expected: usize) -> Result<(), Error> {
+    if found != expected {
+        return Err(argmin_error!(
+            InvalidParameter,
+            format!(
+                "Derivative check: {derivative} has {found} components, but {expected} were expected."
+            )
+        ));
+    }
+    Ok(())
+}
+
+/// Converts a list of columns into a list of rows
+fn columns_to_rows<V, F>(columns: Vec<V>) -> Vec<Vec<F>>
+where
+    for<'a> &'a V: IntoIterator<Item = &'a F>,
+    F: ArgminFloat,
+{
+    let mut rows: Vec<Vec<F>> = vec![];
+    for column in columns.iter() {
+        for (i, value) in column.into_iter().enumerate() {
+            if i >= rows.len() {
+                rows.push(vec![]);
+            }
+            rows[i].push(*value);
+        }
+    }
+    rows
+}
+
+/// Records the comparison of two matrices given as lists of rows
+fn record_matrix<F: ArgminFloat>(
+    report: &mut DerivativeReport<F>,
+    point: usize,
+    analytic: Vec<Vec<F>>,
+    approximation: Vec<Vec<F>>,
+) {
+    let mut k = 0;
+    for (i, (ra, rb)) in analytic.into_iter().zip(approximation).enumerate() {
+        for (j, (a, b)) in ra.into_iter().zip(rb).enumerate() {
+            report.record(k, vec![i, j], point, a, b);
+            k += 1;
+        }
+    }
+}
+
+/// Unit vector `e_i` of length `n`
+fn unit<P: From<Vec<F>>, F: ArgminFloat>(n: usize, i: usize) -> P {
+    let mut e = vec![F::zero(); n];
+    e[i] = F::one();
+    P::from(e)
+}
+
+/// Compares the gradient of `problem` to a central finite difference approximation of its cost
+/// function at all `points`.
+///
+/// Evaluations are counted in `problem` as usual.
+pub fn check_gradient<O, P, G, F>(
+    problem: &mut Problem<O>,
+    points: &[P],
+) -> Result<DerivativeReport<F>, Error>
+where
+    O: CostFunction<Param = P, Output = F> + Gradient<Param = P, Gradient = G>,
+    P: From<Vec<F>>,
+    for<'a> &'a P: IntoIterator<Item = &'a F>,
+    for<'a> &'a G: IntoIterator<Item = &'a F>,
+    F: ArgminFloat,
+{
+    check_points(points)?;
+    let mut report = DerivativeReport::new("gradient");
+    for (k, point) in points.iter().enumerate() {
+        let x: Vec<F> = point.into_iter().cloned().collect();
+        let gradient = problem.gradient(point)?;
+        let analytic: Vec<F> = (&gradient).into_iter().cloned().collect();
+        let approximation = gradient_real(&x, Central::default_step(), true, |x| {
+            problem.cost(&P::from(x))
+        })?;
+        check_len("gradient", analytic.len(), x.len())?;
+        for (i, (a, b)) in analytic.into_iter().zip(approximation).enumerate() {
+            report.record(i, vec![i], k, a, b);
+        }
+    }
+    Ok(report)
+}
+
+/// Compares the Hessian of `problem` to a central finite difference approximation of its cost
+/// function at all `points`.
+///
+/// The columns of the Hessian are obtained via products with unit vectors ([`ArgminDot`]).
+/// Evaluations are counted in `problem` as usual.
+pub fn check_hessian<O, P, H, F>(
+    problem: &mut Problem<O>,
+    points: &[P],
+) -> Result<DerivativeReport<F>, Error>
+where
+    O: CostFunction<Param = P, Output = F> + Hessian<Param = P, Hessian = H>,
+    P: From<Vec<F>>,
+    for<'a> &'a P: IntoIterator<Item = &'a F>,
+    H: ArgminDot<P, P>,
+    F: ArgminFloat,
+{
+    check_points(points)?;
+    let mut report = DerivativeReport::new("hessian");
+    for (k, point) in points.iter().enumerate() {
+        let x: Vec<F> = point.into_iter().cloned().collect();
+        let n = x.len();
+        let hessian = problem.hessian(point)?;
+        let approximation = hessian_real(&x, Central::default_hessian_step(), true, |x| {
+            problem.cost(&P::from(x))
+        })?;
+        let analytic = columns_to_rows((0..n).map(|j| hessian.dot(&unit::<P, F>(n, j))).collect());
+        check_len("hessian", analytic.len(), n)?;
+        record_matrix(&mut report, k, analytic, approximation);
+    }
+    Ok(report)
+}
+
+/// Compares the Jacobian of `problem` to a central finite difference approximation of its
+/// operator at all `points`.
+///
+/// The columns of the Jacobian are obtained via products with unit vectors ([`ArgminDot`]).
+/// Evaluations are counted in `problem` as usual.
+pub fn check_jacobian<O, P, U, J, F>(
+    problem: &mut Problem<O>,
+    points: &[P],
+) -> Result<DerivativeReport<F>, Error>
+where
+    O: Operator<Param = P, Output = U> + Jacobian<Param = P, Jacobian = J>,
+    P: From<Vec<F>>,
+    for<'a> &'a P: IntoIterator<Item = &'a F>,
+    U: ArgminSub<U, U> + ArgminMul<F, U>,
+    for<'a> &'a U: IntoIterator<Item = &'a F>,
+    J: ArgminDot<P, U>,
+    F: ArgminFloat,
+{
+    check_points(points)?;
+    let mut report = DerivativeReport::new("jacobian");
+    for (k, point) in points.iter().enumerate() {
+        let x: Vec<F> = point.into_iter().cloned().collect();
+        let n = x.len();
+        let jacobian = problem.jacobian(point)?;
+        let approximation = jacobian_columns_real(&x, Central::default_step(), true, |x| {
+            problem.apply(&P::from(x))
+        })?;
+        let analytic = columns_to_rows((0..n).map(|j| jacobian.dot(&unit::<P, F>(n, j))).collect());
+        let approximation = columns_to_rows(approximation);
+        check_len("jacobian", analytic.len(), approximation.len())?;
+        record_matrix(&mut report, k, analytic, approximation);
+    }
+    Ok(report)
+}
+
+/// Returns an error if `report` does not pass the tolerance `tol`
+fn assert_passes<F: ArgminFloat>(report: DerivativeReport<F>, tol: f64) -> Result<(), Error> {
+    let tol: F = float!(tol);
+    match report.worst() {
+        Some(c) if !report.passes(tol) => Err(argmin_error!(
+            ConditionViolated,
+            format!(
+                "Derivative check of {} failed: relative error {} of component {:?} exceeds \
+                 tolerance {} (analytic: {}, approximation: {})",
+                report.derivative, c.error, c.index, tol, c.analytic, c.approximation
+            )
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn initial_param<I: State>(state: &I) -> Result<&I::Param, Error> {
+    state.get_param().ok_or_else(argmin_error_closure!(
+        NotInitialized,
+        "Derivative check requires an initial parameter vector."
+    ))
+}
+
+/// Checks the gradient at the parameter vector of `state`. Used by `Executor`.
+pub(crate) fn executor_check_gradient<O, I, G>(
+    problem: &mut Problem<O>,
+    state: &I,
+    tol: f64,
+) -> Result<(), Error>
+where
+    I: State,
+    O: CostFunction<Param = I::Param, Output = I::Float> + Gradient<Param = I::Param, Gradient = G>,
+    I::Param: From<Vec<I::Float>>,
+    for<'a> &'a I::Param: IntoIterator<Item = &'a I::Float>,
+    for<'a> &'a G: IntoIterator<Item = &'a I::Float>,
+{
+    let param = initial_param(state)?;
+    let report = check_gradient(problem, std::slice::from_ref(param))?;
+    assert_passes(report, tol)
+}
+
+/// Checks the Hessian at the parameter vector of `state`. Used by `Executor`.
+pub(crate) fn executor_check_hessian<O, I, H>(
+    problem: &mut Problem<O>,
+    state: &I,
+    tol: f64,
+) -> Result<(), Error>
+where
+    I: State,
+    O: CostFunction<Param = I::Param, Output = I::Float> + Hessian<Param = I::Param, Hessian = H>,
+    I::Param: From<Vec<I::Float>>,
+    for<'a> &'a I::Param: IntoIterator<Item = &'a I::Float>,
+    H: ArgminDot<I::Param, I::Param>,
+{
+    let param = initial_param(state)?;
+    let report = check_hessian(problem, std::slice::from_ref(param))?;
+    assert_passes(report, tol)
+}
+
+/// Checks the Jacobian at the parameter vector of `state`. Used by `Executor`.
+pub(crate) fn executor_check_jacobian<O, I, U, J>(
+    problem: &mut Problem<O>,
+    state: &I,
+    tol: f64,
+) -> Result<(), Error>
+where
+    I: State,
+    O: Operator<Param = I::Param, Output = U> + Jacobian<Param = I::Param, Jacobian = J>,
+    I::Param: From<Vec<I::Float>>,
+    for<'a> &'a I::Param: IntoIterator<Item = &'a I::Float>,
+    U: ArgminSub<U, U> + ArgminMul<I::Float, U>,
+    for<'a> &'a U: IntoIterator<Item = &'a I::Float>,
+    J: ArgminDot<I::Param, U>,
+{
+    let param = initial_param(state)?;
+    let report = check_jacobian(problem, std::slice::from_ref(param))?;
+    assert_passes(report, tol)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_utils::TestProblem;
+    use crate::core::ArgminError;
+
+    /// f(x) = x_0^2 x_1 + exp(x_1) and u(x) = (x_0 x_1, exp(x_0), x_1^2). If `bug` is set, the
+    /// derivatives contain errors in a single component each.
+    struct Model {
+        bug: bool,
+    }
+
+    impl CostFunction for Model {
+        type Param = Vec<f64>;
+        type Output = f64;
+
+        fn cost(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+            Ok(p[0].powi(2) * p[1] + p[1].exp())
+        }
+    }
+
+    impl Gradient for Model {
+        type Param = Vec<f64>;
+        type Gradient = Vec<f64>;
+
+        fn gradient(&self, p: &Self::Param) -> Result<Self::Gradient, Error> {
+            let d1 = if self.bug { p[0] } else { p[0].powi(2) };
+            Ok(vec![2.0 * p[0] * p[1], d1 + p[1].exp()])
+        }
+    }
+
+    impl Hessian for Model {
+        type Param = Vec<f64>;
+        type Hessian = Vec<Vec<f64>>;
+
+        fn hessian(&self, p: &Self::Param) -> Result<Self::Hessian, Error> {
+            let d01 = if self.bug { 2.0 * p[1] } else { 2.0 * p[0] };
+            Ok(vec![vec![2.0 * p[1], 2.0 * p[0]], vec![d01, p[1].exp()]])
+        }
+    }
+
+    impl Operator for Model {
+        type Param = Vec<f64>;
+        type Output = Vec<f64>;
+
+        fn apply(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+            Ok(vec![p[0] * p[1], p[0].exp(), p[1].powi(2)])
+        }
+    }
+
+    impl Jacobian for Model {
+        type Param = Vec<f64>;
+        type Jacobian = Vec<Vec<f64>>;
+
+        fn jacobian(&self, p: &Self::Param) -> Result<Self::Jacobian, Error> {
+            let d21 = if self.bug { p[1] } else { 2.0 * p[1] };
+            Ok(vec![
+                vec![p[1], p[0]],
+                vec![p[0].exp(), 0.0],
+                vec![0.0, d21],
+            ])
+        }
+    }
+
+    fn points() -> Vec<Vec<f64>> {
+        vec![vec![1.0, 0.5], vec![-2.0, 3.0], vec![0.1, -1.0]]
+    }
+
+    #[test]
+    fn test_check_gradient() {
+        let mut problem = Problem::new(Model { bug: false });
+        let report = check_gradient(&mut problem, &points()).unwrap();
+        assert_eq!(report.derivative, "gradient");
+        assert_eq!(report.components.len(), 2);
+        assert_eq!(report.components[0].index, vec![0]);
+        assert_eq!(report.components[1].index, vec![1]);
+        assert!(report.passes(1e-8));
+        assert_eq!(problem.counts["gradient_count"], 3);
+        assert_eq!(problem.counts["cost_count"], 3 * 4);
+
+        let mut problem = Problem::new(Model { bug: true });
+        let report = check_gradient(&mut problem, &points()).unwrap();
+        assert!(!report.passes(1e-6));
+        assert!(report.components[0].error < 1e-8);
+        let worst = report.worst().unwrap();
+        assert_eq!(worst.index, vec![1]);
+        // Largest relative error `|x_0 - x_0^2| / max(1, ...)` occurs at the second point
+        assert_eq!(worst.point, 1);
+        assert_eq!(
+            worst.analytic.to_ne_bytes(),
+            (-2.0f64 + 3.0f64.exp()).to_ne_bytes()
+        );
+        assert!((worst.approximation - (4.0 + 3.0f64.exp())).abs() < 1e-6);
+        assert_eq!(report.max_error().to_ne_bytes(), worst.error.to_ne_bytes());
+    }
+
+    #[test]
+    fn test_check_hessian() {
+        let mut problem = Problem::new(Model { bug: false });
+        let report = check_hessian(&mut problem, &points()).unwrap();
+        assert_eq!(report.derivative, "hessian");
+        let indices: Vec<Vec<usize>> = report.components.iter().map(|c| c.index.clone()).collect();
+        assert_eq!(
+            indices,
+            vec![vec![0, 0], vec![0, 1], vec![1, 0], vec![1, 1]]
+        );
+        assert!(report.passes(1e-6));
+
+        let mut problem = Problem::new(Model { bug: true });
+        let report = check_hessian(&mut problem, &points()).unwrap();
+        assert!(!report.passes(1e-4));
+        assert_eq!(report.worst().unwrap().index, vec![1, 0]);
+    }
+
+    #[test]
+    fn test_check_jacobian() {
+        let mut problem = Problem::new(Model { bug: false });
+        let report = check_jacobian(&mut problem, &points()).unwrap();
+        assert_eq!(report.derivative, "jacobian");
+        let indices: Vec<Vec<usize>> = report.components.iter().map(|c| c.index.clone()).collect();
+        assert_eq!(
+            indices,
+            vec![
+                vec![0, 0],
+                vec![0, 1],
+                vec![1, 0],
+                vec![1, 1],
+                vec![2, 0],
+                vec![2, 1]
+            ]
+        );
+        assert!(report.passes(1e-8));
+
+        let mut problem = Problem::new(Model { bug: true });
+        let report = check_jacobian(&mut problem, &points()).unwrap();
+        assert!(!report.passes(1e-6));
+        assert_eq!(report.worst().unwrap().index, vec![2, 1]);
+        assert_eq!(report.worst().unwrap().point, 1);
+    }
+
+    #[test]
+    fn test_no_points() {
+        let mut problem = Problem::new(Model { bug: false });
+        assert_error!(
+            check_gradient(&mut problem, &[]),
+            ArgminError,
+            "Invalid parameter: \"Derivative check: at least one test point is required.\""
+        );
+    }
+
+    #[test]
+    fn test_wrong_dimension() {
+        // Jacobian of `TestProblem` is always 2x2
+        let mut problem = Problem::new(TestProblem::new());
+        assert_error!(
+            check_jacobian(&mut problem, &[vec![1.0, 2.0, 3.0]]),
+            ArgminError,
+            "Invalid parameter: \"Derivative check: jacobian has 2 components, but 3 were expected.\""
+        );
+    }
+
+    #[test]
+    fn test_nan() {
+        let mut report = DerivativeReport::new("gradient");
+        report.record(0, vec![0], 0, 1.0, 1.0);
+        report.record(1, vec![1], 0, 2.0, 1.0);
+        report.record(1, vec![1], 1, f64::NAN, 1.0);
+        report.record(1, vec![1], 2, 2.0, 1.0);
+        assert!(report.components[1].error.is_nan());
+        assert_eq!(report.components[1].point, 1);
+        assert!(report.worst().unwrap().error.is_nan());
+        assert!(!report.passes(1.0));
+    }
+
+    #[test]
+    fn test_display() {
+        let mut report = DerivativeReport::new("gradient");
+        report.record(0, vec![0], 1, 2.0, 1.0);
+        assert_eq!(
+            format!("{report}"),
+            "Derivative check: gradient\n    [0]: relative error 0.5 at point 1 (analytic: 2, approximation: 1)\n"
+        );
+    }
+
+    #[test]
+    fn test_assert_passes() {
+        let mut report = DerivativeReport::new("gradient");
+        report.record(0, vec![0], 1, 2.0, 1.0);
+        assert!(assert_passes(report.clone(), 0.6).is_ok());
+        assert_error!(
+            assert_passes(report, 0.1),
+            ArgminError,
+            "Condition violated: \"Derivative check of gradient failed: relative error 0.5 of \
+             component [0] exceeds tolerance 0.1 (analytic: 2, approximation: 1)\""
+        );
+    }
+}
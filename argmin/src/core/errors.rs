@@ -48,6 +48,13 @@ pub enum ArgminError {
         text: String,
     },
 
+    /// Checkpoint is corrupt or incompatible
+    #[error("Invalid checkpoint: {text:?}")]
+    InvalidCheckpoint {
+        /// Text
+        text: String,
+    },
+
     /// For errors which are likely bugs.
     #[error("Potential bug: {text:?}. This is potentially a bug. Please file a report on https://github.com/argmin-rs/argmin/issues")]
     PotentialBug {
@@ -5,16 +5,34 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use crate::core::checkpointing::Checkpoint;
+use crate::core::checkpointing::{
+    Checkpoint, CheckpointingFrequency, Snapshots, StateSnapshot, Stateful,
+};
+use crate::core::checks::{
+    executor_check_gradient, executor_check_hessian, executor_check_jacobian,
+};
 use crate::core::observers::{Observe, ObserverMode, Observers};
+use crate::core::termination::TerminationCriterion;
 use crate::core::{
-    DeserializeOwnedAlias, Error, OptimizationResult, Problem, SerializeAlias, Solver, State,
-    TerminationReason, TerminationStatus, KV,
+    CostFunction, DeserializeOwnedAlias, Error, Gradient, Hessian, Jacobian, Operator,
+    OptimizationResult, Problem, SerializeAlias, Solver, State, TerminationReason,
+    TerminationStatus, KV,
 };
+use argmin_math::{ArgminDot, ArgminMul, ArgminSub};
 use instant;
+use num_traits::ToPrimitive;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
+/// Derivative check which is run before the solver is started, together with its tolerance
+type DerivativeCheck<O, I> = (fn(&mut Problem<O>, &I, f64) -> Result<(), Error>, f64);
+
+/// Functions which take and restore a snapshot of the problem
+type ProblemSnapshot<O> = (
+    fn(&O) -> Result<StateSnapshot, Error>,
+    fn(&mut O, &StateSnapshot) -> Result<(), Error>,
+);
+
 /// Solves an optimization problem with a solver
 pub struct Executor<O, S, I> {
     /// Solver
@@ -27,10 +45,26 @@ pub struct Executor<O, S, I> {
     observers: Observers<I>,
     /// Checkpoint
     checkpoint: Option<Box<dyn Checkpoint<S, I>>>,
+    /// Snapshot functions of the problem, if it is included in checkpoints
+    problem_snapshot: Option<ProblemSnapshot<O>>,
     /// Indicates whether Ctrl-C functionality should be active or not
     ctrlc: bool,
     /// Indicates whether to time execution or not
     timer: bool,
+    /// Derivative checks
+    derivative_checks: Vec<DerivativeCheck<O, I>>,
+    /// Additional termination criteria
+    terminations: Vec<Box<dyn TerminationCriterion<I>>>,
+    /// Indicates whether the solver has been initialized
+    started: bool,
+    /// Indicates whether the observers have been notified of the termination
+    finished: bool,
+    /// Set by the Ctrl-C handler
+    interrupt: Arc<AtomicBool>,
+    /// Start of the optimization run
+    total_time: Option<instant::Instant>,
+    /// Time of the last checkpoint (or the start of the run)
+    last_checkpoint: instant::Instant,
 }
 
 impl<O, S, I> Executor<O, S, I>
@@ -67,8 +101,16 @@ where
             state,
             observers: Observers::new(),
             checkpoint: None,
+            problem_snapshot: None,
             ctrlc: true,
             timer: true,
+            derivative_checks: vec![],
+            terminations: vec![],
+            started: false,
+            finished: false,
+            interrupt: Arc::new(AtomicBool::new(false)),
+            total_time: None,
+            last_checkpoint: instant::Instant::now(),
         }
     }
 
@@ -128,28 +170,345 @@ where
     /// # }
     /// ```
     pub fn run(mut self) -> Result<OptimizationResult<O, S, I>, Error> {
+        while self.step()?.is_some() {}
+        self.into_result()
+    }
+
+    /// Performs a single iteration of the solver and returns a mutable reference to the state
+    /// after the iteration, or `None` if the solver has terminated.
+    ///
+    /// The first call loads the checkpoint (if any) and initializes the solver. Observers,
+    /// function evaluation counting, timers and checkpointing behave exactly as in
+    /// [`run`](`Executor::run`), which is equivalent to calling `step` until it returns `None`.
+    /// This allows embedding the optimization in other event loops, inspecting or modifying the
+    /// state after each iteration and stopping early with custom logic. Once done, the result can
+    /// be obtained with [`into_result`](`Executor::into_result`).
+    ///
+    /// Errors of observers, checkpointing and derivative checks leave the state untouched. If
+    /// however the solver itself (`init` or `next_iter`) returns an error, the state it consumed is
+    /// lost. Subsequent calls to `step` and [`into_result`](`Executor::into_result`) then return an
+    /// error and [`state`](`Executor::state`) returns `None`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{Error, Executor, State};
+    /// # use argmin::core::test_utils::{TestSolver, TestProblem};
+    /// #
+    /// # fn main() -> Result<(), Error> {
+    /// # let solver = TestSolver::new();
+    /// # let problem = TestProblem::new();
+    /// # let init_param = vec![1.0f64, 0.0];
+    /// #
+    /// let mut executor = Executor::new(problem, solver)
+    ///     .configure(|state| state.param(init_param).max_iters(100));
+    ///
+    /// while let Some(state) = executor.step()? {
+    ///     // Stop early with custom logic
+    ///     if state.get_iter() >= 10 {
+    ///         break;
+    ///     }
+    /// }
+    ///
+    /// let result = executor.into_result()?;
+    /// # assert_eq!(result.state.get_iter(), 10);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn step(&mut self) -> Result<Option<&mut I>, Error> {
+        if !self.started {
+            self.start()?;
+        }
+
+        if self.interrupt.load(Ordering::SeqCst) {
+            // Solver execution has been interrupted manually. The termination reason is part of
+            // the final checkpoint and reset when the run is resumed from it.
+            let state = self.take_state()?;
+            self.state = Some(state.terminate_with(TerminationReason::KeyboardInterrupt));
+            self.save_final_checkpoint()?;
+            return self.finish();
+        }
+
+        let mut state = self.take_state()?;
+
+        // First, check if it isn't already terminated. If it isn't, evaluate the stopping
+        // criteria. If `self.terminate()` is called without the checking whether it has
+        // terminated already, then it may overwrite a termination set within `next_iter()`!
+        if !state.terminated() {
+            let term = self.solver.terminate_internal(&state);
+            if let TerminationStatus::Terminated(reason) = term {
+                state = state.terminate_with(reason);
+            }
+        }
+        // Then check the additional termination criteria.
+        for criterion in self.terminations.iter_mut() {
+            if state.terminated() {
+                break;
+            }
+            if let TerminationStatus::Terminated(reason) = criterion.terminate(&state) {
+                state = state.terminate_with(reason);
+            }
+        }
+        // Now check once more if the algorithm has terminated.
+        let terminated = state.terminated();
+        self.state = Some(state);
+        if terminated {
+            self.save_final_checkpoint()?;
+            return self.finish();
+        }
+        let state = self.take_state()?;
+
+        // Start time measurement
+        let start = if self.timer {
+            Some(instant::Instant::now())
+        } else {
+            None
+        };
+
+        let (mut state, kv) = self.solver.next_iter(&mut self.problem, state)?;
+
+        state.func_counts(&self.problem);
+
+        // End time measurement
+        let duration = if self.timer {
+            Some(start.unwrap().elapsed())
+        } else {
+            None
+        };
+
+        state.update();
+
+        // Must be queried before the iteration number is incremented
+        let new_best = state.is_best();
+
+        // From here on, the state is stored in the executor such that it is not lost on errors
+        self.state = Some(state);
+
+        if !self.observers.is_empty() {
+            let mut log = if let Some(kv) = kv { kv } else { KV::new() };
+
+            if self.timer {
+                let duration = duration.unwrap();
+                let tmp = kv!(
+                    "time" => duration.as_secs_f64();
+                );
+                log = log.merge(tmp);
+            }
+            self.observers
+                .observe_iter(self.state.as_ref().unwrap(), &log)?;
+        }
+
+        // increment iteration number
+        self.state.as_mut().unwrap().increment_iter();
+
+        self.save_checkpoint(new_best)?;
+
+        let state = self.state.as_mut().unwrap();
+        if let Some(total_time) = self.total_time {
+            state.time(Some(total_time.elapsed()));
+        }
+
+        Ok(Some(state))
+    }
+
+    /// Takes the state out of the executor. Fails if it was lost due to an error of the solver.
+    fn take_state(&mut self) -> Result<I, Error> {
+        self.state.take().ok_or_else(argmin_error_closure!(
+            ConditionViolated,
+            "`Executor`: State was lost due to an error of the solver in a previous step."
+        ))
+    }
+
+    /// Returns an iterator which performs one iteration of the solver per call to `next` and
+    /// yields a copy of the state after each iteration.
+    ///
+    /// This is a convenience wrapper around [`step`](`Executor::step`); use `step` to modify the
+    /// state between iterations. The iterator ends once the solver has terminated or after the
+    /// first error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{Error, Executor, State};
+    /// # use argmin::core::test_utils::{TestSolver, TestProblem};
+    /// #
+    /// # fn main() -> Result<(), Error> {
+    /// # let solver = TestSolver::new();
+    /// # let problem = TestProblem::new();
+    /// # let init_param = vec![1.0f64, 0.0];
+    /// #
+    /// let mut executor = Executor::new(problem, solver)
+    ///     .configure(|state| state.param(init_param).max_iters(10));
+    ///
+    /// for state in executor.iter() {
+    ///     let state = state?;
+    ///     println!("Iteration {}: best cost {}", state.get_iter(), state.get_best_cost());
+    /// }
+    /// # assert_eq!(executor.state().unwrap().get_iter(), 10);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter(&mut self) -> impl Iterator<Item = Result<I, Error>> + '_
+    where
+        I: Clone,
+    {
+        let mut done = false;
+        std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            match self.step() {
+                Ok(Some(state)) => Some(Ok(state.clone())),
+                Ok(None) => {
+                    done = true;
+                    None
+                }
+                Err(e) => {
+                    done = true;
+                    Some(Err(e))
+                }
+            }
+        })
+    }
+
+    /// Returns a reference to the current state.
+    ///
+    /// Returns `None` if the state was lost due to an error of the solver (see
+    /// [`step`](`Executor::step`)).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{Executor, State};
+    /// # use argmin::core::test_utils::{TestSolver, TestProblem};
+    /// # let solver = TestSolver::new();
+    /// # let problem = TestProblem::new();
+    /// let executor = Executor::new(problem, solver).configure(|state| state.max_iters(10));
+    /// assert_eq!(executor.state().unwrap().get_max_iters(), 10);
+    /// ```
+    pub fn state(&self) -> Option<&I> {
+        self.state.as_ref()
+    }
+
+    /// Consumes the executor and returns the result of the optimization run so far.
+    ///
+    /// This is typically used after stepping through the optimization with
+    /// [`step`](`Executor::step`) or [`iter`](`Executor::iter`).
+    ///
+    /// Fails if the state was lost due to an error of the solver (see
+    /// [`step`](`Executor::step`)).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{Error, Executor, State};
+    /// # use argmin::core::test_utils::{TestSolver, TestProblem};
+    /// # fn main() -> Result<(), Error> {
+    /// # let solver = TestSolver::new();
+    /// # let problem = TestProblem::new();
+    /// let mut executor = Executor::new(problem, solver).configure(|state| state.max_iters(10));
+    /// executor.step()?;
+    /// let result = executor.into_result()?;
+    /// # assert_eq!(result.state.get_iter(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_result(mut self) -> Result<OptimizationResult<O, S, I>, Error> {
+        let state = self.take_state()?;
+        Ok(OptimizationResult::new(self.problem, self.solver, state))
+    }
+
+    /// Notifies the observers about the terminated state (only once).
+    fn finish(&mut self) -> Result<Option<&mut I>, Error> {
+        if !self.finished {
+            self.finished = true;
+            if !self.observers.is_empty() {
+                self.observers.observe_final(self.state.as_ref().unwrap())?;
+            }
+        }
+        Ok(None)
+    }
+
+    /// Saves a checkpoint of the state if one is due according to the checkpointing frequency.
+    fn save_checkpoint(&mut self, new_best: bool) -> Result<(), Error> {
+        if let (Some(checkpoint), Some(state)) = (self.checkpoint.as_ref(), self.state.as_ref()) {
+            let saved = checkpoint.save_cond_with_snapshots(
+                &self.solver,
+                state,
+                &|| self.snapshots(),
+                state.get_iter(),
+                self.last_checkpoint.elapsed(),
+                new_best,
+            )?;
+            if saved {
+                self.last_checkpoint = instant::Instant::now();
+            }
+        }
+        Ok(())
+    }
+
+    /// Saves a checkpoint of the state when the run terminates (only once), unless the
+    /// checkpointing frequency is `Never`.
+    fn save_final_checkpoint(&self) -> Result<(), Error> {
+        if let (Some(checkpoint), Some(state)) = (self.checkpoint.as_ref(), self.state.as_ref()) {
+            if !self.finished && checkpoint.frequency() != CheckpointingFrequency::Never {
+                let snapshots = self.snapshots()?;
+                checkpoint.save_with_snapshots(
+                    &self.solver,
+                    state,
+                    &snapshots,
+                    state.get_iter(),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Takes snapshots of the problem (if enabled) and the observers
+    fn snapshots(&self) -> Result<Snapshots, Error> {
+        let problem = match (self.problem_snapshot, self.problem.problem.as_ref()) {
+            (Some((snapshot, _)), Some(problem)) => Some(snapshot(problem)?),
+            _ => None,
+        };
+        Ok(Snapshots {
+            problem,
+            observers: self.observers.snapshots()?,
+        })
+    }
+
+    /// Loads the checkpoint, sets up timers and the Ctrl-C handler and initializes the solver.
+    fn start(&mut self) -> Result<(), Error> {
         // First, load checkpoint if given.
         if let Some(checkpoint) = self.checkpoint.as_ref() {
-            if let Some((solver, state)) = checkpoint.load()? {
+            if let Some((solver, mut state, snapshots)) = checkpoint.load_with_snapshots()? {
+                // A run which was interrupted manually continues where it stopped
+                if state.get_termination_reason() == Some(&TerminationReason::KeyboardInterrupt) {
+                    state = state.reset_termination();
+                }
                 self.state = Some(state);
                 self.solver = solver;
+                if let (Some((_, restore)), Some(problem), Some(snapshot)) = (
+                    self.problem_snapshot,
+                    self.problem.problem.as_mut(),
+                    snapshots.problem.as_ref(),
+                ) {
+                    restore(problem, snapshot)?;
+                }
+                self.observers.restore_snapshots(&snapshots.observers)?;
             }
         }
-        let total_time = if self.timer {
+        self.total_time = if self.timer {
             Some(instant::Instant::now())
         } else {
             None
         };
-
-        let state = self.state.take().unwrap();
-
-        let interrupt = Arc::new(AtomicBool::new(false));
+        self.last_checkpoint = instant::Instant::now();
 
         if self.ctrlc {
             #[cfg(feature = "ctrlc")]
             {
                 // Set up the Ctrl-C handler
-                let interp = interrupt.clone();
+                let interp = self.interrupt.clone();
                 // This is currently a hack to allow checkpoints to be run again within the
                 // same program (usually not really a use case anyway). Unfortunately, this
                 // means that any subsequent run started afterwards will have not Ctrl-C
@@ -169,12 +528,21 @@ where
         // Only call `init` of `solver` if the current iteration number is 0. This avoids that
         // `init` is called when starting from a checkpoint (because `init` could change the state
         // of the `solver`, which would overwrite the state restored from the checkpoint).
-        let mut state = if state.get_iter() == 0 {
+        let state = self.take_state()?;
+        if state.get_iter() == 0 {
+            if let Err(e) = self.run_derivative_checks(&state) {
+                self.state = Some(state);
+                return Err(e);
+            }
+
             let (mut state, kv) = self.solver.init(&mut self.problem, state)?;
             state.update();
+            state.func_counts(&self.problem);
+            let max_iters = state.get_max_iters();
+            self.state = Some(state);
 
             if !self.observers.is_empty() {
-                let mut logs = kv!("max_iters" => state.get_max_iters(););
+                let mut logs = kv!("max_iters" => max_iters;);
 
                 if let Some(kv) = kv {
                     logs = logs.merge(kv);
@@ -183,92 +551,11 @@ where
                 // Observe after init
                 self.observers.observe_init(S::NAME, &logs)?;
             }
-
-            state.func_counts(&self.problem);
-            state
         } else {
-            state
-        };
-
-        while !interrupt.load(Ordering::SeqCst) {
-            // check first if it has already terminated
-            // This should probably be solved better.
-            // First, check if it isn't already terminated. If it isn't, evaluate the
-            // stopping criteria. If `self.terminate()` is called without the checking
-            // whether it has terminated already, then it may overwrite a termination set
-            // within `next_iter()`!
-            state = if !state.terminated() {
-                let term = self.solver.terminate_internal(&state);
-                if let TerminationStatus::Terminated(reason) = term {
-                    state.terminate_with(reason)
-                } else {
-                    state
-                }
-            } else {
-                state
-            };
-            // Now check once more if the algorithm has terminated. If yes, then break.
-            if state.terminated() {
-                break;
-            }
-
-            // Start time measurement
-            let start = if self.timer {
-                Some(instant::Instant::now())
-            } else {
-                None
-            };
-
-            let (state_t, kv) = self.solver.next_iter(&mut self.problem, state)?;
-            state = state_t;
-
-            state.func_counts(&self.problem);
-
-            // End time measurement
-            let duration = if self.timer {
-                Some(start.unwrap().elapsed())
-            } else {
-                None
-            };
-
-            state.update();
-
-            if !self.observers.is_empty() {
-                let mut log = if let Some(kv) = kv { kv } else { KV::new() };
-
-                if self.timer {
-                    let duration = duration.unwrap();
-                    let tmp = kv!(
-                        "time" => duration.as_secs_f64();
-                    );
-                    log = log.merge(tmp);
-                }
-                self.observers.observe_iter(&state, &log)?;
-            }
-
-            // increment iteration number
-            state.increment_iter();
-
-            if let Some(checkpoint) = self.checkpoint.as_ref() {
-                checkpoint.save_cond(&self.solver, &state, state.get_iter())?;
-            }
-
-            if self.timer {
-                total_time.map(|total_time| state.time(Some(total_time.elapsed())));
-            }
-
-            // Check if termination occurred inside next_iter()
-            if state.terminated() {
-                break;
-            }
-        }
-
-        if interrupt.load(Ordering::SeqCst) {
-            // Solver execution has been interrupted manually
-            state = state.terminate_with(TerminationReason::KeyboardInterrupt);
+            self.state = Some(state);
         }
-
-        Ok(OptimizationResult::new(self.problem, self.solver, state))
+        self.started = true;
+        Ok(())
     }
 
     /// Adds an observer to the executor. Observers are required to implement the
@@ -307,8 +594,44 @@ where
         self
     }
 
+    /// Adds a termination criterion to the executor. Criteria are required to implement the
+    /// [`TerminationCriterion`](`crate::core::termination::TerminationCriterion`) trait. See the
+    /// [`termination`](`crate::core::termination`) module for the available criteria.
+    ///
+    /// Criteria are checked before every iteration, after the stopping criteria of the solver. It
+    /// is possible to add multiple criteria; the run terminates as soon as one of them is met.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{Error, Executor};
+    /// # use argmin::core::termination::MaxFuncCount;
+    /// # use argmin::core::test_utils::{TestSolver, TestProblem};
+    /// #
+    /// # fn main() -> Result<(), Error> {
+    /// # let solver = TestSolver::new();
+    /// # let problem = TestProblem::new();
+    /// #
+    /// let executor = Executor::new(problem, solver)
+    ///     .add_termination(MaxFuncCount::new("cost_count", 100));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn add_termination<C: TerminationCriterion<I> + 'static>(mut self, criterion: C) -> Self {
+        self.terminations.push(Box::new(criterion));
+        self
+    }
+
     /// Configures checkpointing
     ///
+    /// Checkpoints are saved according to the
+    /// [`CheckpointingFrequency`](`crate::core::checkpointing::CheckpointingFrequency`) of
+    /// `checkpoint`. Unless the frequency is `Never`, a final checkpoint is saved when the run
+    /// terminates. If the run is interrupted via Ctrl-C, the final checkpoint records the
+    /// termination reason `KeyboardInterrupt`. It is reset when the run is resumed from this
+    /// checkpoint, so the run continues where it stopped.
+    ///
     /// # Example
     ///
     /// ```
@@ -373,6 +696,59 @@ where
         self
     }
 
+    /// Includes snapshots of the problem in checkpoints.
+    ///
+    /// The problem must implement [`Stateful`]. When resuming from a checkpoint, the internal state
+    /// of the problem is restored, such that the run continues exactly as if it had not been
+    /// interrupted. Together with observers which provide snapshots (see
+    /// [`Observe::snapshot`](`crate::core::observers::Observe::snapshot`)), this allows
+    /// bit-identical continuations of stochastic problems.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{Error, Executor};
+    /// # use argmin::core::checkpointing::{StateSnapshot, Stateful};
+    /// # #[cfg(feature = "serde1")]
+    /// # use argmin::core::checkpointing::{FileCheckpoint, CheckpointingFrequency};
+    /// # use argmin::core::test_utils::{TestSolver, TestProblem};
+    /// #
+    /// # struct CachedProblem(TestProblem);
+    /// #
+    /// # impl Stateful for CachedProblem {
+    /// #     fn snapshot(&self) -> Result<StateSnapshot, Error> {
+    /// #         Ok(StateSnapshot::from_bytes(vec![]))
+    /// #     }
+    /// #
+    /// #     fn restore(&mut self, _snapshot: &StateSnapshot) -> Result<(), Error> {
+    /// #         Ok(())
+    /// #     }
+    /// # }
+    /// #
+    /// # fn main() -> Result<(), Error> {
+    /// # let solver = TestSolver::new();
+    /// # let problem = CachedProblem(TestProblem::new());
+    /// #
+    /// # #[cfg(feature = "serde1")]
+    /// let checkpoint = FileCheckpoint::new(".checkpoints", "cached", CheckpointingFrequency::Every(20));
+    ///
+    /// // `problem` implements `Stateful`
+    /// # #[cfg(feature = "serde1")]
+    /// let executor = Executor::new(problem, solver)
+    ///     .checkpointing(checkpoint)
+    ///     .checkpoint_problem();
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn checkpoint_problem(mut self) -> Self
+    where
+        O: Stateful,
+    {
+        self.problem_snapshot = Some((O::snapshot, O::restore));
+        self
+    }
+
     /// Enables or disables timing of individual iterations (default: enabled).
     ///
     /// # Example
@@ -395,15 +771,163 @@ where
         self.timer = timer;
         self
     }
+
+    /// Compares the gradient of the problem to a finite difference approximation at the initial
+    /// parameter vector before the solver is started (see [`checks`](`crate::core::checks`)).
+    /// `run` returns an error if the relative error of any component exceeds `tol`.
+    ///
+    /// The function evaluations needed for the check are not included in the function evaluation
+    /// counts. The check is skipped when resuming from a checkpoint.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{Error, Executor};
+    /// # use argmin::core::test_utils::{TestSolver, TestProblem};
+    /// #
+    /// # fn main() -> Result<(), Error> {
+    /// # let solver = TestSolver::new();
+    /// # let problem = TestProblem::new();
+    /// #
+    /// let result = Executor::new(problem, solver)
+    ///     .configure(|state| state.param(vec![0.0, 0.0]).max_iters(10))
+    ///     // Check gradient before running the solver
+    ///     .check_gradient(1e-6)
+    ///     .run()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn check_gradient<G>(mut self, tol: I::Float) -> Self
+    where
+        O: CostFunction<Param = I::Param, Output = I::Float>
+            + Gradient<Param = I::Param, Gradient = G>,
+        I::Param: From<Vec<I::Float>>,
+        for<'a> &'a I::Param: IntoIterator<Item = &'a I::Float>,
+        for<'a> &'a G: IntoIterator<Item = &'a I::Float>,
+    {
+        self.derivative_checks
+            .push((executor_check_gradient::<O, I, G>, tol.to_f64().unwrap()));
+        self
+    }
+
+    /// Compares the Hessian of the problem to a finite difference approximation at the initial
+    /// parameter vector before the solver is started (see [`checks`](`crate::core::checks`)).
+    /// `run` returns an error if the relative error of any component exceeds `tol`.
+    ///
+    /// The function evaluations needed for the check are not included in the function evaluation
+    /// counts. The check is skipped when resuming from a checkpoint.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{Error, Executor};
+    /// # use argmin::core::test_utils::{TestSolver, TestProblem};
+    /// #
+    /// # fn main() -> Result<(), Error> {
+    /// # let solver = TestSolver::new();
+    /// # let problem = TestProblem::new();
+    /// #
+    /// let result = Executor::new(problem, solver)
+    ///     .configure(|state| state.param(vec![0.0, 0.0]).max_iters(10))
+    ///     // Check Hessian before running the solver
+    ///     .check_hessian(1e-4)
+    ///     .run()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn check_hessian<H>(mut self, tol: I::Float) -> Self
+    where
+        O: CostFunction<Param = I::Param, Output = I::Float>
+            + Hessian<Param = I::Param, Hessian = H>,
+        I::Param: From<Vec<I::Float>>,
+        for<'a> &'a I::Param: IntoIterator<Item = &'a I::Float>,
+        H: ArgminDot<I::Param, I::Param>,
+    {
+        self.derivative_checks
+            .push((executor_check_hessian::<O, I, H>, tol.to_f64().unwrap()));
+        self
+    }
+
+    /// Compares the Jacobian of the problem to a finite difference approximation at the initial
+    /// parameter vector before the solver is started (see [`checks`](`crate::core::checks`)).
+    /// `run` returns an error if the relative error of any component exceeds `tol`.
+    ///
+    /// The function evaluations needed for the check are not included in the function evaluation
+    /// counts. The check is skipped when resuming from a checkpoint.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{Error, Executor};
+    /// # use argmin::core::test_utils::{TestSolver, TestProblem};
+    /// #
+    /// # fn main() -> Result<(), Error> {
+    /// # let solver = TestSolver::new();
+    /// # let problem = TestProblem::new();
+    /// #
+    /// let executor = Executor::new(problem, solver)
+    ///     .configure(|state| state.param(vec![1.0, 2.0]).max_iters(10))
+    ///     // Check Jacobian before running the solver
+    ///     .check_jacobian(1e-6);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn check_jacobian<U, J>(mut self, tol: I::Float) -> Self
+    where
+        O: Operator<Param = I::Param, Output = U> + Jacobian<Param = I::Param, Jacobian = J>,
+        I::Param: From<Vec<I::Float>>,
+        for<'a> &'a I::Param: IntoIterator<Item = &'a I::Float>,
+        U: ArgminSub<U, U> + ArgminMul<I::Float, U>,
+        for<'a> &'a U: IntoIterator<Item = &'a I::Float>,
+        J: ArgminDot<I::Param, U>,
+    {
+        self.derivative_checks
+            .push((executor_check_jacobian::<O, I, U, J>, tol.to_f64().unwrap()));
+        self
+    }
+
+    /// Runs the derivative checks on a separate instance of `Problem` such that the function
+    /// evaluation counts are not affected.
+    fn run_derivative_checks(&mut self, state: &I) -> Result<(), Error> {
+        if self.derivative_checks.is_empty() {
+            return Ok(());
+        }
+        let mut problem = Problem::new(self.problem.take_problem().unwrap());
+        let result = self
+            .derivative_checks
+            .iter()
+            .try_for_each(|(check, tol)| check(&mut problem, state, *tol));
+        self.problem.problem = problem.take_problem();
+        result
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::core::test_utils::{TestProblem, TestSolver};
-    use crate::core::IterState;
+    use crate::core::{ArgminError, IterState};
+    use crate::solver::neldermead::NelderMead;
     use approx::assert_relative_eq;
 
+    struct Sphere {}
+
+    impl CostFunction for Sphere {
+        type Param = Vec<f64>;
+        type Output = f64;
+
+        fn cost(&self, param: &Self::Param) -> Result<Self::Output, Error> {
+            Ok(param.iter().map(|x| x.powi(2)).sum())
+        }
+    }
+
+    fn nelder_mead() -> NelderMead<Vec<f64>, f64> {
+        NelderMead::new(vec![vec![-1.0, 3.0], vec![2.0, 1.5], vec![2.0, -1.0]])
+    }
+
     #[test]
     fn test_update() {
         let problem = TestProblem::new();
@@ -669,4 +1193,644 @@ mod tests {
         // Delete old checkpointing file
         let _ = std::fs::remove_file(".checkpoints/init_test.arg");
     }
+
+    #[test]
+    fn test_derivative_checks() {
+        // `TestProblem` has a constant cost function but returns the parameter vector as
+        // gradient, therefore the gradient is only consistent at the origin.
+        let result = Executor::new(TestProblem::new(), TestSolver::new())
+            .configure(|state| state.param(vec![0.0f64, 0.0]).max_iters(2))
+            .check_gradient(1e-6)
+            .check_hessian(1e-6)
+            .run()
+            .unwrap();
+        // Evaluations of the checks are not counted
+        assert!(!result.problem.counts.contains_key("cost_count"));
+        assert!(!result.problem.counts.contains_key("gradient_count"));
+        assert!(!result.problem.counts.contains_key("hessian_count"));
+        assert!(result.problem.problem.is_some());
+
+        let result = Executor::new(TestProblem::new(), TestSolver::new())
+            .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(2))
+            .check_gradient(1e-6)
+            .run();
+        assert_error!(
+            result,
+            ArgminError,
+            "Condition violated: \"Derivative check of gradient failed: relative error 1 of \
+             component [0] exceeds tolerance 0.000001 (analytic: 1, approximation: 0)\""
+        );
+
+        // Jacobian of `TestProblem` is inconsistent with its (identity) operator
+        let result = Executor::new(TestProblem::new(), TestSolver::new())
+            .configure(|state| state.param(vec![0.0f64, 0.0]).max_iters(2))
+            .check_jacobian(1e-6)
+            .run();
+        assert!(result.is_err());
+
+        let result = Executor::new(TestProblem::new(), TestSolver::new())
+            .configure(|state| state.max_iters(2))
+            .check_gradient(1e-6)
+            .run();
+        assert_error!(
+            result,
+            ArgminError,
+            "Not initialized: \"Derivative check requires an initial parameter vector.\""
+        );
+    }
+
+    #[test]
+    fn test_step_matches_run() {
+        let mut executor = Executor::new(Sphere {}, nelder_mead())
+            .configure(|state| state.max_iters(20))
+            .ctrlc(false);
+        let mut iters = 0;
+        while let Some(state) = executor.step().unwrap() {
+            iters += 1;
+            assert_eq!(state.get_iter(), iters);
+        }
+        assert_eq!(iters, 20);
+        let stepped = executor.into_result().unwrap();
+
+        let res = Executor::new(Sphere {}, nelder_mead())
+            .configure(|state| state.max_iters(20))
+            .ctrlc(false)
+            .run()
+            .unwrap();
+
+        assert_eq!(stepped.state.get_iter(), res.state.get_iter());
+        assert_eq!(
+            stepped.state.get_best_param().unwrap(),
+            res.state.get_best_param().unwrap()
+        );
+        assert_eq!(
+            stepped.state.get_func_counts()["cost_count"],
+            res.state.get_func_counts()["cost_count"]
+        );
+        assert_eq!(
+            stepped.state.get_termination_reason(),
+            Some(&TerminationReason::MaxItersReached)
+        );
+        assert!(stepped.state.get_time().is_some());
+    }
+
+    #[test]
+    fn test_step_observers() {
+        use crate::core::observers::Observe;
+        use std::sync::Mutex;
+
+        #[derive(Clone, Default)]
+        struct Recorder {
+            calls: Arc<Mutex<Vec<u64>>>,
+        }
+
+        impl<I: State> Observe<I> for Recorder {
+            fn observe_init(&mut self, _name: &str, _kv: &KV) -> Result<(), Error> {
+                self.calls.lock().unwrap().push(u64::MAX);
+                Ok(())
+            }
+
+            fn observe_iter(&mut self, state: &I, _kv: &KV) -> Result<(), Error> {
+                self.calls.lock().unwrap().push(state.get_iter());
+                Ok(())
+            }
+
+            fn observe_final(&mut self, state: &I) -> Result<(), Error> {
+                assert!(state.terminated());
+                self.calls.lock().unwrap().push(u64::MAX - 1);
+                Ok(())
+            }
+        }
+
+        let recorder = Recorder::default();
+        let mut executor = Executor::new(TestProblem::new(), TestSolver::new())
+            .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(10))
+            .add_observer(recorder.clone(), ObserverMode::Always)
+            .ctrlc(false);
+
+        assert!(executor.step().unwrap().is_some());
+        assert_eq!(*recorder.calls.lock().unwrap(), vec![u64::MAX, 0]);
+        assert!(executor.step().unwrap().is_some());
+        assert_eq!(*recorder.calls.lock().unwrap(), vec![u64::MAX, 0, 1]);
+
+        // `observe_final` is called exactly once after termination
+        while executor.step().unwrap().is_some() {}
+        assert!(executor.step().unwrap().is_none());
+        let calls = recorder.calls.lock().unwrap();
+        assert_eq!(calls.len(), 12);
+        assert_eq!(calls[10], 9);
+        assert_eq!(calls[11], u64::MAX - 1);
+    }
+
+    #[test]
+    fn test_step_errors() {
+        use crate::core::observers::Observe;
+
+        // Failing derivative checks keep the state
+        let mut executor = Executor::new(TestProblem::new(), TestSolver::new())
+            .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(5))
+            .check_gradient(1e-6)
+            .ctrlc(false);
+        assert!(executor.step().is_err());
+        assert_eq!(
+            *executor.state().unwrap().get_param().unwrap(),
+            vec![1.0, 0.0]
+        );
+
+        // Failing observers keep the state
+        struct Failing {}
+
+        impl<I: State> Observe<I> for Failing {
+            fn observe_iter(&mut self, _state: &I, _kv: &KV) -> Result<(), Error> {
+                Err(argmin_error!(ConditionViolated, "observer failed"))
+            }
+        }
+
+        let mut executor = Executor::new(TestProblem::new(), TestSolver::new())
+            .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(5))
+            .add_observer(Failing {}, ObserverMode::Always)
+            .ctrlc(false);
+        assert!(executor.step().is_err());
+        assert_eq!(
+            *executor.state().unwrap().get_param().unwrap(),
+            vec![1.0, 0.0]
+        );
+        let res = executor.into_result().unwrap();
+        assert_eq!(*res.state.get_param().unwrap(), vec![1.0, 0.0]);
+
+        // The state is lost if the solver fails, which subsequent steps report as error
+        struct FailingSolver {}
+
+        impl<O> Solver<O, IterState<Vec<f64>, (), (), (), f64>> for FailingSolver {
+            const NAME: &'static str = "FailingSolver";
+
+            fn next_iter(
+                &mut self,
+                _problem: &mut Problem<O>,
+                _state: IterState<Vec<f64>, (), (), (), f64>,
+            ) -> Result<(IterState<Vec<f64>, (), (), (), f64>, Option<KV>), Error> {
+                Err(argmin_error!(ConditionViolated, "solver failed"))
+            }
+        }
+
+        let mut executor = Executor::new(TestProblem::new(), FailingSolver {})
+            .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(5))
+            .ctrlc(false);
+        assert_error!(
+            executor.step(),
+            ArgminError,
+            "Condition violated: \"solver failed\""
+        );
+        let lost = concat!(
+            "Condition violated: \"`Executor`: State was lost due to an error of the solver ",
+            "in a previous step.\""
+        );
+        assert_error!(executor.step(), ArgminError, lost);
+        assert!(executor.state().is_none());
+        assert_error!(executor.into_result(), ArgminError, lost);
+    }
+
+    #[test]
+    fn test_step_modify_state() {
+        let mut executor = Executor::new(TestProblem::new(), TestSolver::new())
+            .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(10))
+            .ctrlc(false);
+
+        let state = executor.step().unwrap().unwrap();
+        *state = state.clone().max_iters(3);
+        while executor.step().unwrap().is_some() {}
+        // Stepping after termination has no effect
+        assert!(executor.step().unwrap().is_none());
+
+        let res = executor.into_result().unwrap();
+        assert_eq!(res.state.get_iter(), 3);
+        assert_eq!(
+            res.state.get_termination_reason(),
+            Some(&TerminationReason::MaxItersReached)
+        );
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut executor = Executor::new(TestProblem::new(), TestSolver::new())
+            .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(5))
+            .ctrlc(false);
+
+        let iters: Vec<u64> = executor
+            .iter()
+            .map(|state| state.unwrap().get_iter())
+            .collect();
+        assert_eq!(iters, vec![1, 2, 3, 4, 5]);
+        assert_eq!(executor.iter().count(), 0);
+
+        // The iterator stops after the first error
+        let mut executor = Executor::new(TestProblem::new(), TestSolver::new())
+            .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(5))
+            .check_gradient(1e-6)
+            .ctrlc(false);
+        let results: Vec<_> = executor.iter().collect();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+    }
+
+    #[test]
+    fn test_add_termination() {
+        use crate::core::termination::{MaxFuncCount, TerminationCriterion};
+
+        /// Terminates after a given number of checks
+        struct Checks(u64);
+
+        impl<I> TerminationCriterion<I> for Checks {
+            fn terminate(&mut self, _state: &I) -> TerminationStatus {
+                self.0 -= 1;
+                if self.0 == 0 {
+                    TerminationStatus::Terminated(TerminationReason::SolverExit(
+                        "Checks".to_string(),
+                    ))
+                } else {
+                    TerminationStatus::NotTerminated
+                }
+            }
+        }
+
+        let res = Executor::new(TestProblem::new(), TestSolver::new())
+            .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(10))
+            .add_termination(Checks(4))
+            .ctrlc(false)
+            .run()
+            .unwrap();
+        assert_eq!(res.state.get_iter(), 3);
+        assert_eq!(
+            res.state.get_termination_reason(),
+            Some(&TerminationReason::SolverExit("Checks".to_string()))
+        );
+
+        // Criteria are checked after the stopping criteria of the solver
+        let res = Executor::new(TestProblem::new(), TestSolver::new())
+            .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(2))
+            .add_termination(MaxFuncCount::new("cost_count", 100))
+            .add_termination(Checks(3))
+            .ctrlc(false)
+            .run()
+            .unwrap();
+        assert_eq!(
+            res.state.get_termination_reason(),
+            Some(&TerminationReason::MaxItersReached)
+        );
+    }
+
+    #[test]
+    fn test_max_func_evals() {
+        let res = Executor::new(Sphere {}, nelder_mead())
+            .configure(|state| state.max_iters(100).max_func_evals("cost_count", 10))
+            .ctrlc(false)
+            .run()
+            .unwrap();
+        let evals = res.state.get_func_counts()["cost_count"];
+        assert!(evals >= 10);
+        // Each iteration of Nelder-Mead requires at most 3 evaluations in 2 dimensions
+        assert!(evals < 13);
+        assert_eq!(
+            res.state.get_termination_reason(),
+            Some(&TerminationReason::MaxFuncCountReached)
+        );
+    }
+
+    #[test]
+    fn test_max_time() {
+        let res = Executor::new(Sphere {}, nelder_mead())
+            .configure(|state| state.max_iters(100).max_time(instant::Duration::ZERO))
+            .ctrlc(false)
+            .run()
+            .unwrap();
+        assert_eq!(res.state.get_iter(), 0);
+        assert_eq!(
+            res.state.get_termination_reason(),
+            Some(&TerminationReason::MaxTimeReached)
+        );
+
+        let res = Executor::new(Sphere {}, nelder_mead())
+            .configure(|state| {
+                state
+                    .max_iters(10)
+                    .max_time(instant::Duration::from_secs(3600))
+            })
+            .ctrlc(false)
+            .run()
+            .unwrap();
+        assert_eq!(
+            res.state.get_termination_reason(),
+            Some(&TerminationReason::MaxItersReached)
+        );
+    }
+
+    /// Records the iteration numbers and termination status of all saved checkpoints
+    struct RecordingCheckpoint {
+        frequency: CheckpointingFrequency,
+        saved: Arc<std::sync::Mutex<Vec<(u64, bool)>>>,
+    }
+
+    impl RecordingCheckpoint {
+        #[allow(clippy::type_complexity)]
+        fn new(
+            frequency: CheckpointingFrequency,
+        ) -> (Self, Arc<std::sync::Mutex<Vec<(u64, bool)>>>) {
+            let saved = Arc::new(std::sync::Mutex::new(vec![]));
+            let checkpoint = RecordingCheckpoint {
+                frequency,
+                saved: saved.clone(),
+            };
+            (checkpoint, saved)
+        }
+    }
+
+    impl<S, I: State> Checkpoint<S, I> for RecordingCheckpoint {
+        fn save(&self, solver: &S, state: &I) -> Result<(), Error> {
+            self.save_iter(solver, state, state.get_iter())
+        }
+
+        fn save_iter(&self, _solver: &S, state: &I, iter: u64) -> Result<(), Error> {
+            self.saved.lock().unwrap().push((iter, state.terminated()));
+            Ok(())
+        }
+
+        fn load(&self) -> Result<Option<(S, I)>, Error> {
+            Ok(None)
+        }
+
+        fn frequency(&self) -> CheckpointingFrequency {
+            self.frequency
+        }
+    }
+
+    #[test]
+    fn test_checkpointing_frequency() {
+        let saved = |frequency: CheckpointingFrequency| -> Vec<(u64, bool)> {
+            let (checkpoint, saved) = RecordingCheckpoint::new(frequency);
+            Executor::new(TestProblem::new(), TestSolver::new())
+                .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(3))
+                .checkpointing(checkpoint)
+                .ctrlc(false)
+                .run()
+                .unwrap();
+            let saved = saved.lock().unwrap().clone();
+            saved
+        };
+
+        let all = vec![(1, false), (2, false), (3, false), (3, true)];
+        assert_eq!(saved(CheckpointingFrequency::Always), all);
+        assert_eq!(
+            saved(CheckpointingFrequency::Every(2)),
+            vec![(2, false), (3, true)]
+        );
+        assert_eq!(
+            saved(CheckpointingFrequency::EveryDuration(
+                instant::Duration::ZERO
+            )),
+            all
+        );
+        assert_eq!(
+            saved(CheckpointingFrequency::EveryDuration(
+                instant::Duration::from_secs(3600)
+            )),
+            vec![(3, true)]
+        );
+        assert_eq!(saved(CheckpointingFrequency::Never), vec![]);
+    }
+
+    #[test]
+    fn test_checkpointing_on_new_best() {
+        // Sets a predefined cost in each iteration
+        struct Plateaus {}
+
+        impl<O> Solver<O, IterState<Vec<f64>, (), (), (), f64>> for Plateaus {
+            const NAME: &'static str = "Plateaus";
+
+            fn next_iter(
+                &mut self,
+                _problem: &mut Problem<O>,
+                state: IterState<Vec<f64>, (), (), (), f64>,
+            ) -> Result<(IterState<Vec<f64>, (), (), (), f64>, Option<KV>), Error> {
+                let cost = [4.0, 4.0, 3.0, 3.0, 2.0][state.get_iter() as usize];
+                Ok((state.cost(cost), None))
+            }
+        }
+
+        let (checkpoint, saved) = RecordingCheckpoint::new(CheckpointingFrequency::OnNewBest);
+        Executor::new(TestProblem::new(), Plateaus {})
+            .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(5))
+            .checkpointing(checkpoint)
+            .ctrlc(false)
+            .run()
+            .unwrap();
+        assert_eq!(
+            *saved.lock().unwrap(),
+            vec![(1, false), (3, false), (5, false), (5, true)]
+        );
+    }
+
+    #[test]
+    fn test_checkpointing_custom_condition() {
+        // Saves a checkpoint whenever the iteration number is a power of two
+        struct PowersOfTwo(RecordingCheckpoint);
+
+        impl<S, I: State> Checkpoint<S, I> for PowersOfTwo {
+            fn save(&self, solver: &S, state: &I) -> Result<(), Error> {
+                self.0.save(solver, state)
+            }
+
+            fn save_cond_with_snapshots(
+                &self,
+                solver: &S,
+                state: &I,
+                _snapshots: &dyn Fn() -> Result<Snapshots, Error>,
+                iter: u64,
+                _since_last: std::time::Duration,
+                _new_best: bool,
+            ) -> Result<bool, Error> {
+                let due = iter.is_power_of_two();
+                if due {
+                    self.save_iter(solver, state, iter)?;
+                }
+                Ok(due)
+            }
+
+            fn load(&self) -> Result<Option<(S, I)>, Error> {
+                Ok(None)
+            }
+
+            fn frequency(&self) -> CheckpointingFrequency {
+                Checkpoint::<S, I>::frequency(&self.0)
+            }
+        }
+
+        let (checkpoint, saved) = RecordingCheckpoint::new(CheckpointingFrequency::Every(100));
+        Executor::new(TestProblem::new(), TestSolver::new())
+            .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(5))
+            .checkpointing(PowersOfTwo(checkpoint))
+            .ctrlc(false)
+            .run()
+            .unwrap();
+        assert_eq!(
+            *saved.lock().unwrap(),
+            vec![(1, false), (2, false), (4, false), (5, true)]
+        );
+    }
+
+    #[test]
+    fn test_checkpointing_interrupt() {
+        let (checkpoint, saved) = RecordingCheckpoint::new(CheckpointingFrequency::Every(10));
+        let mut executor = Executor::new(TestProblem::new(), TestSolver::new())
+            .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(10))
+            .checkpointing(checkpoint)
+            .ctrlc(false);
+        executor.step().unwrap();
+        executor.step().unwrap();
+        executor.interrupt.store(true, Ordering::SeqCst);
+        assert!(executor.step().unwrap().is_none());
+        assert!(executor.step().unwrap().is_none());
+        assert_eq!(
+            executor.state().unwrap().get_termination_reason(),
+            Some(&TerminationReason::KeyboardInterrupt)
+        );
+        // The final checkpoint is saved once and records the interruption
+        assert_eq!(*saved.lock().unwrap(), vec![(2, true)]);
+    }
+
+    /// Resuming from a checkpoint which includes snapshots of the problem and the observers
+    /// continues the run exactly as if it had not been interrupted.
+    #[test]
+    #[cfg(feature = "serde1")]
+    fn test_checkpointing_snapshots() {
+        use crate::core::checkpointing::FileCheckpoint;
+        use std::cell::Cell;
+        use std::sync::Mutex;
+
+        /// Sphere function with noise from an internal random number generator
+        struct NoisySphere {
+            seed: Cell<u64>,
+        }
+
+        impl CostFunction for NoisySphere {
+            type Param = Vec<f64>;
+            type Output = f64;
+
+            fn cost(&self, param: &Self::Param) -> Result<Self::Output, Error> {
+                let seed = self
+                    .seed
+                    .get()
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                self.seed.set(seed);
+                let noise = (seed >> 11) as f64 / (1u64 << 53) as f64;
+                Ok(param.iter().map(|x| x.powi(2)).sum::<f64>() + 0.1 * noise)
+            }
+        }
+
+        impl Stateful for NoisySphere {
+            fn snapshot(&self) -> Result<StateSnapshot, Error> {
+                StateSnapshot::new(&self.seed.get())
+            }
+
+            fn restore(&mut self, snapshot: &StateSnapshot) -> Result<(), Error> {
+                self.seed.set(snapshot.restore()?);
+                Ok(())
+            }
+        }
+
+        /// Records the cost of every iteration
+        #[derive(Clone, Default)]
+        struct Costs(Arc<Mutex<Vec<f64>>>);
+
+        impl Observe<IterState<Vec<f64>, (), (), (), f64>> for Costs {
+            fn observe_iter(
+                &mut self,
+                state: &IterState<Vec<f64>, (), (), (), f64>,
+                _kv: &KV,
+            ) -> Result<(), Error> {
+                self.0.lock().unwrap().push(state.get_cost());
+                Ok(())
+            }
+
+            fn snapshot(&self) -> Result<Option<StateSnapshot>, Error> {
+                Ok(Some(StateSnapshot::new(&*self.0.lock().unwrap())?))
+            }
+
+            fn restore(&mut self, snapshot: &StateSnapshot) -> Result<(), Error> {
+                *self.0.lock().unwrap() = snapshot.restore()?;
+                Ok(())
+            }
+        }
+
+        let bits = |costs: &Costs| -> Vec<u64> {
+            costs
+                .0
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|c| c.to_bits())
+                .collect()
+        };
+        let executor = |checkpoint: FileCheckpoint, costs: Costs| {
+            Executor::new(
+                NoisySphere {
+                    seed: Cell::new(42),
+                },
+                nelder_mead(),
+            )
+            .configure(|state| state.max_iters(8))
+            .add_observer(costs, ObserverMode::Always)
+            .checkpointing(checkpoint)
+            .checkpoint_problem()
+            .ctrlc(false)
+        };
+        let dir = std::env::temp_dir().join("argmin_test_executor_snapshots");
+        let _ = std::fs::remove_dir_all(&dir);
+        let checkpoint = |name: &str| {
+            FileCheckpoint::new(
+                dir.to_str().unwrap(),
+                name,
+                CheckpointingFrequency::Every(100),
+            )
+        };
+
+        // Uninterrupted run
+        let costs = Costs::default();
+        let uninterrupted = executor(checkpoint("uninterrupted"), costs.clone())
+            .run()
+            .unwrap();
+        let expected = bits(&costs);
+        assert_eq!(expected.len(), 8);
+
+        // Interrupted after 3 iterations and resumed with fresh problem and observer
+        let mut interrupted = executor(checkpoint("interrupted"), Costs::default());
+        for _ in 0..3 {
+            interrupted.step().unwrap();
+        }
+        interrupted.interrupt.store(true, Ordering::SeqCst);
+        interrupted.step().unwrap();
+        assert_eq!(
+            interrupted.state().unwrap().get_termination_reason(),
+            Some(&TerminationReason::KeyboardInterrupt)
+        );
+
+        let costs = Costs::default();
+        let resumed = executor(checkpoint("interrupted"), costs.clone())
+            .run()
+            .unwrap();
+        assert_eq!(bits(&costs), expected);
+        assert_eq!(
+            resumed.state.get_termination_reason(),
+            Some(&TerminationReason::MaxItersReached)
+        );
+        assert_eq!(
+            resumed.state.get_best_cost().to_bits(),
+            uninterrupted.state.get_best_cost().to_bits()
+        );
+        assert_eq!(
+            resumed.problem.problem.unwrap().seed.get(),
+            uninterrupted.problem.problem.unwrap().seed.get()
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
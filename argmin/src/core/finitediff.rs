@@ -0,0 +1,1170 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Finite differences
+//!
+//! [`FiniteDiff`] wraps a problem which only implements [`CostFunction`] and/or [`Operator`] and
+//! approximates [`Gradient`], [`Hessian`] and [`Jacobian`] via finite differences.
+//!
+//! The difference scheme is selected via the second type parameter of [`FiniteDiff`]:
+//!
+//! * [`Forward`]: `(f(x + h e_i) - f(x)) / h`
+//! * [`Central`] (default): `(f(x + h e_i) - f(x - h e_i)) / 2h`
+//! * [`ComplexStep`]: `Im(f(x + i h e_i)) / h`. This does not suffer from cancellation errors and
+//!   is therefore accurate up to machine precision, but requires the problem to be evaluated with
+//!   complex parameters via [`ComplexCostFunction`] and [`ComplexOperator`].
+//!
+//! For the forward and central schemes, the step size is scaled with the magnitude of the
+//! individual parameters, i.e. `h_i = step * max(1, |x_i|)`.
+//!
+//! ## References
+//!
+//! Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+//!
+//! Joaquim R. R. A. Martins, Peter Sturdza and Juan J. Alonso (2003). The complex-step derivative
+//! approximation. ACM Transactions on Mathematical Software 29(3), 245-262.
+
+use crate::core::{ArgminFloat, CostFunction, Error, Gradient, Hessian, Jacobian, Operator};
+use argmin_math::{ArgminAdd, ArgminDot, ArgminMul, ArgminSub};
+pub use num_complex::Complex;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Forward differences
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Forward;
+
+/// Central differences
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Central;
+
+/// Complex step approximation
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ComplexStep;
+
+/// Difference scheme used by [`FiniteDiff`]
+pub trait FiniteDiffMode {
+    /// Default step size for first derivatives
+    fn default_step<F: ArgminFloat>() -> F;
+
+    /// Default step size for second derivatives
+    fn default_hessian_step<F: ArgminFloat>() -> F;
+}
+
+/// Difference scheme which only requires evaluations with real parameters
+pub trait RealFiniteDiffMode: FiniteDiffMode {
+    /// Whether central differences are used
+    const CENTRAL: bool;
+}
+
+impl FiniteDiffMode for Forward {
+    fn default_step<F: ArgminFloat>() -> F {
+        F::epsilon().sqrt()
+    }
+
+    fn default_hessian_step<F: ArgminFloat>() -> F {
+        F::epsilon().cbrt()
+    }
+}
+
+impl RealFiniteDiffMode for Forward {
+    const CENTRAL: bool = false;
+}
+
+impl FiniteDiffMode for Central {
+    fn default_step<F: ArgminFloat>() -> F {
+        F::epsilon().cbrt()
+    }
+
+    fn default_hessian_step<F: ArgminFloat>() -> F {
+        F::epsilon().sqrt().sqrt()
+    }
+}
+
+impl RealFiniteDiffMode for Central {
+    const CENTRAL: bool = true;
+}
+
+impl FiniteDiffMode for ComplexStep {
+    fn default_step<F: ArgminFloat>() -> F {
+        float!(1e-20)
+    }
+
+    fn default_hessian_step<F: ArgminFloat>() -> F {
+        F::epsilon().cbrt()
+    }
+}
+
+/// Defines the computation of the cost function for complex parameters.
+///
+/// Required by [`FiniteDiff`] in [`ComplexStep`] mode. The implementation must be the analytic
+/// continuation of the cost function, which is typically obtained by simply evaluating the same
+/// expression with complex numbers. Functions such as `abs` must be avoided.
+///
+/// # Example
+///
+/// ```
+/// use argmin::core::{finitediff::{Complex, ComplexCostFunction}, Error};
+///
+/// struct Rosenbrock {}
+///
+/// impl ComplexCostFunction for Rosenbrock {
+///     type Param = Vec<Complex<f64>>;
+///     type Float = f64;
+///
+///     fn cost_complex(&self, p: &Self::Param) -> Result<Complex<f64>, Error> {
+///         Ok((1.0 - p[0]).powi(2) + 100.0 * (p[1] - p[0].powi(2)).powi(2))
+///     }
+/// }
+/// ```
+pub trait ComplexCostFunction {
+    /// Type of the complex parameter vector
+    type Param;
+    /// Precision of floats
+    type Float: ArgminFloat;
+
+    /// Compute cost function for complex parameters
+    fn cost_complex(&self, param: &Self::Param) -> Result<Complex<Self::Float>, Error>;
+}
+
+/// Defines the application of an operator to a complex parameter vector.
+///
+/// Required by [`FiniteDiff`] in [`ComplexStep`] mode. As for [`ComplexCostFunction`], the
+/// implementation must be the analytic continuation of the operator.
+///
+/// # Example
+///
+/// ```
+/// use argmin::core::{finitediff::{Complex, ComplexOperator}, Error};
+///
+/// struct Model {}
+///
+/// impl ComplexOperator for Model {
+///     type Param = Vec<Complex<f64>>;
+///     type Output = Vec<Complex<f64>>;
+///
+///     fn apply_complex(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+///         Ok(vec![p[0] * p[1], p[0].exp()])
+///     }
+/// }
+/// ```
+pub trait ComplexOperator {
+    /// Type of the complex parameter vector
+    type Param;
+    /// Type of the return value of the operator
+    type Output;
+
+    /// Applies the operator to complex parameters
+    fn apply_complex(&self, param: &Self::Param) -> Result<Self::Output, Error>;
+}
+
+/// # Finite difference derivatives
+///
+/// Wraps a problem and implements [`Gradient`] and [`Hessian`] based on its [`CostFunction`] as
+/// well as [`Jacobian`] based on its [`Operator`]. `CostFunction` and `Operator` themselves are
+/// passed through to the wrapped problem.
+///
+/// `M` is the difference scheme ([`Central`] by default, see the [module
+/// documentation](`crate::core::finitediff`)) and `H` is the type of the Hessian or Jacobian,
+/// which defaults to `Vec<Vec<f64>>` and can be changed via
+/// [`with_matrix_type`](`FiniteDiff::with_matrix_type`).
+///
+/// Parameter vectors need to be convertible from `Vec<F>` and iterable by reference. This holds for
+/// `Vec<F>`, `ndarray::Array1<F>` and `nalgebra::DVector<F>`, but not for statically sized nalgebra
+/// vectors. Hessians and Jacobians are assembled via outer products ([`ArgminDot`]), which requires
+/// the corresponding backend of `argmin-math` to be enabled.
+///
+/// The evaluations of the wrapped problem are counted separately from the evaluations of the
+/// approximated derivatives, labeled `finitediff_cost_count` and `finitediff_operator_count` in
+/// the function evaluation counts of [`Problem`](`crate::core::Problem`).
+///
+/// # Example
+///
+/// ```
+/// use argmin::core::{CostFunction, Error, FiniteDiff, Gradient};
+///
+/// struct Paraboloid {}
+///
+/// impl CostFunction for Paraboloid {
+///     type Param = Vec<f64>;
+///     type Output = f64;
+///
+///     fn cost(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+///         Ok(p[0].powi(2) + 2.0 * p[1].powi(2))
+///     }
+/// }
+///
+/// let problem = FiniteDiff::new(Paraboloid {}).forward();
+///
+/// let gradient = problem.gradient(&vec![1.0, 1.0])?;
+/// # assert!((gradient[0] - 2.0).abs() < 1e-6);
+/// # assert!((gradient[1] - 4.0).abs() < 1e-6);
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug)]
+pub struct FiniteDiff<O, M = Central, H = Vec<Vec<f64>>> {
+    /// Wrapped problem
+    problem: O,
+    /// Step size for first derivatives
+    step: Option<f64>,
+    /// Step size for second derivatives
+    hessian_step: Option<f64>,
+    /// Number of evaluations of the cost function
+    cost_count: AtomicU64,
+    /// Number of evaluations of the operator
+    operator_count: AtomicU64,
+    _types: PhantomData<(M, H)>,
+}
+
+impl<O> FiniteDiff<O> {
+    /// Construct a new instance of `FiniteDiff` using central differences
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::FiniteDiff;
+    /// # struct UserDefinedProblem {}
+    /// let problem = FiniteDiff::new(UserDefinedProblem {});
+    /// ```
+    pub fn new(problem: O) -> Self {
+        FiniteDiff {
+            problem,
+            step: None,
+            hessian_step: None,
+            cost_count: AtomicU64::new(0),
+            operator_count: AtomicU64::new(0),
+            _types: PhantomData,
+        }
+    }
+}
+
+impl<O, M, H> FiniteDiff<O, M, H> {
+    fn convert<M2, H2>(self) -> FiniteDiff<O, M2, H2> {
+        FiniteDiff {
+            problem: self.problem,
+            step: self.step,
+            hessian_step: self.hessian_step,
+            cost_count: self.cost_count,
+            operator_count: self.operator_count,
+            _types: PhantomData,
+        }
+    }
+
+    /// Use forward differences
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::FiniteDiff;
+    /// # struct UserDefinedProblem {}
+    /// let problem = FiniteDiff::new(UserDefinedProblem {}).forward();
+    /// ```
+    #[must_use]
+    pub fn forward(self) -> FiniteDiff<O, Forward, H> {
+        self.convert()
+    }
+
+    /// Use central differences (default)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::FiniteDiff;
+    /// # struct UserDefinedProblem {}
+    /// let problem = FiniteDiff::new(UserDefinedProblem {}).central();
+    /// ```
+    #[must_use]
+    pub fn central(self) -> FiniteDiff<O, Central, H> {
+        self.convert()
+    }
+
+    /// Use the complex step approximation. The wrapped problem needs to implement
+    /// [`ComplexCostFunction`] and/or [`ComplexOperator`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::FiniteDiff;
+    /// # struct UserDefinedProblem {}
+    /// let problem = FiniteDiff::new(UserDefinedProblem {}).complex_step();
+    /// ```
+    #[must_use]
+    pub fn complex_step(self) -> FiniteDiff<O, ComplexStep, H> {
+        self.convert()
+    }
+
+    /// Set the type of the Hessian and Jacobian. Defaults to `Vec<Vec<f64>>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::FiniteDiff;
+    /// # struct UserDefinedProblem {}
+    /// let problem = FiniteDiff::new(UserDefinedProblem {}).with_matrix_type::<Vec<Vec<f32>>>();
+    /// ```
+    #[must_use]
+    pub fn with_matrix_type<H2>(self) -> FiniteDiff<O, M, H2> {
+        self.convert()
+    }
+
+    /// Set the step size used for gradients and Jacobians. For forward and central differences it
+    /// is scaled by `max(1, |x_i|)`. Must be positive.
+    ///
+    /// Defaults to `sqrt(EPSILON)` for forward differences, `cbrt(EPSILON)` for central
+    /// differences and `1e-20` for the complex step approximation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{Error, FiniteDiff};
+    /// # struct UserDefinedProblem {}
+    /// let problem = FiniteDiff::new(UserDefinedProblem {}).with_step_size(1e-6)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn with_step_size(mut self, step: f64) -> Result<Self, Error> {
+        if step <= 0.0 || !step.is_finite() {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`FiniteDiff`: step size must be positive and finite."
+            ));
+        }
+        self.step = Some(step);
+        Ok(self)
+    }
+
+    /// Set the step size used for Hessians. It is scaled by `max(1, |x_i|)`. In complex step
+    /// mode, this is the step of the (central) real difference of the complex step gradient.
+    /// Must be positive.
+    ///
+    /// Defaults to `cbrt(EPSILON)` for forward differences and the complex step approximation and
+    /// to `EPSILON^(1/4)` for central differences.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{Error, FiniteDiff};
+    /// # struct UserDefinedProblem {}
+    /// let problem = FiniteDiff::new(UserDefinedProblem {}).with_hessian_step_size(1e-4)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn with_hessian_step_size(mut self, step: f64) -> Result<Self, Error> {
+        if step <= 0.0 || !step.is_finite() {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`FiniteDiff`: Hessian step size must be positive and finite."
+            ));
+        }
+        self.hessian_step = Some(step);
+        Ok(self)
+    }
+
+    /// Returns a reference to the wrapped problem
+    pub fn inner(&self) -> &O {
+        &self.problem
+    }
+
+    /// Returns the wrapped problem
+    pub fn into_inner(self) -> O {
+        self.problem
+    }
+
+    fn step<F: ArgminFloat>(&self, default: F) -> F {
+        self.step.map(|step| float!(step)).unwrap_or(default)
+    }
+
+    fn hessian_step<F: ArgminFloat>(&self, default: F) -> F {
+        self.hessian_step
+            .map(|step| float!(step))
+            .unwrap_or(default)
+    }
+
+    /// Returns and resets the number of evaluations of the wrapped problem
+    fn take_counts(&self) -> Vec<(&'static str, u64)> {
+        [
+            ("finitediff_cost_count", &self.cost_count),
+            ("finitediff_operator_count", &self.operator_count),
+        ]
+        .into_iter()
+        .map(|(name, count)| (name, count.swap(0, Ordering::Relaxed)))
+        .filter(|(_, count)| *count > 0)
+        .collect()
+    }
+
+    fn eval_cost<P, F>(&self, x: Vec<F>) -> Result<F, Error>
+    where
+        O: CostFunction<Param = P, Output = F>,
+        P: From<Vec<F>>,
+    {
+        self.cost_count.fetch_add(1, Ordering::Relaxed);
+        self.problem.cost(&P::from(x))
+    }
+
+    fn eval_cost_complex<CP, F>(&self, x: Vec<Complex<F>>) -> Result<Complex<F>, Error>
+    where
+        O: ComplexCostFunction<Param = CP, Float = F>,
+        CP: From<Vec<Complex<F>>>,
+        F: ArgminFloat,
+    {
+        self.cost_count.fetch_add(1, Ordering::Relaxed);
+        self.problem.cost_complex(&CP::from(x))
+    }
+
+    fn eval_apply<P, U, F>(&self, x: Vec<F>) -> Result<U, Error>
+    where
+        O: Operator<Param = P, Output = U>,
+        P: From<Vec<F>>,
+    {
+        self.operator_count.fetch_add(1, Ordering::Relaxed);
+        self.problem.apply(&P::from(x))
+    }
+
+    fn eval_apply_complex<CP, CU, F>(&self, x: Vec<Complex<F>>) -> Result<CU, Error>
+    where
+        O: ComplexOperator<Param = CP, Output = CU>,
+        CP: From<Vec<Complex<F>>>,
+    {
+        self.operator_count.fetch_add(1, Ordering::Relaxed);
+        self.problem.apply_complex(&CP::from(x))
+    }
+
+    /// Imaginary part of the cost function at `x + i h e_i`, divided by `h`
+    fn complex_derivative<CP, F>(&self, x: &[F], i: usize, h: F) -> Result<F, Error>
+    where
+        O: ComplexCostFunction<Param = CP, Float = F>,
+        CP: From<Vec<Complex<F>>>,
+        F: ArgminFloat,
+    {
+        let mut z: Vec<Complex<F>> = x.iter().map(|xi| Complex::new(*xi, F::zero())).collect();
+        z[i].im = h;
+        Ok(self.eval_cost_complex(z)?.im / h)
+    }
+}
+
+impl<O: Clone, M, H> Clone for FiniteDiff<O, M, H> {
+    fn clone(&self) -> Self {
+        FiniteDiff {
+            problem: self.problem.clone(),
+            step: self.step,
+            hessian_step: self.hessian_step,
+            cost_count: AtomicU64::new(self.cost_count.load(Ordering::Relaxed)),
+            operator_count: AtomicU64::new(self.operator_count.load(Ordering::Relaxed)),
+            _types: PhantomData,
+        }
+    }
+}
+
+/// Step sizes `h_i = step * max(1, |x_i|)`
+fn scaled_steps<F: ArgminFloat>(x: &[F], step: F) -> Vec<F> {
+    x.iter().map(|xi| step * xi.abs().max(F::one())).collect()
+}
+
+/// Returns `x` with `h` added to the entries indicated by `shifts`
+fn perturb<F: ArgminFloat>(x: &[F], shifts: &[(usize, F)]) -> Vec<F> {
+    let mut x = x.to_vec();
+    for &(i, h) in shifts {
+        x[i] = x[i] + h;
+    }
+    x
+}
+
+/// Unit vector `e_i` of length `n`
+fn unit<P: From<Vec<F>>, F: ArgminFloat>(n: usize, i: usize) -> P {
+    let mut e = vec![F::zero(); n];
+    e[i] = F::one();
+    P::from(e)
+}
+
+/// Assembles a matrix as the sum of the outer products `a_i b_i^T`
+fn sum_outer<A, B, H>(pairs: Vec<(A, B)>) -> Result<H, Error>
+where
+    A: ArgminDot<B, H>,
+    H: ArgminAdd<H, H>,
+{
+    pairs
+        .iter()
+        .map(|(a, b)| a.dot(b))
+        .reduce(|acc, m| acc.add(&m))
+        .ok_or_else(argmin_error_closure!(
+            InvalidParameter,
+            "`FiniteDiff`: parameter vector must not be empty."
+        ))
+}
+
+/// Assembles a symmetric matrix from its rows
+fn assemble_symmetric<P, H, F>(rows: Vec<Vec<F>>) -> Result<H, Error>
+where
+    P: From<Vec<F>> + ArgminDot<P, H>,
+    H: ArgminAdd<H, H>,
+    F: ArgminFloat,
+{
+    let n = rows.len();
+    sum_outer(
+        rows.into_iter()
+            .enumerate()
+            .map(|(i, row)| (unit::<P, F>(n, i), P::from(row)))
+            .collect(),
+    )
+}
+
+/// Approximates the gradient of `cost` at `x` via forward or central differences
+pub(crate) fn gradient_real<F, C>(
+    x: &[F],
+    step: F,
+    central: bool,
+    mut cost: C,
+) -> Result<Vec<F>, Error>
+where
+    F: ArgminFloat,
+    C: FnMut(Vec<F>) -> Result<F, Error>,
+{
+    let h = scaled_steps(x, step);
+    let f0 = if central {
+        None
+    } else {
+        Some(cost(x.to_vec())?)
+    };
+    (0..x.len())
+        .map(|i| {
+            let fp = cost(perturb(x, &[(i, h[i])]))?;
+            Ok(match f0 {
+                Some(f0) => (fp - f0) / h[i],
+                None => {
+                    let fm = cost(perturb(x, &[(i, -h[i])]))?;
+                    (fp - fm) / (float!(2.0) * h[i])
+                }
+            })
+        })
+        .collect()
+}
+
+/// Approximates the Hessian of `cost` at `x` via forward or central differences. Returns the rows
+/// of the Hessian.
+pub(crate) fn hessian_real<F, C>(
+    x: &[F],
+    step: F,
+    central: bool,
+    mut cost: C,
+) -> Result<Vec<Vec<F>>, Error>
+where
+    F: ArgminFloat,
+    C: FnMut(Vec<F>) -> Result<F, Error>,
+{
+    let n = x.len();
+    let h = scaled_steps(x, step);
+    let f0 = cost(x.to_vec())?;
+    let mut hessian = vec![vec![F::zero(); n]; n];
+    if central {
+        for i in 0..n {
+            let fp = cost(perturb(x, &[(i, h[i]), (i, h[i])]))?;
+            let fm = cost(perturb(x, &[(i, -h[i]), (i, -h[i])]))?;
+            hessian[i][i] = (fp - float!(2.0) * f0 + fm) / (float!(4.0) * h[i] * h[i]);
+            for j in 0..i {
+                let fpp = cost(perturb(x, &[(i, h[i]), (j, h[j])]))?;
+                let fpm = cost(perturb(x, &[(i, h[i]), (j, -h[j])]))?;
+                let fmp = cost(perturb(x, &[(i, -h[i]), (j, h[j])]))?;
+                let fmm = cost(perturb(x, &[(i, -h[i]), (j, -h[j])]))?;
+                hessian[i][j] = (fpp - fpm - fmp + fmm) / (float!(4.0) * h[i] * h[j]);
+                hessian[j][i] = hessian[i][j];
+            }
+        }
+    } else {
+        let fi = (0..n)
+            .map(|i| cost(perturb(x, &[(i, h[i])])))
+            .collect::<Result<Vec<F>, Error>>()?;
+        for i in 0..n {
+            for j in 0..=i {
+                let fij = cost(perturb(x, &[(i, h[i]), (j, h[j])]))?;
+                hessian[i][j] = (fij - fi[i] - fi[j] + f0) / (h[i] * h[j]);
+                hessian[j][i] = hessian[i][j];
+            }
+        }
+    }
+    Ok(hessian)
+}
+
+/// Approximates the columns of the Jacobian of `apply` at `x` via forward or central differences
+pub(crate) fn jacobian_columns_real<F, U, C>(
+    x: &[F],
+    step: F,
+    central: bool,
+    mut apply: C,
+) -> Result<Vec<U>, Error>
+where
+    F: ArgminFloat,
+    U: ArgminSub<U, U> + ArgminMul<F, U>,
+    C: FnMut(Vec<F>) -> Result<U, Error>,
+{
+    let h = scaled_steps(x, step);
+    let u0 = if central {
+        None
+    } else {
+        Some(apply(x.to_vec())?)
+    };
+    (0..x.len())
+        .map(|j| {
+            let up = apply(perturb(x, &[(j, h[j])]))?;
+            Ok(match u0.as_ref() {
+                Some(u0) => up.sub(u0).mul(&(F::one() / h[j])),
+                None => {
+                    let um = apply(perturb(x, &[(j, -h[j])]))?;
+                    up.sub(&um).mul(&(F::one() / (float!(2.0) * h[j])))
+                }
+            })
+        })
+        .collect()
+}
+
+impl<O, M, H> CostFunction for FiniteDiff<O, M, H>
+where
+    O: CostFunction,
+{
+    type Param = O::Param;
+    type Output = O::Output;
+
+    fn cost(&self, param: &Self::Param) -> Result<Self::Output, Error> {
+        self.problem.cost(param)
+    }
+}
+
+impl<O, M, H> Operator for FiniteDiff<O, M, H>
+where
+    O: Operator,
+{
+    type Param = O::Param;
+    type Output = O::Output;
+
+    fn apply(&self, param: &Self::Param) -> Result<Self::Output, Error> {
+        self.problem.apply(param)
+    }
+}
+
+impl<O, M, H, P, F> Gradient for FiniteDiff<O, M, H>
+where
+    O: CostFunction<Param = P, Output = F>,
+    M: RealFiniteDiffMode,
+    P: From<Vec<F>>,
+    for<'a> &'a P: IntoIterator<Item = &'a F>,
+    F: ArgminFloat,
+{
+    type Param = P;
+    type Gradient = P;
+
+    fn gradient(&self, param: &Self::Param) -> Result<Self::Gradient, Error> {
+        let x: Vec<F> = param.into_iter().cloned().collect();
+        let gradient = gradient_real(&x, self.step(M::default_step()), M::CENTRAL, |x| {
+            self.eval_cost(x)
+        })?;
+        Ok(P::from(gradient))
+    }
+
+    fn take_internal_counts(&self) -> Vec<(&'static str, u64)> {
+        self.take_counts()
+    }
+}
+
+impl<O, H, P, CP, F> Gradient for FiniteDiff<O, ComplexStep, H>
+where
+    O: CostFunction<Param = P, Output = F> + ComplexCostFunction<Param = CP, Float = F>,
+    P: From<Vec<F>>,
+    for<'a> &'a P: IntoIterator<Item = &'a F>,
+    CP: From<Vec<Complex<F>>>,
+    F: ArgminFloat,
+{
+    type Param = P;
+    type Gradient = P;
+
+    fn gradient(&self, param: &Self::Param) -> Result<Self::Gradient, Error> {
+        let x: Vec<F> = param.into_iter().cloned().collect();
+        let h = self.step(ComplexStep::default_step());
+        let gradient = (0..x.len())
+            .map(|i| self.complex_derivative(&x, i, h))
+            .collect::<Result<Vec<F>, Error>>()?;
+        Ok(P::from(gradient))
+    }
+
+    fn take_internal_counts(&self) -> Vec<(&'static str, u64)> {
+        self.take_counts()
+    }
+}
+
+impl<O, M, H, P, F> Hessian for FiniteDiff<O, M, H>
+where
+    O: CostFunction<Param = P, Output = F>,
+    M: RealFiniteDiffMode,
+    P: From<Vec<F>> + ArgminDot<P, H>,
+    for<'a> &'a P: IntoIterator<Item = &'a F>,
+    H: ArgminAdd<H, H>,
+    F: ArgminFloat,
+{
+    type Param = P;
+    type Hessian = H;
+
+    fn hessian(&self, param: &Self::Param) -> Result<Self::Hessian, Error> {
+        let x: Vec<F> = param.into_iter().cloned().collect();
+        let step = self.hessian_step(M::default_hessian_step());
+        let hessian = hessian_real(&x, step, M::CENTRAL, |x| self.eval_cost(x))?;
+        assemble_symmetric::<P, H, F>(hessian)
+    }
+
+    fn take_internal_counts(&self) -> Vec<(&'static str, u64)> {
+        self.take_counts()
+    }
+}
+
+impl<O, H, P, CP, F> Hessian for FiniteDiff<O, ComplexStep, H>
+where
+    O: CostFunction<Param = P, Output = F> + ComplexCostFunction<Param = CP, Float = F>,
+    P: From<Vec<F>> + ArgminDot<P, H>,
+    for<'a> &'a P: IntoIterator<Item = &'a F>,
+    CP: From<Vec<Complex<F>>>,
+    H: ArgminAdd<H, H>,
+    F: ArgminFloat,
+{
+    type Param = P;
+    type Hessian = H;
+
+    fn hessian(&self, param: &Self::Param) -> Result<Self::Hessian, Error> {
+        let x: Vec<F> = param.into_iter().cloned().collect();
+        let n = x.len();
+        let h = self.step(ComplexStep::default_step());
+        let delta = scaled_steps(&x, self.hessian_step(ComplexStep::default_hessian_step()));
+        // Central differences of the complex step gradient
+        let mut hessian = vec![vec![F::zero(); n]; n];
+        for j in 0..n {
+            let xp = perturb(&x, &[(j, delta[j])]);
+            let xm = perturb(&x, &[(j, -delta[j])]);
+            for (i, row) in hessian.iter_mut().enumerate() {
+                let gp = self.complex_derivative(&xp, i, h)?;
+                let gm = self.complex_derivative(&xm, i, h)?;
+                row[j] = (gp - gm) / (float!(2.0) * delta[j]);
+            }
+        }
+        let hessian = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| (hessian[i][j] + hessian[j][i]) / float!(2.0))
+                    .collect()
+            })
+            .collect();
+        assemble_symmetric::<P, H, F>(hessian)
+    }
+
+    fn take_internal_counts(&self) -> Vec<(&'static str, u64)> {
+        self.take_counts()
+    }
+}
+
+impl<O, M, H, P, U, F> Jacobian for FiniteDiff<O, M, H>
+where
+    O: Operator<Param = P, Output = U>,
+    M: RealFiniteDiffMode,
+    P: From<Vec<F>>,
+    for<'a> &'a P: IntoIterator<Item = &'a F>,
+    U: ArgminSub<U, U> + ArgminMul<F, U> + ArgminDot<P, H>,
+    H: ArgminAdd<H, H>,
+    F: ArgminFloat,
+{
+    type Param = P;
+    type Jacobian = H;
+
+    fn jacobian(&self, param: &Self::Param) -> Result<Self::Jacobian, Error> {
+        let x: Vec<F> = param.into_iter().cloned().collect();
+        let n = x.len();
+        let columns = jacobian_columns_real(&x, self.step(M::default_step()), M::CENTRAL, |x| {
+            self.eval_apply(x)
+        })?
+        .into_iter()
+        .enumerate()
+        .map(|(j, column)| (column, unit::<P, F>(n, j)))
+        .collect();
+        sum_outer(columns)
+    }
+
+    fn take_internal_counts(&self) -> Vec<(&'static str, u64)> {
+        self.take_counts()
+    }
+}
+
+impl<O, H, P, U, CP, CU, F> Jacobian for FiniteDiff<O, ComplexStep, H>
+where
+    O: Operator<Param = P, Output = U> + ComplexOperator<Param = CP, Output = CU>,
+    P: From<Vec<F>>,
+    for<'a> &'a P: IntoIterator<Item = &'a F>,
+    U: From<Vec<F>> + ArgminDot<P, H>,
+    CP: From<Vec<Complex<F>>>,
+    for<'a> &'a CU: IntoIterator<Item = &'a Complex<F>>,
+    H: ArgminAdd<H, H>,
+    F: ArgminFloat,
+{
+    type Param = P;
+    type Jacobian = H;
+
+    fn jacobian(&self, param: &Self::Param) -> Result<Self::Jacobian, Error> {
+        let x: Vec<F> = param.into_iter().cloned().collect();
+        let n = x.len();
+        let h = self.step(ComplexStep::default_step());
+        // Columns of the Jacobian
+        let columns = (0..n)
+            .map(|j| {
+                let mut z: Vec<Complex<F>> =
+                    x.iter().map(|xi| Complex::new(*xi, F::zero())).collect();
+                z[j].im = h;
+                let u = self.eval_apply_complex(z)?;
+                let column: Vec<F> = (&u).into_iter().map(|ui| ui.im / h).collect();
+                Ok((U::from(column), unit::<P, F>(n, j)))
+            })
+            .collect::<Result<Vec<(U, P)>, Error>>()?;
+        sum_outer(columns)
+    }
+
+    fn take_internal_counts(&self) -> Vec<(&'static str, u64)> {
+        self.take_counts()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{test_utils::TestProblem, ArgminError, Problem};
+    use approx::assert_relative_eq;
+
+    test_trait_impl!(finitediff, FiniteDiff<TestProblem>);
+
+    test_trait_impl!(finitediff_forward, FiniteDiff<TestProblem, Forward>);
+
+    test_trait_impl!(finitediff_complex_step, FiniteDiff<TestProblem, ComplexStep>);
+
+    /// f(x) = x_0^2 x_1 + exp(x_1) + sin(x_0) and u(x) = (x_0 x_1, exp(x_0), x_1^2)
+    #[derive(Clone)]
+    struct Model {}
+
+    impl CostFunction for Model {
+        type Param = Vec<f64>;
+        type Output = f64;
+
+        fn cost(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+            Ok(p[0].powi(2) * p[1] + p[1].exp() + p[0].sin())
+        }
+    }
+
+    impl ComplexCostFunction for Model {
+        type Param = Vec<Complex<f64>>;
+        type Float = f64;
+
+        fn cost_complex(&self, p: &Self::Param) -> Result<Complex<f64>, Error> {
+            Ok(p[0].powi(2) * p[1] + p[1].exp() + p[0].sin())
+        }
+    }
+
+    impl Operator for Model {
+        type Param = Vec<f64>;
+        type Output = Vec<f64>;
+
+        fn apply(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+            Ok(vec![p[0] * p[1], p[0].exp(), p[1].powi(2)])
+        }
+    }
+
+    impl ComplexOperator for Model {
+        type Param = Vec<Complex<f64>>;
+        type Output = Vec<Complex<f64>>;
+
+        fn apply_complex(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+            Ok(vec![p[0] * p[1], p[0].exp(), p[1].powi(2)])
+        }
+    }
+
+    fn param() -> Vec<f64> {
+        vec![1.5, -0.5]
+    }
+
+    fn expected_gradient(p: &[f64]) -> Vec<f64> {
+        vec![2.0 * p[0] * p[1] + p[0].cos(), p[0].powi(2) + p[1].exp()]
+    }
+
+    fn expected_hessian(p: &[f64]) -> Vec<Vec<f64>> {
+        vec![
+            vec![2.0 * p[1] - p[0].sin(), 2.0 * p[0]],
+            vec![2.0 * p[0], p[1].exp()],
+        ]
+    }
+
+    fn expected_jacobian(p: &[f64]) -> Vec<Vec<f64>> {
+        vec![
+            vec![p[1], p[0]],
+            vec![p[0].exp(), 0.0],
+            vec![0.0, 2.0 * p[1]],
+        ]
+    }
+
+    fn assert_matrix_eq(a: &[Vec<f64>], b: &[Vec<f64>], epsilon: f64) {
+        assert_eq!(a.len(), b.len());
+        for (ra, rb) in a.iter().zip(b.iter()) {
+            assert_eq!(ra.len(), rb.len());
+            for (x, y) in ra.iter().zip(rb.iter()) {
+                assert_relative_eq!(*x, *y, epsilon = epsilon);
+            }
+        }
+    }
+
+    #[test]
+    fn test_new() {
+        let fd = FiniteDiff::new(Model {});
+        let FiniteDiff {
+            problem: _,
+            step,
+            hessian_step,
+            cost_count,
+            operator_count,
+            _types,
+        } = fd;
+        assert!(step.is_none());
+        assert!(hessian_step.is_none());
+        assert_eq!(cost_count.load(Ordering::Relaxed), 0);
+        assert_eq!(operator_count.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_with_step_size() {
+        let fd = FiniteDiff::new(Model {})
+            .with_step_size(1e-4)
+            .unwrap()
+            .with_hessian_step_size(1e-3)
+            .unwrap()
+            .forward();
+        assert_eq!(fd.step.unwrap().to_ne_bytes(), 1e-4f64.to_ne_bytes());
+        assert_eq!(
+            fd.hessian_step.unwrap().to_ne_bytes(),
+            1e-3f64.to_ne_bytes()
+        );
+
+        for step in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert_error!(
+                FiniteDiff::new(Model {}).with_step_size(step),
+                ArgminError,
+                "Invalid parameter: \"`FiniteDiff`: step size must be positive and finite.\""
+            );
+            assert_error!(
+                FiniteDiff::new(Model {}).with_hessian_step_size(step),
+                ArgminError,
+                "Invalid parameter: \"`FiniteDiff`: Hessian step size must be positive and finite.\""
+            );
+        }
+    }
+
+    #[test]
+    fn test_gradient() {
+        let p = param();
+        let expected = expected_gradient(&p);
+        let forward = FiniteDiff::new(Model {}).forward().gradient(&p).unwrap();
+        let central = FiniteDiff::new(Model {}).gradient(&p).unwrap();
+        let complex = FiniteDiff::new(Model {})
+            .complex_step()
+            .gradient(&p)
+            .unwrap();
+        for i in 0..2 {
+            assert_relative_eq!(forward[i], expected[i], epsilon = 1e-6);
+            assert_relative_eq!(central[i], expected[i], epsilon = 1e-9);
+            assert_relative_eq!(complex[i], expected[i], epsilon = 1e-14);
+        }
+    }
+
+    #[test]
+    fn test_gradient_step_size() {
+        let p = param();
+        let expected = expected_gradient(&p);
+        let coarse = FiniteDiff::new(Model {})
+            .forward()
+            .with_step_size(1e-2)
+            .unwrap()
+            .gradient(&p)
+            .unwrap();
+        let fine = FiniteDiff::new(Model {}).forward().gradient(&p).unwrap();
+        assert!((coarse[0] - expected[0]).abs() > 1e-4);
+        assert!((fine[0] - expected[0]).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_hessian() {
+        let p = param();
+        let expected = expected_hessian(&p);
+        let forward: Vec<Vec<f64>> = FiniteDiff::new(Model {}).forward().hessian(&p).unwrap();
+        let central: Vec<Vec<f64>> = FiniteDiff::new(Model {}).hessian(&p).unwrap();
+        let complex: Vec<Vec<f64>> = FiniteDiff::new(Model {})
+            .complex_step()
+            .hessian(&p)
+            .unwrap();
+        assert_matrix_eq(&forward, &expected, 1e-4);
+        assert_matrix_eq(&central, &expected, 1e-7);
+        assert_matrix_eq(&complex, &expected, 1e-9);
+    }
+
+    #[test]
+    fn test_jacobian() {
+        let p = param();
+        let expected = expected_jacobian(&p);
+        let forward: Vec<Vec<f64>> = FiniteDiff::new(Model {}).forward().jacobian(&p).unwrap();
+        let central: Vec<Vec<f64>> = FiniteDiff::new(Model {}).jacobian(&p).unwrap();
+        let complex: Vec<Vec<f64>> = FiniteDiff::new(Model {})
+            .complex_step()
+            .jacobian(&p)
+            .unwrap();
+        assert_matrix_eq(&forward, &expected, 1e-6);
+        assert_matrix_eq(&central, &expected, 1e-9);
+        assert_matrix_eq(&complex, &expected, 1e-14);
+    }
+
+    #[test]
+    fn test_matrix_type() {
+        let p = vec![1.5f32, -0.5];
+        let expected = expected_hessian(&[1.5, -0.5]);
+        let fd = FiniteDiff::new(Model32 {}).with_matrix_type::<Vec<Vec<f32>>>();
+        let hessian: Vec<Vec<f32>> = fd.hessian(&p).unwrap();
+        for i in 0..2 {
+            for j in 0..2 {
+                assert_relative_eq!(hessian[i][j], expected[i][j] as f32, epsilon = 1e-2);
+            }
+        }
+    }
+
+    struct Model32 {}
+
+    impl CostFunction for Model32 {
+        type Param = Vec<f32>;
+        type Output = f32;
+
+        fn cost(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+            Ok(p[0].powi(2) * p[1] + p[1].exp() + p[0].sin())
+        }
+    }
+
+    #[test]
+    fn test_empty_param() {
+        let fd = FiniteDiff::new(TestProblem::new());
+        let hessian: Result<Vec<Vec<f64>>, Error> = fd.hessian(&vec![]);
+        assert_error!(
+            hessian,
+            ArgminError,
+            "Invalid parameter: \"`FiniteDiff`: parameter vector must not be empty.\""
+        );
+    }
+
+    #[test]
+    fn test_passthrough() {
+        let mut problem = Problem::new(FiniteDiff::new(Model {}));
+        let p = param();
+        assert_eq!(
+            problem.cost(&p).unwrap().to_ne_bytes(),
+            Model {}.cost(&p).unwrap().to_ne_bytes()
+        );
+        assert_eq!(problem.apply(&p).unwrap(), Model {}.apply(&p).unwrap());
+        assert_eq!(problem.counts["cost_count"], 1);
+        assert_eq!(problem.counts["operator_count"], 1);
+        assert!(!problem.counts.contains_key("finitediff_cost_count"));
+        assert!(!problem.counts.contains_key("finitediff_operator_count"));
+    }
+
+    #[test]
+    fn test_counts() {
+        let p = param();
+
+        let mut problem = Problem::new(FiniteDiff::new(Model {}).forward());
+        problem.gradient(&p).unwrap();
+        assert_eq!(problem.counts["gradient_count"], 1);
+        assert_eq!(problem.counts["finitediff_cost_count"], 3);
+        let _: Vec<Vec<f64>> = problem.hessian(&p).unwrap();
+        assert_eq!(problem.counts["hessian_count"], 1);
+        assert_eq!(problem.counts["finitediff_cost_count"], 3 + 6);
+        let _: Vec<Vec<f64>> = problem.jacobian(&p).unwrap();
+        assert_eq!(problem.counts["jacobian_count"], 1);
+        assert_eq!(problem.counts["finitediff_operator_count"], 3);
+        assert!(!problem.counts.contains_key("cost_count"));
+        assert!(!problem.counts.contains_key("operator_count"));
+
+        let mut problem = Problem::new(FiniteDiff::new(Model {}));
+        problem.gradient(&p).unwrap();
+        assert_eq!(problem.counts["finitediff_cost_count"], 4);
+        let _: Vec<Vec<f64>> = problem.hessian(&p).unwrap();
+        assert_eq!(problem.counts["finitediff_cost_count"], 4 + 9);
+        let _: Vec<Vec<f64>> = problem.jacobian(&p).unwrap();
+        assert_eq!(problem.counts["finitediff_operator_count"], 4);
+
+        let mut problem = Problem::new(FiniteDiff::new(Model {}).complex_step());
+        problem.gradient(&p).unwrap();
+        assert_eq!(problem.counts["finitediff_cost_count"], 2);
+        let _: Vec<Vec<f64>> = problem.hessian(&p).unwrap();
+        assert_eq!(problem.counts["finitediff_cost_count"], 2 + 8);
+        let _: Vec<Vec<f64>> = problem.jacobian(&p).unwrap();
+        assert_eq!(problem.counts["finitediff_operator_count"], 2);
+
+        // Counts are moved to `Problem`
+        let fd = problem.take_problem().unwrap();
+        assert!(Gradient::take_internal_counts(&fd).is_empty());
+    }
+
+    #[test]
+    fn test_bulk_counts() {
+        let p1 = param();
+        let p2 = vec![0.5, 2.0];
+        let mut problem = Problem::new(FiniteDiff::new(Model {}));
+        let gradients = problem.bulk_gradient(&vec![&p1, &p2]).unwrap();
+        assert_eq!(problem.counts["gradient_count"], 2);
+        assert_eq!(problem.counts["finitediff_cost_count"], 8);
+        for (g, p) in gradients.iter().zip([&p1, &p2]) {
+            let expected = expected_gradient(p);
+            for i in 0..2 {
+                assert_relative_eq!(g[i], expected[i], epsilon = 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mode_switch_keeps_settings() {
+        let fd = FiniteDiff::new(Model {})
+            .with_step_size(1e-3)
+            .unwrap()
+            .forward()
+            .complex_step()
+            .central();
+        assert_eq!(fd.step.unwrap().to_ne_bytes(), 1e-3f64.to_ne_bytes());
+        let clone = fd.clone();
+        assert_eq!(clone.step.unwrap().to_ne_bytes(), 1e-3f64.to_ne_bytes());
+    }
+
+    #[test]
+    fn test_gradient_ndarray_nalgebra() {
+        struct Paraboloid<P> {
+            _param: PhantomData<P>,
+        }
+
+        impl<P: std::ops::Index<usize, Output = f64>> CostFunction for Paraboloid<P> {
+            type Param = P;
+            type Output = f64;
+
+            fn cost(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+                Ok(p[0].powi(2) + 2.0 * p[1].powi(2))
+            }
+        }
+
+        let gradient = FiniteDiff::new(Paraboloid {
+            _param: PhantomData,
+        })
+        .gradient(&ndarray::array![1.0, 1.0])
+        .unwrap();
+        assert_relative_eq!(gradient[0], 2.0, epsilon = 1e-6);
+        assert_relative_eq!(gradient[1], 4.0, epsilon = 1e-6);
+
+        let gradient = FiniteDiff::new(Paraboloid {
+            _param: PhantomData,
+        })
+        .gradient(&nalgebra::dvector![1.0, 1.0])
+        .unwrap();
+        assert_relative_eq!(gradient[0], 2.0, epsilon = 1e-6);
+        assert_relative_eq!(gradient[1], 4.0, epsilon = 1e-6);
+    }
+}
@@ -13,11 +13,15 @@
 /// Macros
 #[macro_use]
 pub mod macros;
+pub mod asktell;
+pub mod asynchronous;
 pub mod checkpointing;
+pub mod checks;
 /// Error handling
 mod errors;
 /// Executor
 mod executor;
+pub mod finitediff;
 /// Trait alias for float types
 mod float;
 /// Key value data structure
@@ -35,8 +39,7 @@ mod serialization;
 mod solver;
 /// iteration state
 mod state;
-/// Definition of termination reasons
-mod termination;
+pub mod termination;
 /// Convenience utilities for testing
 pub mod test_utils;
 
@@ -46,10 +49,14 @@ pub use crate::solver::trustregion::TrustRegionRadius;
 pub use anyhow::Error;
 pub use errors::ArgminError;
 pub use executor::Executor;
+pub use finitediff::{ComplexCostFunction, ComplexOperator, FiniteDiff};
 pub use float::ArgminFloat;
 pub use kv::{KvValue, KV};
 pub use parallelization::{SendAlias, SyncAlias};
-pub use problem::{CostFunction, Gradient, Hessian, Jacobian, LinearProgram, Operator, Problem};
+pub use problem::{
+    CostFunction, EqualityConstraints, Gradient, Hessian, InequalityConstraints, Jacobian,
+    LinearProgram, Operator, Problem,
+};
 pub use result::OptimizationResult;
 pub use serialization::{DeserializeOwnedAlias, SerializeAlias};
 pub use solver::Solver;
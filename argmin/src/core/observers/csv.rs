@@ -0,0 +1,303 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Write the history of an optimization run to a CSV file.
+//!
+//! See documentation of [`WriteToCsv`] for details.
+
+use crate::core::observers::Observe;
+use crate::core::{Error, State, KV};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+/// Write one row per iteration to a CSV file during optimization.
+///
+/// Each row holds the iteration number, the current and the best cost function value, the time
+/// passed since the start of the optimization (in seconds), all function evaluation counts, the
+/// flattened parameter vector and all key-value pairs reported by the solver. The columns are
+/// named `iter`, `cost`, `best_cost`, `time`, the names of the evaluation counts (for instance
+/// `cost_count`), `param_<index>` for the elements of the parameter vector and `kv_<key>` for
+/// the key-value pairs.
+///
+/// The file is created (or truncated) in `observe_init`. If `observe_init` is not called (for
+/// instance when resuming from a checkpoint), rows are appended to an existing file.
+///
+/// # Column schema
+///
+/// The columns are inferred from the first row written after `observe_init` and stay fixed for
+/// the rest of the run, such that the file remains readable by tools which expect a rectangular
+/// table. Values which are not available in later iterations are left empty.
+///
+/// **Keys which are reported for the first time after the first row are dropped.** This concerns
+/// key-value pairs which a solver only reports in some iterations, evaluation counts which start
+/// later and parameter vectors which grow. Solvers which should be recorded completely need to
+/// report all keys from the first iteration on.
+///
+/// The parameter vector is flattened via its `serde` representation, therefore this observer
+/// requires the `serde1` feature.
+///
+/// # Example
+///
+/// ```
+/// use argmin::core::observers::WriteToCsv;
+///
+/// let observer = WriteToCsv::new("history.csv");
+/// ```
+#[derive(Debug)]
+pub struct WriteToCsv {
+    /// Path of the CSV file
+    path: PathBuf,
+    /// Writer, opened at the first observation
+    writer: Option<BufWriter<File>>,
+    /// Column names, inferred from the first row
+    columns: Option<Vec<String>>,
+}
+
+impl WriteToCsv {
+    /// Create a new instance of `WriteToCsv`.
+    ///
+    /// # Example
+    /// ```
+    /// # use argmin::core::observers::WriteToCsv;
+    /// let observer = WriteToCsv::new("history.csv");
+    /// ```
+    pub fn new<N: AsRef<std::path::Path>>(path: N) -> Self {
+        WriteToCsv {
+            path: path.as_ref().to_path_buf(),
+            writer: None,
+            columns: None,
+        }
+    }
+
+    /// Opens the file, either truncating it or appending to it
+    fn open(&mut self, truncate: bool) -> Result<(), Error> {
+        if let Some(dir) = self.path.parent() {
+            if !dir.as_os_str().is_empty() && !dir.exists() {
+                std::fs::create_dir_all(dir)?;
+            }
+        }
+        let file = if truncate {
+            File::create(&self.path)?
+        } else {
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?
+        };
+        self.writer = Some(BufWriter::new(file));
+        Ok(())
+    }
+
+    /// Writes a row, inferring the columns (and writing the header) if this is the first row
+    fn write_row(&mut self, row: Vec<(String, String)>) -> Result<(), Error> {
+        if self.writer.is_none() {
+            self.open(false)?;
+        }
+        let writer = self.writer.as_mut().unwrap();
+        let columns = match self.columns.as_ref() {
+            Some(columns) => columns,
+            None => {
+                let columns: Vec<String> = row.iter().map(|(name, _)| name.clone()).collect();
+                // Only write a header if the file does not contain one already
+                if writer.get_ref().metadata()?.len() == 0 {
+                    write_record(writer, columns.iter())?;
+                }
+                self.columns.insert(columns)
+            }
+        };
+        let mut values: HashMap<String, String> = row.into_iter().collect();
+        let empty = String::new();
+        let record: Vec<String> = columns
+            .iter()
+            .map(|column| values.remove(column).unwrap_or_else(|| empty.clone()))
+            .collect();
+        write_record(writer, record.iter())?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Writes a single CSV record, quoting fields if necessary
+fn write_record<'a, W: Write>(
+    writer: &mut W,
+    fields: impl Iterator<Item = &'a String>,
+) -> Result<(), Error> {
+    let fields: Vec<String> = fields
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect();
+    writeln!(writer, "{}", fields.join(","))?;
+    Ok(())
+}
+
+/// Flattens a `serde` representation into named cells
+fn flatten(name: String, value: &Value, cells: &mut Vec<(String, String)>) {
+    match value {
+        Value::Null => cells.push((name, String::new())),
+        Value::Bool(b) => cells.push((name, b.to_string())),
+        Value::Number(n) => cells.push((name, n.to_string())),
+        Value::String(s) => cells.push((name, s.clone())),
+        Value::Array(values) => {
+            for (idx, value) in values.iter().enumerate() {
+                flatten(format!("{name}_{idx}"), value, cells);
+            }
+        }
+        Value::Object(values) => {
+            for (key, value) in values.iter() {
+                flatten(format!("{name}_{key}"), value, cells);
+            }
+        }
+    }
+}
+
+impl<I> Observe<I> for WriteToCsv
+where
+    I: State,
+    <I as State>::Param: Serialize,
+{
+    /// Creates (or truncates) the file and resets the column schema.
+    fn observe_init(&mut self, _name: &str, _kv: &KV) -> Result<(), Error> {
+        self.columns = None;
+        self.open(true)
+    }
+
+    fn observe_iter(&mut self, state: &I, kv: &KV) -> Result<(), Error> {
+        let mut row = vec![
+            ("iter".to_string(), state.get_iter().to_string()),
+            ("cost".to_string(), state.get_cost().to_string()),
+            ("best_cost".to_string(), state.get_best_cost().to_string()),
+            (
+                "time".to_string(),
+                state
+                    .get_time()
+                    .map(|time| time.as_secs_f64().to_string())
+                    .unwrap_or_default(),
+            ),
+        ];
+
+        let mut counts: Vec<(&String, &u64)> = state.get_func_counts().iter().collect();
+        counts.sort();
+        row.extend(
+            counts
+                .into_iter()
+                .map(|(name, count)| (name.clone(), count.to_string())),
+        );
+
+        if let Some(param) = state.get_param() {
+            flatten("param".to_string(), &serde_json::to_value(param)?, &mut row);
+        }
+
+        let mut kv: Vec<_> = kv.kv.iter().collect();
+        kv.sort_by_key(|(key, _)| **key);
+        row.extend(
+            kv.into_iter()
+                .map(|(key, value)| (format!("kv_{key}"), value.to_string())),
+        );
+
+        self.write_row(row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_utils::{TestProblem, TestSolver};
+    use crate::core::{observers::ObserverMode, Executor, IterState, Problem};
+
+    send_sync_test!(write_to_csv, WriteToCsv);
+
+    /// Path in the temporary directory which is unique to the test and the test process
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("argmin_test_csv_{}_{name}", std::process::id()))
+    }
+
+    fn read(path: &PathBuf) -> Vec<String> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_schema() {
+        let path = temp_path("schema").join("history.csv");
+        let mut observer = WriteToCsv::new(&path);
+        <WriteToCsv as Observe<IterState<Vec<f64>, (), (), (), f64>>>::observe_init(
+            &mut observer,
+            "test",
+            &KV::new(),
+        )
+        .unwrap();
+
+        let mut problem = Problem::new(TestProblem::new());
+        problem.counts.insert("cost_count", 3);
+        let mut state: IterState<Vec<f64>, (), (), (), f64> =
+            IterState::new().param(vec![1.0, 2.5]).cost(4.0);
+        state.func_counts(&problem);
+        state.update();
+        observer
+            .observe_iter(&state, &kv!("b" => 1u64; "a" => "x,y";))
+            .unwrap();
+
+        // Later rows use the same columns: Missing values are left empty and the new key `c` is
+        // dropped
+        state.increment_iter();
+        let state = state.param(vec![0.5]).cost(2.0);
+        observer
+            .observe_iter(&state, &kv!("c" => true; "a" => "z";))
+            .unwrap();
+
+        assert_eq!(
+            read(&path),
+            vec![
+                "iter,cost,best_cost,time,cost_count,param_0,param_1,kv_a,kv_b",
+                "0,4,4,0,3,1.0,2.5,\"x,y\",1",
+                "1,2,4,0,3,0.5,,z,",
+            ]
+        );
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_executor() {
+        let path = temp_path("executor.csv");
+        Executor::new(TestProblem::new(), TestSolver::new())
+            .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(3))
+            .add_observer(WriteToCsv::new(&path), ObserverMode::Always)
+            .timer(false)
+            .ctrlc(false)
+            .run()
+            .unwrap();
+
+        let lines = read(&path);
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "iter,cost,best_cost,time,param_0,param_1");
+        assert_eq!(lines[1], "0,inf,inf,0,1.0,0.0");
+        assert_eq!(lines[3], "2,inf,inf,0,1.0,0.0");
+
+        // Running again truncates the file
+        Executor::new(TestProblem::new(), TestSolver::new())
+            .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(1))
+            .add_observer(WriteToCsv::new(&path), ObserverMode::Always)
+            .timer(false)
+            .ctrlc(false)
+            .run()
+            .unwrap();
+        assert_eq!(read(&path).len(), 2);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,346 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Record the history of an optimization run in memory.
+//!
+//! See documentation of [`HistoryObserver`] for details.
+
+use crate::core::observers::Observe;
+use crate::core::{ArgminFloat, Error};
+use crate::core::{KvValue, State, KV};
+use std::sync::{Arc, Mutex};
+
+/// Snapshot of the state of a solver after a single iteration
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot<P, F> {
+    /// Iteration number
+    pub iter: u64,
+    /// Cost function value
+    pub cost: F,
+    /// Best cost function value so far
+    pub best_cost: F,
+    /// Parameter vector
+    pub param: Option<P>,
+    /// Key-value pairs reported by the solver and the executor
+    pub kv: KV,
+    /// Time passed since the start of the optimization
+    pub time: Option<instant::Duration>,
+}
+
+/// Record a [`Snapshot`] of every observed iteration in memory.
+///
+/// `HistoryObserver` is a shared handle: All clones refer to the same history. Therefore a clone
+/// can be passed to the [`Executor`](`crate::core::Executor`) and the history can be queried via
+/// the original handle after the run. The history is cleared in `observe_init`, which means that
+/// a handle can be reused for multiple runs.
+///
+/// The observer works with any state whose parameter vector implements `Clone`, for instance
+/// [`IterState`](`crate::core::IterState`) (where `P` is the parameter vector) and
+/// [`PopulationState`](`crate::core::PopulationState`) (where `P` is the best individual, for
+/// instance a [`Particle`](`crate::solver::particleswarm::Particle`)).
+///
+/// # Example
+///
+/// ```
+/// use argmin::core::observers::{HistoryObserver, ObserverMode};
+/// use argmin::core::{Error, Executor};
+/// # use argmin::core::test_utils::{TestSolver, TestProblem};
+///
+/// # fn main() -> Result<(), Error> {
+/// # let solver = TestSolver::new();
+/// # let problem = TestProblem::new();
+/// let history = HistoryObserver::new();
+///
+/// let res = Executor::new(problem, solver)
+///     .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(10))
+///     .add_observer(history.clone(), ObserverMode::Always)
+///     .run()?;
+///
+/// let costs: Vec<f64> = history.costs();
+/// let durations = history.kv_series("time");
+/// # assert_eq!(costs.len(), 10);
+/// # assert_eq!(durations.len(), 10);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct HistoryObserver<P, F> {
+    /// Recorded snapshots
+    snapshots: Arc<Mutex<Vec<Snapshot<P, F>>>>,
+}
+
+impl<P, F> Clone for HistoryObserver<P, F> {
+    fn clone(&self) -> Self {
+        HistoryObserver {
+            snapshots: Arc::clone(&self.snapshots),
+        }
+    }
+}
+
+impl<P, F> Default for HistoryObserver<P, F> {
+    fn default() -> Self {
+        HistoryObserver::new()
+    }
+}
+
+impl<P, F> HistoryObserver<P, F> {
+    /// Create a new, empty instance of `HistoryObserver`.
+    ///
+    /// # Example
+    /// ```
+    /// # use argmin::core::observers::HistoryObserver;
+    /// let history: HistoryObserver<Vec<f64>, f64> = HistoryObserver::new();
+    /// # assert!(history.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        HistoryObserver {
+            snapshots: Arc::new(Mutex::new(vec![])),
+        }
+    }
+
+    /// Returns the number of recorded snapshots.
+    ///
+    /// # Example
+    /// ```
+    /// # use argmin::core::observers::HistoryObserver;
+    /// # let history: HistoryObserver<Vec<f64>, f64> = HistoryObserver::new();
+    /// let num_snapshots = history.len();
+    /// # assert_eq!(num_snapshots, 0);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.snapshots.lock().unwrap().len()
+    }
+
+    /// Returns `true` if no snapshots have been recorded.
+    ///
+    /// # Example
+    /// ```
+    /// # use argmin::core::observers::HistoryObserver;
+    /// # let history: HistoryObserver<Vec<f64>, f64> = HistoryObserver::new();
+    /// assert!(history.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.lock().unwrap().is_empty()
+    }
+
+    /// Removes all recorded snapshots.
+    ///
+    /// # Example
+    /// ```
+    /// # use argmin::core::observers::HistoryObserver;
+    /// # let history: HistoryObserver<Vec<f64>, f64> = HistoryObserver::new();
+    /// history.clear();
+    /// # assert!(history.is_empty());
+    /// ```
+    pub fn clear(&self) {
+        self.snapshots.lock().unwrap().clear()
+    }
+
+    /// Applies `f` to every snapshot and returns the results in the order of the iterations
+    fn series<T, G: FnMut(&Snapshot<P, F>) -> T>(&self, f: G) -> Vec<T> {
+        self.snapshots.lock().unwrap().iter().map(f).collect()
+    }
+
+    /// Returns the iteration numbers of all snapshots.
+    ///
+    /// # Example
+    /// ```
+    /// # use argmin::core::observers::HistoryObserver;
+    /// # let history: HistoryObserver<Vec<f64>, f64> = HistoryObserver::new();
+    /// let iters = history.iters();
+    /// ```
+    pub fn iters(&self) -> Vec<u64> {
+        self.series(|snapshot| snapshot.iter)
+    }
+
+    /// Returns the time passed since the start of the optimization for all snapshots.
+    ///
+    /// # Example
+    /// ```
+    /// # use argmin::core::observers::HistoryObserver;
+    /// # let history: HistoryObserver<Vec<f64>, f64> = HistoryObserver::new();
+    /// let times = history.times();
+    /// ```
+    pub fn times(&self) -> Vec<Option<instant::Duration>> {
+        self.series(|snapshot| snapshot.time)
+    }
+
+    /// Returns the value of the key-value pair `key` for all snapshots, or `None` for snapshots
+    /// in which `key` was not reported.
+    ///
+    /// # Example
+    /// ```
+    /// # use argmin::core::observers::HistoryObserver;
+    /// # let history: HistoryObserver<Vec<f64>, f64> = HistoryObserver::new();
+    /// let gamma = history.kv_series("gamma");
+    /// ```
+    pub fn kv_series(&self, key: &str) -> Vec<Option<KvValue>> {
+        self.series(|snapshot| snapshot.kv.kv.get(key).cloned())
+    }
+}
+
+impl<P: Clone, F: ArgminFloat> HistoryObserver<P, F> {
+    /// Returns a copy of all recorded snapshots.
+    ///
+    /// # Example
+    /// ```
+    /// # use argmin::core::observers::HistoryObserver;
+    /// # let history: HistoryObserver<Vec<f64>, f64> = HistoryObserver::new();
+    /// let snapshots = history.snapshots();
+    /// ```
+    pub fn snapshots(&self) -> Vec<Snapshot<P, F>> {
+        self.snapshots.lock().unwrap().clone()
+    }
+
+    /// Returns the cost function values of all snapshots.
+    ///
+    /// # Example
+    /// ```
+    /// # use argmin::core::observers::HistoryObserver;
+    /// # let history: HistoryObserver<Vec<f64>, f64> = HistoryObserver::new();
+    /// let costs = history.costs();
+    /// ```
+    pub fn costs(&self) -> Vec<F> {
+        self.series(|snapshot| snapshot.cost)
+    }
+
+    /// Returns the best cost function values of all snapshots.
+    ///
+    /// # Example
+    /// ```
+    /// # use argmin::core::observers::HistoryObserver;
+    /// # let history: HistoryObserver<Vec<f64>, f64> = HistoryObserver::new();
+    /// let best_costs = history.best_costs();
+    /// ```
+    pub fn best_costs(&self) -> Vec<F> {
+        self.series(|snapshot| snapshot.best_cost)
+    }
+
+    /// Returns the parameter vectors of all snapshots.
+    ///
+    /// # Example
+    /// ```
+    /// # use argmin::core::observers::HistoryObserver;
+    /// # let history: HistoryObserver<Vec<f64>, f64> = HistoryObserver::new();
+    /// let params = history.params();
+    /// ```
+    pub fn params(&self) -> Vec<Option<P>> {
+        self.series(|snapshot| snapshot.param.clone())
+    }
+}
+
+impl<I> Observe<I> for HistoryObserver<I::Param, I::Float>
+where
+    I: State,
+    I::Param: Clone,
+{
+    /// Clears the history.
+    fn observe_init(&mut self, _name: &str, _kv: &KV) -> Result<(), Error> {
+        self.clear();
+        Ok(())
+    }
+
+    /// Records a snapshot of `state` and `kv`.
+    fn observe_iter(&mut self, state: &I, kv: &KV) -> Result<(), Error> {
+        self.snapshots.lock().unwrap().push(Snapshot {
+            iter: state.get_iter(),
+            cost: state.get_cost(),
+            best_cost: state.get_best_cost(),
+            param: state.get_param().cloned(),
+            kv: kv.clone(),
+            time: state.get_time(),
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::observers::ObserverMode;
+    use crate::core::test_utils::{TestProblem, TestSolver};
+    use crate::core::{CostFunction, Executor};
+    use crate::solver::particleswarm::ParticleSwarm;
+
+    send_sync_test!(history_observer, HistoryObserver<Vec<f64>, f64>);
+
+    struct Sphere {}
+
+    impl CostFunction for Sphere {
+        type Param = Vec<f64>;
+        type Output = f64;
+
+        fn cost(&self, param: &Self::Param) -> Result<Self::Output, Error> {
+            Ok(param.iter().map(|x| x.powi(2)).sum())
+        }
+    }
+
+    #[test]
+    fn test_iterstate() {
+        let history = HistoryObserver::new();
+        Executor::new(TestProblem::new(), TestSolver::new())
+            .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(5))
+            .add_observer(history.clone(), ObserverMode::Every(2))
+            .ctrlc(false)
+            .run()
+            .unwrap();
+
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.iters(), vec![0, 2, 4]);
+        assert_eq!(history.params(), vec![Some(vec![1.0, 0.0]); 3]);
+        assert!(history.costs().iter().all(|c| c.is_infinite()));
+        assert!(history.times().iter().all(|t| t.is_some()));
+        assert!(history
+            .kv_series("time")
+            .iter()
+            .all(|t| t.as_ref().and_then(KvValue::get_float).is_some()));
+        assert_eq!(history.kv_series("gamma"), vec![None; 3]);
+
+        let snapshots = history.snapshots();
+        assert_eq!(snapshots[1].iter, 2);
+        assert_eq!(snapshots[1].param, Some(vec![1.0, 0.0]));
+    }
+
+    #[test]
+    fn test_populationstate() {
+        let solver = ParticleSwarm::new((vec![-1.0, -1.0], vec![1.0, 1.0]), 10);
+        let history = HistoryObserver::new();
+        Executor::new(Sphere {}, solver)
+            .configure(|state| state.max_iters(10))
+            .add_observer(history.clone(), ObserverMode::Always)
+            .ctrlc(false)
+            .run()
+            .unwrap();
+
+        assert_eq!(history.len(), 10);
+        let best_costs = history.best_costs();
+        assert!(best_costs.windows(2).all(|w| w[1] <= w[0]));
+        // The parameter vector of a `PopulationState` is the best particle
+        for (particle, best_cost) in history.params().iter().zip(best_costs) {
+            assert_eq!(
+                particle.as_ref().unwrap().cost.to_ne_bytes(),
+                best_cost.to_ne_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn test_observe_init_clears() {
+        let history = HistoryObserver::new();
+        for _ in 0..2 {
+            Executor::new(TestProblem::new(), TestSolver::new())
+                .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(3))
+                .add_observer(history.clone(), ObserverMode::Always)
+                .ctrlc(false)
+                .run()
+                .unwrap();
+        }
+        assert_eq!(history.len(), 3);
+        history.clear();
+        assert!(history.is_empty());
+    }
+}
@@ -16,10 +16,30 @@
 //! to disk and as such requires the parameter vector to be serializable. Hence this feature is
 //! only available with the `serde1` feature.
 //!
+//! The observer [`WriteToCsv`](`crate::core::observers::WriteToCsv`) writes one row per iteration
+//! to a CSV file for analysis after the run. It flattens the parameter vector via its `serde`
+//! representation and therefore also requires the `serde1` feature.
+//!
+//! The observer [`HistoryObserver`](`crate::core::observers::HistoryObserver`) records a
+//! snapshot of every iteration in memory, which can be queried after the run, for instance to
+//! assert on the convergence trajectory in tests.
+//!
+//! The observer [`PrometheusExporter`](`crate::core::observers::PrometheusExporter`) keeps
+//! gauges, counters and a histogram of the iteration durations and exposes them in the Prometheus
+//! text format, optionally via a small HTTP endpoint.
+//!
 //! The observer [`SlogLogger`](`crate::core::observers::SlogLogger`) logs the progress of the
 //! optimization to screen or to disk. This requires the `slog-logger` feature. Writing to disk
 //! in addition requires the `serde1` feature.
 //!
+//! The observer [`ProgressBar`](`crate::core::observers::ProgressBar`) shows a compact live view
+//! of the progress (progress bar, cost function values, iterations per second and estimated
+//! remaining time) in the terminal. This requires the `progress-bar` feature.
+//!
+//! The observer [`TracingLogger`](`crate::core::observers::TracingLogger`) emits spans for the
+//! optimization run and for every iteration via the `tracing` crate, such that optimization runs
+//! show up in existing traces. This requires the `tracing` feature.
+//!
 //! For each observer it can be defined how often it will observe the progress of the solver. This
 //! is indicated via the enum `ObserverMode` which can be either `Always`, `Never`, `NewBest`
 //! (whenever a new best solution is found) or `Every(i)` which means every `i`th iteration.
@@ -124,18 +144,35 @@
 //! # }
 //! ```
 
+#[cfg(feature = "serde1")]
+pub mod csv;
 #[cfg(feature = "serde1")]
 pub mod file;
+pub mod history;
+#[cfg(feature = "progress-bar")]
+pub mod progress;
+pub mod prometheus;
 #[cfg(feature = "slog-logger")]
 pub mod slog_logger;
+#[cfg(feature = "tracing")]
+pub mod tracing_logger;
 
+#[cfg(feature = "serde1")]
+pub use self::csv::*;
 #[cfg(feature = "serde1")]
 pub use file::*;
+pub use history::*;
+#[cfg(feature = "progress-bar")]
+pub use progress::*;
+pub use prometheus::*;
 #[cfg(feature = "serde1")]
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "slog-logger")]
 pub use slog_logger::*;
+#[cfg(feature = "tracing")]
+pub use tracing_logger::*;
 
+use crate::core::checkpointing::StateSnapshot;
 use crate::core::{Error, State, KV};
 use std::default::Default;
 use std::sync::{Arc, Mutex};
@@ -167,6 +204,12 @@ use std::sync::{Arc, Mutex};
 ///         // Is executed after each iteration of a solver
 ///         Ok(())
 ///     }
+///
+///     fn observe_final(&mut self, state: &I) -> Result<(), Error> {
+///         // Do something with the final `state`, for instance its termination status
+///         // Is executed once after the solver has terminated
+///         Ok(())
+///     }
 /// }
 /// ```
 pub trait Observe<I> {
@@ -186,6 +229,27 @@ pub trait Observe<I> {
     fn observe_iter(&mut self, _state: &I, _kv: &KV) -> Result<(), Error> {
         Ok(())
     }
+
+    /// Called once after the solver has terminated.
+    ///
+    /// Has access to the final `state` of the solver, including the termination status.
+    fn observe_final(&mut self, _state: &I) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Returns a snapshot of the internal state of the observer, which is stored in checkpoints.
+    ///
+    /// Returns `None` by default, in which case the observer starts from scratch when an
+    /// optimization is resumed from a checkpoint.
+    fn snapshot(&self) -> Result<Option<StateSnapshot>, Error> {
+        Ok(None)
+    }
+
+    /// Restores the internal state of the observer from a snapshot created by
+    /// [`snapshot`](`Observe::snapshot`) when an optimization is resumed from a checkpoint.
+    fn restore(&mut self, _snapshot: &StateSnapshot) -> Result<(), Error> {
+        Ok(())
+    }
 }
 
 type ObserversVec<I> = Vec<(Arc<Mutex<dyn Observe<I>>>, ObserverMode)>;
@@ -259,6 +323,71 @@ impl<I> Observers<I> {
     pub fn is_empty(&self) -> bool {
         self.observers.is_empty()
     }
+
+    /// Returns the snapshots of all stored observers, in the order in which they were added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use argmin::core::observers::{Observers, ObserverMode};
+    /// use argmin::core::IterState;
+    /// # use argmin::core::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let observers: Observers<IterState<Vec<f64>, (), (), (), f64>> = Observers::new();
+    /// let snapshots = observers.snapshots()?;
+    /// # assert!(snapshots.is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn snapshots(&self) -> Result<Vec<Option<StateSnapshot>>, Error> {
+        self.observers
+            .iter()
+            .map(|l| l.0.lock().unwrap().snapshot())
+            .collect()
+    }
+
+    /// Restores the observers from `snapshots` as returned by
+    /// [`snapshots`](`Observers::snapshots`).
+    ///
+    /// The snapshots are matched to the observers by position. Returns an error if the number of
+    /// snapshots and observers differ, unless none of the snapshots is set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use argmin::core::observers::{Observers, ObserverMode};
+    /// use argmin::core::IterState;
+    /// # use argmin::core::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let mut observers: Observers<IterState<Vec<f64>, (), (), (), f64>> = Observers::new();
+    /// let snapshots = observers.snapshots()?;
+    /// observers.restore_snapshots(&snapshots)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn restore_snapshots(&mut self, snapshots: &[Option<StateSnapshot>]) -> Result<(), Error> {
+        if snapshots.len() != self.observers.len() {
+            if snapshots.iter().all(Option::is_none) {
+                return Ok(());
+            }
+            return Err(argmin_error!(
+                InvalidCheckpoint,
+                format!(
+                    "Checkpoint contains snapshots of {} observers, but {} observers were added.",
+                    snapshots.len(),
+                    self.observers.len()
+                )
+            ));
+        }
+        for (l, snapshot) in self.observers.iter().zip(snapshots.iter()) {
+            if let Some(snapshot) = snapshot {
+                l.0.lock().unwrap().restore(snapshot)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Implementing [`Observe`] for [`Observers`] allows to use it like a single observer. In its
@@ -290,6 +419,18 @@ impl<I: State> Observe<I> for Observers<I> {
         }
         Ok(())
     }
+
+    /// Called after the solver has terminated.
+    ///
+    /// Calls all observers except for those with mode `Never`.
+    fn observe_final(&mut self, state: &I) -> Result<(), Error> {
+        for l in self.observers.iter() {
+            if l.1 != ObserverMode::Never {
+                l.0.lock().unwrap().observe_final(state)?
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Indicates when to call an observer.
@@ -332,6 +473,7 @@ mod tests {
             pub solver_name: String,
             pub init_called: usize,
             pub iter_called: usize,
+            pub final_called: usize,
         }
 
         impl TestStor {
@@ -340,6 +482,7 @@ mod tests {
                     solver_name: String::new(),
                     init_called: 0,
                     iter_called: 0,
+                    final_called: 0,
                 }))
             }
         }
@@ -359,6 +502,11 @@ mod tests {
                 self.data.lock().unwrap().iter_called += 1;
                 Ok(())
             }
+
+            fn observe_final(&mut self, _state: &I) -> Result<(), Error> {
+                self.data.lock().unwrap().final_called += 1;
+                Ok(())
+            }
         }
 
         let test_stor_1 = TestStor::new();
@@ -451,5 +599,81 @@ mod tests {
         assert_eq!(storages[2].lock().unwrap().iter_called, 2);
         assert_eq!(storages[3].lock().unwrap().init_called, 1);
         assert_eq!(storages[3].lock().unwrap().iter_called, 2);
+
+        // all but the deactivated observer are called after termination
+        obs.observe_final(&state).unwrap();
+
+        assert_eq!(storages[0].lock().unwrap().final_called, 0);
+        assert_eq!(storages[1].lock().unwrap().final_called, 1);
+        assert_eq!(storages[2].lock().unwrap().final_called, 1);
+        assert_eq!(storages[3].lock().unwrap().final_called, 1);
+    }
+
+    #[test]
+    fn test_observer_snapshots() {
+        use crate::core::{ArgminError, IterState};
+
+        type TState = IterState<Vec<f64>, (), (), (), f64>;
+
+        /// Counts the iterations and provides snapshots of the count
+        struct Counter(Arc<Mutex<u8>>);
+
+        impl Observe<TState> for Counter {
+            fn observe_iter(&mut self, _state: &TState, _kv: &KV) -> Result<(), Error> {
+                *self.0.lock().unwrap() += 1;
+                Ok(())
+            }
+
+            fn snapshot(&self) -> Result<Option<StateSnapshot>, Error> {
+                Ok(Some(StateSnapshot::from_bytes(vec![*self
+                    .0
+                    .lock()
+                    .unwrap()])))
+            }
+
+            fn restore(&mut self, snapshot: &StateSnapshot) -> Result<(), Error> {
+                *self.0.lock().unwrap() = snapshot.as_bytes()[0];
+                Ok(())
+            }
+        }
+
+        /// Observer without snapshots
+        struct Stateless {}
+
+        impl Observe<TState> for Stateless {}
+
+        let count = Arc::new(Mutex::new(0));
+        let mut obs: Observers<TState> = Observers::new();
+        obs.push(Stateless {}, ObserverMode::Always)
+            .push(Counter(count.clone()), ObserverMode::Always);
+        obs.observe_iter(&IterState::new(), &kv!()).unwrap();
+        obs.observe_iter(&IterState::new(), &kv!()).unwrap();
+
+        let snapshots = obs.snapshots().unwrap();
+        assert_eq!(
+            snapshots,
+            vec![None, Some(StateSnapshot::from_bytes(vec![2]))]
+        );
+
+        // Restoring a fresh set of observers
+        let restored_count = Arc::new(Mutex::new(0));
+        let mut restored: Observers<TState> = Observers::new();
+        restored
+            .push(Stateless {}, ObserverMode::Always)
+            .push(Counter(restored_count.clone()), ObserverMode::Always);
+        restored.restore_snapshots(&snapshots).unwrap();
+        assert_eq!(*restored_count.lock().unwrap(), 2);
+
+        // Checkpoints without snapshots are ignored
+        restored.restore_snapshots(&[]).unwrap();
+        restored.restore_snapshots(&[None]).unwrap();
+        assert_eq!(*restored_count.lock().unwrap(), 2);
+
+        // Snapshots which cannot be matched to the observers
+        assert_error!(
+            restored.restore_snapshots(&snapshots[1..]),
+            ArgminError,
+            "Invalid checkpoint: \"Checkpoint contains snapshots of 1 observers, but 2 observers were added.\""
+        );
     }
 }
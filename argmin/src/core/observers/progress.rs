@@ -0,0 +1,527 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Compact live view of the progress of an optimization run
+//!
+//! See documentation of [`ProgressBar`] for details.
+
+use crate::core::observers::Observe;
+use crate::core::{Error, State, KV};
+use instant::{Duration, Instant};
+use num_traits::ToPrimitive;
+use std::collections::VecDeque;
+use std::io::{IsTerminal, Write};
+
+/// Characters used to draw the sparkline, from lowest to highest
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Display the progress of an optimization run in the terminal.
+///
+/// The display consists of two lines: The first one shows a progress bar against the maximum
+/// number of iterations (if set), the number of iterations per second, the estimated remaining
+/// time and the elapsed time. The second one shows the current and the best cost function value,
+/// a sparkline of the most recent cost function values and all function evaluation counts.
+///
+/// If the output is a terminal, the display is updated in place (at most every 100 ms by
+/// default). Otherwise both lines are joined and written as a single line of plain text (at most
+/// once per second by default), which keeps log files and CI output readable. The last
+/// observed iteration is always displayed when the observer is dropped at the end of the run.
+///
+/// Requires the `progress-bar` feature.
+///
+/// # Example
+///
+/// ```
+/// use argmin::core::observers::{ObserverMode, ProgressBar};
+/// use argmin::core::{Error, Executor};
+/// # use argmin::core::test_utils::{TestSolver, TestProblem};
+///
+/// # fn main() -> Result<(), Error> {
+/// # let solver = TestSolver::new();
+/// # let problem = TestProblem::new();
+/// let res = Executor::new(problem, solver)
+///     .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(100))
+///     .add_observer(ProgressBar::new(), ObserverMode::Always)
+///     .run()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ProgressBar {
+    /// Output
+    writer: Box<dyn Write + Send + Sync>,
+    /// Update the display in place (`true`) or write plain lines (`false`)
+    live: bool,
+    /// Minimum time between two updates of the display
+    interval: Option<Duration>,
+    /// Width of the progress bar in characters
+    bar_width: usize,
+    /// Number of cost function values shown in the sparkline
+    sparkline_len: usize,
+    /// Most recent cost function values
+    costs: VecDeque<f64>,
+    /// Time and number of iterations at the first observation
+    start: Option<(Instant, u64)>,
+    /// Time of the last update of the display
+    last_draw: Option<Instant>,
+    /// Lines rendered for the most recent iteration
+    lines: Vec<String>,
+    /// Whether `lines` have not been displayed yet
+    dirty: bool,
+    /// Number of lines currently on screen (live display only)
+    drawn: usize,
+}
+
+impl ProgressBar {
+    /// Create a new `ProgressBar` writing to `stderr`.
+    ///
+    /// The display is updated in place if `stderr` is a terminal and falls back to plain lines
+    /// otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use argmin::core::observers::ProgressBar;
+    ///
+    /// let progress = ProgressBar::new();
+    /// ```
+    pub fn new() -> Self {
+        let live = std::io::stderr().is_terminal();
+        ProgressBar::with_writer(std::io::stderr()).live(live)
+    }
+
+    /// Create a new `ProgressBar` writing plain lines to `writer`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use argmin::core::observers::ProgressBar;
+    ///
+    /// let progress = ProgressBar::with_writer(std::io::stdout());
+    /// ```
+    pub fn with_writer<W: Write + Send + Sync + 'static>(writer: W) -> Self {
+        ProgressBar {
+            writer: Box::new(writer),
+            live: false,
+            interval: None,
+            bar_width: 30,
+            sparkline_len: 20,
+            costs: VecDeque::new(),
+            start: None,
+            last_draw: None,
+            lines: vec![],
+            dirty: false,
+            drawn: 0,
+        }
+    }
+
+    /// Update the display in place (`true`) or write plain lines (`false`).
+    ///
+    /// Updating in place requires a terminal which understands ANSI escape codes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use argmin::core::observers::ProgressBar;
+    ///
+    /// let progress = ProgressBar::new().live(false);
+    /// ```
+    #[must_use]
+    pub fn live(mut self, live: bool) -> Self {
+        self.live = live;
+        self
+    }
+
+    /// Set the minimum time between two updates of the display.
+    ///
+    /// Defaults to 100 ms for the live display and to one second for plain lines.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use argmin::core::observers::ProgressBar;
+    /// # use instant::Duration;
+    ///
+    /// let progress = ProgressBar::new().interval(Duration::from_secs(5));
+    /// ```
+    #[must_use]
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    /// Set the width of the progress bar in characters (default: 30).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use argmin::core::observers::ProgressBar;
+    ///
+    /// let progress = ProgressBar::new().bar_width(50);
+    /// ```
+    #[must_use]
+    pub fn bar_width(mut self, width: usize) -> Self {
+        self.bar_width = width;
+        self
+    }
+
+    /// Set the number of most recent cost function values shown in the sparkline (default: 20).
+    ///
+    /// A length of zero disables the sparkline.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use argmin::core::observers::ProgressBar;
+    ///
+    /// let progress = ProgressBar::new().sparkline_len(40);
+    /// ```
+    #[must_use]
+    pub fn sparkline_len(mut self, len: usize) -> Self {
+        self.sparkline_len = len;
+        self
+    }
+
+    /// Renders the display for `state`
+    fn render<I: State>(&self, state: &I, now: Instant) -> Vec<String> {
+        let done = state.get_iter() + 1;
+        let max_iters = state.get_max_iters();
+        let (start_time, start_iter) = self.start.unwrap_or((now, 0));
+        let elapsed = now.duration_since(start_time);
+        let rate = if elapsed > Duration::ZERO {
+            Some(done.saturating_sub(start_iter) as f64 / elapsed.as_secs_f64())
+        } else {
+            None
+        };
+
+        let mut progress = vec![];
+        if max_iters == u64::MAX {
+            progress.push(format!("iter {done}"));
+        } else {
+            let fraction = (done as f64 / max_iters.max(1) as f64).min(1.0);
+            let filled = (fraction * self.bar_width as f64).round() as usize;
+            progress.push(format!(
+                "[{}{}] {}/{} ({:.0}%)",
+                "█".repeat(filled),
+                "░".repeat(self.bar_width - filled),
+                done,
+                max_iters,
+                100.0 * fraction
+            ));
+        }
+        if let Some(rate) = rate {
+            progress.push(format!("{rate:.1} it/s"));
+            if max_iters != u64::MAX {
+                if let Some(eta) = eta(max_iters.saturating_sub(done), rate) {
+                    progress.push(format!("ETA {}", fmt_duration(eta)));
+                }
+            }
+        }
+        progress.push(format!("elapsed {}", fmt_duration(elapsed)));
+
+        let mut cost = vec![
+            format!("cost {}", fmt_float(state.get_cost().to_f64())),
+            format!("best {}", fmt_float(state.get_best_cost().to_f64())),
+        ];
+        if self.sparkline_len > 0 {
+            cost.push(sparkline(self.costs.iter().copied()));
+        }
+        let mut counts: Vec<_> = state.get_func_counts().iter().collect();
+        counts.sort();
+        cost.extend(
+            counts
+                .into_iter()
+                .map(|(name, count)| format!("{name} {count}")),
+        );
+
+        vec![progress.join("  "), cost.join("  ")]
+    }
+
+    /// Writes the most recently rendered lines to the output
+    fn draw(&mut self) -> Result<(), Error> {
+        if self.live {
+            let mut out = String::new();
+            if self.drawn > 0 {
+                out.push('\r');
+                for _ in 1..self.drawn {
+                    out.push_str("\x1b[1A");
+                }
+            }
+            for (idx, line) in self.lines.iter().enumerate() {
+                if idx > 0 {
+                    out.push('\n');
+                }
+                out.push_str("\x1b[2K");
+                out.push_str(line);
+            }
+            write!(self.writer, "{out}")?;
+            self.drawn = self.lines.len();
+        } else {
+            writeln!(self.writer, "{}", self.lines.join(" | "))?;
+        }
+        self.writer.flush()?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+impl Default for ProgressBar {
+    fn default() -> Self {
+        ProgressBar::new()
+    }
+}
+
+impl Drop for ProgressBar {
+    /// Displays the last observed iteration and moves the cursor below the live display.
+    fn drop(&mut self) {
+        if self.dirty {
+            let _ = self.draw();
+        }
+        if self.live && self.drawn > 0 {
+            let _ = writeln!(self.writer);
+            let _ = self.writer.flush();
+        }
+    }
+}
+
+/// Formats a cost function value
+fn fmt_float(value: Option<f64>) -> String {
+    match value {
+        Some(value) if value.is_finite() => format!("{value:.4e}"),
+        Some(value) => format!("{value}"),
+        None => "NaN".to_string(),
+    }
+}
+
+/// Estimated time to perform `remaining` iterations at `rate` iterations per second, if it can be
+/// represented
+fn eta(remaining: u64, rate: f64) -> Option<Duration> {
+    if rate > 0.0 {
+        Duration::try_from_secs_f64(remaining as f64 / rate).ok()
+    } else {
+        None
+    }
+}
+
+/// Formats a duration as `4.2s`, `3m05s` or `1h02m03s`
+fn fmt_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs < 60 {
+        format!("{:.1}s", duration.as_secs_f64())
+    } else if secs < 3600 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!(
+            "{}h{:02}m{:02}s",
+            secs / 3600,
+            (secs % 3600) / 60,
+            secs % 60
+        )
+    }
+}
+
+/// Draws a sparkline scaled to the range of the finite `values`. Non-finite values are left blank.
+fn sparkline<V: Iterator<Item = f64> + Clone>(values: V) -> String {
+    let (min, max) = values
+        .clone()
+        .filter(|v| v.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+            (min.min(v), max.max(v))
+        });
+    values
+        .map(|v| {
+            if !v.is_finite() {
+                ' '
+            } else if max > min {
+                let idx = ((v - min) / (max - min) * (SPARKS.len() - 1) as f64).round() as usize;
+                SPARKS[idx]
+            } else {
+                SPARKS[0]
+            }
+        })
+        .collect()
+}
+
+impl<I> Observe<I> for ProgressBar
+where
+    I: State,
+{
+    /// Resets the display.
+    fn observe_init(&mut self, _name: &str, _kv: &KV) -> Result<(), Error> {
+        self.costs.clear();
+        self.start = None;
+        self.last_draw = None;
+        Ok(())
+    }
+
+    /// Updates the display, unless it was updated less than `interval` ago.
+    fn observe_iter(&mut self, state: &I, _kv: &KV) -> Result<(), Error> {
+        let now = Instant::now();
+        if self.start.is_none() {
+            self.start = Some((now, state.get_iter()));
+        }
+        if self.sparkline_len > 0 {
+            if self.costs.len() == self.sparkline_len {
+                self.costs.pop_front();
+            }
+            self.costs
+                .push_back(state.get_cost().to_f64().unwrap_or(f64::NAN));
+        }
+        self.lines = self.render(state, now);
+        self.dirty = true;
+
+        let interval = self.interval.unwrap_or(if self.live {
+            Duration::from_millis(100)
+        } else {
+            Duration::from_secs(1)
+        });
+        let due = match self.last_draw {
+            Some(last) => now.duration_since(last) >= interval,
+            None => true,
+        };
+        if due || state.get_iter() + 1 >= state.get_max_iters() {
+            self.last_draw = Some(now);
+            self.draw()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::observers::ObserverMode;
+    use crate::core::test_utils::{TestProblem, TestSolver};
+    use crate::core::{Executor, IterState, Problem};
+    use std::sync::{Arc, Mutex};
+
+    send_sync_test!(progress_bar, ProgressBar);
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Buffer {
+        fn content(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    fn run(observer: ProgressBar, max_iters: u64) {
+        Executor::new(TestProblem::new(), TestSolver::new())
+            .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(max_iters))
+            .add_observer(observer, ObserverMode::Always)
+            .ctrlc(false)
+            .run()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_sparkline() {
+        let values = (0..8).map(f64::from);
+        assert_eq!(sparkline(values), "▁▂▃▄▅▆▇█");
+        assert_eq!(sparkline([2.0, 2.0].into_iter()), "▁▁");
+        assert_eq!(sparkline([f64::INFINITY, 1.0, 0.0].into_iter()), " █▁");
+        assert_eq!(sparkline(std::iter::empty()), "");
+    }
+
+    #[test]
+    fn test_eta() {
+        assert_eq!(eta(10, 2.0), Some(Duration::from_secs(5)));
+        assert_eq!(eta(0, 2.0), Some(Duration::ZERO));
+        assert_eq!(eta(10, 0.0), None);
+        // Too large to be represented
+        assert_eq!(eta(u64::MAX, 1e-10), None);
+    }
+
+    #[test]
+    fn test_fmt_duration() {
+        assert_eq!(fmt_duration(Duration::from_millis(4200)), "4.2s");
+        assert_eq!(fmt_duration(Duration::from_secs(185)), "3m05s");
+        assert_eq!(fmt_duration(Duration::from_secs(3723)), "1h02m03s");
+    }
+
+    #[test]
+    fn test_render() {
+        let mut problem = Problem::new(TestProblem::new());
+        problem.counts.insert("cost_count", 5);
+        let mut state: IterState<Vec<f64>, (), (), (), f64> =
+            IterState::new().param(vec![1.0]).cost(0.5).max_iters(10);
+        state.func_counts(&problem);
+        state.update();
+        for _ in 0..4 {
+            state.increment_iter();
+        }
+
+        let mut progress = ProgressBar::with_writer(Buffer::default()).bar_width(10);
+        progress.costs.extend([1.0, 0.5]);
+        let lines = progress.render(&state, Instant::now());
+        assert_eq!(lines[0], "[█████░░░░░] 5/10 (50%)  elapsed 0.0s");
+        assert_eq!(lines[1], "cost 5.0000e-1  best 5.0000e-1  █▁  cost_count 5");
+
+        // Without a maximum number of iterations there is no progress bar
+        let state = state.max_iters(u64::MAX);
+        let lines = progress.sparkline_len(0).render(&state, Instant::now());
+        assert_eq!(lines[0], "iter 5  elapsed 0.0s");
+        assert_eq!(lines[1], "cost 5.0000e-1  best 5.0000e-1  cost_count 5");
+    }
+
+    #[test]
+    fn test_plain() {
+        let buffer = Buffer::default();
+        run(
+            ProgressBar::with_writer(buffer.clone()).interval(Duration::ZERO),
+            3,
+        );
+        let content = buffer.content();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].contains("1/3 (33%)"));
+        assert!(lines[2].contains("3/3 (100%)"));
+        assert!(lines[2].contains(" | cost inf  best inf"));
+        assert!(!content.contains('\x1b'));
+    }
+
+    #[test]
+    fn test_interval() {
+        // Only the first and the last iteration are displayed
+        let buffer = Buffer::default();
+        run(
+            ProgressBar::with_writer(buffer.clone()).interval(Duration::from_secs(3600)),
+            5,
+        );
+        let content = buffer.content();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("1/5"));
+        assert!(lines[1].contains("5/5"));
+    }
+
+    #[test]
+    fn test_live() {
+        let buffer = Buffer::default();
+        run(
+            ProgressBar::with_writer(buffer.clone())
+                .live(true)
+                .interval(Duration::ZERO),
+            2,
+        );
+        let content = buffer.content();
+        assert_eq!(content.matches("\r\x1b[1A\x1b[2K").count(), 1);
+        assert_eq!(content.matches("\x1b[2K").count(), 4);
+        assert!(content.ends_with('\n'));
+        assert_eq!(content.matches('\n').count(), 3);
+    }
+}
@@ -0,0 +1,621 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Export metrics of an optimization run in the Prometheus text format.
+//!
+//! See documentation of [`PrometheusExporter`] for details.
+
+use crate::core::observers::Observe;
+use crate::core::{Error, State, TerminationStatus, KV};
+use num_traits::ToPrimitive;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Maximum time to wait for a client to send its request or to receive the response
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Current values of all metrics
+#[derive(Clone, Debug)]
+struct Metrics {
+    /// Name of the solver
+    solver: Option<String>,
+    /// Number of observed runs
+    runs: u64,
+    /// Current iteration number
+    iter: Option<u64>,
+    /// Current cost function value
+    cost: f64,
+    /// Best cost function value
+    best_cost: f64,
+    /// Function evaluation counts
+    func_counts: BTreeMap<String, u64>,
+    /// Upper bounds of the buckets of the iteration duration histogram
+    buckets: Vec<f64>,
+    /// Number of observations per bucket (not cumulative)
+    bucket_counts: Vec<u64>,
+    /// Sum of all iteration durations
+    duration_sum: f64,
+    /// Number of observed iteration durations
+    duration_count: u64,
+    /// Termination status at the end of the run
+    termination: Option<TerminationStatus>,
+}
+
+impl Metrics {
+    /// Resets all values except for the number of runs and the histogram buckets
+    fn reset(&mut self) {
+        self.solver = None;
+        self.iter = None;
+        self.cost = f64::NAN;
+        self.best_cost = f64::NAN;
+        self.func_counts.clear();
+        self.bucket_counts = vec![0; self.buckets.len()];
+        self.duration_sum = 0.0;
+        self.duration_count = 0;
+        self.termination = None;
+    }
+}
+
+/// Export metrics of an optimization run in the
+/// [Prometheus text exposition format](https://prometheus.io/docs/instrumenting/exposition_formats/).
+///
+/// The following metrics are exported (with the default prefix `argmin`):
+///
+/// * `argmin_runs_total`: Number of started optimization runs (counter)
+/// * `argmin_solver_info{solver="..."}`: Name of the solver (constant `1`)
+/// * `argmin_iteration`: Current iteration number (gauge)
+/// * `argmin_cost`: Current cost function value (gauge)
+/// * `argmin_best_cost`: Best cost function value so far (gauge)
+/// * `argmin_func_count_total{name="..."}`: Function evaluation counts per key as reported by
+///   [`State::get_func_counts`](`crate::core::State::get_func_counts`), for instance
+///   `cost_count` (counter)
+/// * `argmin_iteration_duration_seconds`: Duration of the iterations (histogram). This requires
+///   the timer of the [`Executor`](`crate::core::Executor`) to be enabled (the default).
+/// * `argmin_terminated`: `1` once the solver has terminated, `0` otherwise (gauge)
+/// * `argmin_termination_reason{reason="..."}`: Reason of the termination (constant `1`, only
+///   present once the solver has terminated)
+///
+/// All values except for `argmin_runs_total` are reset in `observe_init`. The metrics are only
+/// updated when the observer is called, which depends on the chosen
+/// [`ObserverMode`](`crate::core::observers::ObserverMode`).
+///
+/// `PrometheusExporter` is a shared handle: All clones refer to the same metrics. The metrics
+/// can be obtained as text via [`render`](`PrometheusExporter::render`) from any clone, or be
+/// served via HTTP with [`serve`](`PrometheusExporter::serve`).
+///
+/// # Example
+///
+/// ```
+/// use argmin::core::observers::{ObserverMode, PrometheusExporter};
+/// use argmin::core::{Error, Executor};
+/// # use argmin::core::test_utils::{TestSolver, TestProblem};
+///
+/// # fn main() -> Result<(), Error> {
+/// # let solver = TestSolver::new();
+/// # let problem = TestProblem::new();
+/// let metrics = PrometheusExporter::new();
+///
+/// let res = Executor::new(problem, solver)
+///     .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(10))
+///     .add_observer(metrics.clone(), ObserverMode::Always)
+///     .run()?;
+///
+/// let text = metrics.render();
+/// # assert!(text.contains("argmin_iteration 9\n"));
+/// # assert!(text.contains("argmin_terminated 1\n"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct PrometheusExporter {
+    /// Prefix of all metric names
+    prefix: String,
+    /// Shared metrics
+    metrics: Arc<Mutex<Metrics>>,
+}
+
+impl PrometheusExporter {
+    /// Create a new instance of `PrometheusExporter`.
+    ///
+    /// The buckets of the iteration duration histogram default to 1 ms, 5 ms, 10 ms, 50 ms,
+    /// 100 ms, 500 ms, 1 s, 5 s and 10 s.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use argmin::core::observers::PrometheusExporter;
+    ///
+    /// let metrics = PrometheusExporter::new();
+    /// ```
+    pub fn new() -> Self {
+        let buckets = vec![0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0];
+        let mut metrics = Metrics {
+            solver: None,
+            runs: 0,
+            iter: None,
+            cost: f64::NAN,
+            best_cost: f64::NAN,
+            func_counts: BTreeMap::new(),
+            buckets,
+            bucket_counts: vec![],
+            duration_sum: 0.0,
+            duration_count: 0,
+            termination: None,
+        };
+        metrics.reset();
+        PrometheusExporter {
+            prefix: "argmin".to_string(),
+            metrics: Arc::new(Mutex::new(metrics)),
+        }
+    }
+
+    /// Set the prefix of all metric names (default: `argmin`).
+    ///
+    /// The prefix must be a valid Prometheus metric name (`[a-zA-Z_:][a-zA-Z0-9_:]*`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use argmin::core::observers::PrometheusExporter;
+    /// # use argmin::core::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let metrics = PrometheusExporter::new().with_prefix("calibration")?;
+    /// # assert!(metrics.render().contains("calibration_runs_total 0\n"));
+    /// # assert!(PrometheusExporter::new().with_prefix("1abc").is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_prefix(mut self, prefix: &str) -> Result<Self, Error> {
+        let valid = prefix.chars().enumerate().all(|(idx, c)| {
+            c.is_ascii_alphabetic() || c == '_' || c == ':' || (idx > 0 && c.is_ascii_digit())
+        });
+        if prefix.is_empty() || !valid {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`PrometheusExporter`: prefix must match `[a-zA-Z_:][a-zA-Z0-9_:]*`."
+            ));
+        }
+        self.prefix = prefix.to_string();
+        Ok(self)
+    }
+
+    /// Set the upper bounds (in seconds) of the buckets of the iteration duration histogram.
+    ///
+    /// The bounds must be finite and strictly increasing. A bucket `+Inf` is always added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use argmin::core::observers::PrometheusExporter;
+    /// # use argmin::core::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let metrics = PrometheusExporter::new().with_buckets(vec![0.1, 1.0, 10.0])?;
+    /// # assert!(PrometheusExporter::new().with_buckets(vec![1.0, 0.1]).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_buckets(self, buckets: Vec<f64>) -> Result<Self, Error> {
+        if buckets.iter().any(|b| !b.is_finite()) || buckets.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`PrometheusExporter`: buckets must be finite and strictly increasing."
+            ));
+        }
+        {
+            let mut metrics = self.metrics.lock().unwrap();
+            metrics.buckets = buckets;
+            metrics.bucket_counts = vec![0; metrics.buckets.len()];
+        }
+        Ok(self)
+    }
+
+    /// Returns the current values of all metrics in the Prometheus text exposition format.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use argmin::core::observers::PrometheusExporter;
+    ///
+    /// let metrics = PrometheusExporter::new();
+    /// let text = metrics.render();
+    /// # assert!(text.contains("argmin_terminated 0\n"));
+    /// ```
+    pub fn render(&self) -> String {
+        let m = self.metrics.lock().unwrap().clone();
+        let p = &self.prefix;
+        let mut out = String::new();
+
+        let mut metric = |name: &str, kind: &str, help: &str, samples: Vec<(String, String)>| {
+            let _ = writeln!(out, "# HELP {p}_{name} {help}");
+            let _ = writeln!(out, "# TYPE {p}_{name} {kind}");
+            for (suffix, value) in samples {
+                let _ = writeln!(out, "{p}_{name}{suffix} {value}");
+            }
+        };
+
+        metric(
+            "runs_total",
+            "counter",
+            "Number of started optimization runs.",
+            vec![(String::new(), m.runs.to_string())],
+        );
+        metric(
+            "solver_info",
+            "gauge",
+            "Name of the solver.",
+            m.solver
+                .iter()
+                .map(|solver| {
+                    (
+                        format!("{{solver=\"{}\"}}", escape(solver)),
+                        "1".to_string(),
+                    )
+                })
+                .collect(),
+        );
+        metric(
+            "iteration",
+            "gauge",
+            "Current iteration number.",
+            m.iter
+                .iter()
+                .map(|iter| (String::new(), iter.to_string()))
+                .collect(),
+        );
+        metric(
+            "cost",
+            "gauge",
+            "Current cost function value.",
+            vec![(String::new(), fmt_float(m.cost))],
+        );
+        metric(
+            "best_cost",
+            "gauge",
+            "Best cost function value so far.",
+            vec![(String::new(), fmt_float(m.best_cost))],
+        );
+        metric(
+            "func_count_total",
+            "counter",
+            "Number of function evaluations.",
+            m.func_counts
+                .iter()
+                .map(|(name, count)| (format!("{{name=\"{}\"}}", escape(name)), count.to_string()))
+                .collect(),
+        );
+
+        let mut cumulative = 0;
+        let mut histogram: Vec<(String, String)> = m
+            .buckets
+            .iter()
+            .zip(m.bucket_counts.iter())
+            .map(|(bound, count)| {
+                cumulative += count;
+                (
+                    format!("_bucket{{le=\"{}\"}}", fmt_float(*bound)),
+                    cumulative.to_string(),
+                )
+            })
+            .collect();
+        histogram.push((
+            "_bucket{le=\"+Inf\"}".to_string(),
+            m.duration_count.to_string(),
+        ));
+        histogram.push(("_sum".to_string(), fmt_float(m.duration_sum)));
+        histogram.push(("_count".to_string(), m.duration_count.to_string()));
+        metric(
+            "iteration_duration_seconds",
+            "histogram",
+            "Duration of the iterations in seconds.",
+            histogram,
+        );
+
+        let terminated = matches!(m.termination, Some(ref t) if t.terminated());
+        metric(
+            "terminated",
+            "gauge",
+            "Whether the solver has terminated.",
+            vec![(String::new(), u8::from(terminated).to_string())],
+        );
+        metric(
+            "termination_reason",
+            "gauge",
+            "Reason of the termination.",
+            m.termination
+                .iter()
+                .filter(|t| t.terminated())
+                .map(|t| {
+                    (
+                        format!("{{reason=\"{}\"}}", escape(&t.to_string())),
+                        "1".to_string(),
+                    )
+                })
+                .collect(),
+        );
+        out
+    }
+
+    /// Serves the metrics via HTTP at `http://<addr>/metrics`.
+    ///
+    /// A background thread is spawned which answers every `GET /metrics` request with the output
+    /// of [`render`](`PrometheusExporter::render`) and all other requests with `404 Not Found`.
+    /// The thread runs until the end of the program. Each connection is handled on its own thread
+    /// and closed if the client does not send its request or receive the response within five
+    /// seconds, such that slow or idle clients do not block others. Returns the address the server
+    /// is bound to, which is useful when binding to port 0.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use argmin::core::observers::PrometheusExporter;
+    /// # use argmin::core::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let metrics = PrometheusExporter::new();
+    /// let addr = metrics.serve("127.0.0.1:9184")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn serve<A: ToSocketAddrs>(&self, addr: A) -> Result<SocketAddr, Error> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let exporter = self.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let exporter = exporter.clone();
+                std::thread::spawn(move || {
+                    // A misbehaving client must not stop the server
+                    let _ = exporter.respond(stream);
+                });
+            }
+        });
+        Ok(addr)
+    }
+
+    /// Answers a single HTTP request
+    fn respond(&self, mut stream: TcpStream) -> Result<(), Error> {
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut request = String::new();
+        reader.read_line(&mut request)?;
+        // Skip the headers
+        let mut line = String::new();
+        while reader.read_line(&mut line)? > 2 {
+            line.clear();
+        }
+
+        let path = request.split_whitespace().nth(1).unwrap_or("");
+        let (status, body) = if request.starts_with("GET ") && path == "/metrics" {
+            ("200 OK", self.render())
+        } else {
+            ("404 Not Found", "Not Found\n".to_string())
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )?;
+        stream.flush()?;
+        Ok(())
+    }
+}
+
+impl Default for PrometheusExporter {
+    fn default() -> Self {
+        PrometheusExporter::new()
+    }
+}
+
+/// Formats a float as expected by Prometheus
+fn fmt_float(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Escapes a label value
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+impl<I> Observe<I> for PrometheusExporter
+where
+    I: State,
+{
+    /// Resets the metrics and stores the name of the solver.
+    fn observe_init(&mut self, name: &str, _kv: &KV) -> Result<(), Error> {
+        let mut metrics = self.metrics.lock().unwrap();
+        metrics.reset();
+        metrics.runs += 1;
+        metrics.solver = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Updates the metrics with the current state and the duration of the iteration.
+    fn observe_iter(&mut self, state: &I, kv: &KV) -> Result<(), Error> {
+        let mut metrics = self.metrics.lock().unwrap();
+        metrics.iter = Some(state.get_iter());
+        metrics.cost = state.get_cost().to_f64().unwrap_or(f64::NAN);
+        metrics.best_cost = state.get_best_cost().to_f64().unwrap_or(f64::NAN);
+        metrics.func_counts = state
+            .get_func_counts()
+            .iter()
+            .map(|(name, &count)| (name.clone(), count))
+            .collect();
+        if let Some(duration) = kv.get("time").and_then(|time| time.get_float()) {
+            if let Some(idx) = metrics.buckets.iter().position(|&b| duration <= b) {
+                metrics.bucket_counts[idx] += 1;
+            }
+            metrics.duration_sum += duration;
+            metrics.duration_count += 1;
+        }
+        Ok(())
+    }
+
+    /// Stores the termination status.
+    fn observe_final(&mut self, state: &I) -> Result<(), Error> {
+        let mut metrics = self.metrics.lock().unwrap();
+        metrics.termination = Some(state.get_termination_status().clone());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::observers::ObserverMode;
+    use crate::core::test_utils::{TestProblem, TestSolver};
+    use crate::core::{ArgminError, Executor, IterState, Problem};
+    use std::io::Read;
+
+    send_sync_test!(prometheus_exporter, PrometheusExporter);
+
+    #[test]
+    fn test_with_prefix() {
+        for prefix in ["argmin", "_a:b_1", "Calibration"] {
+            assert!(PrometheusExporter::new().with_prefix(prefix).is_ok());
+        }
+        for prefix in ["", "1a", "a-b", "a b"] {
+            assert_error!(
+                PrometheusExporter::new().with_prefix(prefix),
+                ArgminError,
+                "Invalid parameter: \"`PrometheusExporter`: prefix must match `[a-zA-Z_:][a-zA-Z0-9_:]*`.\""
+            );
+        }
+    }
+
+    #[test]
+    fn test_with_buckets() {
+        assert!(PrometheusExporter::new().with_buckets(vec![]).is_ok());
+        for buckets in [vec![1.0, 1.0], vec![2.0, 1.0], vec![1.0, f64::INFINITY]] {
+            assert_error!(
+                PrometheusExporter::new().with_buckets(buckets),
+                ArgminError,
+                "Invalid parameter: \"`PrometheusExporter`: buckets must be finite and strictly increasing.\""
+            );
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let mut metrics = PrometheusExporter::new()
+            .with_prefix("opt")
+            .unwrap()
+            .with_buckets(vec![0.1, 1.0])
+            .unwrap();
+        type TState = IterState<Vec<f64>, (), (), (), f64>;
+        <PrometheusExporter as Observe<TState>>::observe_init(
+            &mut metrics,
+            "My\"Solver",
+            &KV::new(),
+        )
+        .unwrap();
+
+        let mut problem = Problem::new(TestProblem::new());
+        problem.counts.insert("cost_count", 7);
+        problem.counts.insert("gradient_count", 3);
+        let mut state: TState = IterState::new().param(vec![1.0]).cost(2.5);
+        state.func_counts(&problem);
+        state.update();
+        metrics.observe_iter(&state, &kv!("time" => 0.05;)).unwrap();
+        metrics.observe_iter(&state, &kv!("time" => 0.5;)).unwrap();
+        metrics.observe_iter(&state, &kv!("time" => 2.0;)).unwrap();
+        let state = state.terminate_with(crate::core::TerminationReason::MaxItersReached);
+        metrics.observe_final(&state).unwrap();
+
+        let text = metrics.render();
+        let samples: Vec<&str> = text.lines().filter(|l| !l.starts_with('#')).collect();
+        assert_eq!(
+            samples,
+            vec![
+                "opt_runs_total 1",
+                "opt_solver_info{solver=\"My\\\"Solver\"} 1",
+                "opt_iteration 0",
+                "opt_cost 2.5",
+                "opt_best_cost 2.5",
+                "opt_func_count_total{name=\"cost_count\"} 7",
+                "opt_func_count_total{name=\"gradient_count\"} 3",
+                "opt_iteration_duration_seconds_bucket{le=\"0.1\"} 1",
+                "opt_iteration_duration_seconds_bucket{le=\"1\"} 2",
+                "opt_iteration_duration_seconds_bucket{le=\"+Inf\"} 3",
+                "opt_iteration_duration_seconds_sum 2.55",
+                "opt_iteration_duration_seconds_count 3",
+                "opt_terminated 1",
+                "opt_termination_reason{reason=\"Maximum number of iterations reached\"} 1",
+            ]
+        );
+        assert!(text.contains("# TYPE opt_iteration_duration_seconds histogram\n"));
+        assert!(text.contains("# TYPE opt_func_count_total counter\n"));
+
+        // A new run resets everything but the number of runs
+        <PrometheusExporter as Observe<TState>>::observe_init(&mut metrics, "solver", &KV::new())
+            .unwrap();
+        let text = metrics.render();
+        assert!(text.contains("opt_runs_total 2\n"));
+        assert!(text.contains("opt_cost NaN\n"));
+        assert!(text.contains("opt_iteration_duration_seconds_count 0\n"));
+        assert!(text.contains("opt_terminated 0\n"));
+        assert!(!text.contains("\nopt_iteration "));
+        assert!(!text.contains("\nopt_termination_reason{"));
+    }
+
+    #[test]
+    fn test_executor() {
+        let metrics = PrometheusExporter::new();
+        Executor::new(TestProblem::new(), TestSolver::new())
+            .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(3))
+            .add_observer(metrics.clone(), ObserverMode::Always)
+            .ctrlc(false)
+            .run()
+            .unwrap();
+
+        let text = metrics.render();
+        assert!(text.contains("argmin_solver_info{solver=\"TestSolver\"} 1\n"));
+        assert!(text.contains("argmin_iteration 2\n"));
+        assert!(text.contains("argmin_cost +Inf\n"));
+        assert!(text.contains("argmin_iteration_duration_seconds_count 3\n"));
+        assert!(text.contains(
+            "argmin_termination_reason{reason=\"Maximum number of iterations reached\"} 1\n"
+        ));
+    }
+
+    #[test]
+    fn test_serve() {
+        let metrics = PrometheusExporter::new();
+        let addr = metrics.serve("127.0.0.1:0").unwrap();
+
+        let request = |path: &str| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        // An idle client does not block other clients
+        let _idle = TcpStream::connect(addr).unwrap();
+
+        let response = request("/metrics");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with(&metrics.render()));
+        assert!(request("/").starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+}
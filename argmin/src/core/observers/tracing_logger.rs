@@ -0,0 +1,444 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Observer based on the `tracing` crate
+//!
+//! This observer emits spans for the optimization run and for each iteration, with the state and
+//! the key-value pairs reported by the solver as structured fields.
+//! See [`TracingLogger`] for details regarding usage.
+
+use crate::core::observers::Observe;
+use crate::core::{Error, State, KV};
+use instant::Instant;
+use num_traits::ToPrimitive;
+use tracing::field::Empty;
+use tracing::{info_span, Span};
+
+/// Name of the span covering an optimization run
+const RUN_SPAN: &str = "argmin_run";
+/// Name of the spans emitted for each iteration
+const ITER_SPAN: &str = "argmin_iteration";
+
+/// An observer emitting spans via the [`tracing`](https://crates.io/crates/tracing) crate.
+///
+/// In `observe_init` a span named `argmin_run` is created and entered. Its fields are the name of
+/// the solver (`solver`) and the key-value pairs reported by the solver during initialization
+/// (`kv`). The span is a child of the span which is current when the optimization starts,
+/// therefore optimization runs show up inside existing traces. It stays entered during the
+/// optimization, such that spans and events emitted by the problem or the solver are nested
+/// inside of it, and is exited and closed in `observe_final` (or when the observer is dropped
+/// before).
+///
+/// For each observed iteration a span named `argmin_iteration` is emitted as a child of the run
+/// span. Since observers are only called after an iteration, this span does not cover the
+/// iteration itself. Instead, the time in seconds which passed since the previous observation (or
+/// since `observe_init`) is recorded in the field `elapsed`. Further fields are the iteration
+/// number (`iter`), the current and the best cost function value (`cost`, `best_cost`), all
+/// function evaluation counts (`func_counts`) and all key-value pairs reported by the solver and
+/// the executor (`kv`, for instance the duration of the iteration `time`, if timing is enabled).
+///
+/// The function evaluation counts and the key-value pairs are rendered as `key=value`, sorted by
+/// key and separated by spaces (for instance `cost_count=2 gradient_count=1`). These fields are
+/// left empty if there are no such pairs.
+///
+/// Both spans have level `INFO` and the target `argmin`.
+///
+/// Requires the `tracing` feature.
+///
+/// # Example
+///
+/// ```
+/// use argmin::core::observers::{ObserverMode, TracingLogger};
+/// use argmin::core::{Error, Executor};
+/// # use argmin::core::test_utils::{TestSolver, TestProblem};
+///
+/// # fn main() -> Result<(), Error> {
+/// # let solver = TestSolver::new();
+/// # let problem = TestProblem::new();
+/// let res = Executor::new(problem, solver)
+///     .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(10))
+///     .add_observer(TracingLogger::new(), ObserverMode::Always)
+///     .run()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TracingLogger {
+    /// Span of the current optimization run
+    run: Span,
+    /// Whether `run` was entered by this observer and not exited yet
+    entered: bool,
+    /// Time of the previous observation
+    last: Option<Instant>,
+}
+
+impl TracingLogger {
+    /// Create a new instance of `TracingLogger`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use argmin::core::observers::TracingLogger;
+    ///
+    /// let logger = TracingLogger::new();
+    /// ```
+    pub fn new() -> Self {
+        TracingLogger {
+            run: Span::none(),
+            entered: false,
+            last: None,
+        }
+    }
+
+    /// Exits the span of the optimization run if it was entered
+    fn exit(&mut self) {
+        if self.entered {
+            self.run.with_subscriber(|(id, dispatch)| dispatch.exit(id));
+            self.entered = false;
+        }
+    }
+}
+
+impl Clone for TracingLogger {
+    /// Clones the observer. The clone refers to the same run span, but does not exit it.
+    fn clone(&self) -> Self {
+        TracingLogger {
+            run: self.run.clone(),
+            entered: false,
+            last: self.last,
+        }
+    }
+}
+
+impl Drop for TracingLogger {
+    fn drop(&mut self) {
+        self.exit();
+    }
+}
+
+impl Default for TracingLogger {
+    fn default() -> Self {
+        TracingLogger::new()
+    }
+}
+
+/// Renders key-value pairs sorted by key as `key=value`, separated by spaces
+fn render<K, V>(pairs: impl IntoIterator<Item = (K, V)>) -> String
+where
+    K: AsRef<str> + Ord,
+    V: std::fmt::Display,
+{
+    let mut pairs: Vec<(K, V)> = pairs.into_iter().collect();
+    pairs.sort_by(|(a, _), (b, _)| a.cmp(b));
+    pairs
+        .iter()
+        .map(|(key, value)| format!("{}={value}", key.as_ref()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Records the rendered key-value pairs in the field `field` of `span`, unless there are none
+fn record(span: &Span, field: &str, rendered: String) {
+    if !rendered.is_empty() {
+        span.record(field, rendered.as_str());
+    }
+}
+
+impl<I> Observe<I> for TracingLogger
+where
+    I: State,
+{
+    /// Creates and enters the span of the optimization run.
+    fn observe_init(&mut self, name: &str, kv: &KV) -> Result<(), Error> {
+        self.exit();
+        self.run = info_span!(target: "argmin", RUN_SPAN, solver = name, kv = Empty);
+        record(&self.run, "kv", render(kv.kv.iter().map(|(k, v)| (*k, v))));
+        self.entered = self
+            .run
+            .with_subscriber(|(id, dispatch)| dispatch.enter(id))
+            .is_some();
+        self.last = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Emits a span for the current iteration.
+    fn observe_iter(&mut self, state: &I, kv: &KV) -> Result<(), Error> {
+        let now = Instant::now();
+        let elapsed = self
+            .last
+            .replace(now)
+            .map(|last| now.duration_since(last).as_secs_f64())
+            .unwrap_or(0.0);
+        let span = info_span!(
+            target: "argmin",
+            parent: &self.run,
+            ITER_SPAN,
+            iter = state.get_iter(),
+            cost = state.get_cost().to_f64().unwrap_or(f64::NAN),
+            best_cost = state.get_best_cost().to_f64().unwrap_or(f64::NAN),
+            elapsed,
+            func_counts = Empty,
+            kv = Empty,
+        );
+        record(&span, "func_counts", render(state.get_func_counts()));
+        record(&span, "kv", render(kv.kv.iter().map(|(k, v)| (*k, v))));
+        span.in_scope(|| {});
+        Ok(())
+    }
+
+    /// Exits and closes the span of the optimization run.
+    fn observe_final(&mut self, _state: &I) -> Result<(), Error> {
+        self.exit();
+        self.run = Span::none();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::observers::ObserverMode;
+    use crate::core::test_utils::{TestProblem, TestSolver};
+    use crate::core::Executor;
+    use std::fmt::Debug;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    send_sync_test!(tracing_logger, TracingLogger);
+
+    /// A recorded span: name, name of the parent span and fields
+    type Recorded = (String, Option<String>, Vec<(String, String)>);
+
+    /// Subscriber which records all created spans as well as entering and exiting them
+    #[derive(Clone, Default)]
+    struct Recorder {
+        spans: Arc<Mutex<Vec<Recorded>>>,
+        current: Arc<Mutex<Vec<u64>>>,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Recorder {
+        fn name(&self, span: &Id) -> String {
+            self.spans.lock().unwrap()[span.into_u64() as usize - 1]
+                .0
+                .clone()
+        }
+    }
+
+    /// Removes the `elapsed` field and checks that it is a valid duration
+    fn without_elapsed(fields: &[(String, String)]) -> Vec<(String, String)> {
+        let elapsed: Vec<f64> = fields
+            .iter()
+            .filter(|(name, _)| name == "elapsed")
+            .map(|(_, value)| value.parse().unwrap())
+            .collect();
+        assert_eq!(elapsed.len(), 1);
+        assert!(elapsed[0] >= 0.0);
+        fields
+            .iter()
+            .filter(|(name, _)| name != "elapsed")
+            .cloned()
+            .collect()
+    }
+
+    struct Fields(Vec<(String, String)>);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.0
+                .push((field.name().to_string(), format!("{value:?}")));
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut fields = Fields(vec![]);
+            span.record(&mut fields);
+            let mut spans = self.spans.lock().unwrap();
+            let parent = span
+                .parent()
+                .map(Id::into_u64)
+                .or_else(|| {
+                    span.is_contextual()
+                        .then(|| self.current.lock().unwrap().last().copied())
+                        .flatten()
+                })
+                .map(|id| spans[id as usize - 1].0.clone());
+            spans.push((span.metadata().name().to_string(), parent, fields.0));
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, span: &Id, values: &Record<'_>) {
+            let mut fields = Fields(vec![]);
+            values.record(&mut fields);
+            self.spans.lock().unwrap()[span.into_u64() as usize - 1]
+                .2
+                .extend(fields.0);
+        }
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, _event: &Event<'_>) {}
+
+        fn enter(&self, span: &Id) {
+            self.current.lock().unwrap().push(span.into_u64());
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("enter {}", self.name(span)));
+        }
+
+        fn exit(&self, span: &Id) {
+            self.current.lock().unwrap().pop();
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("exit {}", self.name(span)));
+        }
+    }
+
+    #[test]
+    fn test_spans() {
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            tracing::info_span!("outer").in_scope(|| {
+                Executor::new(TestProblem::new(), TestSolver::new())
+                    .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(2))
+                    .add_observer(TracingLogger::new(), ObserverMode::Always)
+                    .timer(false)
+                    .ctrlc(false)
+                    .run()
+                    .unwrap();
+            })
+        });
+
+        let spans = recorder.spans.lock().unwrap().clone();
+        assert_eq!(spans.len(), 4);
+        assert_eq!(spans[0].0, "outer");
+        assert_eq!(
+            spans[1],
+            (
+                RUN_SPAN.to_string(),
+                Some("outer".to_string()),
+                vec![
+                    ("solver".to_string(), "\"TestSolver\"".to_string()),
+                    ("kv".to_string(), "\"max_iters=2\"".to_string()),
+                ]
+            )
+        );
+        for (iter, span) in spans[2..].iter().enumerate() {
+            assert_eq!(span.0, ITER_SPAN);
+            assert_eq!(span.1, Some(RUN_SPAN.to_string()));
+            assert_eq!(
+                without_elapsed(&span.2),
+                vec![
+                    ("iter".to_string(), iter.to_string()),
+                    ("cost".to_string(), "inf".to_string()),
+                    ("best_cost".to_string(), "inf".to_string()),
+                ]
+            );
+        }
+
+        // The run span is entered during the whole optimization run
+        assert_eq!(
+            *recorder.log.lock().unwrap(),
+            vec![
+                "enter outer",
+                "enter argmin_run",
+                "enter argmin_iteration",
+                "exit argmin_iteration",
+                "enter argmin_iteration",
+                "exit argmin_iteration",
+                "exit argmin_run",
+                "exit outer",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_kv_fields() {
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let mut logger = TracingLogger::new();
+            <TracingLogger as Observe<crate::core::IterState<Vec<f64>, (), (), (), f64>>>::observe_init(
+                &mut logger,
+                "solver",
+                &kv!("b" => 2u64; "a" => "x";),
+            )
+            .unwrap();
+            let mut problem = crate::core::Problem::new(());
+            problem.counts.insert("gradient_count", 1);
+            problem.counts.insert("cost_count", 2);
+            let mut state = crate::core::IterState::<Vec<f64>, (), (), (), f64>::new().cost(1.5);
+            state.func_counts(&problem);
+            logger
+                .observe_iter(&state, &kv!("gamma" => 0.5; "iter" => 7u64; "ok" => true;))
+                .unwrap();
+        });
+
+        let spans = recorder.spans.lock().unwrap().clone();
+        assert_eq!(spans.len(), 2);
+        assert_eq!(
+            spans[0].2,
+            vec![
+                ("solver".to_string(), "\"solver\"".to_string()),
+                ("kv".to_string(), "\"a=x b=2\"".to_string()),
+            ]
+        );
+        assert_eq!(
+            without_elapsed(&spans[1].2),
+            vec![
+                ("iter".to_string(), "0".to_string()),
+                ("cost".to_string(), "1.5".to_string()),
+                ("best_cost".to_string(), "inf".to_string()),
+                (
+                    "func_counts".to_string(),
+                    "\"cost_count=2 gradient_count=1\"".to_string()
+                ),
+                ("kv".to_string(), "\"gamma=0.5 iter=7 ok=true\"".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_drop_exits_run_span() {
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let mut logger = TracingLogger::new();
+            <TracingLogger as Observe<crate::core::IterState<Vec<f64>, (), (), (), f64>>>::observe_init(
+                &mut logger,
+                "solver",
+                &KV::new(),
+            )
+            .unwrap();
+            // Clones do not exit the span
+            drop(logger.clone());
+            assert_eq!(*recorder.log.lock().unwrap(), vec!["enter argmin_run"]);
+            drop(logger);
+        });
+        assert_eq!(
+            *recorder.log.lock().unwrap(),
+            vec!["enter argmin_run", "exit argmin_run"]
+        );
+    }
+
+    #[test]
+    fn test_no_subscriber() {
+        Executor::new(TestProblem::new(), TestSolver::new())
+            .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(2))
+            .add_observer(TracingLogger::new(), ObserverMode::Always)
+            .ctrlc(false)
+            .run()
+            .unwrap();
+    }
+}
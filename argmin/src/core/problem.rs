@@ -151,6 +151,17 @@ impl<O> Problem<O> {
         func(self.problem.as_ref().unwrap())
     }
 
+    /// Adds the function evaluation counts which the stored `problem` performed internally (as
+    /// reported by `take_counts`) to the function evaluation counts.
+    fn add_internal_counts<F: FnOnce(&O) -> Vec<(&'static str, u64)>>(&mut self, take_counts: F) {
+        if let Some(problem) = self.problem.as_ref() {
+            for (k, v) in take_counts(problem) {
+                let count = self.counts.entry(k).or_insert(0);
+                *count += v;
+            }
+        }
+    }
+
     /// Returns the internally stored problem and replaces it with `None`.
     ///
     /// # Example
@@ -411,6 +422,14 @@ pub trait Gradient {
     fn gradient(&self, param: &Self::Param) -> Result<Self::Gradient, Error>;
 
     bulk!(gradient, Self::Param, Self::Gradient);
+
+    /// Returns and resets the number of evaluations of other functions which were performed
+    /// internally by `gradient`, labeled by `<something>_count`. These are added to the function
+    /// evaluation counts of [`Problem`]. This is only of interest for wrappers such as
+    /// [`FiniteDiff`](`crate::core::FiniteDiff`) and by default no evaluations are reported.
+    fn take_internal_counts(&self) -> Vec<(&'static str, u64)> {
+        vec![]
+    }
 }
 
 /// Defines the computation of the Hessian.
@@ -442,6 +461,14 @@ pub trait Hessian {
     fn hessian(&self, param: &Self::Param) -> Result<Self::Hessian, Error>;
 
     bulk!(hessian, Self::Param, Self::Hessian);
+
+    /// Returns and resets the number of evaluations of other functions which were performed
+    /// internally by `hessian`, labeled by `<something>_count`. These are added to the function
+    /// evaluation counts of [`Problem`]. This is only of interest for wrappers such as
+    /// [`FiniteDiff`](`crate::core::FiniteDiff`) and by default no evaluations are reported.
+    fn take_internal_counts(&self) -> Vec<(&'static str, u64)> {
+        vec![]
+    }
 }
 
 /// Defines the computation of the Jacobian.
@@ -476,9 +503,122 @@ pub trait Jacobian {
     fn jacobian(&self, param: &Self::Param) -> Result<Self::Jacobian, Error>;
 
     bulk!(jacobian, Self::Param, Self::Jacobian);
+
+    /// Returns and resets the number of evaluations of other functions which were performed
+    /// internally by `jacobian`, labeled by `<something>_count`. These are added to the function
+    /// evaluation counts of [`Problem`]. This is only of interest for wrappers such as
+    /// [`FiniteDiff`](`crate::core::FiniteDiff`) and by default no evaluations are reported.
+    fn take_internal_counts(&self) -> Vec<(&'static str, u64)> {
+        vec![]
+    }
+}
+
+/// Defines equality constraints `c(x) = 0`
+///
+/// The constraints are returned as a vector with one entry per constraint. The Jacobian is
+/// optional and given as a list of the gradients of the individual constraints (i.e. the rows of
+/// the Jacobian). It is only needed by solvers which use derivatives.
+///
+/// # Example
+///
+/// ```
+/// use argmin::core::{EqualityConstraints, Error};
+///
+/// struct Problem {}
+///
+/// impl EqualityConstraints for Problem {
+///     type Param = Vec<f64>;
+///     type Gradient = Vec<f64>;
+///     type Float = f64;
+///
+///     /// x_0 + x_1 = 1
+///     fn equality_constraints(&self, p: &Self::Param) -> Result<Vec<Self::Float>, Error> {
+///         Ok(vec![p[0] + p[1] - 1.0])
+///     }
+///
+///     fn equality_jacobian(&self, p: &Self::Param) -> Result<Vec<Self::Gradient>, Error> {
+///         Ok(vec![vec![1.0, 1.0]])
+///     }
+/// }
+/// ```
+pub trait EqualityConstraints {
+    /// Type of the parameter vector
+    type Param;
+    /// Type of the gradient of a single constraint
+    type Gradient;
+    /// Precision of floats
+    type Float: ArgminFloat;
+
+    /// Compute values of the equality constraints
+    fn equality_constraints(&self, param: &Self::Param) -> Result<Vec<Self::Float>, Error>;
+
+    /// Compute Jacobian of the equality constraints, given as a list of rows
+    fn equality_jacobian(&self, _param: &Self::Param) -> Result<Vec<Self::Gradient>, Error> {
+        Err(argmin_error!(
+            NotImplemented,
+            "Method `equality_jacobian` of EqualityConstraints trait not implemented!"
+        ))
+    }
+}
+
+/// Defines inequality constraints `c(x) >= 0`
+///
+/// The constraints are returned as a vector with one entry per constraint. The Jacobian is
+/// optional and given as a list of the gradients of the individual constraints (i.e. the rows of
+/// the Jacobian). It is only needed by solvers which use derivatives.
+///
+/// # Example
+///
+/// ```
+/// use argmin::core::{InequalityConstraints, Error};
+///
+/// struct Problem {}
+///
+/// impl InequalityConstraints for Problem {
+///     type Param = Vec<f64>;
+///     type Gradient = Vec<f64>;
+///     type Float = f64;
+///
+///     /// x_0^2 + x_1^2 <= 1
+///     fn inequality_constraints(&self, p: &Self::Param) -> Result<Vec<Self::Float>, Error> {
+///         Ok(vec![1.0 - p[0].powi(2) - p[1].powi(2)])
+///     }
+///
+///     fn inequality_jacobian(&self, p: &Self::Param) -> Result<Vec<Self::Gradient>, Error> {
+///         Ok(vec![vec![-2.0 * p[0], -2.0 * p[1]]])
+///     }
+/// }
+/// ```
+pub trait InequalityConstraints {
+    /// Type of the parameter vector
+    type Param;
+    /// Type of the gradient of a single constraint
+    type Gradient;
+    /// Precision of floats
+    type Float: ArgminFloat;
+
+    /// Compute values of the inequality constraints
+    fn inequality_constraints(&self, param: &Self::Param) -> Result<Vec<Self::Float>, Error>;
+
+    /// Compute Jacobian of the inequality constraints, given as a list of rows
+    fn inequality_jacobian(&self, _param: &Self::Param) -> Result<Vec<Self::Gradient>, Error> {
+        Err(argmin_error!(
+            NotImplemented,
+            "Method `inequality_jacobian` of InequalityConstraints trait not implemented!"
+        ))
+    }
 }
 
-/// Defines a linear Program
+/// Defines a linear program in standard form
+///
+/// ```text
+/// minimize    c^T x
+/// subject to  A x = b
+///             x >= 0
+/// ```
+///
+/// Inequality constraints can be brought into this form by adding slack variables and free
+/// variables can be split into the difference of two nonnegative variables.
 ///
 /// # Example
 ///
@@ -510,8 +650,7 @@ pub trait LinearProgram {
     /// Precision of floats
     type Float: ArgminFloat;
 
-    /// TODO c for linear programs
-    /// Those three could maybe be merged into a single function; name unclear
+    /// Coefficients `c` of the linear objective function
     fn c(&self) -> Result<Vec<Self::Float>, Error> {
         Err(argmin_error!(
             NotImplemented,
@@ -519,7 +658,7 @@ pub trait LinearProgram {
         ))
     }
 
-    /// TODO b for linear programs
+    /// Right-hand side `b` of the equality constraints
     fn b(&self) -> Result<Vec<Self::Float>, Error> {
         Err(argmin_error!(
             NotImplemented,
@@ -527,7 +666,7 @@ pub trait LinearProgram {
         ))
     }
 
-    /// TODO A for linear programs
+    /// Constraint matrix `A`, given as a list of rows
     #[allow(non_snake_case)]
     fn A(&self) -> Result<Vec<Vec<Self::Float>>, Error> {
         Err(argmin_error!(
@@ -731,7 +870,9 @@ impl<O: Gradient> Problem<O> {
     /// # assert_eq!(res.unwrap(), vec![1.0f64, 1.0f64]);
     /// ```
     pub fn gradient(&mut self, param: &O::Param) -> Result<O::Gradient, Error> {
-        self.problem("gradient_count", |problem| problem.gradient(param))
+        let gradient = self.problem("gradient_count", |problem| problem.gradient(param));
+        self.add_internal_counts(Gradient::take_internal_counts);
+        gradient
     }
 
     /// Calls `bulk_gradient` defined in the `Gradient` trait and keeps track of the number of
@@ -773,9 +914,11 @@ impl<O: Gradient> Problem<O> {
         O::Gradient: SendAlias,
         O: SyncAlias,
     {
-        self.bulk_problem("gradient_count", params.len(), |problem| {
+        let gradients = self.bulk_problem("gradient_count", params.len(), |problem| {
             problem.bulk_gradient(params)
-        })
+        });
+        self.add_internal_counts(Gradient::take_internal_counts);
+        gradients
     }
 }
 
@@ -811,7 +954,9 @@ impl<O: Hessian> Problem<O> {
     /// # assert_eq!(res.unwrap(), vec![vec![1.0f64, 0.0f64], vec![0.0f64, 1.0f64]]);
     /// ```
     pub fn hessian(&mut self, param: &O::Param) -> Result<O::Hessian, Error> {
-        self.problem("hessian_count", |problem| problem.hessian(param))
+        let hessian = self.problem("hessian_count", |problem| problem.hessian(param));
+        self.add_internal_counts(Hessian::take_internal_counts);
+        hessian
     }
 
     /// Calls `bulk_hessian` defined in the `Hessian` trait and keeps track of the number of
@@ -853,9 +998,11 @@ impl<O: Hessian> Problem<O> {
         O::Hessian: SendAlias,
         O: SyncAlias,
     {
-        self.bulk_problem("hessian_count", params.len(), |problem| {
+        let hessians = self.bulk_problem("hessian_count", params.len(), |problem| {
             problem.bulk_hessian(params)
-        })
+        });
+        self.add_internal_counts(Hessian::take_internal_counts);
+        hessians
     }
 }
 
@@ -892,7 +1039,9 @@ impl<O: Jacobian> Problem<O> {
     /// # assert_eq!(res.unwrap(), vec![vec![1.0f64, 0.0f64], vec![0.0f64, 1.0f64]]);
     /// ```
     pub fn jacobian(&mut self, param: &O::Param) -> Result<O::Jacobian, Error> {
-        self.problem("jacobian_count", |problem| problem.jacobian(param))
+        let jacobian = self.problem("jacobian_count", |problem| problem.jacobian(param));
+        self.add_internal_counts(Jacobian::take_internal_counts);
+        jacobian
     }
 
     /// Calls `bulk_jacobian` defined in the `Jacobian` trait and keeps track of the number of
@@ -932,8 +1081,180 @@ impl<O: Jacobian> Problem<O> {
         O::Jacobian: SendAlias,
         O: SyncAlias,
     {
-        self.bulk_problem("jacobian_count", params.len(), |problem| {
+        let jacobians = self.bulk_problem("jacobian_count", params.len(), |problem| {
             problem.bulk_jacobian(params)
+        });
+        self.add_internal_counts(Jacobian::take_internal_counts);
+        jacobians
+    }
+}
+
+/// Wraps calls to `equality_constraints` and `equality_jacobian` defined in the `EqualityConstraints`
+/// trait and as such allows to call those methods on an instance of `Problem`. Internally, the
+/// number of evaluations is counted.
+impl<O: EqualityConstraints> Problem<O> {
+    /// Calls `equality_constraints` defined in the `EqualityConstraints` trait and keeps track of
+    /// the number of evaluations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{Problem, EqualityConstraints, Error};
+    /// #
+    /// # #[derive(Eq, PartialEq, Debug, Clone)]
+    /// # struct UserDefinedProblem {};
+    /// #
+    /// # impl EqualityConstraints for UserDefinedProblem {
+    /// #     type Param = Vec<f64>;
+    /// #     type Gradient = Vec<f64>;
+    /// #     type Float = f64;
+    /// #
+    /// #     fn equality_constraints(&self, p: &Self::Param) -> Result<Vec<f64>, Error> {
+    /// #         Ok(vec![p[0] + p[1] - 1.0])
+    /// #     }
+    /// #
+    /// #     fn equality_jacobian(&self, p: &Self::Param) -> Result<Vec<Vec<f64>>, Error> {
+    /// #         Ok(vec![vec![1.0, 1.0]])
+    /// #     }
+    /// # }
+    /// // `UserDefinedProblem` implements `EqualityConstraints`.
+    /// let mut problem1 = Problem::new(UserDefinedProblem {});
+    ///
+    /// let param = vec![2.0f64, 1.0f64];
+    ///
+    /// let res = problem1.equality_constraints(&param);
+    ///
+    /// assert_eq!(problem1.counts["equality_constraints_count"], 1);
+    /// # assert_eq!(res.unwrap(), vec![2.0f64]);
+    /// ```
+    pub fn equality_constraints(&mut self, param: &O::Param) -> Result<Vec<O::Float>, Error> {
+        self.problem("equality_constraints_count", |problem| {
+            problem.equality_constraints(param)
+        })
+    }
+
+    /// Calls `equality_jacobian` defined in the `EqualityConstraints` trait and keeps track of the
+    /// number of evaluations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{Problem, EqualityConstraints, Error};
+    /// #
+    /// # #[derive(Eq, PartialEq, Debug, Clone)]
+    /// # struct UserDefinedProblem {};
+    /// #
+    /// # impl EqualityConstraints for UserDefinedProblem {
+    /// #     type Param = Vec<f64>;
+    /// #     type Gradient = Vec<f64>;
+    /// #     type Float = f64;
+    /// #
+    /// #     fn equality_constraints(&self, p: &Self::Param) -> Result<Vec<f64>, Error> {
+    /// #         Ok(vec![p[0] + p[1] - 1.0])
+    /// #     }
+    /// #
+    /// #     fn equality_jacobian(&self, p: &Self::Param) -> Result<Vec<Vec<f64>>, Error> {
+    /// #         Ok(vec![vec![1.0, 1.0]])
+    /// #     }
+    /// # }
+    /// // `UserDefinedProblem` implements `EqualityConstraints`.
+    /// let mut problem1 = Problem::new(UserDefinedProblem {});
+    ///
+    /// let param = vec![2.0f64, 1.0f64];
+    ///
+    /// let res = problem1.equality_jacobian(&param);
+    ///
+    /// assert_eq!(problem1.counts["equality_jacobian_count"], 1);
+    /// # assert_eq!(res.unwrap(), vec![vec![1.0f64, 1.0f64]]);
+    /// ```
+    pub fn equality_jacobian(&mut self, param: &O::Param) -> Result<Vec<O::Gradient>, Error> {
+        self.problem("equality_jacobian_count", |problem| {
+            problem.equality_jacobian(param)
+        })
+    }
+}
+
+/// Wraps calls to `inequality_constraints` and `inequality_jacobian` defined in the `InequalityConstraints`
+/// trait and as such allows to call those methods on an instance of `Problem`. Internally, the
+/// number of evaluations is counted.
+impl<O: InequalityConstraints> Problem<O> {
+    /// Calls `inequality_constraints` defined in the `InequalityConstraints` trait and keeps track of
+    /// the number of evaluations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{Problem, InequalityConstraints, Error};
+    /// #
+    /// # #[derive(Eq, PartialEq, Debug, Clone)]
+    /// # struct UserDefinedProblem {};
+    /// #
+    /// # impl InequalityConstraints for UserDefinedProblem {
+    /// #     type Param = Vec<f64>;
+    /// #     type Gradient = Vec<f64>;
+    /// #     type Float = f64;
+    /// #
+    /// #     fn inequality_constraints(&self, p: &Self::Param) -> Result<Vec<f64>, Error> {
+    /// #         Ok(vec![p[0] - p[1] + 1.0])
+    /// #     }
+    /// #
+    /// #     fn inequality_jacobian(&self, p: &Self::Param) -> Result<Vec<Vec<f64>>, Error> {
+    /// #         Ok(vec![vec![1.0, -1.0]])
+    /// #     }
+    /// # }
+    /// // `UserDefinedProblem` implements `InequalityConstraints`.
+    /// let mut problem1 = Problem::new(UserDefinedProblem {});
+    ///
+    /// let param = vec![2.0f64, 1.0f64];
+    ///
+    /// let res = problem1.inequality_constraints(&param);
+    ///
+    /// assert_eq!(problem1.counts["inequality_constraints_count"], 1);
+    /// # assert_eq!(res.unwrap(), vec![2.0f64]);
+    /// ```
+    pub fn inequality_constraints(&mut self, param: &O::Param) -> Result<Vec<O::Float>, Error> {
+        self.problem("inequality_constraints_count", |problem| {
+            problem.inequality_constraints(param)
+        })
+    }
+
+    /// Calls `inequality_jacobian` defined in the `InequalityConstraints` trait and keeps track of the
+    /// number of evaluations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{Problem, InequalityConstraints, Error};
+    /// #
+    /// # #[derive(Eq, PartialEq, Debug, Clone)]
+    /// # struct UserDefinedProblem {};
+    /// #
+    /// # impl InequalityConstraints for UserDefinedProblem {
+    /// #     type Param = Vec<f64>;
+    /// #     type Gradient = Vec<f64>;
+    /// #     type Float = f64;
+    /// #
+    /// #     fn inequality_constraints(&self, p: &Self::Param) -> Result<Vec<f64>, Error> {
+    /// #         Ok(vec![p[0] - p[1] + 1.0])
+    /// #     }
+    /// #
+    /// #     fn inequality_jacobian(&self, p: &Self::Param) -> Result<Vec<Vec<f64>>, Error> {
+    /// #         Ok(vec![vec![1.0, -1.0]])
+    /// #     }
+    /// # }
+    /// // `UserDefinedProblem` implements `InequalityConstraints`.
+    /// let mut problem1 = Problem::new(UserDefinedProblem {});
+    ///
+    /// let param = vec![2.0f64, 1.0f64];
+    ///
+    /// let res = problem1.inequality_jacobian(&param);
+    ///
+    /// assert_eq!(problem1.counts["inequality_jacobian_count"], 1);
+    /// # assert_eq!(res.unwrap(), vec![vec![1.0f64, -1.0f64]]);
+    /// ```
+    pub fn inequality_jacobian(&mut self, param: &O::Param) -> Result<Vec<O::Gradient>, Error> {
+        self.problem("inequality_jacobian_count", |problem| {
+            problem.inequality_jacobian(param)
         })
     }
 }
@@ -51,3 +51,55 @@ impl<T> DeserializeOwnedAlias for T where T: DeserializeOwned {}
 
 #[cfg(not(feature = "serde1"))]
 impl<T> DeserializeOwnedAlias for T {}
+
+/// Serializes infinite and NaN floats as the strings `"inf"`, `"-inf"` and `"NaN"` in
+/// human-readable formats such as JSON, which cannot represent them otherwise. Other formats are
+/// not affected.
+///
+/// Used via `#[serde(with = "non_finite")]` for the cost function values of the states, which are
+/// infinite until the first evaluation. The bounds are `Float` and `Deserialize<'de>` rather than
+/// `ArgminFloat`, because the latter conflicts with the bounds serde infers for other fields.
+#[cfg(feature = "serde1")]
+pub(crate) mod non_finite {
+    use num_traits::Float;
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<F: Float + Serialize, S: Serializer>(
+        value: &F,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() || value.is_finite() {
+            value.serialize(serializer)
+        } else if value.is_nan() {
+            serializer.serialize_str("NaN")
+        } else if value.is_sign_positive() {
+            serializer.serialize_str("inf")
+        } else {
+            serializer.serialize_str("-inf")
+        }
+    }
+
+    pub fn deserialize<'de, F: Float + Deserialize<'de>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<F, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr<F> {
+            Float(F),
+            String(String),
+        }
+
+        if !deserializer.is_human_readable() {
+            return F::deserialize(deserializer);
+        }
+        match Repr::deserialize(deserializer)? {
+            Repr::Float(value) => Ok(value),
+            Repr::String(value) => match value.as_str() {
+                "NaN" => Ok(F::nan()),
+                "inf" => Ok(F::infinity()),
+                "-inf" => Ok(F::neg_infinity()),
+                _ => Err(de::Error::custom(format!("invalid float `{value}`"))),
+            },
+        }
+    }
+}
@@ -93,6 +93,8 @@ pub trait Solver<O, I: State> {
     /// 1) algorithm was terminated somewhere else in the Executor
     /// 2) iteration count exceeds maximum number of iterations
     /// 3) best cost is lower than or equal to the target cost
+    /// 4) time exceeds maximum time
+    /// 5) any function evaluation count exceeds its maximum number of evaluations
     ///
     /// This can be overwritten; however it is not advised. It is recommended to implement other
     /// stopping criteria via ([`terminate`](`Solver::terminate`).
@@ -107,6 +109,17 @@ pub trait Solver<O, I: State> {
         if state.get_best_cost() <= state.get_target_cost() {
             return TerminationStatus::Terminated(TerminationReason::TargetCostReached);
         }
+        if let (Some(max_time), Some(time)) = (state.get_max_time(), state.get_time()) {
+            if time >= max_time {
+                return TerminationStatus::Terminated(TerminationReason::MaxTimeReached);
+            }
+        }
+        let counts = state.get_func_counts();
+        for (count, &max_evals) in state.get_max_func_evals().into_iter().flatten() {
+            if matches!(counts.get(count), Some(&evals) if evals >= max_evals) {
+                return TerminationStatus::Terminated(TerminationReason::MaxFuncCountReached);
+            }
+        }
         TerminationStatus::NotTerminated
     }
 
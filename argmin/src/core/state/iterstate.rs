@@ -5,9 +5,13 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+#[cfg(feature = "serde1")]
+use crate::core::serialization::non_finite;
 use crate::core::{ArgminFloat, Problem, State, TerminationReason, TerminationStatus};
 use instant;
 #[cfg(feature = "serde1")]
+use num_traits::Float;
+#[cfg(feature = "serde1")]
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -21,6 +25,7 @@ use std::collections::HashMap;
 /// * best parameter vector of current and previous iteration
 /// * gradient of current and previous iteration
 /// * Jacobian of current and previous iteration
+/// * Lagrange multipliers of equality and inequality constraints
 /// * Hessian of current and previous iteration
 /// * inverse Hessian of current and previous iteration
 /// * cost function value of current and previous iteration
@@ -45,14 +50,29 @@ pub struct IterState<P, G, J, H, F> {
     /// Previous best parameter vector
     pub prev_best_param: Option<P>,
     /// Current cost function value
+    // The bounds cover all cost function values below
+    #[cfg_attr(
+        feature = "serde1",
+        serde(
+            with = "non_finite",
+            bound(
+                serialize = "F: Float + Serialize",
+                deserialize = "F: Float + Deserialize<'de>"
+            )
+        )
+    )]
     pub cost: F,
     /// Previous cost function value
+    #[cfg_attr(feature = "serde1", serde(with = "non_finite"))]
     pub prev_cost: F,
     /// Current best cost function value
+    #[cfg_attr(feature = "serde1", serde(with = "non_finite"))]
     pub best_cost: F,
     /// Previous best cost function value
+    #[cfg_attr(feature = "serde1", serde(with = "non_finite"))]
     pub prev_best_cost: F,
     /// Target cost function value
+    #[cfg_attr(feature = "serde1", serde(with = "non_finite"))]
     pub target_cost: F,
     /// Current gradient
     pub grad: Option<G>,
@@ -70,12 +90,24 @@ pub struct IterState<P, G, J, H, F> {
     pub jacobian: Option<J>,
     /// Previous Jacobian
     pub prev_jacobian: Option<J>,
+    /// Lagrange multipliers of the equality constraints
+    #[cfg_attr(feature = "serde1", serde(default = "Option::default"))]
+    pub equality_multipliers: Option<Vec<F>>,
+    /// Lagrange multipliers of the inequality constraints
+    #[cfg_attr(feature = "serde1", serde(default = "Option::default"))]
+    pub inequality_multipliers: Option<Vec<F>>,
     /// Current iteration
     pub iter: u64,
     /// Iteration number of last best cost
     pub last_best_iter: u64,
     /// Maximum number of iterations
     pub max_iters: u64,
+    /// Maximum time
+    #[cfg_attr(feature = "serde1", serde(default))]
+    pub max_time: Option<instant::Duration>,
+    /// Maximum number of evaluations per evaluation count
+    #[cfg_attr(feature = "serde1", serde(default))]
+    pub max_func_evals: HashMap<String, u64>,
     /// Evaluation counts
     pub counts: HashMap<String, u64>,
     /// Time required so far
@@ -220,6 +252,42 @@ where
         self
     }
 
+    /// Set the Lagrange multipliers of the equality constraints.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{IterState, State};
+    /// # let state: IterState<Vec<f64>, (), (), (), f64> = IterState::new();
+    /// # assert!(state.equality_multipliers.is_none());
+    /// let state = state.equality_multipliers(vec![1.0, 2.0]);
+    /// # assert_eq!(state.equality_multipliers.as_ref().unwrap()[0].to_ne_bytes(), 1.0f64.to_ne_bytes());
+    /// # assert_eq!(state.equality_multipliers.as_ref().unwrap()[1].to_ne_bytes(), 2.0f64.to_ne_bytes());
+    /// ```
+    #[must_use]
+    pub fn equality_multipliers(mut self, multipliers: Vec<F>) -> Self {
+        self.equality_multipliers = Some(multipliers);
+        self
+    }
+
+    /// Set the Lagrange multipliers of the inequality constraints.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{IterState, State};
+    /// # let state: IterState<Vec<f64>, (), (), (), f64> = IterState::new();
+    /// # assert!(state.inequality_multipliers.is_none());
+    /// let state = state.inequality_multipliers(vec![1.0, 0.0]);
+    /// # assert_eq!(state.inequality_multipliers.as_ref().unwrap()[0].to_ne_bytes(), 1.0f64.to_ne_bytes());
+    /// # assert_eq!(state.inequality_multipliers.as_ref().unwrap()[1].to_ne_bytes(), 0.0f64.to_ne_bytes());
+    /// ```
+    #[must_use]
+    pub fn inequality_multipliers(mut self, multipliers: Vec<F>) -> Self {
+        self.inequality_multipliers = Some(multipliers);
+        self
+    }
+
     /// Set the current cost function value. This shifts the stored cost function value to the
     /// previous cost function value.
     ///
@@ -281,6 +349,46 @@ where
         self
     }
 
+    /// Set maximum time
+    ///
+    /// The time is measured by the [`Executor`](`crate::core::Executor`), therefore this limit
+    /// only applies if timing is enabled (see [`Executor::timer`](`crate::core::Executor::timer`)).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{IterState, State, ArgminFloat};
+    /// # let state: IterState<Vec<f64>, (), (), (), f64> = IterState::new();
+    /// # assert!(state.max_time.is_none());
+    /// let state = state.max_time(std::time::Duration::from_secs(600));
+    /// # assert_eq!(state.max_time.unwrap().as_secs(), 600);
+    /// ```
+    #[must_use]
+    pub fn max_time(mut self, time: instant::Duration) -> Self {
+        self.max_time = Some(time);
+        self
+    }
+
+    /// Set maximum number of evaluations of a function
+    ///
+    /// `count` is the name of the evaluation count as reported by
+    /// [`get_func_counts`](`State::get_func_counts`), for instance `"cost_count"` or
+    /// `"gradient_count"`. Can be called multiple times to limit different counts.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{IterState, State, ArgminFloat};
+    /// # let state: IterState<Vec<f64>, (), (), (), f64> = IterState::new();
+    /// let state = state.max_func_evals("cost_count", 5000);
+    /// # assert_eq!(state.max_func_evals["cost_count"], 5000);
+    /// ```
+    #[must_use]
+    pub fn max_func_evals(mut self, count: &str, evals: u64) -> Self {
+        self.max_func_evals.insert(count.to_string(), evals);
+        self
+    }
+
     /// Returns the current cost function value
     ///
     /// # Example
@@ -860,6 +968,78 @@ where
     pub fn take_prev_jacobian(&mut self) -> Option<J> {
         self.prev_jacobian.take()
     }
+
+    /// Returns a reference to the Lagrange multipliers of the equality constraints
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{IterState, State, ArgminFloat};
+    /// # let mut state: IterState<Vec<f64>, (), (), (), f64> = IterState::new();
+    /// # assert!(state.get_equality_multipliers().is_none());
+    /// # state.equality_multipliers = Some(vec![1.0, 2.0]);
+    /// let multipliers = state.get_equality_multipliers();  // Option<&Vec<F>>
+    /// # assert_eq!(multipliers.unwrap()[0].to_ne_bytes(), 1.0f64.to_ne_bytes());
+    /// # assert_eq!(multipliers.unwrap()[1].to_ne_bytes(), 2.0f64.to_ne_bytes());
+    /// ```
+    pub fn get_equality_multipliers(&self) -> Option<&Vec<F>> {
+        self.equality_multipliers.as_ref()
+    }
+
+    /// Moves the Lagrange multipliers of the equality constraints out and replaces them
+    /// internally with `None`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{IterState, State, ArgminFloat};
+    /// # let mut state: IterState<Vec<f64>, (), (), (), f64> = IterState::new();
+    /// # assert!(state.take_equality_multipliers().is_none());
+    /// # state.equality_multipliers = Some(vec![1.0, 2.0]);
+    /// let multipliers = state.take_equality_multipliers();  // Option<Vec<F>>
+    /// # assert!(state.take_equality_multipliers().is_none());
+    /// # assert_eq!(multipliers.as_ref().unwrap()[0].to_ne_bytes(), 1.0f64.to_ne_bytes());
+    /// # assert_eq!(multipliers.as_ref().unwrap()[1].to_ne_bytes(), 2.0f64.to_ne_bytes());
+    /// ```
+    pub fn take_equality_multipliers(&mut self) -> Option<Vec<F>> {
+        self.equality_multipliers.take()
+    }
+
+    /// Returns a reference to the Lagrange multipliers of the inequality constraints
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{IterState, State, ArgminFloat};
+    /// # let mut state: IterState<Vec<f64>, (), (), (), f64> = IterState::new();
+    /// # assert!(state.get_inequality_multipliers().is_none());
+    /// # state.inequality_multipliers = Some(vec![1.0, 0.0]);
+    /// let multipliers = state.get_inequality_multipliers();  // Option<&Vec<F>>
+    /// # assert_eq!(multipliers.unwrap()[0].to_ne_bytes(), 1.0f64.to_ne_bytes());
+    /// # assert_eq!(multipliers.unwrap()[1].to_ne_bytes(), 0.0f64.to_ne_bytes());
+    /// ```
+    pub fn get_inequality_multipliers(&self) -> Option<&Vec<F>> {
+        self.inequality_multipliers.as_ref()
+    }
+
+    /// Moves the Lagrange multipliers of the inequality constraints out and replaces them
+    /// internally with `None`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{IterState, State, ArgminFloat};
+    /// # let mut state: IterState<Vec<f64>, (), (), (), f64> = IterState::new();
+    /// # assert!(state.take_inequality_multipliers().is_none());
+    /// # state.inequality_multipliers = Some(vec![1.0, 0.0]);
+    /// let multipliers = state.take_inequality_multipliers();  // Option<Vec<F>>
+    /// # assert!(state.take_inequality_multipliers().is_none());
+    /// # assert_eq!(multipliers.as_ref().unwrap()[0].to_ne_bytes(), 1.0f64.to_ne_bytes());
+    /// # assert_eq!(multipliers.as_ref().unwrap()[1].to_ne_bytes(), 0.0f64.to_ne_bytes());
+    /// ```
+    pub fn take_inequality_multipliers(&mut self) -> Option<Vec<F>> {
+        self.inequality_multipliers.take()
+    }
 }
 
 impl<P, G, J, H, F> State for IterState<P, G, J, H, F>
@@ -898,9 +1078,13 @@ where
     /// # assert!(state.prev_inv_hessian.is_none());
     /// # assert!(state.jacobian.is_none());
     /// # assert!(state.prev_jacobian.is_none());
+    /// # assert!(state.equality_multipliers.is_none());
+    /// # assert!(state.inequality_multipliers.is_none());
     /// # assert_eq!(state.iter, 0);
     /// # assert_eq!(state.last_best_iter, 0);
     /// # assert_eq!(state.max_iters, std::u64::MAX);
+    /// # assert!(state.max_time.is_none());
+    /// # assert!(state.max_func_evals.is_empty());
     /// # assert_eq!(state.counts.len(), 0);
     /// # assert_eq!(state.time.unwrap(), instant::Duration::new(0, 0));
     /// # assert_eq!(state.termination_status, TerminationStatus::NotTerminated);
@@ -924,9 +1108,13 @@ where
             prev_inv_hessian: None,
             jacobian: None,
             prev_jacobian: None,
+            equality_multipliers: None,
+            inequality_multipliers: None,
             iter: 0,
             last_best_iter: 0,
             max_iters: std::u64::MAX,
+            max_time: None,
+            max_func_evals: HashMap::new(),
             counts: HashMap::new(),
             time: Some(instant::Duration::new(0, 0)),
             termination_status: TerminationStatus::NotTerminated,
@@ -1053,6 +1241,22 @@ where
         self
     }
 
+    /// Resets the termination status to [`NotTerminated`](`TerminationStatus::NotTerminated`)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{IterState, State, ArgminFloat, TerminationReason, TerminationStatus};
+    /// # let mut state: IterState<Vec<f64>, (), (), (), f64> = IterState::new();
+    /// let state = state.terminate_with(TerminationReason::KeyboardInterrupt);
+    /// let state = state.reset_termination();
+    /// # assert_eq!(state.termination_status, TerminationStatus::NotTerminated);
+    /// ```
+    fn reset_termination(mut self) -> Self {
+        self.termination_status = TerminationStatus::NotTerminated;
+        self
+    }
+
     /// Sets the time required so far.
     ///
     /// # Example
@@ -1160,6 +1364,36 @@ where
         self.max_iters
     }
 
+    /// Returns the maximum time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{IterState, State, ArgminFloat};
+    /// # let mut state: IterState<Vec<f64>, (), (), (), f64> = IterState::new();
+    /// # state.max_time = Some(instant::Duration::from_secs(12));
+    /// let max_time = state.get_max_time();
+    /// # assert_eq!(max_time.unwrap().as_secs(), 12);
+    /// ```
+    fn get_max_time(&self) -> Option<instant::Duration> {
+        self.max_time
+    }
+
+    /// Returns the maximum number of evaluations per evaluation count.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{IterState, State, ArgminFloat};
+    /// # let state: IterState<Vec<f64>, (), (), (), f64> = IterState::new();
+    /// # let state = state.max_func_evals("cost_count", 12);
+    /// let max_func_evals = state.get_max_func_evals();
+    /// # assert_eq!(max_func_evals.unwrap()["cost_count"], 12);
+    /// ```
+    fn get_max_func_evals(&self) -> Option<&HashMap<String, u64>> {
+        Some(&self.max_func_evals)
+    }
+
     /// Returns the termination status.
     ///
     /// # Example
@@ -1339,6 +1573,8 @@ mod tests {
         assert!(state.get_prev_inv_hessian().is_none());
         assert!(state.get_jacobian().is_none());
         assert!(state.get_prev_jacobian().is_none());
+        assert!(state.get_equality_multipliers().is_none());
+        assert!(state.get_inequality_multipliers().is_none());
         assert_eq!(state.get_iter(), 0);
 
         assert!(state.is_best());
@@ -1356,6 +1592,17 @@ mod tests {
 
         assert_eq!(state.get_max_iters(), 42);
 
+        assert!(state.get_max_time().is_none());
+        state = state.max_time(instant::Duration::from_secs(42));
+        assert_eq!(state.get_max_time(), Some(instant::Duration::from_secs(42)));
+
+        assert!(state.get_max_func_evals().unwrap().is_empty());
+        state = state
+            .max_func_evals("cost_count", 42)
+            .max_func_evals("gradient_count", 21);
+        assert_eq!(state.get_max_func_evals().unwrap()["cost_count"], 42);
+        assert_eq!(state.get_max_func_evals().unwrap()["gradient_count"], 21);
+
         let mut state = state.cost(cost);
 
         assert_eq!(state.get_cost().to_ne_bytes(), cost.to_ne_bytes());
@@ -1432,11 +1679,27 @@ mod tests {
 
         let new_jacobian = vec![2.0f64, 1.0];
 
-        let mut state = state.jacobian(new_jacobian.clone());
+        let state = state.jacobian(new_jacobian.clone());
 
         assert_eq!(*state.get_jacobian().unwrap(), new_jacobian);
         assert_eq!(*state.get_prev_jacobian().unwrap(), jacobian);
 
+        let equality_multipliers = vec![1.0f64, -1.0];
+        let inequality_multipliers = vec![0.0f64, 2.0];
+
+        let mut state = state
+            .equality_multipliers(equality_multipliers.clone())
+            .inequality_multipliers(inequality_multipliers.clone());
+
+        assert_eq!(
+            *state.get_equality_multipliers().unwrap(),
+            equality_multipliers
+        );
+        assert_eq!(
+            *state.get_inequality_multipliers().unwrap(),
+            inequality_multipliers
+        );
+
         state.increment_iter();
 
         assert_eq!(state.get_iter(), 2);
@@ -1473,6 +1736,16 @@ mod tests {
         assert_eq!(state.take_prev_inv_hessian().unwrap(), inv_hessian);
         assert_eq!(state.take_jacobian().unwrap(), new_jacobian);
         assert_eq!(state.take_prev_jacobian().unwrap(), jacobian);
+        assert_eq!(
+            state.take_equality_multipliers().unwrap(),
+            equality_multipliers
+        );
+        assert_eq!(
+            state.take_inequality_multipliers().unwrap(),
+            inequality_multipliers
+        );
+        assert!(state.get_equality_multipliers().is_none());
+        assert!(state.get_inequality_multipliers().is_none());
         let func_counts = state.get_func_counts().clone();
         assert!(!func_counts.contains_key("cost_count"));
         assert!(!func_counts.contains_key("operator_count"));
@@ -1481,4 +1754,29 @@ mod tests {
         assert!(!func_counts.contains_key("jacobian_count"));
         assert!(!func_counts.contains_key("modify_count"));
     }
+
+    #[test]
+    #[cfg(feature = "serde1")]
+    fn test_deserialize_without_new_fields() {
+        let state: IterState<Vec<f64>, (), (), (), f64> = IterState::new()
+            .param(vec![1.0, 2.0])
+            .max_time(instant::Duration::from_secs(12))
+            .max_func_evals("cost_count", 12);
+        let mut value = serde_json::to_value(&state).unwrap();
+        // States stored by previous versions lack these fields
+        for field in [
+            "equality_multipliers",
+            "inequality_multipliers",
+            "max_time",
+            "max_func_evals",
+        ] {
+            value.as_object_mut().unwrap().remove(field).unwrap();
+        }
+        let state: IterState<Vec<f64>, (), (), (), f64> = serde_json::from_value(value).unwrap();
+        assert_eq!(*state.get_param().unwrap(), vec![1.0, 2.0]);
+        assert!(state.get_equality_multipliers().is_none());
+        assert!(state.get_inequality_multipliers().is_none());
+        assert!(state.get_max_time().is_none());
+        assert!(state.get_max_func_evals().unwrap().is_empty());
+    }
 }
@@ -5,9 +5,13 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+#[cfg(feature = "serde1")]
+use crate::core::serialization::non_finite;
 use crate::core::{ArgminFloat, Problem, State, TerminationReason, TerminationStatus};
 use instant;
 #[cfg(feature = "serde1")]
+use num_traits::Float;
+#[cfg(feature = "serde1")]
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -40,14 +44,29 @@ pub struct LinearProgramState<P, F> {
     /// Previous best parameter vector
     pub prev_best_param: Option<P>,
     /// Current cost function value
+    // The bounds cover all cost function values below
+    #[cfg_attr(
+        feature = "serde1",
+        serde(
+            with = "non_finite",
+            bound(
+                serialize = "F: Float + Serialize",
+                deserialize = "F: Float + Deserialize<'de>"
+            )
+        )
+    )]
     pub cost: F,
     /// Previous cost function value
+    #[cfg_attr(feature = "serde1", serde(with = "non_finite"))]
     pub prev_cost: F,
     /// Current best cost function value
+    #[cfg_attr(feature = "serde1", serde(with = "non_finite"))]
     pub best_cost: F,
     /// Previous best cost function value
+    #[cfg_attr(feature = "serde1", serde(with = "non_finite"))]
     pub prev_best_cost: F,
     /// Target cost function value
+    #[cfg_attr(feature = "serde1", serde(with = "non_finite"))]
     pub target_cost: F,
     /// Current iteration
     pub iter: u64,
@@ -55,6 +74,12 @@ pub struct LinearProgramState<P, F> {
     pub last_best_iter: u64,
     /// Maximum number of iterations
     pub max_iters: u64,
+    /// Maximum time
+    #[cfg_attr(feature = "serde1", serde(default))]
+    pub max_time: Option<instant::Duration>,
+    /// Maximum number of evaluations per evaluation count
+    #[cfg_attr(feature = "serde1", serde(default))]
+    pub max_func_evals: HashMap<String, u64>,
     /// Evaluation counts
     pub counts: HashMap<String, u64>,
     /// Time required so far
@@ -128,6 +153,46 @@ impl<P, F> LinearProgramState<P, F> {
         self
     }
 
+    /// Set maximum time
+    ///
+    /// The time is measured by the [`Executor`](`crate::core::Executor`), therefore this limit
+    /// only applies if timing is enabled (see [`Executor::timer`](`crate::core::Executor::timer`)).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{LinearProgramState, State, ArgminFloat};
+    /// # let state: LinearProgramState<Vec<f64>, f64> = LinearProgramState::new();
+    /// # assert!(state.max_time.is_none());
+    /// let state = state.max_time(std::time::Duration::from_secs(600));
+    /// # assert_eq!(state.max_time.unwrap().as_secs(), 600);
+    /// ```
+    #[must_use]
+    pub fn max_time(mut self, time: instant::Duration) -> Self {
+        self.max_time = Some(time);
+        self
+    }
+
+    /// Set maximum number of evaluations of a function
+    ///
+    /// `count` is the name of the evaluation count as reported by
+    /// [`get_func_counts`](`State::get_func_counts`), for instance `"cost_count"` or
+    /// `"gradient_count"`. Can be called multiple times to limit different counts.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{LinearProgramState, State, ArgminFloat};
+    /// # let state: LinearProgramState<Vec<f64>, f64> = LinearProgramState::new();
+    /// let state = state.max_func_evals("cost_count", 5000);
+    /// # assert_eq!(state.max_func_evals["cost_count"], 5000);
+    /// ```
+    #[must_use]
+    pub fn max_func_evals(mut self, count: &str, evals: u64) -> Self {
+        self.max_func_evals.insert(count.to_string(), evals);
+        self
+    }
+
     /// Set the current cost function value. This shifts the stored cost function value to the
     /// previous cost function value.
     ///
@@ -187,6 +252,8 @@ where
     /// # assert_eq!(state.iter, 0);
     /// # assert_eq!(state.last_best_iter, 0);
     /// # assert_eq!(state.max_iters, std::u64::MAX);
+    /// # assert!(state.max_time.is_none());
+    /// # assert!(state.max_func_evals.is_empty());
     /// # assert_eq!(state.counts, HashMap::new());
     /// # assert_eq!(state.time.unwrap(), instant::Duration::new(0, 0));
     /// # assert_eq!(state.termination_status, TerminationStatus::NotTerminated);
@@ -205,6 +272,8 @@ where
             iter: 0,
             last_best_iter: 0,
             max_iters: std::u64::MAX,
+            max_time: None,
+            max_func_evals: HashMap::new(),
             counts: HashMap::new(),
             time: Some(instant::Duration::new(0, 0)),
             termination_status: TerminationStatus::NotTerminated,
@@ -330,6 +399,22 @@ where
         self
     }
 
+    /// Resets the termination status to [`NotTerminated`](`TerminationStatus::NotTerminated`)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{LinearProgramState, State, ArgminFloat, TerminationReason, TerminationStatus};
+    /// # let mut state: LinearProgramState<Vec<f64>, f64> = LinearProgramState::new();
+    /// let state = state.terminate_with(TerminationReason::KeyboardInterrupt);
+    /// let state = state.reset_termination();
+    /// # assert_eq!(state.termination_status, TerminationStatus::NotTerminated);
+    /// ```
+    fn reset_termination(mut self) -> Self {
+        self.termination_status = TerminationStatus::NotTerminated;
+        self
+    }
+
     /// Sets the time required so far.
     ///
     /// # Example
@@ -437,6 +522,36 @@ where
         self.max_iters
     }
 
+    /// Returns the maximum time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{LinearProgramState, State, ArgminFloat};
+    /// # let mut state: LinearProgramState<Vec<f64>, f64> = LinearProgramState::new();
+    /// # state.max_time = Some(instant::Duration::from_secs(12));
+    /// let max_time = state.get_max_time();
+    /// # assert_eq!(max_time.unwrap().as_secs(), 12);
+    /// ```
+    fn get_max_time(&self) -> Option<instant::Duration> {
+        self.max_time
+    }
+
+    /// Returns the maximum number of evaluations per evaluation count.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::{LinearProgramState, State, ArgminFloat};
+    /// # let state: LinearProgramState<Vec<f64>, f64> = LinearProgramState::new();
+    /// # let state = state.max_func_evals("cost_count", 12);
+    /// let max_func_evals = state.get_max_func_evals();
+    /// # assert_eq!(max_func_evals.unwrap()["cost_count"], 12);
+    /// ```
+    fn get_max_func_evals(&self) -> Option<&HashMap<String, u64>> {
+        Some(&self.max_func_evals)
+    }
+
     /// Returns the termination status.
     ///
     /// # Example
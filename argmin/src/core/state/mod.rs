@@ -68,6 +68,20 @@ pub trait State {
     /// Returns maximum number of iterations that are to be performed
     fn get_max_iters(&self) -> u64;
 
+    /// Returns maximum time, if set
+    ///
+    /// Defaults to `None`, i.e. no time limit.
+    fn get_max_time(&self) -> Option<instant::Duration> {
+        None
+    }
+
+    /// Returns maximum numbers of function evaluations, indexed by evaluation count, if set
+    ///
+    /// Defaults to `None`, i.e. no limits on the number of function evaluations.
+    fn get_max_func_evals(&self) -> Option<&HashMap<String, u64>> {
+        None
+    }
+
     /// Increment the number of iterations by one
     fn increment_iter(&mut self);
 
@@ -107,6 +121,10 @@ pub trait State {
     #[must_use]
     fn terminate_with(self, termination_reason: TerminationReason) -> Self;
 
+    /// Resets the termination status to [`NotTerminated`](`TerminationStatus::NotTerminated`)
+    #[must_use]
+    fn reset_termination(self) -> Self;
+
     /// Returns termination status.
     fn get_termination_status(&self) -> &TerminationStatus;
 
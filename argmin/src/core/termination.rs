@@ -5,6 +5,48 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+//! # Termination
+//!
+//! Besides the stopping criteria built into the solvers (maximum number of iterations, target cost
+//! function value and solver specific tolerances), additional termination criteria can be added
+//! to an [`Executor`](`crate::core::Executor`) via
+//! [`add_termination`](`crate::core::Executor::add_termination`). Criteria implement the
+//! [`TerminationCriterion`] trait and are checked before every iteration, after the stopping
+//! criteria of the solver. The following criteria are available:
+//!
+//! * [`MaxTime`]: Wall-clock time limit
+//! * [`MaxFuncCount`]: Budget of function evaluations as reported by
+//!   [`get_func_counts`](`crate::core::State::get_func_counts`)
+//! * [`CostStagnation`]: No improvement of the best cost function value over a number of
+//!   iterations
+//! * [`ParamChange`]: Relative change of the parameter vector below a tolerance
+//! * [`Any`] and [`All`]: Combinations of other criteria
+//!
+//! # Example
+//!
+//! ```
+//! use argmin::core::termination::{CostStagnation, MaxFuncCount, MaxTime};
+//! use argmin::core::{Error, Executor, State};
+//! # use argmin::core::test_utils::{TestSolver, TestProblem};
+//! use std::time::Duration;
+//!
+//! # fn main() -> Result<(), Error> {
+//! # let solver = TestSolver::new();
+//! # let problem = TestProblem::new();
+//! let res = Executor::new(problem, solver)
+//!     .configure(|state| state.param(vec![1.0f64, 0.0]).max_iters(1000))
+//!     .add_termination(MaxTime::new(Duration::from_secs(60)))
+//!     .add_termination(MaxFuncCount::new("cost_count", 500))
+//!     .add_termination(CostStagnation::new(50))
+//!     .run()?;
+//!
+//! println!("{}", res.state.get_termination_status());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::core::{ArgminFloat, Error, State};
+use argmin_math::{ArgminL2Norm, ArgminSub};
 #[cfg(feature = "serde1")]
 use serde::{Deserialize, Serialize};
 
@@ -31,6 +73,8 @@ impl TerminationStatus {
     /// assert!(TerminationStatus::Terminated(TerminationReason::TargetCostReached).terminated());
     /// assert!(TerminationStatus::Terminated(TerminationReason::SolverConverged).terminated());
     /// assert!(TerminationStatus::Terminated(TerminationReason::KeyboardInterrupt).terminated());
+    /// assert!(TerminationStatus::Terminated(TerminationReason::ProblemInfeasible).terminated());
+    /// assert!(TerminationStatus::Terminated(TerminationReason::ProblemUnbounded).terminated());
     /// assert!(TerminationStatus::Terminated(TerminationReason::SolverExit("Exit reason".to_string())).terminated());
     /// ```
     pub fn terminated(&self) -> bool {
@@ -59,8 +103,20 @@ pub enum TerminationReason {
     KeyboardInterrupt,
     /// Converged
     SolverConverged,
+    /// Problem has no feasible solution
+    ProblemInfeasible,
+    /// Problem is unbounded
+    ProblemUnbounded,
     /// Solver exit with given reason
     SolverExit(String),
+    /// Reached maximum wall-clock time
+    MaxTimeReached,
+    /// Reached maximum number of function evaluations
+    MaxFuncCountReached,
+    /// Best cost function value did not improve over a number of iterations
+    CostStagnated,
+    /// Relative change of the parameter vector is below the tolerance
+    ParamChangeBelowTolerance,
 }
 
 impl TerminationReason {
@@ -88,9 +144,33 @@ impl TerminationReason {
     ///     "Solver converged"
     /// );
     /// assert_eq!(
+    ///     TerminationReason::ProblemInfeasible.text(),
+    ///     "Problem is infeasible"
+    /// );
+    /// assert_eq!(
+    ///     TerminationReason::ProblemUnbounded.text(),
+    ///     "Problem is unbounded"
+    /// );
+    /// assert_eq!(
     ///     TerminationReason::SolverExit("Aborted".to_string()).text(),
     ///     "Aborted"
     /// );
+    /// assert_eq!(
+    ///     TerminationReason::MaxTimeReached.text(),
+    ///     "Maximum time reached"
+    /// );
+    /// assert_eq!(
+    ///     TerminationReason::MaxFuncCountReached.text(),
+    ///     "Maximum number of function evaluations reached"
+    /// );
+    /// assert_eq!(
+    ///     TerminationReason::CostStagnated.text(),
+    ///     "Best cost function value stagnated"
+    /// );
+    /// assert_eq!(
+    ///     TerminationReason::ParamChangeBelowTolerance.text(),
+    ///     "Relative change of parameter vector below tolerance"
+    /// );
     /// ```
     pub fn text(&self) -> &str {
         match self {
@@ -98,7 +178,17 @@ impl TerminationReason {
             TerminationReason::TargetCostReached => "Target cost value reached",
             TerminationReason::KeyboardInterrupt => "Keyboard interrupt",
             TerminationReason::SolverConverged => "Solver converged",
+            TerminationReason::ProblemInfeasible => "Problem is infeasible",
+            TerminationReason::ProblemUnbounded => "Problem is unbounded",
             TerminationReason::SolverExit(reason) => reason.as_ref(),
+            TerminationReason::MaxTimeReached => "Maximum time reached",
+            TerminationReason::MaxFuncCountReached => {
+                "Maximum number of function evaluations reached"
+            }
+            TerminationReason::CostStagnated => "Best cost function value stagnated",
+            TerminationReason::ParamChangeBelowTolerance => {
+                "Relative change of parameter vector below tolerance"
+            }
         }
     }
 }
@@ -115,9 +205,506 @@ impl Default for TerminationReason {
     }
 }
 
+/// Termination criterion which is checked by the [`Executor`](`crate::core::Executor`) before
+/// every iteration.
+///
+/// Criteria are called exactly once per iteration (as long as no other criterion terminated the
+/// run before) and may therefore keep track of the history of the optimization.
+///
+/// # Example
+///
+/// ```
+/// use argmin::core::termination::TerminationCriterion;
+/// use argmin::core::{State, TerminationReason, TerminationStatus};
+///
+/// /// Terminates once the cost function value drops below a threshold
+/// struct CostBelow(f64);
+///
+/// impl<I: State<Float = f64>> TerminationCriterion<I> for CostBelow {
+///     fn terminate(&mut self, state: &I) -> TerminationStatus {
+///         if state.get_cost() < self.0 {
+///             TerminationStatus::Terminated(TerminationReason::SolverExit(
+///                 "Cost function value below threshold".to_string(),
+///             ))
+///         } else {
+///             TerminationStatus::NotTerminated
+///         }
+///     }
+/// }
+/// ```
+pub trait TerminationCriterion<I> {
+    /// Checks whether the optimization should terminate given the current state
+    fn terminate(&mut self, state: &I) -> TerminationStatus;
+}
+
+/// Terminates once a given wall-clock time has passed since the first check.
+#[derive(Clone, Debug)]
+pub struct MaxTime {
+    /// Maximum duration
+    max_time: instant::Duration,
+    /// Time of the first check
+    start: Option<instant::Instant>,
+}
+
+impl MaxTime {
+    /// Construct a new instance of `MaxTime`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::termination::MaxTime;
+    /// let criterion = MaxTime::new(std::time::Duration::from_secs(60));
+    /// ```
+    pub fn new(max_time: instant::Duration) -> Self {
+        MaxTime {
+            max_time,
+            start: None,
+        }
+    }
+}
+
+impl<I> TerminationCriterion<I> for MaxTime {
+    fn terminate(&mut self, _state: &I) -> TerminationStatus {
+        let start = *self.start.get_or_insert_with(instant::Instant::now);
+        if start.elapsed() >= self.max_time {
+            TerminationStatus::Terminated(TerminationReason::MaxTimeReached)
+        } else {
+            TerminationStatus::NotTerminated
+        }
+    }
+}
+
+/// Terminates once the number of evaluations of a function exceeds a budget.
+///
+/// The counts are read from [`get_func_counts`](`State::get_func_counts`), therefore the name of
+/// the count has to be given, such as `"cost_count"` or `"gradient_count"`.
+#[derive(Clone, Debug)]
+pub struct MaxFuncCount {
+    /// Name of the count
+    name: String,
+    /// Maximum number of evaluations
+    max_count: u64,
+}
+
+impl MaxFuncCount {
+    /// Construct a new instance of `MaxFuncCount`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::termination::MaxFuncCount;
+    /// let criterion = MaxFuncCount::new("cost_count", 1000);
+    /// ```
+    pub fn new(name: &str, max_count: u64) -> Self {
+        MaxFuncCount {
+            name: name.to_string(),
+            max_count,
+        }
+    }
+}
+
+impl<I: State> TerminationCriterion<I> for MaxFuncCount {
+    fn terminate(&mut self, state: &I) -> TerminationStatus {
+        match state.get_func_counts().get(&self.name) {
+            Some(&count) if count >= self.max_count => {
+                TerminationStatus::Terminated(TerminationReason::MaxFuncCountReached)
+            }
+            _ => TerminationStatus::NotTerminated,
+        }
+    }
+}
+
+/// Terminates if the best cost function value did not improve by more than a tolerance over a
+/// given number of iterations.
+#[derive(Clone, Debug)]
+pub struct CostStagnation<F> {
+    /// Number of iterations without improvement
+    iters: u64,
+    /// Minimal improvement
+    tolerance: F,
+    /// Best cost function value at the last improvement and the corresponding iteration
+    last_improvement: Option<(F, u64)>,
+}
+
+impl<F: ArgminFloat> CostStagnation<F> {
+    /// Construct a new instance of `CostStagnation`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::termination::CostStagnation;
+    /// let criterion: CostStagnation<f64> = CostStagnation::new(20);
+    /// ```
+    pub fn new(iters: u64) -> Self {
+        CostStagnation {
+            iters,
+            tolerance: float!(0.0),
+            last_improvement: None,
+        }
+    }
+
+    /// Set the minimal improvement of the best cost function value
+    ///
+    /// Defaults to `0`, which means that any improvement resets the iteration count. Must be
+    /// non-negative.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::termination::CostStagnation;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// let criterion = CostStagnation::new(20).with_tolerance(1e-6)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_tolerance(mut self, tolerance: F) -> Result<Self, Error> {
+        if tolerance < float!(0.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`CostStagnation`: tolerance must be >= 0."
+            ));
+        }
+        self.tolerance = tolerance;
+        Ok(self)
+    }
+}
+
+impl<I, F> TerminationCriterion<I> for CostStagnation<F>
+where
+    I: State<Float = F>,
+    F: ArgminFloat,
+{
+    fn terminate(&mut self, state: &I) -> TerminationStatus {
+        let best_cost = state.get_best_cost();
+        let iter = state.get_iter();
+        match self.last_improvement {
+            Some((cost, _)) if best_cost >= cost - self.tolerance => {}
+            _ => self.last_improvement = Some((best_cost, iter)),
+        }
+        let (_, last_iter) = self.last_improvement.unwrap();
+        if iter - last_iter >= self.iters {
+            TerminationStatus::Terminated(TerminationReason::CostStagnated)
+        } else {
+            TerminationStatus::NotTerminated
+        }
+    }
+}
+
+/// Terminates if the relative change of the parameter vector between two consecutive iterations,
+/// `||x_k - x_{k-1}|| / max(||x_{k-1}||, 1)`, is below a tolerance.
+#[derive(Clone, Debug)]
+pub struct ParamChange<P, F> {
+    /// Tolerance
+    tolerance: F,
+    /// Parameter vector of the previous check
+    prev_param: Option<P>,
+}
+
+impl<P, F: ArgminFloat> ParamChange<P, F> {
+    /// Construct a new instance of `ParamChange`
+    ///
+    /// `tolerance` must be non-negative.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::core::termination::ParamChange;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// let criterion: ParamChange<Vec<f64>, f64> = ParamChange::new(1e-8)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(tolerance: F) -> Result<Self, Error> {
+        if tolerance < float!(0.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`ParamChange`: tolerance must be >= 0."
+            ));
+        }
+        Ok(ParamChange {
+            tolerance,
+            prev_param: None,
+        })
+    }
+}
+
+impl<I, P, F> TerminationCriterion<I> for ParamChange<P, F>
+where
+    I: State<Param = P, Float = F>,
+    P: Clone + ArgminSub<P, P> + ArgminL2Norm<F>,
+    F: ArgminFloat,
+{
+    fn terminate(&mut self, state: &I) -> TerminationStatus {
+        let param = match state.get_param() {
+            Some(param) => param,
+            None => return TerminationStatus::NotTerminated,
+        };
+        let status = match self.prev_param.as_ref() {
+            Some(prev) => {
+                let change = param.sub(prev).l2_norm() / prev.l2_norm().max(float!(1.0));
+                if change < self.tolerance {
+                    TerminationStatus::Terminated(TerminationReason::ParamChangeBelowTolerance)
+                } else {
+                    TerminationStatus::NotTerminated
+                }
+            }
+            None => TerminationStatus::NotTerminated,
+        };
+        self.prev_param = Some(param.clone());
+        status
+    }
+}
+
+/// Terminates as soon as any of its criteria terminates, with the reason of the first criterion
+/// which terminated.
+///
+/// All criteria are checked in every iteration, such that criteria which keep track of the
+/// history of the optimization stay up to date.
+///
+/// # Example
+///
+/// ```
+/// # use argmin::core::termination::{Any, CostStagnation, MaxTime};
+/// # use argmin::core::IterState;
+/// let criterion: Any<IterState<Vec<f64>, (), (), (), f64>> = Any::new()
+///     .with_criterion(MaxTime::new(std::time::Duration::from_secs(60)))
+///     .with_criterion(CostStagnation::new(20));
+/// ```
+pub struct Any<I> {
+    /// Criteria
+    criteria: Vec<Box<dyn TerminationCriterion<I>>>,
+}
+
+impl<I> Any<I> {
+    /// Construct a new instance of `Any` without criteria, which never terminates.
+    pub fn new() -> Self {
+        Any { criteria: vec![] }
+    }
+
+    /// Add a criterion
+    #[must_use]
+    pub fn with_criterion<C: TerminationCriterion<I> + 'static>(mut self, criterion: C) -> Self {
+        self.criteria.push(Box::new(criterion));
+        self
+    }
+}
+
+impl<I> Default for Any<I> {
+    fn default() -> Self {
+        Any::new()
+    }
+}
+
+impl<I> TerminationCriterion<I> for Any<I> {
+    fn terminate(&mut self, state: &I) -> TerminationStatus {
+        self.criteria
+            .iter_mut()
+            .map(|criterion| criterion.terminate(state))
+            .fold(TerminationStatus::NotTerminated, |status, next| {
+                if status.terminated() {
+                    status
+                } else {
+                    next
+                }
+            })
+    }
+}
+
+/// Terminates once all of its criteria terminate in the same iteration.
+///
+/// All criteria are checked in every iteration. The termination reason is
+/// [`SolverExit`](`TerminationReason::SolverExit`) with the texts of the reasons of all
+/// criteria, joined by `" and "`.
+///
+/// # Example
+///
+/// ```
+/// # use argmin::core::termination::{All, CostStagnation, MaxFuncCount};
+/// # use argmin::core::IterState;
+/// let criterion: All<IterState<Vec<f64>, (), (), (), f64>> = All::new()
+///     .with_criterion(MaxFuncCount::new("cost_count", 100))
+///     .with_criterion(CostStagnation::new(20));
+/// ```
+pub struct All<I> {
+    /// Criteria
+    criteria: Vec<Box<dyn TerminationCriterion<I>>>,
+}
+
+impl<I> All<I> {
+    /// Construct a new instance of `All` without criteria, which never terminates.
+    pub fn new() -> Self {
+        All { criteria: vec![] }
+    }
+
+    /// Add a criterion
+    #[must_use]
+    pub fn with_criterion<C: TerminationCriterion<I> + 'static>(mut self, criterion: C) -> Self {
+        self.criteria.push(Box::new(criterion));
+        self
+    }
+}
+
+impl<I> Default for All<I> {
+    fn default() -> Self {
+        All::new()
+    }
+}
+
+impl<I> TerminationCriterion<I> for All<I> {
+    fn terminate(&mut self, state: &I) -> TerminationStatus {
+        let statuses: Vec<TerminationStatus> = self
+            .criteria
+            .iter_mut()
+            .map(|criterion| criterion.terminate(state))
+            .collect();
+        if statuses.is_empty() || !statuses.iter().all(TerminationStatus::terminated) {
+            return TerminationStatus::NotTerminated;
+        }
+        let texts: Vec<String> = statuses.iter().map(|status| status.to_string()).collect();
+        TerminationStatus::Terminated(TerminationReason::SolverExit(texts.join(" and ")))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use crate::core::test_utils::TestProblem;
+    use crate::core::{ArgminError, IterState, Problem};
+
     send_sync_test!(termination_reason, TerminationReason);
+
+    type TestState = IterState<Vec<f64>, (), (), (), f64>;
+
+    /// Advances `state` by one iteration with the given parameter vector and cost
+    fn iterate(state: TestState, param: Vec<f64>, cost: f64) -> TestState {
+        let mut state = state.param(param).cost(cost);
+        state.update();
+        state.increment_iter();
+        state
+    }
+
+    #[test]
+    fn test_max_time() {
+        let state = TestState::new();
+        let mut criterion = MaxTime::new(instant::Duration::from_secs(3600));
+        assert!(!criterion.terminate(&state).terminated());
+        let mut criterion = MaxTime::new(instant::Duration::ZERO);
+        assert_eq!(
+            criterion.terminate(&state),
+            TerminationStatus::Terminated(TerminationReason::MaxTimeReached)
+        );
+    }
+
+    #[test]
+    fn test_max_func_count() {
+        let mut criterion = MaxFuncCount::new("cost_count", 10);
+        let mut state = TestState::new();
+        // Not evaluated at all yet
+        assert!(!criterion.terminate(&state).terminated());
+
+        let mut problem = Problem::new(TestProblem::new());
+        problem.counts.insert("cost_count", 9);
+        problem.counts.insert("gradient_count", 20);
+        state.func_counts(&problem);
+        assert!(!criterion.terminate(&state).terminated());
+
+        problem.counts.insert("cost_count", 10);
+        state.func_counts(&problem);
+        assert_eq!(
+            criterion.terminate(&state),
+            TerminationStatus::Terminated(TerminationReason::MaxFuncCountReached)
+        );
+    }
+
+    #[test]
+    fn test_cost_stagnation() {
+        let mut criterion = CostStagnation::new(2).with_tolerance(0.1).unwrap();
+        let mut state = iterate(TestState::new(), vec![0.0], 10.0);
+        assert!(!criterion.terminate(&state).terminated());
+        // Improvement larger than the tolerance
+        state = iterate(state, vec![0.0], 9.0);
+        assert!(!criterion.terminate(&state).terminated());
+        // Improvements smaller than the tolerance
+        state = iterate(state, vec![0.0], 8.95);
+        assert!(!criterion.terminate(&state).terminated());
+        state = iterate(state, vec![0.0], 8.91);
+        assert_eq!(
+            criterion.terminate(&state),
+            TerminationStatus::Terminated(TerminationReason::CostStagnated)
+        );
+    }
+
+    #[test]
+    fn test_cost_stagnation_with_tolerance() {
+        assert_error!(
+            CostStagnation::new(2).with_tolerance(-1.0f64),
+            ArgminError,
+            "Invalid parameter: \"`CostStagnation`: tolerance must be >= 0.\""
+        );
+    }
+
+    #[test]
+    fn test_param_change() {
+        assert_error!(
+            ParamChange::<Vec<f64>, f64>::new(-1.0),
+            ArgminError,
+            "Invalid parameter: \"`ParamChange`: tolerance must be >= 0.\""
+        );
+
+        let mut criterion = ParamChange::new(1e-3).unwrap();
+        // No parameter vector yet
+        assert!(!criterion.terminate(&TestState::new()).terminated());
+
+        let mut state = iterate(TestState::new(), vec![10.0, 0.0], 1.0);
+        assert!(!criterion.terminate(&state).terminated());
+        state = iterate(state, vec![10.1, 0.0], 1.0);
+        assert!(!criterion.terminate(&state).terminated());
+        // Relative change of 0.001 / 10.1
+        state = iterate(state, vec![10.101, 0.0], 1.0);
+        assert_eq!(
+            criterion.terminate(&state),
+            TerminationStatus::Terminated(TerminationReason::ParamChangeBelowTolerance)
+        );
+    }
+
+    #[test]
+    fn test_any() {
+        let state = TestState::new();
+        let mut criterion: Any<TestState> = Any::new();
+        assert!(!criterion.terminate(&state).terminated());
+
+        let mut criterion = Any::new()
+            .with_criterion(MaxFuncCount::new("cost_count", 10))
+            .with_criterion(MaxTime::new(instant::Duration::ZERO))
+            .with_criterion(CostStagnation::new(0));
+        assert_eq!(
+            criterion.terminate(&state),
+            TerminationStatus::Terminated(TerminationReason::MaxTimeReached)
+        );
+    }
+
+    #[test]
+    fn test_all() {
+        let state = TestState::new();
+        let mut criterion: All<TestState> = All::new();
+        assert!(!criterion.terminate(&state).terminated());
+
+        let mut criterion = All::new()
+            .with_criterion(MaxFuncCount::new("cost_count", 10))
+            .with_criterion(MaxTime::new(instant::Duration::ZERO));
+        assert!(!criterion.terminate(&state).terminated());
+
+        let mut criterion = All::new()
+            .with_criterion(MaxTime::new(instant::Duration::ZERO))
+            .with_criterion(CostStagnation::new(0));
+        assert_eq!(
+            criterion.terminate(&state),
+            TerminationStatus::Terminated(TerminationReason::SolverExit(
+                "Maximum time reached and Best cost function value stagnated".to_string()
+            ))
+        );
+    }
 }
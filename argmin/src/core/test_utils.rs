@@ -5,6 +5,7 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+use crate::core::asynchronous::{AsyncCostFunction, AsyncGradient, BoxFuture};
 use crate::core::{
     CostFunction, Error, Gradient, Hessian, IterState, Jacobian, Operator, Problem, Solver, KV,
 };
@@ -12,11 +13,14 @@ use crate::solver::simulatedannealing::Anneal;
 #[cfg(feature = "serde1")]
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
 
 /// Pseudo problem useful for testing
 ///
-/// Implements [`CostFunction`], [`Operator`], [`Gradient`], [`Jacobian`], [`Hessian`], and
-/// [`Anneal`].
+/// Implements [`CostFunction`], [`Operator`], [`Gradient`], [`Jacobian`], [`Hessian`],
+/// [`Anneal`], [`AsyncCostFunction`] and [`AsyncGradient`].
 #[derive(Clone, Copy, Default, Debug, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
 pub struct TestProblem {}
@@ -207,6 +211,62 @@ impl Anneal for TestProblem {
     }
 }
 
+impl AsyncCostFunction for TestProblem {
+    type Param = Vec<f64>;
+    type Output = f64;
+
+    /// Returns `1.0f64`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use argmin::core::test_utils::{block_on, TestProblem};
+    /// use argmin::core::asynchronous::AsyncCostFunction;
+    /// # use argmin::core::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let problem = TestProblem::new();
+    ///
+    /// let param = vec![1.0, 2.0];
+    ///
+    /// let res = block_on(problem.cost(&param))?;
+    /// # assert_eq!(res, 1.0f64);
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn cost<'a>(&'a self, _p: &'a Self::Param) -> BoxFuture<'a, Result<Self::Output, Error>> {
+        Box::pin(async { Ok(1.0f64) })
+    }
+}
+
+impl AsyncGradient for TestProblem {
+    type Param = Vec<f64>;
+    type Gradient = Vec<f64>;
+
+    /// Returns a clone of parameter `p`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use argmin::core::test_utils::{block_on, TestProblem};
+    /// use argmin::core::asynchronous::AsyncGradient;
+    /// # use argmin::core::Error;
+    ///
+    /// # fn main() -> Result<(), Error> {
+    /// let problem = TestProblem::new();
+    ///
+    /// let param = vec![1.0, 2.0];
+    ///
+    /// let res = block_on(problem.gradient(&param))?;
+    /// # assert_eq!(res, param);
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn gradient<'a>(&'a self, p: &'a Self::Param) -> BoxFuture<'a, Result<Self::Gradient, Error>> {
+        Box::pin(async move { Ok(p.clone()) })
+    }
+}
+
 /// A struct representing the following sparse problem.
 ///
 /// Example 1: x = [1, 1, 0, 0], y =  1
@@ -337,3 +397,36 @@ impl<O> Solver<O, IterState<Vec<f64>, (), (), (), f64>> for TestSolver {
         Ok((state, None))
     }
 }
+
+/// Wakes up the thread running `block_on`
+struct ThreadWaker(std::thread::Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Minimal executor which runs a future to completion on the current thread
+///
+/// Useful for testing asynchronous problems without an async runtime.
+///
+/// # Example
+///
+/// ```
+/// use argmin::core::test_utils::block_on;
+///
+/// let res = block_on(async { 1 + 1 });
+/// # assert_eq!(res, 2);
+/// ```
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
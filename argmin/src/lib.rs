@@ -32,6 +32,11 @@
 //!
 //! * [Checkpointing](`crate::core::checkpointing`)
 //! * [Observers](`crate::core::observers`)
+//! * [Finite difference derivatives](`crate::core::finitediff`)
+//! * [Derivative checks](`crate::core::checks`)
+//! * [Asynchronous cost functions](`crate::core::asynchronous`)
+//! * [Ask-and-tell interface](`crate::core::asktell`)
+//! * [Termination criteria](`crate::core::termination`)
 //!
 //!
 //! # Algorithms
@@ -59,6 +64,7 @@
 //! - [Quasi-Newton methods](`crate::solver::quasinewton`)
 //!   - [BFGS](`crate::solver::quasinewton::BFGS`)
 //!   - [L-BFGS](`crate::solver::quasinewton::LBFGS`)
+//!   - [L-BFGS-B](`crate::solver::quasinewton::LBFGSB`)
 //!   - [DFP](`crate::solver::quasinewton::DFP`)
 //!   - [SR1](`crate::solver::quasinewton::SR1`)
 //!   - [SR1-TrustRegion](`crate::solver::quasinewton::SR1TrustRegion`)
@@ -67,6 +73,8 @@
 //!   - [Gauss-Newton method](`crate::solver::gaussnewton::GaussNewton`)
 //!   - [Gauss-Newton method with linesearch](`crate::solver::gaussnewton::GaussNewtonLS`)
 //!
+//! - [Levenberg-Marquardt method](`crate::solver::levenbergmarquardt::LevenbergMarquardt`)
+//!
 //! - [Golden-section search](`crate::solver::goldensectionsearch::GoldenSectionSearch`)
 //!
 //! - [Landweber iteration](`crate::solver::landweber::Landweber`)
@@ -81,6 +89,22 @@
 //!
 //! - [Particle Swarm Optimization](`crate::solver::particleswarm::ParticleSwarm`)
 //!
+//! - [Differential Evolution](`crate::solver::differentialevolution::DifferentialEvolution`)
+//!
+//! - [CMA-ES](`crate::solver::cmaes::CMAES`)
+//!
+//! - [Linear programming](`crate::solver::linearprogramming`)
+//!   - [Revised simplex method](`crate::solver::linearprogramming::Simplex`)
+//!   - [Interior point method](`crate::solver::linearprogramming::InteriorPoint`)
+//!
+//! - [Augmented Lagrangian method](`crate::solver::augmentedlagrangian::AugmentedLagrangian`)
+//!
+//! - [Sequential Quadratic Programming](`crate::solver::sqp::SQP`)
+//!
+//! - [Multi-start methods](`crate::solver::multistart`)
+//!   - [Multi-start method](`crate::solver::multistart::MultiStart`)
+//!   - [Basin-hopping](`crate::solver::multistart::BasinHopping`)
+//!
 //! ## External solvers compatible with argmin
 //!
 //! External solvers which implement the `Solver` trait are compatible with argmins `Executor`,
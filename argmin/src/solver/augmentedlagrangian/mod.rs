@@ -0,0 +1,873 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Augmented Lagrangian method
+//!
+//! Solves nonlinearly constrained problems of the form
+//!
+//! ```text
+//! minimize    f(x)
+//! subject to  c_E(x) = 0
+//!             c_I(x) >= 0
+//! ```
+//!
+//! by solving a sequence of unconstrained subproblems with an arbitrary inner solver.
+//!
+//! For details see [`AugmentedLagrangian`].
+//!
+//! ## Reference
+//!
+//! Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+
+use crate::core::{
+    ArgminFloat, CostFunction, DeserializeOwnedAlias, EqualityConstraints, Error, Executor,
+    Gradient, InequalityConstraints, IterState, OptimizationResult, Problem, SerializeAlias,
+    Solver, State, TerminationReason, TerminationStatus, KV,
+};
+use argmin_math::ArgminScaledAdd;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// # Augmented Lagrangian method
+///
+/// Solves problems with equality constraints `c_E(x) = 0` and inequality constraints
+/// `c_I(x) >= 0` by minimizing the augmented Lagrangian
+///
+/// ```text
+/// L(x) = f(x) - sum_i lambda_i c_i(x) + mu/2 sum_i c_i(x)^2 + sum_j psi(c_j(x), sigma_j, mu)
+/// ```
+///
+/// with an inner solver, where the first sum runs over the equality constraints and the second
+/// one over the inequality constraints with
+/// `psi(t, sigma, mu) = -sigma t + mu/2 t^2` if `t <= sigma/mu` and `-sigma^2/(2 mu)` otherwise
+/// (Nocedal & Wright, section 17.4). After each inner solve, the Lagrange multipliers `lambda` and
+/// `sigma` are updated. If the constraint violation did not decrease sufficiently, the penalty
+/// parameter `mu` is increased.
+///
+/// Any solver which operates on an [`IterState`], such as
+/// [`LBFGS`](`crate::solver::quasinewton::LBFGS`) or
+/// [`NelderMead`](`crate::solver::neldermead::NelderMead`), can be used as inner solver. The inner
+/// solver is warm started: Each inner solve continues with the solver instance returned by the
+/// previous one and the current iterate as initial parameter vector. Solvers which keep their own
+/// set of parameter vectors (like the simplex of `NelderMead`) therefore continue from where they
+/// stopped. The number of iterations of each inner solve is limited by
+/// [`with_inner_max_iters`](`AugmentedLagrangian::with_inner_max_iters`).
+///
+/// The algorithm terminates once the infinity norm of the constraint violation after an inner
+/// solve is below the tolerance set with
+/// [`with_tolerance_constraints`](`AugmentedLagrangian::with_tolerance_constraints`). The
+/// constraint violation is reported as `constraint_violation` in the KV output.
+///
+/// An initial parameter vector is required, which is to be provided via the
+/// [`configure`](`crate::core::Executor::configure`) method of the
+/// [`Executor`](`crate::core::Executor`). The cost reported in the state is the value of the
+/// objective function `f` if the constraint violation is within the tolerance and infinity
+/// otherwise. The best parameter vector of the final state is therefore the feasible iterate with
+/// the lowest objective function value. The current estimates of the Lagrange multipliers are
+/// available via [`IterState::get_equality_multipliers`] and
+/// [`IterState::get_inequality_multipliers`].
+///
+/// ## Requirements on the optimization problem
+///
+/// The optimization problem is required to implement [`CostFunction`], [`EqualityConstraints`]
+/// and [`InequalityConstraints`]. Problems without equality or inequality constraints return an
+/// empty vector. If the inner solver requires gradients, the problem additionally needs to
+/// implement [`Gradient`] as well as the Jacobians of both kinds of constraints.
+///
+/// ## Reference
+///
+/// Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct AugmentedLagrangian<S, F> {
+    /// Inner solver
+    inner: S,
+    /// Penalty parameter
+    penalty: F,
+    /// Factor by which the penalty parameter is increased
+    penalty_factor: F,
+    /// Tolerance on the constraint violation
+    tol_constraints: F,
+    /// Maximum number of iterations of each inner solve
+    inner_max_iters: u64,
+    /// Lagrange multipliers of the equality constraints
+    multipliers_eq: Vec<F>,
+    /// Lagrange multipliers of the inequality constraints
+    multipliers_ineq: Vec<F>,
+    /// Constraint violation of the current iterate
+    violation: F,
+    /// Whether at least one inner solve was performed
+    solved: bool,
+}
+
+impl<S, F> AugmentedLagrangian<S, F>
+where
+    F: ArgminFloat,
+{
+    /// Construct a new instance of [`AugmentedLagrangian`]
+    ///
+    /// Takes the inner solver as input.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::augmentedlagrangian::AugmentedLagrangian;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// # let inner: NelderMead<Vec<f64>, f64> = NelderMead::new(vec![vec![0.0], vec![1.0]]);
+    /// let solver: AugmentedLagrangian<_, f64> = AugmentedLagrangian::new(inner);
+    /// ```
+    pub fn new(inner: S) -> Self {
+        AugmentedLagrangian {
+            inner,
+            penalty: float!(10.0),
+            penalty_factor: float!(10.0),
+            tol_constraints: float!(1e-6),
+            inner_max_iters: 1000,
+            multipliers_eq: vec![],
+            multipliers_ineq: vec![],
+            violation: F::infinity(),
+            solved: false,
+        }
+    }
+
+    /// Set initial penalty parameter
+    ///
+    /// Must be larger than 0. Defaults to `10`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::augmentedlagrangian::AugmentedLagrangian;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// # let inner: NelderMead<Vec<f64>, f64> = NelderMead::new(vec![vec![0.0], vec![1.0]]);
+    /// let solver = AugmentedLagrangian::new(inner).with_penalty(100.0)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_penalty(mut self, penalty: F) -> Result<Self, Error> {
+        if penalty <= float!(0.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`AugmentedLagrangian`: penalty must be > 0."
+            ));
+        }
+        self.penalty = penalty;
+        Ok(self)
+    }
+
+    /// Set factor by which the penalty parameter is increased
+    ///
+    /// Must be larger than 1. Defaults to `10`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::augmentedlagrangian::AugmentedLagrangian;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// # let inner: NelderMead<Vec<f64>, f64> = NelderMead::new(vec![vec![0.0], vec![1.0]]);
+    /// let solver = AugmentedLagrangian::new(inner).with_penalty_factor(5.0)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_penalty_factor(mut self, factor: F) -> Result<Self, Error> {
+        if factor <= float!(1.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`AugmentedLagrangian`: penalty factor must be > 1."
+            ));
+        }
+        self.penalty_factor = factor;
+        Ok(self)
+    }
+
+    /// Set tolerance on the infinity norm of the constraint violation
+    ///
+    /// Must be non-negative. Defaults to `1e-6`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::augmentedlagrangian::AugmentedLagrangian;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// # let inner: NelderMead<Vec<f64>, f64> = NelderMead::new(vec![vec![0.0], vec![1.0]]);
+    /// let solver = AugmentedLagrangian::new(inner).with_tolerance_constraints(1e-8)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_tolerance_constraints(mut self, tol: F) -> Result<Self, Error> {
+        if tol < float!(0.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`AugmentedLagrangian`: constraint tolerance must be >= 0."
+            ));
+        }
+        self.tol_constraints = tol;
+        Ok(self)
+    }
+
+    /// Set maximum number of iterations of each inner solve
+    ///
+    /// Must be larger than 0. Defaults to `1000`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::augmentedlagrangian::AugmentedLagrangian;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// # let inner: NelderMead<Vec<f64>, f64> = NelderMead::new(vec![vec![0.0], vec![1.0]]);
+    /// let solver: AugmentedLagrangian<_, f64> =
+    ///     AugmentedLagrangian::new(inner).with_inner_max_iters(100)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_inner_max_iters(mut self, iters: u64) -> Result<Self, Error> {
+        if iters == 0 {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`AugmentedLagrangian`: inner max iters must be > 0."
+            ));
+        }
+        self.inner_max_iters = iters;
+        Ok(self)
+    }
+}
+
+impl<S, F: ArgminFloat> AugmentedLagrangian<S, F> {
+    /// Objective function value if the current constraint violation is within the tolerance,
+    /// infinity otherwise
+    fn cost<O, P>(&self, problem: &mut Problem<O>, param: &P) -> Result<F, Error>
+    where
+        O: CostFunction<Param = P, Output = F>,
+    {
+        if self.violation <= self.tol_constraints {
+            problem.cost(param)
+        } else {
+            Ok(F::infinity())
+        }
+    }
+}
+
+/// Infinity norm of the violation of equality constraints `ceq` and inequality constraints `cineq`
+pub(crate) fn violation<F: ArgminFloat>(ceq: &[F], cineq: &[F]) -> F {
+    let v = ceq.iter().fold(float!(0.0), |acc: F, c| acc.max(c.abs()));
+    cineq.iter().fold(v, |acc, &c| acc.max(-c))
+}
+
+/// Augmented Lagrangian of the wrapped problem for fixed multipliers and penalty parameter
+#[derive(Clone)]
+pub(crate) struct AugmentedLagrangianProblem<O, F> {
+    pub(crate) problem: O,
+    pub(crate) multipliers_eq: Vec<F>,
+    pub(crate) multipliers_ineq: Vec<F>,
+    pub(crate) penalty: F,
+}
+
+impl<O, F: ArgminFloat> AugmentedLagrangianProblem<O, F> {
+    /// Gives the wrapped problem back to `problem` and takes care of the function evaluation
+    /// counts. Each evaluation of the augmented Lagrangian evaluates the objective and the
+    /// constraints once, each evaluation of its gradient evaluates the gradients and the
+    /// constraints once. Constraints and Jacobians of empty constraint sets are not evaluated.
+    pub(crate) fn restore(problem: &mut Problem<O>, mut inner: Problem<Self>) {
+        let lagrangian = inner.take_problem().unwrap();
+        let num_cost = inner.counts.get("cost_count").copied().unwrap_or(0);
+        let num_grad = inner.counts.get("gradient_count").copied().unwrap_or(0);
+        let (num_eq, num_eq_jacobian) = if lagrangian.multipliers_eq.is_empty() {
+            (0, 0)
+        } else {
+            (num_cost + num_grad, num_grad)
+        };
+        let (num_ineq, num_ineq_jacobian) = if lagrangian.multipliers_ineq.is_empty() {
+            (0, 0)
+        } else {
+            (num_cost + num_grad, num_grad)
+        };
+        problem.problem = Some(lagrangian.problem);
+        for (key, count) in [
+            ("cost_count", num_cost),
+            ("equality_constraints_count", num_eq),
+            ("inequality_constraints_count", num_ineq),
+            ("gradient_count", num_grad),
+            ("equality_jacobian_count", num_eq_jacobian),
+            ("inequality_jacobian_count", num_ineq_jacobian),
+        ] {
+            if count > 0 {
+                *problem.counts.entry(key).or_insert(0) += count;
+            }
+        }
+    }
+
+    /// Evaluates the equality and inequality constraints of the wrapped problem. Empty constraint
+    /// sets are not evaluated.
+    fn constraints<P>(&self, param: &P) -> Result<(Vec<F>, Vec<F>), Error>
+    where
+        O: EqualityConstraints<Param = P, Float = F> + InequalityConstraints<Param = P, Float = F>,
+    {
+        let ceq = if self.multipliers_eq.is_empty() {
+            vec![]
+        } else {
+            self.problem.equality_constraints(param)?
+        };
+        let cineq = if self.multipliers_ineq.is_empty() {
+            vec![]
+        } else {
+            self.problem.inequality_constraints(param)?
+        };
+        Ok((ceq, cineq))
+    }
+
+    /// Weights `w` of the constraint gradients in the gradient of the Lagrangian,
+    /// `grad L = grad f - sum_i w_i grad c_i`
+    fn weights(&self, ceq: &[F], cineq: &[F]) -> (Vec<F>, Vec<F>) {
+        let mu = self.penalty;
+        (
+            self.multipliers_eq
+                .iter()
+                .zip(ceq.iter())
+                .map(|(&l, &c)| l - mu * c)
+                .collect(),
+            self.multipliers_ineq
+                .iter()
+                .zip(cineq.iter())
+                .map(|(&s, &c)| (s - mu * c).max(float!(0.0)))
+                .collect(),
+        )
+    }
+}
+
+impl<O, P, F> CostFunction for AugmentedLagrangianProblem<O, F>
+where
+    O: CostFunction<Param = P, Output = F>
+        + EqualityConstraints<Param = P, Float = F>
+        + InequalityConstraints<Param = P, Float = F>,
+    F: ArgminFloat,
+{
+    type Param = P;
+    type Output = F;
+
+    fn cost(&self, param: &Self::Param) -> Result<Self::Output, Error> {
+        let mu = self.penalty;
+        let half: F = float!(0.5);
+        let cost = self.problem.cost(param)?;
+        let (ceq, cineq) = self.constraints(param)?;
+        let cost = self
+            .multipliers_eq
+            .iter()
+            .zip(ceq.iter())
+            .fold(cost, |acc, (&l, &c)| acc - l * c + half * mu * c * c);
+        Ok(self
+            .multipliers_ineq
+            .iter()
+            .zip(cineq.iter())
+            .fold(cost, |acc, (&s, &c)| {
+                if c <= s / mu {
+                    acc - s * c + half * mu * c * c
+                } else {
+                    acc - half * s * s / mu
+                }
+            }))
+    }
+}
+
+impl<O, P, G, F> Gradient for AugmentedLagrangianProblem<O, F>
+where
+    O: Gradient<Param = P, Gradient = G>
+        + EqualityConstraints<Param = P, Gradient = G, Float = F>
+        + InequalityConstraints<Param = P, Gradient = G, Float = F>,
+    G: ArgminScaledAdd<G, F, G>,
+    F: ArgminFloat,
+{
+    type Param = P;
+    type Gradient = G;
+
+    fn gradient(&self, param: &Self::Param) -> Result<Self::Gradient, Error> {
+        let (ceq, cineq) = self.constraints(param)?;
+        let (weq, wineq) = self.weights(&ceq, &cineq);
+        let mut grad = self.problem.gradient(param)?;
+        if !weq.is_empty() {
+            for (w, j) in weq
+                .iter()
+                .zip(self.problem.equality_jacobian(param)?.iter())
+            {
+                grad = grad.scaled_add(&-*w, j);
+            }
+        }
+        if !wineq.is_empty() {
+            for (w, j) in wineq
+                .iter()
+                .zip(self.problem.inequality_jacobian(param)?.iter())
+            {
+                grad = grad.scaled_add(&-*w, j);
+            }
+        }
+        Ok(grad)
+    }
+}
+
+impl<O, S, P, G, F> Solver<O, IterState<P, G, (), (), F>> for AugmentedLagrangian<S, F>
+where
+    O: CostFunction<Param = P, Output = F>
+        + EqualityConstraints<Param = P, Float = F>
+        + InequalityConstraints<Param = P, Float = F>,
+    S: Solver<AugmentedLagrangianProblem<O, F>, IterState<P, G, (), (), F>>
+        + Clone
+        + SerializeAlias,
+    P: Clone + SerializeAlias + DeserializeOwnedAlias,
+    G: SerializeAlias + DeserializeOwnedAlias,
+    F: ArgminFloat,
+{
+    const NAME: &'static str = "Augmented Lagrangian";
+
+    fn init(
+        &mut self,
+        problem: &mut Problem<O>,
+        mut state: IterState<P, G, (), (), F>,
+    ) -> Result<(IterState<P, G, (), (), F>, Option<KV>), Error> {
+        let param = state.take_param().ok_or_else(argmin_error_closure!(
+            NotInitialized,
+            concat!(
+                "`AugmentedLagrangian` requires an initial parameter vector. ",
+                "Please provide an initial guess via `Executor`s `configure` method."
+            )
+        ))?;
+
+        let ceq = problem.equality_constraints(&param)?;
+        let cineq = problem.inequality_constraints(&param)?;
+        self.multipliers_eq = vec![float!(0.0); ceq.len()];
+        self.multipliers_ineq = vec![float!(0.0); cineq.len()];
+        self.violation = violation(&ceq, &cineq);
+        self.solved = false;
+
+        let cost = self.cost(problem, &param)?;
+        Ok((
+            state.param(param).cost(cost),
+            Some(kv!("constraint_violation" => self.violation;)),
+        ))
+    }
+
+    fn next_iter(
+        &mut self,
+        problem: &mut Problem<O>,
+        mut state: IterState<P, G, (), (), F>,
+    ) -> Result<(IterState<P, G, (), (), F>, Option<KV>), Error> {
+        let param = state.take_param().ok_or_else(argmin_error_closure!(
+            PotentialBug,
+            "`AugmentedLagrangian`: Parameter vector in state not set."
+        ))?;
+
+        let inner_problem = AugmentedLagrangianProblem {
+            problem: problem.take_problem().unwrap(),
+            multipliers_eq: self.multipliers_eq.clone(),
+            multipliers_ineq: self.multipliers_ineq.clone(),
+            penalty: self.penalty,
+        };
+        let inner_max_iters = self.inner_max_iters;
+        let OptimizationResult {
+            problem: inner_problem,
+            solver: inner,
+            state: mut inner_state,
+        } = Executor::new(inner_problem, self.inner.clone())
+            .configure(|config| config.param(param).max_iters(inner_max_iters))
+            .ctrlc(false)
+            .run()?;
+        self.inner = inner;
+        let inner_iters = inner_state.get_iter();
+
+        AugmentedLagrangianProblem::restore(problem, inner_problem);
+
+        let param = inner_state
+            .take_best_param()
+            .or_else(|| inner_state.take_param())
+            .ok_or_else(argmin_error_closure!(
+                PotentialBug,
+                "`AugmentedLagrangian`: Inner solver did not return a parameter vector."
+            ))?;
+
+        // Update multipliers and penalty parameter
+        let ceq = problem.equality_constraints(&param)?;
+        let cineq = problem.inequality_constraints(&param)?;
+        let mu = self.penalty;
+        for (l, &c) in self.multipliers_eq.iter_mut().zip(ceq.iter()) {
+            *l = *l - mu * c;
+        }
+        for (s, &c) in self.multipliers_ineq.iter_mut().zip(cineq.iter()) {
+            *s = (*s - mu * c).max(float!(0.0));
+        }
+        let new_violation = violation(&ceq, &cineq);
+        if new_violation > float!(0.25) * self.violation {
+            self.penalty = self.penalty * self.penalty_factor;
+        }
+        self.violation = new_violation;
+        self.solved = true;
+
+        let cost = self.cost(problem, &param)?;
+        Ok((
+            state
+                .param(param)
+                .cost(cost)
+                .equality_multipliers(self.multipliers_eq.clone())
+                .inequality_multipliers(self.multipliers_ineq.clone()),
+            Some(kv!(
+                "constraint_violation" => self.violation;
+                "penalty" => self.penalty;
+                "inner_iters" => inner_iters;
+            )),
+        ))
+    }
+
+    fn terminate(&mut self, _state: &IterState<P, G, (), (), F>) -> TerminationStatus {
+        if self.solved && self.violation <= self.tol_constraints {
+            return TerminationStatus::Terminated(TerminationReason::SolverConverged);
+        }
+        TerminationStatus::NotTerminated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ArgminError, State};
+    use crate::solver::linesearch::MoreThuenteLineSearch;
+    use crate::solver::neldermead::NelderMead;
+    use crate::solver::quasinewton::LBFGS;
+    use approx::assert_relative_eq;
+
+    test_trait_impl!(
+        augmented_lagrangian,
+        AugmentedLagrangian<NelderMead<Vec<f64>, f64>, f64>
+    );
+
+    /// `min (x - 2)^2 + (y - 1)^2` s.t. `x + y = 1` with solution `(1, 0)`
+    #[derive(Clone)]
+    struct EqualityProblem {}
+
+    impl CostFunction for EqualityProblem {
+        type Param = Vec<f64>;
+        type Output = f64;
+
+        fn cost(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+            Ok((p[0] - 2.0).powi(2) + (p[1] - 1.0).powi(2))
+        }
+    }
+
+    impl Gradient for EqualityProblem {
+        type Param = Vec<f64>;
+        type Gradient = Vec<f64>;
+
+        fn gradient(&self, p: &Self::Param) -> Result<Self::Gradient, Error> {
+            Ok(vec![2.0 * (p[0] - 2.0), 2.0 * (p[1] - 1.0)])
+        }
+    }
+
+    impl EqualityConstraints for EqualityProblem {
+        type Param = Vec<f64>;
+        type Gradient = Vec<f64>;
+        type Float = f64;
+
+        fn equality_constraints(&self, p: &Self::Param) -> Result<Vec<f64>, Error> {
+            Ok(vec![p[0] + p[1] - 1.0])
+        }
+
+        fn equality_jacobian(&self, _p: &Self::Param) -> Result<Vec<Vec<f64>>, Error> {
+            Ok(vec![vec![1.0, 1.0]])
+        }
+    }
+
+    impl InequalityConstraints for EqualityProblem {
+        type Param = Vec<f64>;
+        type Gradient = Vec<f64>;
+        type Float = f64;
+
+        fn inequality_constraints(&self, _p: &Self::Param) -> Result<Vec<f64>, Error> {
+            Ok(vec![])
+        }
+
+        fn inequality_jacobian(&self, _p: &Self::Param) -> Result<Vec<Vec<f64>>, Error> {
+            Ok(vec![])
+        }
+    }
+
+    /// `min x^2 + y^2` s.t. `x + y >= 1` and `y >= -1` with solution `(0.5, 0.5)`
+    #[derive(Clone)]
+    struct InequalityProblem {}
+
+    impl CostFunction for InequalityProblem {
+        type Param = Vec<f64>;
+        type Output = f64;
+
+        fn cost(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+            Ok(p[0].powi(2) + p[1].powi(2))
+        }
+    }
+
+    impl Gradient for InequalityProblem {
+        type Param = Vec<f64>;
+        type Gradient = Vec<f64>;
+
+        fn gradient(&self, p: &Self::Param) -> Result<Self::Gradient, Error> {
+            Ok(vec![2.0 * p[0], 2.0 * p[1]])
+        }
+    }
+
+    impl EqualityConstraints for InequalityProblem {
+        type Param = Vec<f64>;
+        type Gradient = Vec<f64>;
+        type Float = f64;
+
+        fn equality_constraints(&self, _p: &Self::Param) -> Result<Vec<f64>, Error> {
+            Ok(vec![])
+        }
+
+        fn equality_jacobian(&self, _p: &Self::Param) -> Result<Vec<Vec<f64>>, Error> {
+            Ok(vec![])
+        }
+    }
+
+    impl InequalityConstraints for InequalityProblem {
+        type Param = Vec<f64>;
+        type Gradient = Vec<f64>;
+        type Float = f64;
+
+        fn inequality_constraints(&self, p: &Self::Param) -> Result<Vec<f64>, Error> {
+            Ok(vec![p[0] + p[1] - 1.0, p[1] + 1.0])
+        }
+
+        fn inequality_jacobian(&self, _p: &Self::Param) -> Result<Vec<Vec<f64>>, Error> {
+            Ok(vec![vec![1.0, 1.0], vec![0.0, 1.0]])
+        }
+    }
+
+    type Inner = LBFGS<MoreThuenteLineSearch<Vec<f64>, Vec<f64>, f64>, Vec<f64>, Vec<f64>, f64>;
+
+    fn lbfgs() -> Inner {
+        LBFGS::new(MoreThuenteLineSearch::new(), 5)
+    }
+
+    #[test]
+    fn test_new() {
+        let solver: AugmentedLagrangian<_, f64> = AugmentedLagrangian::new(lbfgs());
+        assert_eq!(solver.penalty.to_ne_bytes(), 10.0f64.to_ne_bytes());
+        assert_eq!(solver.penalty_factor.to_ne_bytes(), 10.0f64.to_ne_bytes());
+        assert_eq!(solver.tol_constraints.to_ne_bytes(), 1e-6f64.to_ne_bytes());
+        assert_eq!(solver.inner_max_iters, 1000);
+        assert!(solver.multipliers_eq.is_empty());
+        assert!(solver.multipliers_ineq.is_empty());
+        assert!(solver.violation.is_infinite());
+        assert!(!solver.solved);
+    }
+
+    #[test]
+    fn test_builders() {
+        let solver: AugmentedLagrangian<_, f64> = AugmentedLagrangian::new(lbfgs())
+            .with_penalty(2.0)
+            .unwrap()
+            .with_penalty_factor(3.0)
+            .unwrap()
+            .with_tolerance_constraints(0.0)
+            .unwrap()
+            .with_inner_max_iters(7)
+            .unwrap();
+        assert_eq!(solver.penalty.to_ne_bytes(), 2.0f64.to_ne_bytes());
+        assert_eq!(solver.penalty_factor.to_ne_bytes(), 3.0f64.to_ne_bytes());
+        assert_eq!(solver.tol_constraints.to_ne_bytes(), 0.0f64.to_ne_bytes());
+        assert_eq!(solver.inner_max_iters, 7);
+    }
+
+    #[test]
+    fn test_builder_errors() {
+        let new = || AugmentedLagrangian::<_, f64>::new(lbfgs());
+        for penalty in [0.0, -1.0] {
+            assert_error!(
+                new().with_penalty(penalty),
+                ArgminError,
+                "Invalid parameter: \"`AugmentedLagrangian`: penalty must be > 0.\""
+            );
+        }
+        for factor in [1.0, 0.5] {
+            assert_error!(
+                new().with_penalty_factor(factor),
+                ArgminError,
+                "Invalid parameter: \"`AugmentedLagrangian`: penalty factor must be > 1.\""
+            );
+        }
+        assert_error!(
+            new().with_tolerance_constraints(-1e-6),
+            ArgminError,
+            "Invalid parameter: \"`AugmentedLagrangian`: constraint tolerance must be >= 0.\""
+        );
+        assert_error!(
+            new().with_inner_max_iters(0),
+            ArgminError,
+            "Invalid parameter: \"`AugmentedLagrangian`: inner max iters must be > 0.\""
+        );
+    }
+
+    #[test]
+    fn test_init_param_not_initialized() {
+        let mut solver: AugmentedLagrangian<_, f64> = AugmentedLagrangian::new(lbfgs());
+        let res = solver.init(&mut Problem::new(EqualityProblem {}), IterState::new());
+        assert_error!(
+            res,
+            ArgminError,
+            concat!(
+                "Not initialized: \"`AugmentedLagrangian` requires an initial parameter vector. ",
+                "Please provide an initial guess via `Executor`s `configure` method.\""
+            )
+        );
+    }
+
+    #[test]
+    fn test_init() {
+        let mut solver: AugmentedLagrangian<_, f64> = AugmentedLagrangian::new(lbfgs());
+        let mut problem = Problem::new(InequalityProblem {});
+        let (state, kv) = solver
+            .init(&mut problem, IterState::new().param(vec![0.0, -2.0]))
+            .unwrap();
+        assert_eq!(solver.multipliers_eq.len(), 0);
+        assert_eq!(solver.multipliers_ineq.len(), 2);
+        assert_relative_eq!(solver.violation, 3.0);
+        assert!(state.get_cost().is_infinite());
+        let (state, _) = solver
+            .init(&mut problem, IterState::new().param(vec![2.0, 0.0]))
+            .unwrap();
+        assert_relative_eq!(solver.violation, 0.0);
+        assert_relative_eq!(state.get_cost(), 4.0);
+        assert_relative_eq!(
+            kv.unwrap()
+                .get("constraint_violation")
+                .unwrap()
+                .get_float()
+                .unwrap(),
+            3.0
+        );
+    }
+
+    #[test]
+    fn test_augmented_lagrangian_problem() {
+        let problem = AugmentedLagrangianProblem {
+            problem: InequalityProblem {},
+            multipliers_eq: vec![],
+            multipliers_ineq: vec![1.0, 0.5],
+            penalty: 2.0,
+        };
+        // c = (-2, 0): both constraints are in the quadratic regime of `psi`
+        let p = vec![0.0, -1.0];
+        let c = problem.problem.inequality_constraints(&p).unwrap();
+        assert_relative_eq!(c[0], -2.0);
+        assert_relative_eq!(c[1], 0.0);
+        // f = 1, psi_1 = -1 * (-2) + 1 * 4 = 6, psi_2 = 0
+        let cost = problem.cost(&p).unwrap();
+        assert_relative_eq!(cost, 1.0 + 6.0);
+        // weights: max(1 + 4, 0) = 5, max(0.5 - 0, 0) = 0.5
+        let grad = problem.gradient(&p).unwrap();
+        assert_relative_eq!(grad[0], 0.0 - 5.0);
+        assert_relative_eq!(grad[1], -2.0 - 5.0 - 0.5);
+
+        // c = (3, 3): inactive constraints only contribute a constant term
+        let p = vec![2.0, 2.0];
+        let cost = problem.cost(&p).unwrap();
+        assert_relative_eq!(cost, 8.0 - 0.25 - 0.0625);
+        let grad = problem.gradient(&p).unwrap();
+        assert_relative_eq!(grad[0], 4.0);
+        assert_relative_eq!(grad[1], 4.0);
+    }
+
+    #[test]
+    fn test_equality_lbfgs() {
+        let solver = AugmentedLagrangian::new(lbfgs());
+        let res = Executor::new(EqualityProblem {}, solver)
+            .configure(|state| state.param(vec![0.0, 0.0]).max_iters(50))
+            .run()
+            .unwrap();
+        assert_eq!(
+            res.state.get_termination_reason(),
+            Some(&TerminationReason::SolverConverged)
+        );
+        let param = res.state.get_best_param().unwrap();
+        assert_relative_eq!(param[0], 1.0, epsilon = 1e-5);
+        assert_relative_eq!(param[1], 0.0, epsilon = 1e-5);
+        assert_relative_eq!(res.state.get_best_cost(), 2.0, epsilon = 1e-5);
+        // Lagrange multiplier of the constraint is -2
+        assert_relative_eq!(res.solver.multipliers_eq[0], -2.0, epsilon = 1e-4);
+        assert_relative_eq!(
+            res.state.get_equality_multipliers().unwrap()[0],
+            -2.0,
+            epsilon = 1e-4
+        );
+        assert!(res.state.get_inequality_multipliers().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_inequality_lbfgs() {
+        let solver = AugmentedLagrangian::new(lbfgs());
+        let res = Executor::new(InequalityProblem {}, solver)
+            .configure(|state| state.param(vec![3.0, -2.0]).max_iters(50))
+            .run()
+            .unwrap();
+        assert_eq!(
+            res.state.get_termination_reason(),
+            Some(&TerminationReason::SolverConverged)
+        );
+        let param = res.state.get_best_param().unwrap();
+        assert_relative_eq!(param[0], 0.5, epsilon = 1e-5);
+        assert_relative_eq!(param[1], 0.5, epsilon = 1e-5);
+        assert_relative_eq!(res.solver.multipliers_ineq[0], 1.0, epsilon = 1e-4);
+        assert_relative_eq!(res.solver.multipliers_ineq[1], 0.0);
+    }
+
+    #[test]
+    fn test_nelder_mead() {
+        let inner = NelderMead::new(vec![vec![0.0, 0.0], vec![1.0, 0.0], vec![0.0, 1.0]])
+            .with_sd_tolerance(1e-12)
+            .unwrap();
+        let solver = AugmentedLagrangian::new(inner)
+            .with_tolerance_constraints(1e-5)
+            .unwrap();
+        let res = Executor::new(EqualityProblem {}, solver)
+            .configure(|state| state.param(vec![0.0, 0.0]).max_iters(50))
+            .run()
+            .unwrap();
+        let param = res.state.get_best_param().unwrap();
+        assert_relative_eq!(param[0], 1.0, epsilon = 1e-4);
+        assert_relative_eq!(param[1], 0.0, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn test_func_counts() {
+        let mut solver: AugmentedLagrangian<_, f64> = AugmentedLagrangian::new(lbfgs());
+        let mut problem = Problem::new(EqualityProblem {});
+        let (state, _) = solver
+            .init(&mut problem, IterState::new().param(vec![0.0, 0.0]))
+            .unwrap();
+        let (_, kv) = solver.next_iter(&mut problem, state).unwrap();
+        assert!(kv.unwrap().get("inner_iters").unwrap().get_uint().unwrap() > 0);
+        // The first iterate is infeasible, therefore the outer solver only evaluates the
+        // constraints in `init` and `next_iter` but not the objective function. The empty set of
+        // inequality constraints is not evaluated by the inner solver.
+        let counts = &problem.counts;
+        let num_cost = counts["cost_count"];
+        let num_grad = counts["gradient_count"];
+        assert!(num_cost > 0);
+        assert!(num_grad > 0);
+        assert_eq!(
+            counts["equality_constraints_count"],
+            num_cost + num_grad + 2
+        );
+        assert_eq!(counts["inequality_constraints_count"], 2);
+        assert_eq!(counts["equality_jacobian_count"], num_grad);
+        assert!(!counts.contains_key("inequality_jacobian_count"));
+    }
+}
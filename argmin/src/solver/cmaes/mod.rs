@@ -0,0 +1,1035 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Covariance Matrix Adaptation Evolution Strategy (CMA-ES)
+//!
+//! Implementation of the CMA-ES with weighted recombination, cumulative step-size adaptation and
+//! rank-one and rank-mu updates of the covariance matrix as outlined in \[0\]. Optionally, the
+//! search is restarted with increasing population size (IPOP-CMA-ES, \[1\]) or with alternating
+//! large and small populations (BIPOP-CMA-ES, \[2\]).
+//!
+//! For details see [`CMAES`].
+//!
+//! ## References
+//!
+//! \[0\] Nikolaus Hansen (2016). The CMA Evolution Strategy: A Tutorial.
+//! <https://arxiv.org/abs/1604.00772>
+//!
+//! \[1\] Anne Auger and Nikolaus Hansen (2005). A Restart CMA Evolution Strategy With Increasing
+//! Population Size. 2005 IEEE Congress on Evolutionary Computation.
+//! <https://doi.org/10.1109/CEC.2005.1554902>
+//!
+//! \[2\] Nikolaus Hansen (2009). Benchmarking a BI-Population CMA-ES on the BBOB-2009 Function
+//! Testbed. GECCO '09. <https://doi.org/10.1145/1570256.1570333>
+
+use crate::core::{
+    ArgminFloat, CostFunction, Error, PopulationState, Problem, SerializeAlias, Solver, State,
+    SyncAlias, TerminationReason, KV,
+};
+use crate::solver::utils::{cmp_nan_last, dot, identity};
+use rand::prelude::*;
+use rand_xoshiro::Xoshiro256PlusPlus;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Restart strategy of [`CMAES`]
+///
+/// A restart is triggered once the search distribution has converged, i.e. if the cost function
+/// values stagnate, the step size becomes negligible or the covariance matrix becomes
+/// ill-conditioned. Every restart starts from the initial mean.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub enum RestartStrategy {
+    /// Terminate once the search distribution has converged
+    None,
+    /// Restart up to the given number of times, doubling the population size each time (IPOP)
+    Ipop(usize),
+    /// Restart up to the given number of times, alternating between increasing large populations
+    /// and randomly sized small populations with small initial step sizes (BIPOP)
+    Bipop(usize),
+}
+
+/// State of the search distribution of a single run
+#[derive(Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+struct Distribution<F> {
+    /// Number of offspring
+    lambda: usize,
+    /// Recombination weights
+    weights: Vec<F>,
+    /// Variance effective selection mass
+    mueff: F,
+    /// Learning rate for the cumulation of the rank-one update
+    cc: F,
+    /// Learning rate for the cumulation of the step-size control
+    cs: F,
+    /// Learning rate of the rank-one update
+    c1: F,
+    /// Learning rate of the rank-mu update
+    cmu: F,
+    /// Damping of the step-size update
+    damps: F,
+    /// Expectation of the norm of a standard normally distributed vector
+    chi_n: F,
+    /// Mean
+    mean: Vec<F>,
+    /// Step size
+    sigma: F,
+    /// Covariance matrix
+    c: Vec<Vec<F>>,
+    /// Eigenvectors of the covariance matrix (columns)
+    b: Vec<Vec<F>>,
+    /// Square roots of the eigenvalues of the covariance matrix
+    d: Vec<F>,
+    /// Evolution path of the covariance matrix
+    pc: Vec<F>,
+    /// Evolution path of the step size
+    ps: Vec<F>,
+    /// Number of generations
+    generation: u64,
+    /// Generation in which the eigendecomposition was last computed
+    eigen_generation: u64,
+    /// Best cost function values of the most recent generations
+    history: VecDeque<F>,
+}
+
+impl<F: ArgminFloat> Distribution<F> {
+    /// Initial distribution with the default strategy parameters of \[0\]
+    fn new(mean: Vec<F>, sigma: F, lambda: usize) -> Self {
+        let n = mean.len();
+        let nf: F = float!(n as f64);
+        let mu = lambda / 2;
+        let raw: Vec<F> = (1..=mu)
+            .map(|i| float!(((lambda as f64 + 1.0) / 2.0).ln() - (i as f64).ln()))
+            .collect();
+        let sum = raw.iter().fold(float!(0.0), |acc: F, &w| acc + w);
+        let weights: Vec<F> = raw.iter().map(|&w| w / sum).collect();
+        let mueff = float!(1.0) / weights.iter().fold(float!(0.0), |acc: F, &w| acc + w * w);
+
+        let cc = (float!(4.0) + mueff / nf) / (nf + float!(4.0) + float!(2.0) * mueff / nf);
+        let cs = (mueff + float!(2.0)) / (nf + mueff + float!(5.0));
+        let c1 = float!(2.0) / ((nf + float!(1.3)).powi(2) + mueff);
+        let cmu = (float!(1.0) - c1).min(
+            float!(2.0) * (mueff - float!(2.0) + float!(1.0) / mueff)
+                / ((nf + float!(2.0)).powi(2) + mueff),
+        );
+        let damps = float!(1.0)
+            + float!(2.0)
+                * float!(0.0)
+                    .max(((mueff - float!(1.0)) / (nf + float!(1.0))).sqrt() - float!(1.0))
+            + cs;
+        let chi_n = nf.sqrt()
+            * (float!(1.0) - float!(1.0) / (float!(4.0) * nf)
+                + float!(1.0) / (float!(21.0) * nf * nf));
+
+        Distribution {
+            lambda,
+            weights,
+            mueff,
+            cc,
+            cs,
+            c1,
+            cmu,
+            damps,
+            chi_n,
+            mean,
+            sigma,
+            c: identity(n),
+            b: identity(n),
+            d: vec![float!(1.0); n],
+            pc: vec![float!(0.0); n],
+            ps: vec![float!(0.0); n],
+            generation: 0,
+            eigen_generation: 0,
+            history: VecDeque::new(),
+        }
+    }
+
+    /// Recomputes the eigendecomposition of the covariance matrix if it is outdated. To save
+    /// computation time, this is done only every `lambda / (c1 + cmu) / n / 10` generations.
+    fn update_eigendecomposition(&mut self) {
+        let n = self.mean.len();
+        let lag: F = float!(self.lambda as f64) / ((self.c1 + self.cmu) * float!(n as f64 * 10.0));
+        if float!((self.generation - self.eigen_generation) as f64) <= lag {
+            return;
+        }
+        self.eigen_generation = self.generation;
+        let (eigenvalues, b) = symmetric_eigen(self.c.clone());
+        self.d = eigenvalues
+            .into_iter()
+            .map(|e| e.max(F::epsilon()).sqrt())
+            .collect();
+        self.b = b;
+    }
+
+    /// Computes `B D z`
+    fn transform(&self, z: &[F]) -> Vec<F> {
+        let dz: Vec<F> = self.d.iter().zip(z.iter()).map(|(&d, &z)| d * z).collect();
+        self.b.iter().map(|row| dot(row, &dz)).collect()
+    }
+
+    /// Computes `C^(-1/2) y = B D^(-1) B^T y`
+    fn inv_sqrt(&self, y: &[F]) -> Vec<F> {
+        let n = y.len();
+        let bty: Vec<F> = (0..n)
+            .map(|j| {
+                self.b
+                    .iter()
+                    .zip(y.iter())
+                    .fold(float!(0.0), |acc, (row, &yi)| acc + row[j] * yi)
+                    / self.d[j]
+            })
+            .collect();
+        self.b.iter().map(|row| dot(row, &bty)).collect()
+    }
+
+    /// Updates mean, evolution paths, covariance matrix and step size from the steps `y` of the
+    /// offspring (`x = mean + sigma * y`), sorted by their cost function value.
+    fn update(&mut self, sorted_steps: &[&Vec<F>]) {
+        let n = self.mean.len();
+        let nf: F = float!(n as f64);
+        self.generation += 1;
+
+        let mut y_w = vec![float!(0.0); n];
+        for (&w, y) in self.weights.iter().zip(sorted_steps.iter()) {
+            for (ywi, &yi) in y_w.iter_mut().zip(y.iter()) {
+                *ywi = *ywi + w * yi;
+            }
+        }
+        for (mi, &ywi) in self.mean.iter_mut().zip(y_w.iter()) {
+            *mi = *mi + self.sigma * ywi;
+        }
+
+        // Cumulation for the step size
+        let cs_factor = (self.cs * (float!(2.0) - self.cs) * self.mueff).sqrt();
+        let c_inv_y = self.inv_sqrt(&y_w);
+        for (psi, &ci) in self.ps.iter_mut().zip(c_inv_y.iter()) {
+            *psi = (float!(1.0) - self.cs) * *psi + cs_factor * ci;
+        }
+        let ps_norm = dot(&self.ps, &self.ps).sqrt();
+
+        // Stall the update of `pc` if `ps` is large
+        let hsig = ps_norm
+            / (float!(1.0)
+                - (float!(1.0) - self.cs)
+                    .powi(2 * self.generation.min(i32::MAX as u64 / 2) as i32))
+            .sqrt()
+            / self.chi_n
+            < float!(1.4) + float!(2.0) / (nf + float!(1.0));
+
+        // Cumulation for the covariance matrix
+        let cc_factor = (self.cc * (float!(2.0) - self.cc) * self.mueff).sqrt();
+        for (pci, &ywi) in self.pc.iter_mut().zip(y_w.iter()) {
+            *pci = (float!(1.0) - self.cc) * *pci;
+            if hsig {
+                *pci = *pci + cc_factor * ywi;
+            }
+        }
+
+        // Rank-one and rank-mu update of the covariance matrix
+        let delta_hsig = if hsig {
+            float!(0.0)
+        } else {
+            self.cc * (float!(2.0) - self.cc)
+        };
+        let old = float!(1.0) - self.c1 - self.cmu + self.c1 * delta_hsig;
+        for i in 0..n {
+            for j in 0..=i {
+                let rank_mu = self
+                    .weights
+                    .iter()
+                    .zip(sorted_steps.iter())
+                    .fold(float!(0.0), |acc, (&w, y)| acc + w * y[i] * y[j]);
+                let cij =
+                    old * self.c[i][j] + self.c1 * self.pc[i] * self.pc[j] + self.cmu * rank_mu;
+                self.c[i][j] = cij;
+                self.c[j][i] = cij;
+            }
+        }
+
+        // Step-size adaptation
+        self.sigma =
+            self.sigma * ((self.cs / self.damps) * (ps_norm / self.chi_n - float!(1.0))).exp();
+    }
+
+    /// Number of generations considered by the stagnation criterion
+    fn history_len(&self) -> usize {
+        10 + (30 * self.mean.len()).div_ceil(self.lambda)
+    }
+
+    /// Checks whether the distribution has converged and the run should be stopped.
+    fn converged(&self, costs: &[F], tol_fun: F, tol_x: F) -> bool {
+        // Stagnation of the cost function values
+        if self.history.len() >= self.history_len() {
+            let (min, max) = self
+                .history
+                .iter()
+                .chain(costs.iter())
+                .fold((F::infinity(), F::neg_infinity()), |(min, max), &c| {
+                    (min.min(c), max.max(c))
+                });
+            if max - min < tol_fun {
+                return true;
+            }
+        }
+
+        // Negligible standard deviation in all coordinates
+        if self
+            .pc
+            .iter()
+            .zip(self.c.iter().enumerate())
+            .all(|(&pci, (i, row))| self.sigma * pci.abs().max(row[i].sqrt()) < tol_x)
+        {
+            return true;
+        }
+
+        // Ill-conditioned covariance matrix
+        let (dmin, dmax) = self
+            .d
+            .iter()
+            .fold((F::infinity(), F::neg_infinity()), |(min, max), &d| {
+                (min.min(d), max.max(d))
+            });
+        dmax > float!(1e7) * dmin
+    }
+}
+
+/// # Covariance Matrix Adaptation Evolution Strategy (CMA-ES)
+///
+/// In each iteration, `lambda` candidate solutions are sampled from a multivariate normal
+/// distribution. The mean of the distribution moves towards the weighted mean of the `lambda / 2`
+/// best candidates. The step size is adapted via cumulative step-size adaptation and the
+/// covariance matrix is learned via rank-one and rank-mu updates. This makes the method invariant
+/// to rotations and scaling of the search space and suitable for non-separable and badly
+/// conditioned problems. Default strategy parameters are chosen as recommended in \[0\].
+///
+/// The initial mean and the initial step size `sigma` are passed to [`CMAES::new`]. `sigma` should
+/// be about a third of the size of the region in which the optimum is expected. The population
+/// size `lambda` defaults to `4 + floor(3 ln(n))`, where `n` is the number of parameters, and can
+/// be changed with [`with_population_size`](`CMAES::with_population_size`).
+///
+/// A run is stopped once the range of the best cost function values of the recent generations is
+/// below [`with_tolerance_fun`](`CMAES::with_tolerance_fun`), the standard deviations in all
+/// coordinates are below [`with_tolerance_x`](`CMAES::with_tolerance_x`) or the covariance matrix
+/// is ill-conditioned (condition number above `1e14`). Depending on the
+/// [`RestartStrategy`] set with [`with_restarts`](`CMAES::with_restarts`), the algorithm is then
+/// restarted from the initial mean (IPOP \[1\] and BIPOP \[2\]) or terminates.
+///
+/// Candidate solutions are evaluated with [`Problem::bulk_cost`]. The `rayon` feature enables
+/// parallel computation of the cost function. This can be beneficial for expensive cost
+/// functions, but may cause a drop in performance for cheap cost functions. Be sure to benchmark
+/// both parallel and sequential computation.
+///
+/// Parameter vectors need to be convertible from `Vec<F>` and iterable by reference. The
+/// population in the state holds the candidate solutions of the last generation sorted by their
+/// cost function value, and the individual is the best candidate of the last generation.
+///
+/// ## Requirements on the optimization problem
+///
+/// The optimization problem is required to implement [`CostFunction`].
+///
+/// ## References
+///
+/// \[0\] Nikolaus Hansen (2016). The CMA Evolution Strategy: A Tutorial.
+/// <https://arxiv.org/abs/1604.00772>
+///
+/// \[1\] Anne Auger and Nikolaus Hansen (2005). A Restart CMA Evolution Strategy With Increasing
+/// Population Size. 2005 IEEE Congress on Evolutionary Computation.
+/// <https://doi.org/10.1109/CEC.2005.1554902>
+///
+/// \[2\] Nikolaus Hansen (2009). Benchmarking a BI-Population CMA-ES on the BBOB-2009 Function
+/// Testbed. GECCO '09. <https://doi.org/10.1145/1570256.1570333>
+#[derive(Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct CMAES<P, F, R> {
+    /// Initial mean
+    initial_mean: P,
+    /// Initial step size
+    sigma0: F,
+    /// Population size of the first run
+    default_lambda: usize,
+    /// Tolerance on the range of cost function values
+    tol_fun: F,
+    /// Tolerance on the standard deviation
+    tol_x: F,
+    /// Restart strategy
+    restarts: RestartStrategy,
+    /// Number of restarts performed so far
+    num_restarts: usize,
+    /// Number of large population runs performed so far (BIPOP)
+    num_large_runs: usize,
+    /// Cost function evaluations spent in large population runs (BIPOP)
+    evals_large: u64,
+    /// Cost function evaluations spent in small population runs (BIPOP)
+    evals_small: u64,
+    /// Whether the current run uses a small population (BIPOP)
+    small_run: bool,
+    /// Search distribution of the current run
+    distribution: Option<Distribution<F>>,
+    /// Random number generator
+    rng: R,
+}
+
+impl<P, F> CMAES<P, F, Xoshiro256PlusPlus>
+where
+    for<'a> &'a P: IntoIterator<Item = &'a F>,
+    F: ArgminFloat,
+{
+    /// Construct a new instance of [`CMAES`]
+    ///
+    /// Takes the initial mean of the search distribution and the initial step size as inputs.
+    /// `sigma` must be larger than zero.
+    ///
+    /// Uses the `Xoshiro256PlusPlus` RNG internally. For use of another RNG, consider using
+    /// [`CMAES::new_with_rng`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::cmaes::CMAES;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// let cmaes = CMAES::new(vec![1.0f64, 2.0], 0.5)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(initial_mean: P, sigma: F) -> Result<Self, Error> {
+        CMAES::new_with_rng(initial_mean, sigma, Xoshiro256PlusPlus::from_entropy())
+    }
+}
+
+impl<P, F, R> CMAES<P, F, R>
+where
+    for<'a> &'a P: IntoIterator<Item = &'a F>,
+    F: ArgminFloat,
+{
+    /// Construct a new instance of [`CMAES`]
+    ///
+    /// Takes the initial mean of the search distribution and the initial step size as inputs.
+    /// `sigma` must be larger than zero.
+    /// Requires a RNG which must implement `rand::Rng` (and `serde::Serialize` if the `serde1`
+    /// feature is enabled).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::cmaes::CMAES;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// # let my_rng = ();
+    /// let cmaes = CMAES::new_with_rng(vec![1.0f64, 2.0], 0.5, my_rng)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_with_rng(initial_mean: P, sigma: F, rng: R) -> Result<Self, Error> {
+        let n = (&initial_mean).into_iter().count();
+        if n == 0 {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`CMAES`: initial mean must not be empty."
+            ));
+        }
+        if sigma <= float!(0.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`CMAES`: initial step size must be > 0."
+            ));
+        }
+        Ok(CMAES {
+            initial_mean,
+            sigma0: sigma,
+            default_lambda: 4 + (3.0 * (n as f64).ln()).floor() as usize,
+            tol_fun: float!(1e-12),
+            tol_x: float!(1e-12),
+            restarts: RestartStrategy::None,
+            num_restarts: 0,
+            num_large_runs: 0,
+            evals_large: 0,
+            evals_small: 0,
+            small_run: false,
+            distribution: None,
+            rng,
+        })
+    }
+
+    /// Set population size `lambda`
+    ///
+    /// Must be at least 2. Defaults to `4 + floor(3 ln(n))`, where `n` is the number of
+    /// parameters. With restarts enabled, this is the population size of the first run.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::cmaes::CMAES;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// let cmaes = CMAES::new(vec![1.0f64, 2.0], 0.5)?.with_population_size(20)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_population_size(mut self, lambda: usize) -> Result<Self, Error> {
+        if lambda < 2 {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`CMAES`: population size must be >= 2."
+            ));
+        }
+        self.default_lambda = lambda;
+        Ok(self)
+    }
+
+    /// Set tolerance on the range of the best cost function values of the recent generations
+    ///
+    /// Must be non-negative. Defaults to `1e-12`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::cmaes::CMAES;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// let cmaes = CMAES::new(vec![1.0f64, 2.0], 0.5)?.with_tolerance_fun(1e-8)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_tolerance_fun(mut self, tol_fun: F) -> Result<Self, Error> {
+        if tol_fun < float!(0.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`CMAES`: tol_fun must be >= 0."
+            ));
+        }
+        self.tol_fun = tol_fun;
+        Ok(self)
+    }
+
+    /// Set tolerance on the standard deviation of the search distribution in all coordinates
+    ///
+    /// Must be non-negative. Defaults to `1e-12`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::cmaes::CMAES;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// let cmaes = CMAES::new(vec![1.0f64, 2.0], 0.5)?.with_tolerance_x(1e-8)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_tolerance_x(mut self, tol_x: F) -> Result<Self, Error> {
+        if tol_x < float!(0.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`CMAES`: tol_x must be >= 0."
+            ));
+        }
+        self.tol_x = tol_x;
+        Ok(self)
+    }
+
+    /// Set restart strategy
+    ///
+    /// Defaults to [`RestartStrategy::None`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::cmaes::{CMAES, RestartStrategy};
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// let cmaes = CMAES::new(vec![1.0f64, 2.0], 0.5)?.with_restarts(RestartStrategy::Ipop(5));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_restarts(mut self, restarts: RestartStrategy) -> Self {
+        self.restarts = restarts;
+        self
+    }
+}
+
+impl<P, F, R> CMAES<P, F, R>
+where
+    for<'a> &'a P: IntoIterator<Item = &'a F>,
+    F: ArgminFloat,
+    R: Rng,
+{
+    /// Draws a sample from the standard normal distribution (Box-Muller transform)
+    fn standard_normal(&mut self) -> F {
+        let u1: f64 = 1.0 - self.rng.gen::<f64>();
+        let u2: f64 = self.rng.gen();
+        float!((-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos())
+    }
+
+    /// Starts a new run. Returns `false` if no restarts are left.
+    fn restart(&mut self) -> bool {
+        let max_restarts = match self.restarts {
+            RestartStrategy::None => return false,
+            RestartStrategy::Ipop(max) | RestartStrategy::Bipop(max) => max,
+        };
+        if self.num_restarts >= max_restarts {
+            return false;
+        }
+        self.num_restarts += 1;
+
+        let mean: Vec<F> = (&self.initial_mean).into_iter().copied().collect();
+        let (lambda, sigma) = match self.restarts {
+            RestartStrategy::Bipop(_) if self.evals_small < self.evals_large => {
+                // Small population with random size and step size
+                self.small_run = true;
+                let large_lambda = self.default_lambda * 2usize.pow(self.num_large_runs as u32);
+                let u1: f64 = self.rng.gen();
+                let u2: f64 = self.rng.gen();
+                let lambda = (self.default_lambda as f64
+                    * (0.5 * large_lambda as f64 / self.default_lambda as f64).powf(u1 * u1))
+                .floor() as usize;
+                (lambda.max(2), self.sigma0 * float!(10.0f64.powf(-2.0 * u2)))
+            }
+            RestartStrategy::Bipop(_) => {
+                self.small_run = false;
+                self.num_large_runs += 1;
+                (
+                    self.default_lambda * 2usize.pow(self.num_large_runs as u32),
+                    self.sigma0,
+                )
+            }
+            _ => (
+                self.default_lambda * 2usize.pow(self.num_restarts as u32),
+                self.sigma0,
+            ),
+        };
+        self.distribution = Some(Distribution::new(mean, sigma, lambda));
+        true
+    }
+}
+
+impl<O, P, F, R> Solver<O, PopulationState<P, F>> for CMAES<P, F, R>
+where
+    O: CostFunction<Param = P, Output = F> + SyncAlias,
+    P: Clone + SerializeAlias + SyncAlias + From<Vec<F>>,
+    for<'a> &'a P: IntoIterator<Item = &'a F>,
+    F: ArgminFloat,
+    R: Rng + SerializeAlias,
+{
+    const NAME: &'static str = "CMA-ES";
+
+    fn init(
+        &mut self,
+        problem: &mut Problem<O>,
+        state: PopulationState<P, F>,
+    ) -> Result<(PopulationState<P, F>, Option<KV>), Error> {
+        let mean: Vec<F> = (&self.initial_mean).into_iter().copied().collect();
+        self.distribution = Some(Distribution::new(mean, self.sigma0, self.default_lambda));
+        self.num_restarts = 0;
+        self.num_large_runs = 0;
+        self.evals_large = 0;
+        self.evals_small = 0;
+        self.small_run = false;
+
+        let cost = problem.cost(&self.initial_mean)?;
+        Ok((state.individual(self.initial_mean.clone()).cost(cost), None))
+    }
+
+    fn next_iter(
+        &mut self,
+        problem: &mut Problem<O>,
+        mut state: PopulationState<P, F>,
+    ) -> Result<(PopulationState<P, F>, Option<KV>), Error> {
+        let mut dist = self.distribution.take().ok_or_else(argmin_error_closure!(
+            PotentialBug,
+            "`CMAES`: Search distribution not initialized."
+        ))?;
+        dist.update_eigendecomposition();
+        let n = dist.mean.len();
+
+        // Sample offspring x = mean + sigma * B D z with z ~ N(0, I)
+        let steps: Vec<Vec<F>> = (0..dist.lambda)
+            .map(|_| {
+                let z: Vec<F> = (0..n).map(|_| self.standard_normal()).collect();
+                dist.transform(&z)
+            })
+            .collect();
+        let candidates: Vec<P> = steps
+            .iter()
+            .map(|y| {
+                P::from(
+                    dist.mean
+                        .iter()
+                        .zip(y.iter())
+                        .map(|(&m, &yi)| m + dist.sigma * yi)
+                        .collect(),
+                )
+            })
+            .collect();
+
+        let costs = problem.bulk_cost(&candidates)?;
+
+        let mut order: Vec<usize> = (0..dist.lambda).collect();
+        order.sort_by(|&a, &b| cmp_nan_last(&costs[a], &costs[b]));
+        let sorted_steps: Vec<&Vec<F>> = order.iter().map(|&i| &steps[i]).collect();
+        dist.update(&sorted_steps);
+
+        let sorted_costs: Vec<F> = order.iter().map(|&i| costs[i]).collect();
+        let best_cost = sorted_costs[0];
+        dist.history.push_back(best_cost);
+        if dist.history.len() > dist.history_len() {
+            dist.history.pop_front();
+        }
+
+        if self.small_run {
+            self.evals_small += dist.lambda as u64;
+        } else {
+            self.evals_large += dist.lambda as u64;
+        }
+
+        let kv = kv!(
+            "sigma" => dist.sigma;
+            "population_size" => dist.lambda as u64;
+            "restarts" => self.num_restarts as u64;
+        );
+
+        let converged = dist.converged(&sorted_costs, self.tol_fun, self.tol_x);
+        self.distribution = Some(dist);
+        if converged && !self.restart() {
+            state = state.terminate_with(TerminationReason::SolverConverged);
+        }
+
+        let mut candidates: Vec<Option<P>> = candidates.into_iter().map(Some).collect();
+        let population: Vec<P> = order.iter().filter_map(|&i| candidates[i].take()).collect();
+
+        Ok((
+            state
+                .individual(population[0].clone())
+                .cost(best_cost)
+                .population(population),
+            Some(kv),
+        ))
+    }
+}
+
+/// Eigendecomposition of a symmetric matrix via the cyclic Jacobi method. Returns the eigenvalues
+/// and a matrix whose columns are the corresponding eigenvectors.
+fn symmetric_eigen<F: ArgminFloat>(mut a: Vec<Vec<F>>) -> (Vec<F>, Vec<Vec<F>>) {
+    let n = a.len();
+    let mut v = identity(n);
+    for _ in 0..100 {
+        let off = (0..n).fold(float!(0.0), |acc: F, i| {
+            (0..n)
+                .filter(|&j| j != i)
+                .fold(acc, |acc, j| acc + a[i][j] * a[i][j])
+        });
+        let total = (0..n).fold(off, |acc, i| acc + a[i][i] * a[i][i]);
+        if off <= F::epsilon() * F::epsilon() * total {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                if a[p][q] == float!(0.0) {
+                    continue;
+                }
+                // Rotation which annihilates a[p][q]
+                let theta = (a[q][q] - a[p][p]) / (float!(2.0) * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + float!(1.0)).sqrt());
+                let c = float!(1.0) / (t * t + float!(1.0)).sqrt();
+                let s = t * c;
+                for row in a.iter_mut() {
+                    let (akp, akq) = (row[p], row[q]);
+                    row[p] = c * akp - s * akq;
+                    row[q] = s * akp + c * akq;
+                }
+                let (head, tail) = a.split_at_mut(q);
+                for (apk, aqk) in head[p].iter_mut().zip(tail[0].iter_mut()) {
+                    let (vp, vq) = (*apk, *aqk);
+                    *apk = c * vp - s * vq;
+                    *aqk = s * vp + c * vq;
+                }
+                for row in v.iter_mut() {
+                    let (vkp, vkq) = (row[p], row[q]);
+                    row[p] = c * vkp - s * vkq;
+                    row[q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+    ((0..n).map(|i| a[i][i]).collect(), v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ArgminError, Executor, TerminationStatus};
+    use approx::assert_relative_eq;
+
+    test_trait_impl!(cmaes, CMAES<Vec<f64>, f64, Xoshiro256PlusPlus>);
+
+    /// Rotated and badly scaled ellipsoid
+    #[derive(Clone)]
+    struct Ellipsoid {}
+
+    impl CostFunction for Ellipsoid {
+        type Param = Vec<f64>;
+        type Output = f64;
+
+        fn cost(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+            let n = p.len();
+            // Rotation by 45 degrees in consecutive pairs of coordinates makes the problem
+            // non-separable
+            Ok((0..n)
+                .map(|i| {
+                    let j = if i % 2 == 0 { i + 1 } else { i - 1 };
+                    let r = if j < n {
+                        (p[i] + p[j]) / 2f64.sqrt() * if i % 2 == 0 { 1.0 } else { -1.0 }
+                    } else {
+                        p[i]
+                    };
+                    1e6f64.powf(i as f64 / (n - 1) as f64) * r * r
+                })
+                .sum())
+        }
+    }
+
+    #[derive(Clone)]
+    struct Rosenbrock {}
+
+    impl CostFunction for Rosenbrock {
+        type Param = Vec<f64>;
+        type Output = f64;
+
+        fn cost(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+            Ok(p.windows(2)
+                .map(|w| 100.0 * (w[1] - w[0].powi(2)).powi(2) + (1.0 - w[0]).powi(2))
+                .sum())
+        }
+    }
+
+    fn seeded(mean: Vec<f64>, sigma: f64) -> CMAES<Vec<f64>, f64, Xoshiro256PlusPlus> {
+        CMAES::new_with_rng(mean, sigma, Xoshiro256PlusPlus::seed_from_u64(42)).unwrap()
+    }
+
+    #[test]
+    fn test_new() {
+        let cmaes: CMAES<Vec<f64>, f64, _> = CMAES::new(vec![1.0; 10], 0.5).unwrap();
+        assert_eq!(cmaes.initial_mean, vec![1.0; 10]);
+        assert_eq!(cmaes.sigma0.to_ne_bytes(), 0.5f64.to_ne_bytes());
+        assert_eq!(cmaes.default_lambda, 10);
+        assert_eq!(cmaes.tol_fun.to_ne_bytes(), 1e-12f64.to_ne_bytes());
+        assert_eq!(cmaes.tol_x.to_ne_bytes(), 1e-12f64.to_ne_bytes());
+        assert_eq!(cmaes.restarts, RestartStrategy::None);
+        assert_eq!(cmaes.num_restarts, 0);
+        assert!(cmaes.distribution.is_none());
+    }
+
+    #[test]
+    fn test_new_errors() {
+        assert_error!(
+            CMAES::<Vec<f64>, f64, _>::new(vec![], 0.5),
+            ArgminError,
+            "Invalid parameter: \"`CMAES`: initial mean must not be empty.\""
+        );
+        for sigma in [0.0, -1.0] {
+            assert_error!(
+                CMAES::<Vec<f64>, f64, _>::new(vec![1.0], sigma),
+                ArgminError,
+                "Invalid parameter: \"`CMAES`: initial step size must be > 0.\""
+            );
+        }
+    }
+
+    #[test]
+    fn test_builders() {
+        let cmaes = seeded(vec![1.0, 2.0], 0.5)
+            .with_population_size(20)
+            .unwrap()
+            .with_tolerance_fun(1e-6)
+            .unwrap()
+            .with_tolerance_x(1e-7)
+            .unwrap()
+            .with_restarts(RestartStrategy::Bipop(3));
+        assert_eq!(cmaes.default_lambda, 20);
+        assert_eq!(cmaes.tol_fun.to_ne_bytes(), 1e-6f64.to_ne_bytes());
+        assert_eq!(cmaes.tol_x.to_ne_bytes(), 1e-7f64.to_ne_bytes());
+        assert_eq!(cmaes.restarts, RestartStrategy::Bipop(3));
+
+        assert_error!(
+            seeded(vec![1.0], 0.5).with_population_size(1),
+            ArgminError,
+            "Invalid parameter: \"`CMAES`: population size must be >= 2.\""
+        );
+        assert_error!(
+            seeded(vec![1.0], 0.5).with_tolerance_fun(-1.0),
+            ArgminError,
+            "Invalid parameter: \"`CMAES`: tol_fun must be >= 0.\""
+        );
+        assert_error!(
+            seeded(vec![1.0], 0.5).with_tolerance_x(-1.0),
+            ArgminError,
+            "Invalid parameter: \"`CMAES`: tol_x must be >= 0.\""
+        );
+    }
+
+    #[test]
+    fn test_symmetric_eigen() {
+        let a = vec![
+            vec![4.0, 1.0, 2.0],
+            vec![1.0, 3.0, 0.5],
+            vec![2.0, 0.5, 5.0],
+        ];
+        let (e, v) = symmetric_eigen(a.clone());
+        for k in 0..3 {
+            for i in 0..3 {
+                let av: f64 = (0..3).map(|j| a[i][j] * v[j][k]).sum();
+                assert_relative_eq!(av, e[k] * v[i][k], epsilon = 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_init() {
+        let mut cmaes = seeded(vec![0.0, 0.0], 0.5);
+        let (state, kv) = cmaes
+            .init(&mut Problem::new(Rosenbrock {}), PopulationState::new())
+            .unwrap();
+        assert!(kv.is_none());
+        assert_eq!(state.individual.unwrap(), vec![0.0, 0.0]);
+        assert_relative_eq!(state.cost, 1.0);
+        assert_eq!(cmaes.distribution.unwrap().lambda, 6);
+    }
+
+    #[test]
+    fn test_next_iter() {
+        let mut cmaes = seeded(vec![0.0, 0.0], 0.5);
+        let mut problem = Problem::new(Rosenbrock {});
+        let (state, _) = cmaes.init(&mut problem, PopulationState::new()).unwrap();
+        let (state, kv) = cmaes.next_iter(&mut problem, state).unwrap();
+        let population = state.get_population().unwrap();
+        assert_eq!(population.len(), 6);
+        let costs: Vec<f64> = population
+            .iter()
+            .map(|p| Rosenbrock {}.cost(p).unwrap())
+            .collect();
+        assert!(costs.windows(2).all(|w| w[0] <= w[1]));
+        assert_relative_eq!(state.get_cost(), costs[0]);
+        assert_eq!(state.individual.as_ref().unwrap(), &population[0]);
+        assert_eq!(problem.counts["cost_count"], 7);
+        let kv = kv.unwrap();
+        assert_eq!(kv.get("population_size").unwrap().get_uint(), Some(6));
+        assert_eq!(kv.get("restarts").unwrap().get_uint(), Some(0));
+    }
+
+    #[test]
+    fn test_next_iter_nan() {
+        // Undefined for negative first parameters
+        #[derive(Clone)]
+        struct HalfSphere {}
+
+        impl CostFunction for HalfSphere {
+            type Param = Vec<f64>;
+            type Output = f64;
+
+            fn cost(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+                Ok(if p[0] < 0.0 {
+                    f64::NAN
+                } else {
+                    p.iter().map(|x| x * x).sum()
+                })
+            }
+        }
+
+        let mut cmaes = seeded(vec![0.0, 0.0], 0.5);
+        let mut problem = Problem::new(HalfSphere {});
+        let (mut state, _) = cmaes.init(&mut problem, PopulationState::new()).unwrap();
+        let mut nan_count = 0;
+        for _ in 0..10 {
+            (state, _) = cmaes.next_iter(&mut problem, state).unwrap();
+            let costs: Vec<f64> = state
+                .get_population()
+                .unwrap()
+                .iter()
+                .map(|p| HalfSphere {}.cost(p).unwrap())
+                .collect();
+            // NaN costs are ranked last
+            let defined = costs.iter().take_while(|c| !c.is_nan()).count();
+            assert!(defined > 0);
+            assert!(costs[defined..].iter().all(|c| c.is_nan()));
+            assert!(costs[..defined].windows(2).all(|w| w[0] <= w[1]));
+            assert_relative_eq!(state.get_cost(), costs[0]);
+            nan_count += costs.len() - defined;
+        }
+        assert!(nan_count > 0);
+    }
+
+    #[test]
+    fn test_rosenbrock() {
+        let res = Executor::new(Rosenbrock {}, seeded(vec![-1.2, 1.0, -1.2, 1.0], 0.5))
+            .configure(|state| state.max_iters(5000))
+            .run()
+            .unwrap();
+        assert_eq!(
+            res.state.termination_status,
+            TerminationStatus::Terminated(TerminationReason::SolverConverged)
+        );
+        assert!(res.state.get_best_cost() < 1e-10);
+        for xi in res.state.best_individual.unwrap() {
+            assert_relative_eq!(xi, 1.0, epsilon = 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_ellipsoid() {
+        let res = Executor::new(Ellipsoid {}, seeded(vec![1.0; 6], 1.0))
+            .configure(|state| state.max_iters(5000))
+            .run()
+            .unwrap();
+        assert!(res.state.get_best_cost() < 1e-10);
+        let cmaes = res.solver;
+        let dist = cmaes.distribution.unwrap();
+        // The covariance matrix has adapted to the scaling of the problem
+        let (dmin, dmax) = dist
+            .d
+            .iter()
+            .fold((f64::INFINITY, 0.0f64), |(a, b), &d| (a.min(d), b.max(d)));
+        assert!(dmax / dmin > 100.0);
+    }
+
+    #[test]
+    fn test_ipop() {
+        let cmaes = seeded(vec![1.0, 1.0], 0.5)
+            .with_tolerance_fun(1e-6)
+            .unwrap()
+            .with_restarts(RestartStrategy::Ipop(2));
+        let res = Executor::new(Rosenbrock {}, cmaes)
+            .configure(|state| state.max_iters(5000))
+            .run()
+            .unwrap();
+        assert_eq!(
+            res.state.termination_status,
+            TerminationStatus::Terminated(TerminationReason::SolverConverged)
+        );
+        let cmaes = res.solver;
+        assert_eq!(cmaes.num_restarts, 2);
+        assert_eq!(cmaes.distribution.unwrap().lambda, 24);
+    }
+
+    #[test]
+    fn test_bipop() {
+        let cmaes = seeded(vec![1.0, 1.0], 0.5)
+            .with_tolerance_fun(1e-6)
+            .unwrap()
+            .with_restarts(RestartStrategy::Bipop(4));
+        let res = Executor::new(Rosenbrock {}, cmaes)
+            .configure(|state| state.max_iters(5000))
+            .run()
+            .unwrap();
+        let cmaes = res.solver;
+        assert_eq!(cmaes.num_restarts, 4);
+        assert!(cmaes.num_large_runs >= 1);
+        assert!(cmaes.evals_small > 0);
+        assert!(cmaes.evals_large > 0);
+    }
+}
@@ -0,0 +1,855 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Differential Evolution (DE)
+//!
+//! Implementation of the differential evolution method as outlined in \[0\] with the classic
+//! mutation strategies described in \[1\].
+//!
+//! For details see [`DifferentialEvolution`].
+//!
+//! ## References
+//!
+//! \[0\] Rainer Storn and Kenneth Price (1997). Differential Evolution – A Simple and Efficient
+//! Heuristic for global Optimization over Continuous Spaces. Journal of Global Optimization 11,
+//! pp. 341-359. <https://doi.org/10.1023/A:1008202821328>
+//!
+//! \[1\] Swagatam Das and Ponnuthurai N. Suganthan (2011). Differential Evolution: A Survey of the
+//! State-of-the-Art. IEEE Transactions on Evolutionary Computation 15(1), pp. 4-31.
+//! <https://doi.org/10.1109/TEVC.2010.2059031>
+
+use crate::core::{
+    ArgminFloat, CostFunction, Error, PopulationState, Problem, SerializeAlias, Solver, SyncAlias,
+    KV,
+};
+use crate::solver::utils::cmp_nan_last;
+use argmin_math::{ArgminAdd, ArgminMinMax, ArgminMul, ArgminRandom, ArgminSub};
+use rand::{seq::index::sample, Rng, SeedableRng};
+use rand_xoshiro::Xoshiro256PlusPlus;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Mutation and crossover strategy of [`DifferentialEvolution`]
+///
+/// For each member `x_i` of the population, a mutant vector `v` is created from the difference of
+/// randomly chosen, distinct members `x_r1`, `x_r2` and `x_r3` (all different from `x_i`), scaled
+/// by the mutation factor `f`. `x_best` denotes the best member of the population.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub enum Strategy {
+    /// DE/rand/1/bin: `v = x_r1 + f * (x_r2 - x_r3)`
+    Rand1Bin,
+    /// DE/best/1/bin: `v = x_best + f * (x_r1 - x_r2)`
+    Best1Bin,
+    /// DE/current-to-best/1/bin: `v = x_i + f * (x_best - x_i) + f * (x_r1 - x_r2)`
+    CurrentToBest1Bin,
+}
+
+/// # Differential Evolution (DE)
+///
+/// Implementation of the differential evolution method as outlined in \[0\].
+///
+/// In each iteration, a mutant vector is created for every member of the population according to
+/// the chosen [`Strategy`] (default: [`Strategy::Rand1Bin`]). The trial vector takes each
+/// parameter from the mutant vector with the crossover probability and from the current member
+/// otherwise (binomial crossover), but at least one parameter is taken from the mutant vector.
+/// The trial vector is limited to the search window. A trial vector replaces
+/// the current member if its cost function value is not worse. Since only cost function values
+/// are compared, the method is suitable for discontinuous cost functions.
+///
+/// The mutation factor and the crossover probability can be adapted with
+/// [`with_mutation_factor`](`DifferentialEvolution::with_mutation_factor`) and
+/// [`with_crossover_probability`](`DifferentialEvolution::with_crossover_probability`),
+/// respectively.
+///
+/// The `rayon` feature enables parallel computation of the cost function. This can be beneficial
+/// for expensive cost functions, but may cause a drop in performance for cheap cost functions. Be
+/// sure to benchmark both parallel and sequential computation.
+///
+/// The initial population, the choice of the individuals a mutant is built from and the crossover
+/// all depend on the RNG of the solver. Seeding it via
+/// [`new_with_rng`](`DifferentialEvolution::new_with_rng`) yields the same population in each run.
+///
+/// Parameter vectors need to be convertible from `Vec<F>` and iterable by reference.
+///
+/// ## Requirements on the optimization problem
+///
+/// The optimization problem is required to implement [`CostFunction`].
+///
+/// ## References
+///
+/// \[0\] Rainer Storn and Kenneth Price (1997). Differential Evolution – A Simple and Efficient
+/// Heuristic for global Optimization over Continuous Spaces. Journal of Global Optimization 11,
+/// pp. 341-359. <https://doi.org/10.1023/A:1008202821328>
+///
+/// \[1\] Swagatam Das and Ponnuthurai N. Suganthan (2011). Differential Evolution: A Survey of the
+/// State-of-the-Art. IEEE Transactions on Evolutionary Computation 15(1), pp. 4-31.
+/// <https://doi.org/10.1109/TEVC.2010.2059031>
+#[derive(Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct DifferentialEvolution<P, F, R = Xoshiro256PlusPlus> {
+    /// Mutation factor (differential weight)
+    mutation_factor: F,
+    /// Crossover probability
+    crossover_probability: F,
+    /// Mutation and crossover strategy
+    strategy: Strategy,
+    /// Bounds on parameter space
+    bounds: (P, P),
+    /// Number of members of the population
+    population_size: usize,
+    /// Random number generator
+    rng: R,
+}
+
+impl<P, F> DifferentialEvolution<P, F, Xoshiro256PlusPlus>
+where
+    P: Clone
+        + SyncAlias
+        + ArgminAdd<P, P>
+        + ArgminSub<P, P>
+        + ArgminMul<F, P>
+        + ArgminRandom
+        + From<Vec<F>>,
+    for<'a> &'a P: IntoIterator<Item = &'a F>,
+    F: ArgminFloat,
+{
+    /// Construct a new instance of `DifferentialEvolution`
+    ///
+    /// Takes the bounds on the search space and the population size as inputs. `bounds` is a
+    /// tuple `(lower_bound, upper_bound)`, where `lower_bound` and `upper_bound` are of the same
+    /// type as the parameter vector (`P`) and of the same length as the problem as dimensions.
+    /// The bounds must not be empty and the population must consist of at least 4 members.
+    ///
+    /// Uses the `Xoshiro256PlusPlus` RNG internally. For use of another RNG, consider using
+    /// [`DifferentialEvolution::new_with_rng`].
+    ///
+    /// The mutation factor, the crossover probability and the strategy can be adapted with
+    /// [`with_mutation_factor`](`DifferentialEvolution::with_mutation_factor`),
+    /// [`with_crossover_probability`](`DifferentialEvolution::with_crossover_probability`) and
+    /// [`with_strategy`](`DifferentialEvolution::with_strategy`), respectively.
+    ///
+    /// The parameters default to:
+    ///
+    /// * mutation factor: `0.8`
+    /// * crossover probability: `0.9`
+    /// * strategy: [`Strategy::Rand1Bin`]
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::differentialevolution::DifferentialEvolution;
+    /// # let lower_bound: Vec<f64> = vec![-1.0, -1.0];
+    /// # let upper_bound: Vec<f64> = vec![1.0, 1.0];
+    /// let de: DifferentialEvolution<_, f64> =
+    ///     DifferentialEvolution::new((lower_bound, upper_bound), 40);
+    /// ```
+    pub fn new(bounds: (P, P), population_size: usize) -> Self {
+        DifferentialEvolution::new_with_rng(
+            bounds,
+            population_size,
+            Xoshiro256PlusPlus::from_entropy(),
+        )
+    }
+}
+
+impl<P, F, R> DifferentialEvolution<P, F, R>
+where
+    P: Clone
+        + SyncAlias
+        + ArgminAdd<P, P>
+        + ArgminSub<P, P>
+        + ArgminMul<F, P>
+        + ArgminRandom
+        + From<Vec<F>>,
+    for<'a> &'a P: IntoIterator<Item = &'a F>,
+    F: ArgminFloat,
+{
+    /// Construct a new instance of `DifferentialEvolution` with a given random number generator
+    ///
+    /// Same as [`DifferentialEvolution::new`], but requires a RNG which must implement
+    /// `rand::Rng` (and `serde::Serialize` if the `serde1` feature is enabled).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::differentialevolution::DifferentialEvolution;
+    /// use rand::SeedableRng;
+    /// use rand_xoshiro::Xoshiro256PlusPlus;
+    /// # let lower_bound: Vec<f64> = vec![-1.0, -1.0];
+    /// # let upper_bound: Vec<f64> = vec![1.0, 1.0];
+    /// let rng = Xoshiro256PlusPlus::seed_from_u64(42);
+    /// let de: DifferentialEvolution<_, f64, _> =
+    ///     DifferentialEvolution::new_with_rng((lower_bound, upper_bound), 40, rng);
+    /// ```
+    pub fn new_with_rng(bounds: (P, P), population_size: usize, rng: R) -> Self {
+        DifferentialEvolution {
+            mutation_factor: float!(0.8),
+            crossover_probability: float!(0.9),
+            strategy: Strategy::Rand1Bin,
+            bounds,
+            population_size,
+            rng,
+        }
+    }
+
+    /// Set mutation factor (differential weight)
+    ///
+    /// Must be in `(0, 2]`. Defaults to `0.8`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::differentialevolution::DifferentialEvolution;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// # let lower_bound: Vec<f64> = vec![-1.0, -1.0];
+    /// # let upper_bound: Vec<f64> = vec![1.0, 1.0];
+    /// let de: DifferentialEvolution<_, f64> =
+    ///     DifferentialEvolution::new((lower_bound, upper_bound), 40).with_mutation_factor(0.5)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_mutation_factor(mut self, factor: F) -> Result<Self, Error> {
+        if factor <= float!(0.0) || factor > float!(2.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`DifferentialEvolution`: mutation factor must be in (0, 2]."
+            ));
+        }
+        self.mutation_factor = factor;
+        Ok(self)
+    }
+
+    /// Set crossover probability
+    ///
+    /// Must be in `[0, 1]`. Defaults to `0.9`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::differentialevolution::DifferentialEvolution;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// # let lower_bound: Vec<f64> = vec![-1.0, -1.0];
+    /// # let upper_bound: Vec<f64> = vec![1.0, 1.0];
+    /// let de: DifferentialEvolution<_, f64> = DifferentialEvolution::new((lower_bound, upper_bound), 40)
+    ///     .with_crossover_probability(0.3)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_crossover_probability(mut self, probability: F) -> Result<Self, Error> {
+        if probability < float!(0.0) || probability > float!(1.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`DifferentialEvolution`: crossover probability must be in [0, 1]."
+            ));
+        }
+        self.crossover_probability = probability;
+        Ok(self)
+    }
+
+    /// Set mutation and crossover strategy
+    ///
+    /// Defaults to [`Strategy::Rand1Bin`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::differentialevolution::{DifferentialEvolution, Strategy};
+    /// # let lower_bound: Vec<f64> = vec![-1.0, -1.0];
+    /// # let upper_bound: Vec<f64> = vec![1.0, 1.0];
+    /// let de: DifferentialEvolution<_, f64> = DifferentialEvolution::new((lower_bound, upper_bound), 40)
+    ///     .with_strategy(Strategy::Best1Bin);
+    /// ```
+    #[must_use]
+    pub fn with_strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+}
+
+impl<P, F, R> DifferentialEvolution<P, F, R>
+where
+    P: Clone
+        + SyncAlias
+        + ArgminAdd<P, P>
+        + ArgminSub<P, P>
+        + ArgminMul<F, P>
+        + ArgminRandom
+        + From<Vec<F>>,
+    for<'a> &'a P: IntoIterator<Item = &'a F>,
+    F: ArgminFloat,
+    R: Rng,
+{
+    /// Initializes all members of the population randomly within the bounds
+    fn initialize_population<O: CostFunction<Param = P, Output = F> + SyncAlias>(
+        &mut self,
+        problem: &mut Problem<O>,
+    ) -> Result<Vec<Individual<P, F>>, Error> {
+        let (min, max) = &self.bounds;
+        let rng = &mut self.rng;
+        let positions: Vec<P> = (0..self.population_size)
+            .map(|_| P::rand_from_range(min, max, rng))
+            .collect();
+
+        let costs = problem.bulk_cost(&positions)?;
+
+        Ok(positions
+            .into_iter()
+            .zip(costs)
+            .map(|(p, c)| Individual::new(p, c))
+            .collect())
+    }
+
+    /// Computes the mutant vector for the member at `index`
+    fn mutate(&mut self, population: &[Individual<P, F>], index: usize, best: &P) -> P {
+        // Distinct members which differ from the current one
+        let r: Vec<&P> = sample(&mut self.rng, population.len() - 1, 3)
+            .into_iter()
+            .map(|i| &population[if i >= index { i + 1 } else { i }].position)
+            .collect();
+        let f = self.mutation_factor;
+        match self.strategy {
+            Strategy::Rand1Bin => r[0].add(&r[1].sub(r[2]).mul(&f)),
+            Strategy::Best1Bin => best.add(&r[0].sub(r[1]).mul(&f)),
+            Strategy::CurrentToBest1Bin => {
+                let current = &population[index].position;
+                current
+                    .add(&best.sub(current).mul(&f))
+                    .add(&r[0].sub(r[1]).mul(&f))
+            }
+        }
+    }
+
+    /// Binomial crossover: Each parameter is taken from `mutant` with the crossover probability
+    /// and from `target` otherwise. The parameter at a randomly chosen index is always taken from
+    /// `mutant`, such that the trial vector differs from `target`.
+    fn crossover(&mut self, target: &P, mutant: &P) -> P {
+        let j_rand = self.rng.gen_range(0..target.into_iter().count());
+        let probability = self.crossover_probability;
+        let rng = &mut self.rng;
+        target
+            .into_iter()
+            .zip(mutant)
+            .enumerate()
+            .map(|(j, (&t, &m))| {
+                if j == j_rand || float!(rng.gen::<f64>()) < probability {
+                    m
+                } else {
+                    t
+                }
+            })
+            .collect::<Vec<F>>()
+            .into()
+    }
+}
+
+impl<O, P, F, R> Solver<O, PopulationState<Individual<P, F>, F>> for DifferentialEvolution<P, F, R>
+where
+    O: CostFunction<Param = P, Output = F> + SyncAlias,
+    P: SerializeAlias
+        + Clone
+        + SyncAlias
+        + ArgminAdd<P, P>
+        + ArgminSub<P, P>
+        + ArgminMul<F, P>
+        + ArgminRandom
+        + ArgminMinMax
+        + From<Vec<F>>,
+    for<'a> &'a P: IntoIterator<Item = &'a F>,
+    F: ArgminFloat,
+    R: Rng + SerializeAlias,
+{
+    const NAME: &'static str = "Differential Evolution";
+
+    fn init(
+        &mut self,
+        problem: &mut Problem<O>,
+        mut state: PopulationState<Individual<P, F>, F>,
+    ) -> Result<(PopulationState<Individual<P, F>, F>, Option<KV>), Error> {
+        if self.population_size < 4 {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`DifferentialEvolution`: population size must be >= 4."
+            ));
+        }
+        let dim = self.bounds.0.into_iter().count();
+        if dim == 0 || self.bounds.1.into_iter().count() != dim {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`DifferentialEvolution`: bounds must be non-empty and of the same length."
+            ));
+        }
+
+        // Users can provide a population or it will be randomly created.
+        let population = match state.take_population() {
+            Some(population)
+                if population.len() == self.population_size
+                    && population
+                        .iter()
+                        .all(|m| (&m.position).into_iter().count() == dim) =>
+            {
+                population
+            }
+            Some(population) if population.len() == self.population_size => {
+                return Err(argmin_error!(
+                    InvalidParameter,
+                    format!(
+                        "`DifferentialEvolution`: Provided individuals must be of length {dim}"
+                    )
+                ))
+            }
+            Some(population) => {
+                return Err(argmin_error!(
+                    InvalidParameter,
+                    format!(
+                        "`DifferentialEvolution`: Provided population is of length {}, expected {}",
+                        population.len(),
+                        self.population_size
+                    )
+                ))
+            }
+            None => self.initialize_population(problem)?,
+        };
+
+        let best = best_individual(&population).clone();
+        Ok((
+            state
+                .cost(best.cost)
+                .individual(best)
+                .population(population),
+            None,
+        ))
+    }
+
+    /// Perform one iteration of algorithm
+    fn next_iter(
+        &mut self,
+        problem: &mut Problem<O>,
+        mut state: PopulationState<Individual<P, F>, F>,
+    ) -> Result<(PopulationState<Individual<P, F>, F>, Option<KV>), Error> {
+        let mut best = state.take_individual().ok_or_else(argmin_error_closure!(
+            PotentialBug,
+            "`DifferentialEvolution`: No current best individual in state."
+        ))?;
+        let mut population = state.take_population().ok_or_else(argmin_error_closure!(
+            PotentialBug,
+            "`DifferentialEvolution`: No population in state."
+        ))?;
+
+        let mut trials: Vec<P> = Vec::with_capacity(population.len());
+        for i in 0..population.len() {
+            let mutant = self.mutate(&population, i, &best.position);
+            let trial = self.crossover(&population[i].position, &mutant);
+            // Limit to search window
+            trials.push(P::min(&P::max(&trial, &self.bounds.0), &self.bounds.1));
+        }
+
+        let costs = problem.bulk_cost(&trials)?;
+
+        let mut replaced = 0u64;
+        for ((member, trial), cost) in population.iter_mut().zip(trials).zip(costs) {
+            if cmp_nan_last(&cost, &member.cost) != Ordering::Greater {
+                member.position = trial;
+                member.cost = cost;
+                replaced += 1;
+                if cmp_nan_last(&cost, &best.cost) == Ordering::Less {
+                    best = member.clone();
+                }
+            }
+        }
+
+        Ok((
+            state
+                .cost(best.cost)
+                .individual(best)
+                .population(population),
+            Some(kv!("replaced" => replaced;)),
+        ))
+    }
+}
+
+/// Returns the member of the population with the lowest cost function value (NaN is ranked last)
+fn best_individual<P, F: ArgminFloat>(population: &[Individual<P, F>]) -> &Individual<P, F> {
+    population
+        .iter()
+        .min_by(|a, b| cmp_nan_last(&a.cost, &b.cost))
+        .unwrap()
+}
+
+/// A single member of the population
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct Individual<T, F> {
+    /// Position of individual
+    pub position: T,
+    /// Cost of individual
+    pub cost: F,
+}
+
+impl<T, F> Individual<T, F> {
+    /// Create a new individual with a given position and cost.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::differentialevolution::Individual;
+    /// let individual: Individual<Vec<f64>, f64> = Individual::new(vec![0.0, 1.4], 12.0);
+    /// ```
+    pub fn new(position: T, cost: F) -> Individual<T, F> {
+        Individual { position, cost }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ArgminError, Executor, State};
+    use approx::assert_relative_eq;
+
+    test_trait_impl!(differentialevolution, DifferentialEvolution<Vec<f64>, f64>);
+
+    /// Discontinuous function with its minimum at `(1, -2)`
+    #[derive(Clone)]
+    struct StepSphere {}
+
+    impl CostFunction for StepSphere {
+        type Param = Vec<f64>;
+        type Output = f64;
+
+        fn cost(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+            let r2 = (p[0] - 1.0).powi(2) + (p[1] + 2.0).powi(2);
+            Ok(r2 + if r2 > 0.01 { 1.0 } else { 0.0 })
+        }
+    }
+
+    fn bounds() -> (Vec<f64>, Vec<f64>) {
+        (vec![-5.0, -5.0], vec![5.0, 5.0])
+    }
+
+    #[test]
+    fn test_new() {
+        let (lower_bound, upper_bound) = bounds();
+        let DifferentialEvolution {
+            mutation_factor,
+            crossover_probability,
+            strategy,
+            bounds,
+            population_size,
+            rng: _,
+        }: DifferentialEvolution<_, f64> =
+            DifferentialEvolution::new((lower_bound.clone(), upper_bound.clone()), 40);
+
+        assert_eq!(mutation_factor.to_ne_bytes(), 0.8f64.to_ne_bytes());
+        assert_eq!(crossover_probability.to_ne_bytes(), 0.9f64.to_ne_bytes());
+        assert_eq!(strategy, Strategy::Rand1Bin);
+        assert_eq!(bounds, (lower_bound, upper_bound));
+        assert_eq!(population_size, 40);
+    }
+
+    #[test]
+    fn test_with_mutation_factor() {
+        for factor in [f64::EPSILON, 0.5, 1.0, 2.0] {
+            let res = DifferentialEvolution::new(bounds(), 40).with_mutation_factor(factor);
+            assert_eq!(
+                res.unwrap().mutation_factor.to_ne_bytes(),
+                factor.to_ne_bytes()
+            );
+        }
+
+        for factor in [-1.0, 0.0, 2.0 + f64::EPSILON * 4.0, 3.0] {
+            let res = DifferentialEvolution::new(bounds(), 40).with_mutation_factor(factor);
+            assert_error!(
+                res,
+                ArgminError,
+                concat!(
+                    "Invalid parameter: \"`DifferentialEvolution`: ",
+                    "mutation factor must be in (0, 2].\""
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_with_crossover_probability() {
+        for probability in [0.0, 0.5, 1.0] {
+            let res =
+                DifferentialEvolution::new(bounds(), 40).with_crossover_probability(probability);
+            assert_eq!(
+                res.unwrap().crossover_probability.to_ne_bytes(),
+                probability.to_ne_bytes()
+            );
+        }
+
+        for probability in [-f64::EPSILON, 1.0 + f64::EPSILON, 2.0] {
+            let res =
+                DifferentialEvolution::new(bounds(), 40).with_crossover_probability(probability);
+            assert_error!(
+                res,
+                ArgminError,
+                concat!(
+                    "Invalid parameter: \"`DifferentialEvolution`: ",
+                    "crossover probability must be in [0, 1].\""
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_with_strategy() {
+        let de: DifferentialEvolution<_, f64> =
+            DifferentialEvolution::new(bounds(), 40).with_strategy(Strategy::CurrentToBest1Bin);
+        assert_eq!(de.strategy, Strategy::CurrentToBest1Bin);
+    }
+
+    #[test]
+    fn test_crossover() {
+        let target = vec![1.0, 2.0, 3.0];
+        let mutant = vec![-1.0, -2.0, -3.0];
+        let bounds = (vec![-5.0; 3], vec![5.0; 3]);
+
+        let mut de: DifferentialEvolution<_, f64> = DifferentialEvolution::new(bounds.clone(), 4)
+            .with_crossover_probability(1.0)
+            .unwrap();
+        assert_eq!(de.crossover(&target, &mutant), mutant);
+
+        let mut de: DifferentialEvolution<_, f64> = DifferentialEvolution::new(bounds.clone(), 4)
+            .with_crossover_probability(0.5)
+            .unwrap();
+        for _ in 0..10 {
+            let trial = de.crossover(&target, &mutant);
+            for i in 0..3 {
+                let t = trial[i].to_ne_bytes();
+                assert!(t == target[i].to_ne_bytes() || t == mutant[i].to_ne_bytes());
+            }
+        }
+
+        // At least one parameter is taken from the mutant vector
+        let mut de: DifferentialEvolution<_, f64> = DifferentialEvolution::new(bounds, 4)
+            .with_crossover_probability(0.0)
+            .unwrap();
+        for _ in 0..10 {
+            let trial = de.crossover(&target, &mutant);
+            let from_mutant = (0..3)
+                .filter(|&i| trial[i].to_ne_bytes() == mutant[i].to_ne_bytes())
+                .count();
+            assert_eq!(from_mutant, 1);
+        }
+    }
+
+    #[test]
+    fn test_crossover_infinite_bounds() {
+        let bounds = (vec![f64::NEG_INFINITY; 3], vec![f64::INFINITY; 3]);
+        let mut de: DifferentialEvolution<_, f64> = DifferentialEvolution::new(bounds, 4);
+        for _ in 0..10 {
+            let trial = de.crossover(&vec![1.0, 2.0, 3.0], &vec![-1.0, -2.0, -3.0]);
+            assert!(trial.iter().all(|x| x.is_finite()));
+        }
+    }
+
+    #[test]
+    fn test_init_errors() {
+        let mut de: DifferentialEvolution<_, f64> = DifferentialEvolution::new(bounds(), 3);
+        assert_error!(
+            de.init(&mut Problem::new(StepSphere {}), PopulationState::new()),
+            ArgminError,
+            "Invalid parameter: \"`DifferentialEvolution`: population size must be >= 4.\""
+        );
+
+        let mut de: DifferentialEvolution<_, f64> = DifferentialEvolution::new(bounds(), 5);
+        let population = vec![Individual::new(vec![0.0, 0.0], 5.0); 4];
+        assert_error!(
+            de.init(
+                &mut Problem::new(StepSphere {}),
+                PopulationState::new().population(population)
+            ),
+            ArgminError,
+            concat!(
+                "Invalid parameter: \"`DifferentialEvolution`: ",
+                "Provided population is of length 4, expected 5\""
+            )
+        );
+
+        let population = vec![Individual::new(vec![0.0], 5.0); 5];
+        assert_error!(
+            de.init(
+                &mut Problem::new(StepSphere {}),
+                PopulationState::new().population(population)
+            ),
+            ArgminError,
+            concat!(
+                "Invalid parameter: \"`DifferentialEvolution`: ",
+                "Provided individuals must be of length 2\""
+            )
+        );
+
+        for bounds in [(vec![], vec![]), (vec![-1.0, -1.0], vec![1.0])] {
+            let mut de: DifferentialEvolution<_, f64> = DifferentialEvolution::new(bounds, 5);
+            assert_error!(
+                de.init(&mut Problem::new(StepSphere {}), PopulationState::new()),
+                ArgminError,
+                concat!(
+                    "Invalid parameter: \"`DifferentialEvolution`: ",
+                    "bounds must be non-empty and of the same length.\""
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_best_individual_nan() {
+        let population = vec![
+            Individual::new(vec![0.0], f64::NAN),
+            Individual::new(vec![1.0], 2.0),
+            Individual::new(vec![2.0], f64::NAN),
+            Individual::new(vec![3.0], 1.0),
+        ];
+        assert_eq!(best_individual(&population).position, vec![3.0]);
+        let population = vec![Individual::new(vec![0.0], f64::NAN); 4];
+        assert!(best_individual(&population).cost.is_nan());
+
+        // Members with NaN cost are replaced by any trial vector
+        let mut de: DifferentialEvolution<_, f64> = DifferentialEvolution::new(bounds(), 4);
+        let mut problem = Problem::new(StepSphere {});
+        let population = (0..4)
+            .map(|i| Individual::new(vec![f64::from(i), 0.0], f64::NAN))
+            .collect();
+        let (state, _) = de
+            .init(&mut problem, PopulationState::new().population(population))
+            .unwrap();
+        let (state, kv) = de.next_iter(&mut problem, state).unwrap();
+        assert!(state.get_cost().is_finite());
+        assert_eq!(kv.unwrap().get("replaced").unwrap().get_uint(), Some(4));
+    }
+
+    #[test]
+    fn test_init() {
+        let mut de: DifferentialEvolution<_, f64> = DifferentialEvolution::new(bounds(), 20);
+        let mut problem = Problem::new(StepSphere {});
+        let (mut state, kv) = de.init(&mut problem, PopulationState::new()).unwrap();
+        assert!(kv.is_none());
+        assert_eq!(problem.counts["cost_count"], 20);
+
+        let best = state.take_individual().unwrap();
+        let population = state.take_population().unwrap();
+        assert_eq!(population.len(), 20);
+        for member in population.iter() {
+            for &x in member.position.iter() {
+                assert!((-5.0..=5.0).contains(&x));
+            }
+            assert_relative_eq!(member.cost, StepSphere {}.cost(&member.position).unwrap());
+            assert!(best.cost <= member.cost);
+        }
+        assert_eq!(state.get_cost().to_ne_bytes(), best.cost.to_ne_bytes());
+    }
+
+    #[test]
+    fn test_next_iter_never_worse() {
+        let mut de: DifferentialEvolution<_, f64> = DifferentialEvolution::new(bounds(), 10);
+        let mut problem = Problem::new(StepSphere {});
+        let (state, _) = de.init(&mut problem, PopulationState::new()).unwrap();
+        let before = state.get_population().unwrap().clone();
+        let (state, kv) = de.next_iter(&mut problem, state).unwrap();
+        let after = state.get_population().unwrap();
+        assert_eq!(problem.counts["cost_count"], 20);
+        let mut replaced = 0;
+        for (b, a) in before.iter().zip(after.iter()) {
+            assert!(a.cost <= b.cost);
+            if a.position != b.position {
+                replaced += 1;
+            }
+        }
+        assert!(kv.unwrap().get("replaced").unwrap().get_uint().unwrap() >= replaced);
+    }
+
+    #[test]
+    fn test_strategies() {
+        for strategy in [
+            Strategy::Rand1Bin,
+            Strategy::Best1Bin,
+            Strategy::CurrentToBest1Bin,
+        ] {
+            let solver = DifferentialEvolution::new(bounds(), 30).with_strategy(strategy);
+            let res = Executor::new(StepSphere {}, solver)
+                .configure(|state| state.max_iters(300))
+                .run()
+                .unwrap();
+            let best = res.state.get_best_param().unwrap();
+            assert_relative_eq!(best.position[0], 1.0, epsilon = 1e-4);
+            assert_relative_eq!(best.position[1], -2.0, epsilon = 1e-4);
+        }
+    }
+
+    /// Returns the bit patterns of positions and costs of all members of the population
+    fn population_bits(state: &PopulationState<Individual<Vec<f64>, f64>, f64>) -> Vec<u64> {
+        state
+            .get_population()
+            .unwrap()
+            .iter()
+            .flat_map(|m| m.position.iter().chain(std::iter::once(&m.cost)))
+            .map(|x| x.to_bits())
+            .collect()
+    }
+
+    #[test]
+    fn test_seeded_population() {
+        let run = |seed: u64| {
+            let solver = DifferentialEvolution::new_with_rng(
+                bounds(),
+                10,
+                Xoshiro256PlusPlus::seed_from_u64(seed),
+            );
+            let res = Executor::new(StepSphere {}, solver)
+                .configure(|state| state.max_iters(20))
+                .run()
+                .unwrap();
+            population_bits(&res.state)
+        };
+        assert_eq!(run(42), run(42));
+        assert_ne!(run(42), run(43));
+    }
+
+    #[test]
+    #[cfg(feature = "serde1")]
+    fn test_checkpoint_resume() {
+        use crate::core::checkpointing::{Checkpoint, CheckpointingFrequency, FileCheckpoint};
+
+        type DE = DifferentialEvolution<Vec<f64>, f64>;
+        type DEState = PopulationState<Individual<Vec<f64>, f64>, f64>;
+
+        let mut problem = Problem::new(StepSphere {});
+        let solver = || DE::new_with_rng(bounds(), 10, Xoshiro256PlusPlus::seed_from_u64(42));
+
+        // Uninterrupted run
+        let mut de = solver();
+        let (mut expected, _) = de.init(&mut problem, PopulationState::new()).unwrap();
+        for _ in 0..10 {
+            (expected, _) = de.next_iter(&mut problem, expected).unwrap();
+        }
+
+        // Interrupted after 5 iterations and resumed from a checkpoint
+        let mut de = solver();
+        let (mut state, _) = de.init(&mut problem, PopulationState::new()).unwrap();
+        for _ in 0..5 {
+            (state, _) = de.next_iter(&mut problem, state).unwrap();
+        }
+        let dir = std::env::temp_dir().join("argmin_test_de_checkpoint");
+        let _ = std::fs::remove_dir_all(&dir);
+        let check =
+            FileCheckpoint::new(dir.to_str().unwrap(), "de", CheckpointingFrequency::Always);
+        check.save(&de, &state).unwrap();
+        let (mut de, mut state): (DE, DEState) = check.load().unwrap().unwrap();
+        for _ in 0..5 {
+            (state, _) = de.next_iter(&mut problem, state).unwrap();
+        }
+
+        assert_eq!(population_bits(&state), population_bits(&expected));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
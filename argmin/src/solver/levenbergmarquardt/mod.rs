@@ -0,0 +1,611 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Levenberg-Marquardt method
+//!
+//! [`LevenbergMarquardt`] solves nonlinear least squares problems.
+//!
+//! ## References
+//!
+//! Kaj Madsen, Hans Bruun Nielsen and Ole Tingleff (2004). Methods for Non-Linear Least Squares
+//! Problems. Informatics and Mathematical Modelling, Technical University of Denmark.
+//!
+//! Jorge J. Moré (1978). The Levenberg-Marquardt algorithm: Implementation and theory.
+//! Numerical Analysis, Lecture Notes in Mathematics 630, pp. 105-116. Springer.
+//! <https://doi.org/10.1007/BFb0067700>
+
+use crate::core::{
+    ArgminFloat, Error, IterState, Jacobian, Operator, Problem, Solver, State, TerminationReason,
+    TerminationStatus, KV,
+};
+use argmin_math::{
+    ArgminAdd, ArgminDot, ArgminEye, ArgminInv, ArgminL2Norm, ArgminMul, ArgminSub, ArgminTranspose,
+};
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// # Levenberg-Marquardt method
+///
+/// The Levenberg-Marquardt method solves nonlinear least squares problems
+///
+/// ```text
+/// minimize 1/2 ||r(x)||^2
+/// ```
+///
+/// where `r` are the residuals given by [`Operator::apply`]. In each iteration, the damped
+/// Gauss-Newton system
+///
+/// ```text
+/// (J^T J + lambda D) h = -J^T r
+/// ```
+///
+/// is solved for a trial step `h`. The damping parameter `lambda` interpolates between the
+/// Gauss-Newton method (small `lambda`) and steepest descent (large `lambda`), which makes the
+/// method robust for ill-conditioned and rank-deficient Jacobians. The trial step is accepted if
+/// it decreases the cost; afterwards `lambda` is adapted based on the ratio of actual to predicted
+/// cost reduction. Rejected steps leave the parameter vector unchanged and increase `lambda`.
+///
+/// With Marquardt scaling (default), `D` is the diagonal of `J^T J`, which makes the method
+/// invariant to the scaling of the parameters. Otherwise, `D` is the identity matrix.
+///
+/// The cost is computed as half the squared norm of the residuals. The gradient `J^T r` and the
+/// Jacobian of the current parameter vector are stored in the state.
+///
+/// The method terminates if either the norm of the gradient, the norm of the step relative to the
+/// norm of the parameter vector, or the relative cost reduction of an accepted step falls below
+/// the respective tolerance.
+///
+/// Requires an initial parameter vector.
+///
+/// ## Requirements on the optimization problem
+///
+/// The optimization problem is required to implement [`Operator`] and [`Jacobian`].
+///
+/// ## References
+///
+/// Kaj Madsen, Hans Bruun Nielsen and Ole Tingleff (2004). Methods for Non-Linear Least Squares
+/// Problems. Informatics and Mathematical Modelling, Technical University of Denmark.
+///
+/// Jorge J. Moré (1978). The Levenberg-Marquardt algorithm: Implementation and theory.
+/// Numerical Analysis, Lecture Notes in Mathematics 630, pp. 105-116. Springer.
+/// <https://doi.org/10.1007/BFb0067700>
+#[derive(Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct LevenbergMarquardt<F> {
+    /// Damping parameter
+    lambda: F,
+    /// Factor by which `lambda` is increased after a rejected step
+    nu: F,
+    /// Scale damping by the diagonal of `J^T J`
+    marquardt_scaling: bool,
+    /// Tolerance on the norm of the gradient
+    tol_grad: F,
+    /// Tolerance on the norm of the step relative to the norm of the parameter vector
+    tol_step: F,
+    /// Tolerance on the relative cost reduction of accepted steps
+    tol_cost: F,
+    /// Norm of the last trial step
+    step_norm: F,
+    /// Relative cost reduction of the last step (`None` if it was rejected)
+    cost_reduction: Option<F>,
+}
+
+impl<F: ArgminFloat> LevenbergMarquardt<F> {
+    /// Construct a new instance of [`LevenbergMarquardt`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::levenbergmarquardt::LevenbergMarquardt;
+    /// let lm: LevenbergMarquardt<f64> = LevenbergMarquardt::new();
+    /// ```
+    pub fn new() -> Self {
+        LevenbergMarquardt {
+            lambda: float!(1e-3),
+            nu: float!(2.0),
+            marquardt_scaling: true,
+            tol_grad: F::epsilon().sqrt(),
+            tol_step: F::epsilon().sqrt(),
+            tol_cost: F::epsilon().sqrt(),
+            step_norm: F::infinity(),
+            cost_reduction: None,
+        }
+    }
+
+    /// Set initial damping parameter.
+    ///
+    /// Must be larger than zero and defaults to `1e-3`. With Marquardt scaling, the damping is
+    /// relative to the diagonal of `J^T J`, otherwise it is absolute.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::levenbergmarquardt::LevenbergMarquardt;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// let lm = LevenbergMarquardt::new().with_initial_damping(1e-2f64)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_initial_damping(mut self, lambda: F) -> Result<Self, Error> {
+        if lambda <= float!(0.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`LevenbergMarquardt`: initial damping must be > 0."
+            ));
+        }
+        self.lambda = lambda;
+        Ok(self)
+    }
+
+    /// Enable or disable Marquardt scaling.
+    ///
+    /// If enabled (default), the damping term is scaled by the diagonal of `J^T J`. Otherwise the
+    /// identity matrix is used (Levenberg's original method).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::levenbergmarquardt::LevenbergMarquardt;
+    /// let lm: LevenbergMarquardt<f64> = LevenbergMarquardt::new().with_marquardt_scaling(false);
+    /// ```
+    #[must_use]
+    pub fn with_marquardt_scaling(mut self, marquardt_scaling: bool) -> Self {
+        self.marquardt_scaling = marquardt_scaling;
+        self
+    }
+
+    /// Set tolerance on the norm of the gradient.
+    ///
+    /// Must be non-negative and defaults to `sqrt(EPSILON)`. A tolerance of zero disables this
+    /// stopping criterion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::levenbergmarquardt::LevenbergMarquardt;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// let lm = LevenbergMarquardt::new().with_tolerance_grad(1e-10f64)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_tolerance_grad(mut self, tol: F) -> Result<Self, Error> {
+        if tol < float!(0.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`LevenbergMarquardt`: gradient tolerance must be >= 0."
+            ));
+        }
+        self.tol_grad = tol;
+        Ok(self)
+    }
+
+    /// Set tolerance on the norm of the step relative to the norm of the parameter vector.
+    ///
+    /// Must be non-negative and defaults to `sqrt(EPSILON)`. A tolerance of zero disables this
+    /// stopping criterion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::levenbergmarquardt::LevenbergMarquardt;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// let lm = LevenbergMarquardt::new().with_tolerance_step(1e-10f64)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_tolerance_step(mut self, tol: F) -> Result<Self, Error> {
+        if tol < float!(0.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`LevenbergMarquardt`: step tolerance must be >= 0."
+            ));
+        }
+        self.tol_step = tol;
+        Ok(self)
+    }
+
+    /// Set tolerance on the relative cost reduction of accepted steps.
+    ///
+    /// Must be non-negative and defaults to `sqrt(EPSILON)`. A tolerance of zero disables this
+    /// stopping criterion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::levenbergmarquardt::LevenbergMarquardt;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// let lm = LevenbergMarquardt::new().with_tolerance_cost(1e-10f64)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_tolerance_cost(mut self, tol: F) -> Result<Self, Error> {
+        if tol < float!(0.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`LevenbergMarquardt`: cost tolerance must be >= 0."
+            ));
+        }
+        self.tol_cost = tol;
+        Ok(self)
+    }
+}
+
+impl<F: ArgminFloat> Default for LevenbergMarquardt<F> {
+    fn default() -> LevenbergMarquardt<F> {
+        LevenbergMarquardt::new()
+    }
+}
+
+impl<O, F, P, J, U> Solver<O, IterState<P, P, J, (), F>> for LevenbergMarquardt<F>
+where
+    O: Operator<Param = P, Output = U> + Jacobian<Param = P, Jacobian = J>,
+    P: Clone
+        + ArgminAdd<P, P>
+        + ArgminSub<P, P>
+        + ArgminMul<F, P>
+        + ArgminDot<P, F>
+        + ArgminL2Norm<F>,
+    U: ArgminL2Norm<F>,
+    J: Clone
+        + ArgminTranspose<J>
+        + ArgminInv<J>
+        + ArgminEye
+        + ArgminAdd<J, J>
+        + ArgminMul<J, J>
+        + ArgminMul<F, J>
+        + ArgminDot<J, J>
+        + ArgminDot<U, P>
+        + ArgminDot<P, P>,
+    F: ArgminFloat,
+{
+    const NAME: &'static str = "Levenberg-Marquardt method";
+
+    fn init(
+        &mut self,
+        problem: &mut Problem<O>,
+        state: IterState<P, P, J, (), F>,
+    ) -> Result<(IterState<P, P, J, (), F>, Option<KV>), Error> {
+        let param = state.get_param().ok_or_else(argmin_error_closure!(
+            NotInitialized,
+            concat!(
+                "`LevenbergMarquardt` requires an initial parameter vector. ",
+                "Please provide an initial guess via `Executor`s `configure` method."
+            )
+        ))?;
+        let residuals = problem.apply(param)?;
+        let jacobian = problem.jacobian(param)?;
+        let gradient = jacobian.clone().t().dot(&residuals);
+        let norm = residuals.l2_norm();
+        Ok((
+            state
+                .cost(float!(0.5) * norm * norm)
+                .gradient(gradient)
+                .jacobian(jacobian),
+            None,
+        ))
+    }
+
+    fn next_iter(
+        &mut self,
+        problem: &mut Problem<O>,
+        mut state: IterState<P, P, J, (), F>,
+    ) -> Result<(IterState<P, P, J, (), F>, Option<KV>), Error> {
+        let param = state.take_param().ok_or_else(argmin_error_closure!(
+            NotInitialized,
+            concat!(
+                "`LevenbergMarquardt` requires an initial parameter vector. ",
+                "Please provide an initial guess via `Executor`s `configure` method."
+            )
+        ))?;
+        let gradient = state.take_gradient().ok_or_else(argmin_error_closure!(
+            PotentialBug,
+            "`LevenbergMarquardt`: Gradient in state not set."
+        ))?;
+        let jacobian = state.take_jacobian().ok_or_else(argmin_error_closure!(
+            PotentialBug,
+            "`LevenbergMarquardt`: Jacobian in state not set."
+        ))?;
+        let cost = state.get_cost();
+
+        let jtj = jacobian.clone().t().dot(&jacobian);
+        let eye = jtj.eye_like();
+        // A tiny multiple of the identity keeps the damping matrix regular if a column of the
+        // Jacobian vanishes.
+        let damping = if self.marquardt_scaling {
+            jtj.mul(&eye).add(&eye.mul(&F::epsilon()))
+        } else {
+            eye
+        };
+
+        // Solve (J^T J + lambda D) h = -J^T r
+        let step = jtj
+            .add(&damping.mul(&self.lambda))
+            .inv()?
+            .dot(&gradient)
+            .mul(&float!(-1.0));
+        self.step_norm = step.l2_norm();
+
+        // Cost reduction predicted by the local linear model: 1/2 h^T (lambda D h - g)
+        let predicted =
+            float!(0.5) * step.dot(&damping.dot(&step).mul(&self.lambda).sub(&gradient));
+
+        let new_param = param.add(&step);
+        let new_residuals = problem.apply(&new_param)?;
+        let norm = new_residuals.l2_norm();
+        let new_cost = float!(0.5) * norm * norm;
+
+        let gain_ratio = if predicted > float!(0.0) {
+            (cost - new_cost) / predicted
+        } else {
+            float!(-1.0)
+        };
+
+        let lambda = self.lambda;
+        let accepted = gain_ratio > float!(0.0);
+        if accepted {
+            let new_jacobian = problem.jacobian(&new_param)?;
+            let new_gradient = new_jacobian.clone().t().dot(&new_residuals);
+            let tmp = float!(2.0) * gain_ratio - float!(1.0);
+            self.lambda = self.lambda * float!(1.0 / 3.0).max(float!(1.0) - tmp * tmp * tmp);
+            self.nu = float!(2.0);
+            self.cost_reduction = Some((cost - new_cost) / cost.max(F::min_positive_value()));
+            state = state
+                .param(new_param)
+                .cost(new_cost)
+                .gradient(new_gradient)
+                .jacobian(new_jacobian);
+        } else {
+            self.lambda = self.lambda * self.nu;
+            self.nu = self.nu * float!(2.0);
+            self.cost_reduction = None;
+            state = state
+                .param(param)
+                .cost(cost)
+                .gradient(gradient)
+                .jacobian(jacobian);
+        }
+
+        Ok((
+            state,
+            Some(kv!(
+                "lambda" => lambda;
+                "gain_ratio" => gain_ratio;
+                "accepted" => accepted;
+            )),
+        ))
+    }
+
+    fn terminate(&mut self, state: &IterState<P, P, J, (), F>) -> TerminationStatus {
+        if let Some(gradient) = state.get_gradient() {
+            if gradient.l2_norm() <= self.tol_grad {
+                return TerminationStatus::Terminated(TerminationReason::SolverConverged);
+            }
+        }
+        if let Some(param) = state.get_param() {
+            if self.step_norm <= self.tol_step * (param.l2_norm() + self.tol_step) {
+                return TerminationStatus::Terminated(TerminationReason::SolverConverged);
+            }
+        }
+        if let Some(cost_reduction) = self.cost_reduction {
+            if cost_reduction <= self.tol_cost {
+                return TerminationStatus::Terminated(TerminationReason::SolverConverged);
+            }
+        }
+        TerminationStatus::NotTerminated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ArgminError;
+    #[cfg(feature = "_ndarrayl")]
+    use crate::core::Executor;
+    #[cfg(feature = "_ndarrayl")]
+    use approx::assert_relative_eq;
+
+    test_trait_impl!(levenberg_marquardt, LevenbergMarquardt<f64>);
+
+    #[test]
+    fn test_new() {
+        let LevenbergMarquardt {
+            lambda,
+            nu,
+            marquardt_scaling,
+            tol_grad,
+            tol_step,
+            tol_cost,
+            step_norm,
+            cost_reduction,
+        } = LevenbergMarquardt::<f64>::new();
+
+        assert_eq!(lambda.to_ne_bytes(), 1e-3f64.to_ne_bytes());
+        assert_eq!(nu.to_ne_bytes(), 2.0f64.to_ne_bytes());
+        assert!(marquardt_scaling);
+        assert_eq!(tol_grad.to_ne_bytes(), f64::EPSILON.sqrt().to_ne_bytes());
+        assert_eq!(tol_step.to_ne_bytes(), f64::EPSILON.sqrt().to_ne_bytes());
+        assert_eq!(tol_cost.to_ne_bytes(), f64::EPSILON.sqrt().to_ne_bytes());
+        assert!(step_norm.is_infinite());
+        assert!(cost_reduction.is_none());
+    }
+
+    #[test]
+    fn test_initial_damping() {
+        let lm = LevenbergMarquardt::new()
+            .with_initial_damping(0.1f64)
+            .unwrap();
+        assert_eq!(lm.lambda.to_ne_bytes(), 0.1f64.to_ne_bytes());
+
+        for lambda in [0.0, -1.0] {
+            assert_error!(
+                LevenbergMarquardt::new().with_initial_damping(lambda),
+                ArgminError,
+                "Invalid parameter: \"`LevenbergMarquardt`: initial damping must be > 0.\""
+            );
+        }
+    }
+
+    #[test]
+    fn test_marquardt_scaling() {
+        let lm: LevenbergMarquardt<f64> = LevenbergMarquardt::new().with_marquardt_scaling(false);
+        assert!(!lm.marquardt_scaling);
+    }
+
+    #[test]
+    fn test_tolerances() {
+        let lm = LevenbergMarquardt::new()
+            .with_tolerance_grad(1e-4f64)
+            .unwrap()
+            .with_tolerance_step(1e-5)
+            .unwrap()
+            .with_tolerance_cost(0.0)
+            .unwrap();
+        assert_eq!(lm.tol_grad.to_ne_bytes(), 1e-4f64.to_ne_bytes());
+        assert_eq!(lm.tol_step.to_ne_bytes(), 1e-5f64.to_ne_bytes());
+        assert_eq!(lm.tol_cost.to_ne_bytes(), 0.0f64.to_ne_bytes());
+
+        assert_error!(
+            LevenbergMarquardt::new().with_tolerance_grad(-1.0f64),
+            ArgminError,
+            "Invalid parameter: \"`LevenbergMarquardt`: gradient tolerance must be >= 0.\""
+        );
+        assert_error!(
+            LevenbergMarquardt::new().with_tolerance_step(-1.0f64),
+            ArgminError,
+            "Invalid parameter: \"`LevenbergMarquardt`: step tolerance must be >= 0.\""
+        );
+        assert_error!(
+            LevenbergMarquardt::new().with_tolerance_cost(-1.0f64),
+            ArgminError,
+            "Invalid parameter: \"`LevenbergMarquardt`: cost tolerance must be >= 0.\""
+        );
+    }
+
+    #[cfg(feature = "_ndarrayl")]
+    mod ndarray_tests {
+        use super::*;
+        use ndarray::{array, Array1, Array2};
+
+        /// Rosenbrock function as least squares problem
+        struct Rosenbrock {}
+
+        impl Operator for Rosenbrock {
+            type Param = Array1<f64>;
+            type Output = Array1<f64>;
+
+            fn apply(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+                Ok(array![10.0 * (p[1] - p[0].powi(2)), 1.0 - p[0]])
+            }
+        }
+
+        impl Jacobian for Rosenbrock {
+            type Param = Array1<f64>;
+            type Jacobian = Array2<f64>;
+
+            fn jacobian(&self, p: &Self::Param) -> Result<Self::Jacobian, Error> {
+                Ok(array![[-20.0 * p[0], 10.0], [-1.0, 0.0]])
+            }
+        }
+
+        /// Fit of `(a + b) * exp(c * t)`, which has a rank-deficient Jacobian because `a` and `b`
+        /// cannot be distinguished.
+        struct RankDeficientFit {
+            t: Vec<f64>,
+            y: Vec<f64>,
+        }
+
+        impl RankDeficientFit {
+            fn new() -> Self {
+                let t: Vec<f64> = (0..10).map(|i| f64::from(i) * 0.3).collect();
+                let y = t.iter().map(|t| 2.0 * (-t).exp()).collect();
+                RankDeficientFit { t, y }
+            }
+        }
+
+        impl Operator for RankDeficientFit {
+            type Param = Array1<f64>;
+            type Output = Array1<f64>;
+
+            fn apply(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+                Ok(self
+                    .t
+                    .iter()
+                    .zip(self.y.iter())
+                    .map(|(t, y)| (p[0] + p[1]) * (p[2] * t).exp() - y)
+                    .collect())
+            }
+        }
+
+        impl Jacobian for RankDeficientFit {
+            type Param = Array1<f64>;
+            type Jacobian = Array2<f64>;
+
+            fn jacobian(&self, p: &Self::Param) -> Result<Self::Jacobian, Error> {
+                let mut jac = Array2::zeros((self.t.len(), 3));
+                for (i, t) in self.t.iter().enumerate() {
+                    let e = (p[2] * t).exp();
+                    jac[[i, 0]] = e;
+                    jac[[i, 1]] = e;
+                    jac[[i, 2]] = (p[0] + p[1]) * t * e;
+                }
+                Ok(jac)
+            }
+        }
+
+        #[test]
+        fn test_next_iter_param_not_initialized() {
+            let mut lm = LevenbergMarquardt::<f64>::new();
+            let res = lm.init(&mut Problem::new(Rosenbrock {}), IterState::new());
+            assert_error!(
+                res,
+                ArgminError,
+                concat!(
+                    "Not initialized: \"`LevenbergMarquardt` requires an initial parameter ",
+                    "vector. Please provide an initial guess via `Executor`s `configure` method.\""
+                )
+            );
+        }
+
+        #[test]
+        fn test_rosenbrock() {
+            for scaling in [true, false] {
+                let solver = LevenbergMarquardt::new().with_marquardt_scaling(scaling);
+                let res = Executor::new(Rosenbrock {}, solver)
+                    .configure(|state| state.param(array![-1.2, 1.0]).max_iters(200))
+                    .run()
+                    .unwrap();
+                assert_eq!(
+                    res.state.termination_status,
+                    TerminationStatus::Terminated(TerminationReason::SolverConverged)
+                );
+                let param = res.state.best_param.unwrap();
+                assert_relative_eq!(param[0], 1.0, epsilon = 1e-6);
+                assert_relative_eq!(param[1], 1.0, epsilon = 1e-6);
+            }
+        }
+
+        #[test]
+        fn test_rank_deficient() {
+            let res = Executor::new(RankDeficientFit::new(), LevenbergMarquardt::new())
+                .configure(|state| state.param(array![0.5, 0.5, 0.0]).max_iters(200))
+                .run()
+                .unwrap();
+            assert_eq!(
+                res.state.termination_status,
+                TerminationStatus::Terminated(TerminationReason::SolverConverged)
+            );
+            assert!(res.state.best_cost < 1e-12);
+            let param = res.state.best_param.unwrap();
+            assert_relative_eq!(param[0] + param[1], 2.0, epsilon = 1e-6);
+            assert_relative_eq!(param[2], -1.0, epsilon = 1e-6);
+        }
+    }
+}
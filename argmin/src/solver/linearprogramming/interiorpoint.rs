@@ -0,0 +1,661 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::{remove_redundant_rows, standard_form};
+use crate::core::{
+    ArgminFloat, Error, LinearProgram, LinearProgramState, Problem, Solver, State,
+    TerminationReason, KV,
+};
+use crate::solver::utils::dot;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// # Primal-dual interior point method
+///
+/// Mehrotra predictor-corrector interior point method for linear programs in standard form
+///
+/// ```text
+/// minimize    c^T x
+/// subject to  A x = b
+///             x >= 0
+/// ```
+///
+/// The method operates on the homogeneous self-dual embedding of the problem, which does not
+/// require a feasible starting point and allows to detect infeasible and unbounded problems: The
+/// solver terminates with [`TerminationReason::ProblemInfeasible`] if a certificate of primal
+/// infeasibility is found and with [`TerminationReason::ProblemUnbounded`] if a certificate of
+/// dual infeasibility is found. Otherwise it terminates with
+/// [`TerminationReason::SolverConverged`] once the relative primal and dual infeasibilities and
+/// the relative duality gap are below the tolerance.
+///
+/// The linear systems are solved via the normal equations using a dense Cholesky decomposition.
+/// Therefore this solver is intended for small to medium sized problems.
+///
+/// As long as the current iterate is not primal feasible, the cost reported in the state is
+/// infinite.
+///
+/// ## Requirements on the optimization problem
+///
+/// The optimization problem is required to implement [`LinearProgram`].
+///
+/// ## Reference
+///
+/// Erling D. Andersen and Knud D. Andersen (2000). The MOSEK interior point optimizer for linear
+/// programming: an implementation of the homogeneous algorithm. High Performance Optimization,
+/// pp. 197-232. Springer. <https://doi.org/10.1007/978-1-4757-3216-0_8>
+///
+/// Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct InteriorPoint<F> {
+    /// Tolerance for the termination criteria
+    tol: F,
+    /// Constraint matrix
+    a: Vec<Vec<F>>,
+    /// Right-hand side of the constraints
+    b: Vec<F>,
+    /// Cost vector
+    c: Vec<F>,
+    /// Primal variables
+    x: Vec<F>,
+    /// Dual variables
+    y: Vec<F>,
+    /// Dual slack variables
+    z: Vec<F>,
+    /// Homogenizing variable
+    tau: F,
+    /// Homogenizing slack variable
+    kappa: F,
+    /// Primal, dual and gap residual norms of the starting point
+    init_residuals: (F, F, F),
+}
+
+/// Residuals of the homogeneous self-dual embedding
+struct Residuals<F> {
+    /// `b tau - A x`
+    primal: Vec<F>,
+    /// `c tau - A^T y - z`
+    dual: Vec<F>,
+    /// `c^T x - b^T y + kappa`
+    gap: F,
+}
+
+/// Factorized normal equations of the current iterate
+struct NormalEquations<F> {
+    /// Cholesky factor of `A diag(d) A^T`
+    l: Vec<Vec<F>>,
+    /// Diagonal scaling `x / z`
+    d: Vec<F>,
+    /// Solution of the augmented system with right-hand side `(c, b)`
+    p: (Vec<F>, Vec<F>),
+}
+
+/// Search direction
+struct Direction<F> {
+    x: Vec<F>,
+    y: Vec<F>,
+    z: Vec<F>,
+    tau: F,
+    kappa: F,
+}
+
+impl<F: ArgminFloat> InteriorPoint<F> {
+    /// Construct a new instance of [`InteriorPoint`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::linearprogramming::InteriorPoint;
+    /// let ipm: InteriorPoint<f64> = InteriorPoint::new();
+    /// ```
+    pub fn new() -> Self {
+        InteriorPoint {
+            tol: float!(1e-8),
+            a: vec![],
+            b: vec![],
+            c: vec![],
+            x: vec![],
+            y: vec![],
+            z: vec![],
+            tau: float!(1.0),
+            kappa: float!(1.0),
+            init_residuals: (float!(1.0), float!(1.0), float!(1.0)),
+        }
+    }
+
+    /// Set tolerance for the termination criteria.
+    ///
+    /// Must be larger than zero and defaults to `1e-8`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::linearprogramming::InteriorPoint;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// let ipm = InteriorPoint::new().with_tolerance(1e-10f64)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_tolerance(mut self, tol: F) -> Result<Self, Error> {
+        if tol <= float!(0.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`InteriorPoint`: tolerance must be > 0."
+            ));
+        }
+        self.tol = tol;
+        Ok(self)
+    }
+
+    /// Computes `A v`
+    fn a_mul(&self, v: &[F]) -> Vec<F> {
+        self.a.iter().map(|row| dot(row, v)).collect()
+    }
+
+    /// Computes `A^T v`
+    fn at_mul(&self, v: &[F]) -> Vec<F> {
+        let mut out = vec![float!(0.0); self.c.len()];
+        for (row, &vi) in self.a.iter().zip(v.iter()) {
+            for (o, &aij) in out.iter_mut().zip(row.iter()) {
+                *o = *o + aij * vi;
+            }
+        }
+        out
+    }
+
+    fn residuals(&self) -> Residuals<F> {
+        let ax = self.a_mul(&self.x);
+        let aty = self.at_mul(&self.y);
+        Residuals {
+            primal: self
+                .b
+                .iter()
+                .zip(ax.iter())
+                .map(|(&bi, &axi)| bi * self.tau - axi)
+                .collect(),
+            dual: self
+                .c
+                .iter()
+                .zip(aty.iter().zip(self.z.iter()))
+                .map(|(&ci, (&ai, &zi))| ci * self.tau - ai - zi)
+                .collect(),
+            gap: dot(&self.c, &self.x) - dot(&self.b, &self.y) + self.kappa,
+        }
+    }
+
+    /// Complementarity measure of the current iterate
+    fn mu(&self) -> F {
+        (dot(&self.x, &self.z) + self.tau * self.kappa) / F::from_usize(self.x.len() + 1).unwrap()
+    }
+
+    /// Cholesky factorization of `A diag(d) A^T`. Pivots which vanish due to (numerically)
+    /// linearly dependent constraints are replaced by a huge number, which effectively removes
+    /// the corresponding component from the solution.
+    fn factorize(&self, d: &[F]) -> Vec<Vec<F>> {
+        let m = self.a.len();
+        let mut l = vec![vec![float!(0.0); m]; m];
+        for i in 0..m {
+            for j in 0..=i {
+                let mut s = self.a[i]
+                    .iter()
+                    .zip(self.a[j].iter().zip(d.iter()))
+                    .fold(float!(0.0), |acc, (&aik, (&ajk, &dk))| acc + aik * ajk * dk);
+                s = l[i]
+                    .iter()
+                    .zip(l[j].iter())
+                    .take(j)
+                    .fold(s, |acc, (&lik, &ljk)| acc - lik * ljk);
+                if i == j {
+                    let scale = self.a[i]
+                        .iter()
+                        .zip(d.iter())
+                        .fold(float!(0.0), |acc, (&aik, &dk)| acc + aik * aik * dk);
+                    l[i][i] = if s <= F::epsilon().sqrt() * scale.max(float!(1.0)) {
+                        float!(1e64)
+                    } else {
+                        s.sqrt()
+                    };
+                } else {
+                    l[i][j] = s / l[j][j];
+                }
+            }
+        }
+        l
+    }
+
+    /// Solves `L L^T v = r`
+    fn cholesky_solve(l: &[Vec<F>], r: &[F]) -> Vec<F> {
+        let m = r.len();
+        let mut v = r.to_vec();
+        for i in 0..m {
+            for k in 0..i {
+                v[i] = v[i] - l[i][k] * v[k];
+            }
+            v[i] = v[i] / l[i][i];
+        }
+        for i in (0..m).rev() {
+            for k in i + 1..m {
+                v[i] = v[i] - l[k][i] * v[k];
+            }
+            v[i] = v[i] / l[i][i];
+        }
+        v
+    }
+
+    /// Solves the augmented system
+    ///
+    /// ```text
+    /// -D^-1 u + A^T v = r1
+    ///     A u         = r2
+    /// ```
+    ///
+    /// with `D = diag(d)` using the factorization of `A D A^T`.
+    fn sym_solve(&self, l: &[Vec<F>], d: &[F], r1: &[F], r2: &[F]) -> (Vec<F>, Vec<F>) {
+        let dr1: Vec<F> = d.iter().zip(r1.iter()).map(|(&di, &ri)| di * ri).collect();
+        let rhs: Vec<F> = r2
+            .iter()
+            .zip(self.a_mul(&dr1).iter())
+            .map(|(&r2i, &ai)| r2i + ai)
+            .collect();
+        let v = Self::cholesky_solve(l, &rhs);
+        let u = self
+            .at_mul(&v)
+            .iter()
+            .zip(r1.iter().zip(d.iter()))
+            .map(|(&ai, (&r1i, &di))| di * (ai - r1i))
+            .collect();
+        (u, v)
+    }
+
+    /// Factorizes the normal equations of the current iterate. The solution of the augmented
+    /// system with right-hand side `(c, b)` is shared by predictor and corrector.
+    fn normal_equations(&self) -> NormalEquations<F> {
+        let d: Vec<F> = self
+            .x
+            .iter()
+            .zip(self.z.iter())
+            .map(|(&xi, &zi)| xi / zi)
+            .collect();
+        let l = self.factorize(&d);
+        let p = self.sym_solve(&l, &d, &self.c, &self.b);
+        NormalEquations { l, d, p }
+    }
+
+    /// Computes a search direction of the homogeneous self-dual embedding, where `gamma` is the
+    /// centering parameter and `eta` the fraction of the residuals to be eliminated. If a
+    /// `predictor` direction is given, its second order term is included (Mehrotra corrector).
+    fn direction(
+        &self,
+        ne: &NormalEquations<F>,
+        res: &Residuals<F>,
+        gamma: F,
+        eta: F,
+        predictor: Option<&Direction<F>>,
+    ) -> Direction<F> {
+        let mu = self.mu();
+        let mut rhatxs: Vec<F> = self
+            .x
+            .iter()
+            .zip(self.z.iter())
+            .map(|(&xi, &zi)| gamma * mu - xi * zi)
+            .collect();
+        let mut rhattk = gamma * mu - self.tau * self.kappa;
+        if let Some(pred) = predictor {
+            for (r, (&dx, &dz)) in rhatxs.iter_mut().zip(pred.x.iter().zip(pred.z.iter())) {
+                *r = *r - dx * dz;
+            }
+            rhattk = rhattk - pred.tau * pred.kappa;
+        }
+
+        let rhatp: Vec<F> = res.primal.iter().map(|&r| eta * r).collect();
+        let rhatd: Vec<F> = res.dual.iter().map(|&r| eta * r).collect();
+        let rhatg = eta * res.gap;
+
+        // r1 = rhat_d - rhat_xs / x
+        let r1: Vec<F> = rhatd
+            .iter()
+            .zip(rhatxs.iter().zip(self.x.iter()))
+            .map(|(&rd, (&rxs, &xi))| rd - rxs / xi)
+            .collect();
+        let (u, v) = self.sym_solve(&ne.l, &ne.d, &r1, &rhatp);
+
+        let (ref p_x, ref p_y) = ne.p;
+        let d_tau = (rhatg + rhattk / self.tau - (-dot(&self.c, &u) + dot(&self.b, &v)))
+            / (self.kappa / self.tau + (-dot(&self.c, p_x) + dot(&self.b, p_y)));
+        let d_x: Vec<F> = u
+            .iter()
+            .zip(p_x.iter())
+            .map(|(&ui, &pi)| ui + pi * d_tau)
+            .collect();
+        let d_y: Vec<F> = v
+            .iter()
+            .zip(p_y.iter())
+            .map(|(&vi, &qi)| vi + qi * d_tau)
+            .collect();
+        let d_z: Vec<F> = rhatxs
+            .iter()
+            .zip(self.z.iter().zip(d_x.iter().zip(self.x.iter())))
+            .map(|(&rxs, (&zi, (&dxi, &xi)))| (rxs - zi * dxi) / xi)
+            .collect();
+        let d_kappa = (rhattk - self.kappa * d_tau) / self.tau;
+
+        Direction {
+            x: d_x,
+            y: d_y,
+            z: d_z,
+            tau: d_tau,
+            kappa: d_kappa,
+        }
+    }
+
+    /// Largest step length in `(0, 1]` which keeps `x`, `z`, `tau` and `kappa` positive.
+    fn step_length(&self, dir: &Direction<F>) -> F {
+        let max_step = |v: F, dv: F| {
+            if dv < float!(0.0) {
+                -v / dv
+            } else {
+                F::infinity()
+            }
+        };
+        let alpha = self
+            .x
+            .iter()
+            .zip(dir.x.iter())
+            .chain(self.z.iter().zip(dir.z.iter()))
+            .map(|(&v, &dv)| max_step(v, dv))
+            .fold(float!(1.0), |acc, s| acc.min(s));
+        alpha
+            .min(max_step(self.tau, dir.tau))
+            .min(max_step(self.kappa, dir.kappa))
+    }
+
+    /// Relative primal infeasibility, dual infeasibility, duality gap and complementarity of the
+    /// current iterate
+    fn indicators(&self, res: &Residuals<F>, mu0: F) -> (F, F, F, F, F) {
+        let norm = |v: &[F]| dot(v, v).sqrt();
+        let (p0, d0, g0) = self.init_residuals;
+        let one = float!(1.0);
+        let rho_p = norm(&res.primal) / p0.max(one);
+        let rho_d = norm(&res.dual) / d0.max(one);
+        let rho_g = res.gap.abs() / g0.max(one);
+        let ctx = dot(&self.c, &self.x);
+        let bty = dot(&self.b, &self.y);
+        let rho_a = (ctx - bty).abs() / (self.tau + bty.abs());
+        let rho_mu = self.mu() / mu0;
+        (rho_p, rho_d, rho_g, rho_a, rho_mu)
+    }
+
+    /// Solution of the original problem corresponding to the current iterate
+    fn solution(&self) -> Vec<F> {
+        self.x.iter().map(|&xi| xi / self.tau).collect()
+    }
+
+    /// Cost reported to the state: The objective function value if the current iterate is
+    /// (approximately) primal feasible, infinity otherwise.
+    fn reported_cost(&self, x: &[F]) -> F {
+        let ax = self.a_mul(x);
+        let infeasibility = self
+            .b
+            .iter()
+            .zip(ax.iter())
+            .fold(float!(0.0), |acc, (&bi, &axi)| acc + (bi - axi).powi(2))
+            .sqrt();
+        let scale = dot(&self.b, &self.b).sqrt().max(float!(1.0));
+        if infeasibility <= self.tol.sqrt() * scale {
+            dot(&self.c, x)
+        } else {
+            F::infinity()
+        }
+    }
+}
+
+impl<F: ArgminFloat> Default for InteriorPoint<F> {
+    fn default() -> InteriorPoint<F> {
+        InteriorPoint::new()
+    }
+}
+
+impl<O, F> Solver<O, LinearProgramState<Vec<F>, F>> for InteriorPoint<F>
+where
+    O: LinearProgram<Param = Vec<F>, Float = F>,
+    F: ArgminFloat,
+{
+    const NAME: &'static str = "Interior point method";
+
+    fn init(
+        &mut self,
+        problem: &mut Problem<O>,
+        state: LinearProgramState<Vec<F>, F>,
+    ) -> Result<(LinearProgramState<Vec<F>, F>, Option<KV>), Error> {
+        let (a, b, c) = standard_form(problem, "InteriorPoint")?;
+        // Linearly dependent constraints render the normal equations singular. If they are
+        // inconsistent, the problem is infeasible.
+        let (a, b) = match remove_redundant_rows(a, b, F::epsilon().sqrt()) {
+            Some(ab) => ab,
+            None => {
+                let n = c.len();
+                return Ok((
+                    state
+                        .param(vec![float!(0.0); n])
+                        .cost(F::infinity())
+                        .terminate_with(TerminationReason::ProblemInfeasible),
+                    None,
+                ));
+            }
+        };
+        let m = b.len();
+        let n = c.len();
+        self.a = a;
+        self.b = b;
+        self.c = c;
+        self.x = vec![float!(1.0); n];
+        self.y = vec![float!(0.0); m];
+        self.z = vec![float!(1.0); n];
+        self.tau = float!(1.0);
+        self.kappa = float!(1.0);
+
+        let res = self.residuals();
+        self.init_residuals = (
+            dot(&res.primal, &res.primal).sqrt(),
+            dot(&res.dual, &res.dual).sqrt(),
+            res.gap.abs(),
+        );
+
+        let x = self.solution();
+        let cost = self.reported_cost(&x);
+        Ok((state.param(x).cost(cost), None))
+    }
+
+    fn next_iter(
+        &mut self,
+        _problem: &mut Problem<O>,
+        mut state: LinearProgramState<Vec<F>, F>,
+    ) -> Result<(LinearProgramState<Vec<F>, F>, Option<KV>), Error> {
+        // Initial complementarity is 1 by construction of the starting point
+        let mu0 = float!(1.0);
+        let res = self.residuals();
+
+        let ne = self.normal_equations();
+
+        // Predictor (affine scaling direction)
+        let pred = self.direction(&ne, &res, float!(0.0), float!(1.0), None);
+        let alpha_aff = self.step_length(&pred);
+
+        // Corrector
+        let gamma = (float!(1.0) - alpha_aff).powi(2) * (float!(1.0) - alpha_aff).min(float!(0.1));
+        let eta = float!(1.0) - gamma;
+        let dir = self.direction(&ne, &res, gamma, eta, Some(&pred));
+        let alpha = (self.step_length(&dir) * float!(0.99995)).min(float!(1.0));
+
+        let update = |v: &mut Vec<F>, dv: &[F]| {
+            for (vi, &dvi) in v.iter_mut().zip(dv.iter()) {
+                *vi = *vi + alpha * dvi;
+            }
+        };
+        update(&mut self.x, &dir.x);
+        update(&mut self.y, &dir.y);
+        update(&mut self.z, &dir.z);
+        self.tau = self.tau + alpha * dir.tau;
+        self.kappa = self.kappa + alpha * dir.kappa;
+
+        let res = self.residuals();
+        let (rho_p, rho_d, rho_g, rho_a, rho_mu) = self.indicators(&res, mu0);
+        let tol = self.tol;
+        let one = float!(1.0);
+
+        if rho_p <= tol && rho_d <= tol && rho_a <= tol {
+            state = state.terminate_with(TerminationReason::SolverConverged);
+        } else if (rho_p < tol
+            && rho_d < tol
+            && rho_g < tol
+            && self.tau < tol * self.kappa.max(one))
+            || (rho_mu < tol && self.tau < tol * self.kappa.min(one))
+        {
+            // The iterate is a certificate of infeasibility: A positive dual objective proves
+            // primal infeasibility, otherwise the dual problem is infeasible.
+            if dot(&self.b, &self.y) > tol {
+                state = state.terminate_with(TerminationReason::ProblemInfeasible);
+            } else {
+                state = state.terminate_with(TerminationReason::ProblemUnbounded);
+            }
+        }
+
+        let x = self.solution();
+        let cost = self.reported_cost(&x);
+        Ok((
+            state.param(x).cost(cost),
+            Some(kv!(
+                "alpha" => alpha;
+                "tau" => self.tau;
+                "kappa" => self.kappa;
+                "rho_p" => rho_p;
+                "rho_d" => rho_d;
+                "rho_a" => rho_a;
+            )),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{beale_lp, infeasible_lp, redundant_lp, unbounded_lp, SmallLp};
+    use super::*;
+    use crate::core::{ArgminError, Executor, TerminationStatus};
+    use approx::assert_relative_eq;
+
+    test_trait_impl!(interiorpoint, InteriorPoint<f64>);
+
+    fn run<O>(problem: O) -> LinearProgramState<Vec<f64>, f64>
+    where
+        O: LinearProgram<Param = Vec<f64>, Float = f64>,
+    {
+        Executor::new(problem, InteriorPoint::new())
+            .configure(|state| state.max_iters(100))
+            .run()
+            .unwrap()
+            .state
+    }
+
+    #[test]
+    fn test_new() {
+        let ipm: InteriorPoint<f64> = InteriorPoint::new();
+        assert_eq!(ipm.tol.to_ne_bytes(), 1e-8f64.to_ne_bytes());
+        assert!(ipm.a.is_empty());
+        assert!(ipm.b.is_empty());
+        assert!(ipm.c.is_empty());
+        assert!(ipm.x.is_empty());
+        assert!(ipm.y.is_empty());
+        assert!(ipm.z.is_empty());
+        assert_eq!(ipm.tau.to_ne_bytes(), 1.0f64.to_ne_bytes());
+        assert_eq!(ipm.kappa.to_ne_bytes(), 1.0f64.to_ne_bytes());
+    }
+
+    #[test]
+    fn test_with_tolerance() {
+        let ipm = InteriorPoint::new().with_tolerance(1e-4f64).unwrap();
+        assert_eq!(ipm.tol.to_ne_bytes(), 1e-4f64.to_ne_bytes());
+
+        for tol in [0.0, -1.0] {
+            assert_error!(
+                InteriorPoint::new().with_tolerance(tol),
+                ArgminError,
+                "Invalid parameter: \"`InteriorPoint`: tolerance must be > 0.\""
+            );
+        }
+    }
+
+    #[test]
+    fn test_init() {
+        let mut ipm: InteriorPoint<f64> = InteriorPoint::new();
+        let (state, kv) = ipm
+            .init(&mut Problem::new(SmallLp {}), LinearProgramState::new())
+            .unwrap();
+        assert_eq!(ipm.x, vec![1.0; 4]);
+        assert_eq!(ipm.y, vec![0.0; 2]);
+        assert_eq!(ipm.z, vec![1.0; 4]);
+        assert_eq!(state.param.unwrap(), vec![1.0; 4]);
+        assert!(state.cost.is_infinite());
+        assert!(kv.is_none());
+    }
+
+    #[test]
+    fn test_solver() {
+        let state = run(SmallLp {});
+        assert_eq!(
+            state.termination_status,
+            TerminationStatus::Terminated(TerminationReason::SolverConverged)
+        );
+        assert_relative_eq!(state.best_cost, -5.0, epsilon = 1e-6);
+        let x = state.best_param.unwrap();
+        for (xi, ei) in x.iter().zip([3.0, 1.0, 0.0, 0.0].iter()) {
+            assert_relative_eq!(xi, ei, epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_infeasible() {
+        let state = run(infeasible_lp());
+        assert_eq!(
+            state.termination_status,
+            TerminationStatus::Terminated(TerminationReason::ProblemInfeasible)
+        );
+    }
+
+    #[test]
+    fn test_unbounded() {
+        let state = run(unbounded_lp());
+        assert_eq!(
+            state.termination_status,
+            TerminationStatus::Terminated(TerminationReason::ProblemUnbounded)
+        );
+    }
+
+    #[test]
+    fn test_degenerate() {
+        let state = run(beale_lp());
+        assert_eq!(
+            state.termination_status,
+            TerminationStatus::Terminated(TerminationReason::SolverConverged)
+        );
+        assert_relative_eq!(state.best_cost, -1.25, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_redundant_constraints() {
+        let state = run(redundant_lp());
+        assert_eq!(
+            state.termination_status,
+            TerminationStatus::Terminated(TerminationReason::SolverConverged)
+        );
+        assert_relative_eq!(state.best_cost, 2.0, epsilon = 1e-6);
+        let x = state.best_param.unwrap();
+        assert_relative_eq!(x[0], 2.0, epsilon = 1e-6);
+        assert_relative_eq!(x[1], 0.0, epsilon = 1e-6);
+    }
+}
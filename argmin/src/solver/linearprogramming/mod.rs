@@ -0,0 +1,286 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Linear programming
+//!
+//! Solvers for linear programs in standard form as defined by the
+//! [`LinearProgram`](`crate::core::LinearProgram`) trait:
+//!
+//! ```text
+//! minimize    c^T x
+//! subject to  A x = b
+//!             x >= 0
+//! ```
+//!
+//! * [Revised simplex method](`Simplex`)
+//! * [Primal-dual interior point method](`InteriorPoint`)
+//!
+//! Both solvers detect infeasible and unbounded problems and terminate with
+//! [`TerminationReason::ProblemInfeasible`](`crate::core::TerminationReason::ProblemInfeasible`)
+//! and [`TerminationReason::ProblemUnbounded`](`crate::core::TerminationReason::ProblemUnbounded`),
+//! respectively.
+//!
+//! ## References
+//!
+//! Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+//!
+//! Erling D. Andersen and Knud D. Andersen (2000). The MOSEK interior point optimizer for linear
+//! programming: an implementation of the homogeneous algorithm. High Performance Optimization,
+//! pp. 197-232. Springer. <https://doi.org/10.1007/978-1-4757-3216-0_8>
+
+mod interiorpoint;
+mod simplex;
+
+pub use interiorpoint::InteriorPoint;
+pub use simplex::Simplex;
+
+use crate::core::{ArgminFloat, Error, LinearProgram, Problem};
+
+/// Coefficients of a linear program in standard form
+type StandardForm<F> = (Vec<Vec<F>>, Vec<F>, Vec<F>);
+
+/// Fetches `A`, `b` and `c` from the problem and checks whether their dimensions are consistent.
+fn standard_form<O, F>(problem: &Problem<O>, name: &str) -> Result<StandardForm<F>, Error>
+where
+    O: LinearProgram<Float = F>,
+    F: ArgminFloat,
+{
+    let a = problem.A()?;
+    let b = problem.b()?;
+    let c = problem.c()?;
+
+    if c.is_empty() {
+        return Err(argmin_error!(
+            InvalidParameter,
+            format!("`{name}`: `c` must not be empty.")
+        ));
+    }
+    if a.len() != b.len() {
+        return Err(argmin_error!(
+            InvalidParameter,
+            format!(
+                "`{name}`: `A` has {} rows, but `b` is of length {}.",
+                a.len(),
+                b.len()
+            )
+        ));
+    }
+    if let Some(row) = a.iter().find(|row| row.len() != c.len()) {
+        return Err(argmin_error!(
+            InvalidParameter,
+            format!(
+                "`{name}`: Rows of `A` must be of length {}, found row of length {}.",
+                c.len(),
+                row.len()
+            )
+        ));
+    }
+    Ok((a, b, c))
+}
+
+/// Removes linearly dependent rows from `A x = b` via Gaussian elimination. Returns `None` if the
+/// dependent rows are inconsistent, i.e. if the constraints cannot be satisfied by any `x`.
+fn remove_redundant_rows<F: ArgminFloat>(
+    a: Vec<Vec<F>>,
+    b: Vec<F>,
+    tol: F,
+) -> Option<(Vec<Vec<F>>, Vec<F>)> {
+    // Reduced rows (including the right-hand side) and their pivot columns
+    let mut echelon: Vec<(Vec<F>, F, usize)> = vec![];
+    let mut kept_a = vec![];
+    let mut kept_b = vec![];
+    for (row, bi) in a.into_iter().zip(b) {
+        let scale = row.iter().fold(bi.abs(), |acc, v| acc.max(v.abs()));
+        let mut r = row.clone();
+        let mut rb = bi;
+        for (er, eb, p) in echelon.iter() {
+            let factor = r[*p] / er[*p];
+            for (v, &e) in r.iter_mut().zip(er.iter()) {
+                *v = *v - factor * e;
+            }
+            rb = rb - factor * *eb;
+        }
+        let (pivot, max) = r
+            .iter()
+            .enumerate()
+            .fold((0, F::zero()), |(pi, pm), (i, v)| {
+                if v.abs() > pm {
+                    (i, v.abs())
+                } else {
+                    (pi, pm)
+                }
+            });
+        if max <= tol * scale.max(F::one()) {
+            if rb.abs() > tol * scale.max(F::one()) {
+                return None;
+            }
+            continue;
+        }
+        echelon.push((r, rb, pivot));
+        kept_a.push(row);
+        kept_b.push(bi);
+    }
+    Some((kept_a, kept_b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ArgminError;
+
+    /// minimize -x1 - 2x2 s.t. x1 + x2 + s1 = 4, x1 + 3x2 + s2 = 6 (optimum -5 at x = (3, 1, 0, 0))
+    #[derive(Clone)]
+    pub(super) struct SmallLp {}
+
+    impl LinearProgram for SmallLp {
+        type Param = Vec<f64>;
+        type Float = f64;
+
+        fn c(&self) -> Result<Vec<f64>, Error> {
+            Ok(vec![-1.0, -2.0, 0.0, 0.0])
+        }
+
+        fn b(&self) -> Result<Vec<f64>, Error> {
+            Ok(vec![4.0, 6.0])
+        }
+
+        fn A(&self) -> Result<Vec<Vec<f64>>, Error> {
+            Ok(vec![vec![1.0, 1.0, 1.0, 0.0], vec![1.0, 3.0, 0.0, 1.0]])
+        }
+    }
+
+    /// Linear program defined by arbitrary `A`, `b` and `c`
+    #[derive(Clone)]
+    pub(super) struct Lp {
+        pub a: Vec<Vec<f64>>,
+        pub b: Vec<f64>,
+        pub c: Vec<f64>,
+    }
+
+    impl LinearProgram for Lp {
+        type Param = Vec<f64>;
+        type Float = f64;
+
+        fn c(&self) -> Result<Vec<f64>, Error> {
+            Ok(self.c.clone())
+        }
+
+        fn b(&self) -> Result<Vec<f64>, Error> {
+            Ok(self.b.clone())
+        }
+
+        fn A(&self) -> Result<Vec<Vec<f64>>, Error> {
+            Ok(self.a.clone())
+        }
+    }
+
+    /// `x1 + x2 = 1` and `x1 + x2 = 2` cannot both hold.
+    pub(super) fn infeasible_lp() -> Lp {
+        Lp {
+            a: vec![vec![1.0, 1.0], vec![1.0, 1.0]],
+            b: vec![1.0, 2.0],
+            c: vec![1.0, 1.0],
+        }
+    }
+
+    /// minimize `-x1` s.t. `x1 - x2 = 1` can be decreased arbitrarily.
+    pub(super) fn unbounded_lp() -> Lp {
+        Lp {
+            a: vec![vec![1.0, -1.0]],
+            b: vec![1.0],
+            c: vec![-1.0, 0.0],
+        }
+    }
+
+    /// Beale's example which cycles with the textbook pivoting rule
+    /// (optimum -1.25 at x = (0.75, 0, 0, 1, 0, 1, 0)).
+    pub(super) fn beale_lp() -> Lp {
+        Lp {
+            a: vec![
+                vec![1.0, 0.0, 0.0, 0.25, -8.0, -1.0, 9.0],
+                vec![0.0, 1.0, 0.0, 0.5, -12.0, -0.5, 3.0],
+                vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0],
+            ],
+            b: vec![0.0, 0.0, 1.0],
+            c: vec![0.0, 0.0, 0.0, -0.75, 20.0, -0.5, 6.0],
+        }
+    }
+
+    /// Second constraint is a multiple of the first one (optimum 2 at x = (2, 0)).
+    pub(super) fn redundant_lp() -> Lp {
+        Lp {
+            a: vec![vec![1.0, 1.0], vec![2.0, 2.0]],
+            b: vec![2.0, 4.0],
+            c: vec![1.0, 2.0],
+        }
+    }
+
+    #[test]
+    fn test_standard_form() {
+        let (a, b, c) = standard_form(&Problem::new(SmallLp {}), "Test").unwrap();
+        assert_eq!(a, SmallLp {}.A().unwrap());
+        assert_eq!(b, SmallLp {}.b().unwrap());
+        assert_eq!(c, SmallLp {}.c().unwrap());
+    }
+
+    #[test]
+    fn test_remove_redundant_rows() {
+        let Lp { a, b, .. } = redundant_lp();
+        let (a, b) = remove_redundant_rows(a, b, 1e-10).unwrap();
+        assert_eq!(a, vec![vec![1.0, 1.0]]);
+        assert_eq!(b, vec![2.0]);
+
+        let a = SmallLp {}.A().unwrap();
+        let b = SmallLp {}.b().unwrap();
+        let (a_red, b_red) = remove_redundant_rows(a.clone(), b.clone(), 1e-10).unwrap();
+        assert_eq!(a_red, a);
+        assert_eq!(b_red, b);
+
+        let Lp { a, b, .. } = infeasible_lp();
+        assert!(remove_redundant_rows(a, b, 1e-10).is_none());
+    }
+
+    #[test]
+    fn test_standard_form_errors() {
+        let lp = Lp {
+            a: vec![vec![1.0, 1.0]],
+            b: vec![1.0, 2.0],
+            c: vec![1.0, 1.0],
+        };
+        assert_error!(
+            standard_form(&Problem::new(lp), "Test"),
+            ArgminError,
+            "Invalid parameter: \"`Test`: `A` has 1 rows, but `b` is of length 2.\""
+        );
+
+        let lp = Lp {
+            a: vec![vec![1.0, 1.0], vec![1.0]],
+            b: vec![1.0, 2.0],
+            c: vec![1.0, 1.0],
+        };
+        assert_error!(
+            standard_form(&Problem::new(lp), "Test"),
+            ArgminError,
+            concat!(
+                "Invalid parameter: \"`Test`: Rows of `A` must be of length 2, ",
+                "found row of length 1.\""
+            )
+        );
+
+        let lp = Lp {
+            a: vec![],
+            b: vec![],
+            c: vec![],
+        };
+        assert_error!(
+            standard_form(&Problem::new(lp), "Test"),
+            ArgminError,
+            "Invalid parameter: \"`Test`: `c` must not be empty.\""
+        );
+    }
+}
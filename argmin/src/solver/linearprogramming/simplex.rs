@@ -0,0 +1,526 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::standard_form;
+use crate::core::{
+    ArgminFloat, Error, LinearProgram, LinearProgramState, Problem, Solver, State,
+    TerminationReason, KV,
+};
+use crate::solver::utils::dot;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// Phase of the two-phase simplex method
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+enum Phase {
+    /// Searching for a feasible basis by minimizing the sum of the artificial variables
+    One,
+    /// Minimizing the actual objective starting from a feasible basis
+    Two,
+}
+
+/// # Revised simplex method
+///
+/// Two-phase revised simplex method for linear programs in standard form
+///
+/// ```text
+/// minimize    c^T x
+/// subject to  A x = b
+///             x >= 0
+/// ```
+///
+/// In phase one, an artificial variable is added to each constraint and the sum of the artificial
+/// variables is minimized in order to find a feasible basis. If no such basis exists, the solver
+/// terminates with [`TerminationReason::ProblemInfeasible`]. In phase two, the actual objective is
+/// minimized starting from the feasible basis. If the objective can be decreased indefinitely,
+/// the solver terminates with [`TerminationReason::ProblemUnbounded`].
+///
+/// Each iteration performs a single pivot. Entering and leaving variables are chosen according to
+/// Bland's rule, which guarantees termination on degenerate problems. The inverse of the basis
+/// matrix is updated in product form.
+///
+/// As long as no feasible basis has been found (phase one), the cost reported in the state is
+/// infinite.
+///
+/// ## Requirements on the optimization problem
+///
+/// The optimization problem is required to implement [`LinearProgram`].
+///
+/// ## Reference
+///
+/// Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+///
+/// Robert G. Bland (1977). New finite pivoting rules for the simplex method. Mathematics of
+/// Operations Research 2(2), pp. 103-107.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct Simplex<F> {
+    /// Tolerance used for pivoting decisions and feasibility checks
+    tol: F,
+    /// Constraint matrix extended by the identity matrix of the artificial variables
+    a: Vec<Vec<F>>,
+    /// Cost vector of the original variables
+    c: Vec<F>,
+    /// Number of original variables
+    num_vars: usize,
+    /// Indices of the basic variables
+    basis: Vec<usize>,
+    /// Inverse of the basis matrix
+    b_inv: Vec<Vec<F>>,
+    /// Values of the basic variables
+    x_b: Vec<F>,
+    /// Current phase
+    phase: Phase,
+}
+
+impl<F: ArgminFloat> Simplex<F> {
+    /// Construct a new instance of [`Simplex`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::linearprogramming::Simplex;
+    /// let simplex: Simplex<f64> = Simplex::new();
+    /// ```
+    pub fn new() -> Self {
+        Simplex {
+            tol: F::epsilon().sqrt(),
+            a: vec![],
+            c: vec![],
+            num_vars: 0,
+            basis: vec![],
+            b_inv: vec![],
+            x_b: vec![],
+            phase: Phase::One,
+        }
+    }
+
+    /// Set tolerance used for pivoting decisions and feasibility checks.
+    ///
+    /// Must be larger than zero and defaults to `sqrt(EPSILON)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::linearprogramming::Simplex;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// let simplex = Simplex::new().with_tolerance(1e-10f64)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_tolerance(mut self, tol: F) -> Result<Self, Error> {
+        if tol <= float!(0.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`Simplex`: tolerance must be > 0."
+            ));
+        }
+        self.tol = tol;
+        Ok(self)
+    }
+
+    /// Cost coefficient of variable `j` in the current phase
+    fn phase_cost(&self, j: usize) -> F {
+        match (self.phase, j < self.num_vars) {
+            (Phase::One, true) => float!(0.0),
+            (Phase::One, false) => float!(1.0),
+            (Phase::Two, true) => self.c[j],
+            (Phase::Two, false) => float!(0.0),
+        }
+    }
+
+    /// Computes `B^-1 A_j`
+    fn column(&self, j: usize) -> Vec<F> {
+        self.b_inv
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(self.a.iter())
+                    .fold(float!(0.0), |acc, (&bij, a_row)| acc + bij * a_row[j])
+            })
+            .collect()
+    }
+
+    /// Selects the entering variable according to Bland's rule, which is the nonbasic variable
+    /// with the smallest index and a negative reduced cost. Artificial variables never reenter
+    /// the basis in phase two.
+    fn entering(&self) -> Option<usize> {
+        let m = self.basis.len();
+        // simplex multipliers y^T = c_B^T B^-1
+        let y: Vec<F> = (0..m)
+            .map(|k| {
+                self.basis
+                    .iter()
+                    .zip(self.b_inv.iter())
+                    .fold(float!(0.0), |acc, (&bi, row)| {
+                        acc + self.phase_cost(bi) * row[k]
+                    })
+            })
+            .collect();
+
+        let num_cols = match self.phase {
+            Phase::One => self.num_vars + m,
+            Phase::Two => self.num_vars,
+        };
+        (0..num_cols)
+            .filter(|j| !self.basis.contains(j))
+            .find(|&j| {
+                let reduced_cost = self.phase_cost(j)
+                    - y.iter()
+                        .zip(self.a.iter())
+                        .fold(float!(0.0), |acc, (&yi, row)| acc + yi * row[j]);
+                reduced_cost < -self.tol
+            })
+    }
+
+    /// Selects the leaving row via the minimum ratio test. Ties are broken by choosing the basic
+    /// variable with the smallest index (Bland's rule). Returns `None` if the entering direction
+    /// is unbounded.
+    fn leaving(&self, d: &[F]) -> Option<usize> {
+        let mut best: Option<(usize, F)> = None;
+        for (i, (&di, &xi)) in d.iter().zip(self.x_b.iter()).enumerate() {
+            if di <= self.tol {
+                continue;
+            }
+            let ratio = xi / di;
+            best = match best {
+                None => Some((i, ratio)),
+                Some((r, best_ratio)) => {
+                    if ratio < best_ratio - self.tol
+                        || (ratio <= best_ratio + self.tol && self.basis[i] < self.basis[r])
+                    {
+                        Some((i, ratio))
+                    } else {
+                        Some((r, best_ratio))
+                    }
+                }
+            };
+        }
+        best.map(|(r, _)| r)
+    }
+
+    /// Exchanges the basic variable of row `r` with variable `j`, where `d = B^-1 A_j`.
+    fn pivot(&mut self, r: usize, j: usize, d: &[F]) {
+        let pivot = d[r];
+        for v in self.b_inv[r].iter_mut() {
+            *v = *v / pivot;
+        }
+        self.x_b[r] = self.x_b[r] / pivot;
+        let pivot_row = self.b_inv[r].clone();
+        let pivot_x = self.x_b[r];
+        for (i, &di) in d.iter().enumerate() {
+            if i == r || di == float!(0.0) {
+                continue;
+            }
+            for (v, &p) in self.b_inv[i].iter_mut().zip(pivot_row.iter()) {
+                *v = *v - di * p;
+            }
+            self.x_b[i] = self.x_b[i] - di * pivot_x;
+        }
+        self.basis[r] = j;
+    }
+
+    /// Removes artificial variables from the basis after phase one. Rows in which no original
+    /// variable can be pivoted in are redundant; their artificial variable remains in the basis at
+    /// level zero.
+    fn drive_out_artificials(&mut self) {
+        for r in 0..self.basis.len() {
+            if self.basis[r] < self.num_vars {
+                continue;
+            }
+            let candidate = (0..self.num_vars)
+                .filter(|j| !self.basis.contains(j))
+                .map(|j| (j, self.column(j)))
+                .find(|(_, d)| d[r].abs() > self.tol);
+            if let Some((j, d)) = candidate {
+                self.pivot(r, j, &d);
+            }
+        }
+    }
+
+    /// Current values of the original variables
+    fn solution(&self) -> Vec<F> {
+        let mut x = vec![float!(0.0); self.num_vars];
+        for (&bi, &xi) in self.basis.iter().zip(self.x_b.iter()) {
+            if bi < self.num_vars {
+                x[bi] = xi.max(float!(0.0));
+            }
+        }
+        x
+    }
+
+    /// Objective of the current phase
+    fn phase_objective(&self) -> F {
+        self.basis
+            .iter()
+            .zip(self.x_b.iter())
+            .fold(float!(0.0), |acc, (&bi, &xi)| {
+                acc + self.phase_cost(bi) * xi
+            })
+    }
+
+    /// Cost reported to the state: The objective function value if the current basis is feasible,
+    /// infinity otherwise.
+    fn reported_cost(&self, x: &[F]) -> F {
+        match self.phase {
+            Phase::One => F::infinity(),
+            Phase::Two => dot(&self.c, x),
+        }
+    }
+
+    fn kv(&self) -> KV {
+        kv!(
+            "phase" => match self.phase { Phase::One => 1u64, Phase::Two => 2u64 };
+            "phase_objective" => self.phase_objective();
+        )
+    }
+}
+
+impl<F: ArgminFloat> Default for Simplex<F> {
+    fn default() -> Simplex<F> {
+        Simplex::new()
+    }
+}
+
+impl<O, F> Solver<O, LinearProgramState<Vec<F>, F>> for Simplex<F>
+where
+    O: LinearProgram<Param = Vec<F>, Float = F>,
+    F: ArgminFloat,
+{
+    const NAME: &'static str = "Simplex";
+
+    fn init(
+        &mut self,
+        problem: &mut Problem<O>,
+        state: LinearProgramState<Vec<F>, F>,
+    ) -> Result<(LinearProgramState<Vec<F>, F>, Option<KV>), Error> {
+        let (mut a, mut b, c) = standard_form(problem, "Simplex")?;
+        let m = b.len();
+        let n = c.len();
+
+        // Artificial variables require a nonnegative right-hand side
+        for (row, bi) in a.iter_mut().zip(b.iter_mut()) {
+            if *bi < float!(0.0) {
+                *bi = -*bi;
+                for v in row.iter_mut() {
+                    *v = -*v;
+                }
+            }
+        }
+
+        // Append artificial variables. They form the initial basis, therefore B^-1 = I.
+        let identity: Vec<Vec<F>> = (0..m)
+            .map(|i| {
+                (0..m)
+                    .map(|k| if i == k { float!(1.0) } else { float!(0.0) })
+                    .collect()
+            })
+            .collect();
+        for (row, e) in a.iter_mut().zip(identity.iter()) {
+            row.extend_from_slice(e);
+        }
+
+        self.a = a;
+        self.c = c;
+        self.num_vars = n;
+        self.basis = (n..n + m).collect();
+        self.b_inv = identity;
+        self.x_b = b;
+        self.phase = Phase::One;
+
+        let x = self.solution();
+        let cost = self.reported_cost(&x);
+        Ok((state.param(x).cost(cost), Some(self.kv())))
+    }
+
+    fn next_iter(
+        &mut self,
+        _problem: &mut Problem<O>,
+        mut state: LinearProgramState<Vec<F>, F>,
+    ) -> Result<(LinearProgramState<Vec<F>, F>, Option<KV>), Error> {
+        match self.entering() {
+            None => match self.phase {
+                Phase::One => {
+                    let scale = self.x_b.iter().fold(float!(1.0), |acc, x| acc.max(x.abs()));
+                    if self.phase_objective() > self.tol * scale {
+                        state = state.terminate_with(TerminationReason::ProblemInfeasible);
+                    } else {
+                        self.drive_out_artificials();
+                        self.phase = Phase::Two;
+                    }
+                }
+                Phase::Two => {
+                    state = state.terminate_with(TerminationReason::SolverConverged);
+                }
+            },
+            Some(j) => {
+                let d = self.column(j);
+                match self.leaving(&d) {
+                    Some(r) => self.pivot(r, j, &d),
+                    None => {
+                        state = state.terminate_with(TerminationReason::ProblemUnbounded);
+                    }
+                }
+            }
+        }
+
+        let x = self.solution();
+        let cost = self.reported_cost(&x);
+        Ok((state.param(x).cost(cost), Some(self.kv())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{beale_lp, infeasible_lp, redundant_lp, unbounded_lp, SmallLp};
+    use super::*;
+    use crate::core::{ArgminError, Executor, TerminationStatus};
+    use approx::assert_relative_eq;
+
+    test_trait_impl!(simplex, Simplex<f64>);
+
+    fn run<O>(problem: O) -> LinearProgramState<Vec<f64>, f64>
+    where
+        O: LinearProgram<Param = Vec<f64>, Float = f64>,
+    {
+        Executor::new(problem, Simplex::new())
+            .configure(|state| state.max_iters(100))
+            .run()
+            .unwrap()
+            .state
+    }
+
+    #[test]
+    fn test_new() {
+        let Simplex {
+            tol,
+            a,
+            c,
+            num_vars,
+            basis,
+            b_inv,
+            x_b,
+            phase,
+        } = Simplex::<f64>::new();
+        assert_eq!(tol.to_ne_bytes(), f64::EPSILON.sqrt().to_ne_bytes());
+        assert!(a.is_empty());
+        assert!(c.is_empty());
+        assert_eq!(num_vars, 0);
+        assert!(basis.is_empty());
+        assert!(b_inv.is_empty());
+        assert!(x_b.is_empty());
+        assert_eq!(phase, Phase::One);
+    }
+
+    #[test]
+    fn test_with_tolerance() {
+        let simplex = Simplex::new().with_tolerance(1e-4f64).unwrap();
+        assert_eq!(simplex.tol.to_ne_bytes(), 1e-4f64.to_ne_bytes());
+
+        for tol in [0.0, -1.0] {
+            assert_error!(
+                Simplex::new().with_tolerance(tol),
+                ArgminError,
+                "Invalid parameter: \"`Simplex`: tolerance must be > 0.\""
+            );
+        }
+    }
+
+    #[test]
+    fn test_init() {
+        let mut simplex: Simplex<f64> = Simplex::new();
+        let (state, kv) = simplex
+            .init(&mut Problem::new(SmallLp {}), LinearProgramState::new())
+            .unwrap();
+        assert_eq!(simplex.basis, vec![4, 5]);
+        assert_eq!(simplex.x_b, vec![4.0, 6.0]);
+        assert_eq!(simplex.a[0], vec![1.0, 1.0, 1.0, 0.0, 1.0, 0.0]);
+        assert_eq!(simplex.a[1], vec![1.0, 3.0, 0.0, 1.0, 0.0, 1.0]);
+        assert_eq!(state.param.unwrap(), vec![0.0; 4]);
+        assert!(state.cost.is_infinite());
+        assert_eq!(kv.unwrap().get("phase").unwrap().get_uint(), Some(1));
+    }
+
+    #[test]
+    fn test_negative_rhs() {
+        // -x1 - x2 = -2 is equivalent to x1 + x2 = 2
+        let lp = super::super::tests::Lp {
+            a: vec![vec![-1.0, -1.0]],
+            b: vec![-2.0],
+            c: vec![1.0, 2.0],
+        };
+        let state = run(lp);
+        assert_eq!(
+            state.termination_status,
+            TerminationStatus::Terminated(TerminationReason::SolverConverged)
+        );
+        assert_relative_eq!(state.best_cost, 2.0, epsilon = 1e-10);
+        assert_relative_eq!(state.best_param.unwrap()[0], 2.0, epsilon = 1e-10);
+    }
+
+    #[test]
+    fn test_solver() {
+        let state = run(SmallLp {});
+        assert_eq!(
+            state.termination_status,
+            TerminationStatus::Terminated(TerminationReason::SolverConverged)
+        );
+        assert_relative_eq!(state.best_cost, -5.0, epsilon = 1e-10);
+        let x = state.best_param.unwrap();
+        for (xi, ei) in x.iter().zip([3.0, 1.0, 0.0, 0.0].iter()) {
+            assert_relative_eq!(xi, ei, epsilon = 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_infeasible() {
+        let state = run(infeasible_lp());
+        assert_eq!(
+            state.termination_status,
+            TerminationStatus::Terminated(TerminationReason::ProblemInfeasible)
+        );
+    }
+
+    #[test]
+    fn test_unbounded() {
+        let state = run(unbounded_lp());
+        assert_eq!(
+            state.termination_status,
+            TerminationStatus::Terminated(TerminationReason::ProblemUnbounded)
+        );
+    }
+
+    #[test]
+    fn test_degenerate_does_not_cycle() {
+        let state = run(beale_lp());
+        assert_eq!(
+            state.termination_status,
+            TerminationStatus::Terminated(TerminationReason::SolverConverged)
+        );
+        assert_relative_eq!(state.best_cost, -1.25, epsilon = 1e-10);
+        let x = state.best_param.unwrap();
+        for (xi, ei) in x.iter().zip([0.75, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0].iter()) {
+            assert_relative_eq!(xi, ei, epsilon = 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_redundant_constraints() {
+        let state = run(redundant_lp());
+        assert_eq!(
+            state.termination_status,
+            TerminationStatus::Terminated(TerminationReason::SolverConverged)
+        );
+        assert_relative_eq!(state.best_cost, 2.0, epsilon = 1e-10);
+        let x = state.best_param.unwrap();
+        assert_relative_eq!(x[0], 2.0, epsilon = 1e-10);
+        assert_relative_eq!(x[1], 0.0, epsilon = 1e-10);
+    }
+}
@@ -5,16 +5,25 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+pub mod augmentedlagrangian;
 pub mod brent;
+pub mod cmaes;
 pub mod conjugategradient;
+pub mod differentialevolution;
 pub mod gaussnewton;
 pub mod goldensectionsearch;
 pub mod gradientdescent;
 pub mod landweber;
+pub mod levenbergmarquardt;
+pub mod linearprogramming;
 pub mod linesearch;
+pub mod multistart;
 pub mod neldermead;
 pub mod newton;
 pub mod particleswarm;
 pub mod quasinewton;
 pub mod simulatedannealing;
+pub mod sqp;
 pub mod trustregion;
+
+mod utils;
@@ -0,0 +1,185 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::core::{
+    ArgminFloat, DeserializeOwnedAlias, Error, Executor, IterState, OptimizationResult, Problem,
+    SerializeAlias, Solver, State,
+};
+use argmin_math::{ArgminL2Norm, ArgminSub};
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// A local minimum found by one of the local searches of a meta-solver
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct LocalMinimum<P, F> {
+    /// Parameter vector of the local minimum
+    pub param: P,
+    /// Cost function value of the local minimum
+    pub cost: F,
+    /// Number of local searches which ended in this minimum
+    pub hits: u64,
+}
+
+impl<P, F> LocalMinimum<P, F> {
+    /// Create a new local minimum with a given parameter vector and cost.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::multistart::LocalMinimum;
+    /// let minimum: LocalMinimum<Vec<f64>, f64> = LocalMinimum::new(vec![0.0, 1.4], 12.0);
+    /// # assert_eq!(minimum.hits, 1);
+    /// ```
+    pub fn new(param: P, cost: F) -> Self {
+        LocalMinimum {
+            param,
+            cost,
+            hits: 1,
+        }
+    }
+}
+
+/// Archive of distinct local minima, ranked by their cost function values
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub(crate) struct MinimaArchive<P, F> {
+    /// Local minima, best first
+    pub(crate) minima: Vec<LocalMinimum<P, F>>,
+    /// Maximum number of minima kept
+    pub(crate) size: usize,
+    /// Minima closer than this (in the L2 norm) are considered to be the same
+    pub(crate) tolerance: F,
+}
+
+impl<P, F> MinimaArchive<P, F>
+where
+    P: ArgminSub<P, P> + ArgminL2Norm<F>,
+    F: ArgminFloat,
+{
+    pub(crate) fn new() -> Self {
+        MinimaArchive {
+            minima: vec![],
+            size: 10,
+            tolerance: float!(1e-4),
+        }
+    }
+
+    /// Inserts a local minimum and returns whether it was not known before.
+    ///
+    /// If the archive already holds a minimum within the tolerance, its hit count is increased and
+    /// it is replaced by the new one if the new one has a lower cost. Otherwise the minimum is
+    /// inserted according to its rank and the worst minimum is dropped if the archive is full.
+    pub(crate) fn insert(&mut self, param: P, cost: F) -> bool {
+        let known = self
+            .minima
+            .iter()
+            .position(|m| m.param.sub(&param).l2_norm() <= self.tolerance);
+        let new = if let Some(idx) = known {
+            let mut minimum = self.minima.remove(idx);
+            minimum.hits += 1;
+            if cost < minimum.cost {
+                minimum.param = param;
+                minimum.cost = cost;
+            }
+            minimum
+        } else {
+            LocalMinimum::new(param, cost)
+        };
+        let idx = self
+            .minima
+            .iter()
+            .position(|m| new.cost < m.cost || m.cost.is_nan())
+            .unwrap_or(self.minima.len());
+        self.minima.insert(idx, new);
+        self.minima.truncate(self.size);
+        known.is_none()
+    }
+}
+
+/// Runs the inner solver from `start` and returns the best parameter vector and cost found as
+/// well as the number of iterations of the inner solver.
+///
+/// The function evaluation counts of the local search are merged into `problem`.
+pub(crate) fn local_search<O, S, P, G, J, H, F>(
+    problem: &mut Problem<O>,
+    inner: &S,
+    start: P,
+    max_iters: u64,
+) -> Result<(P, F, u64), Error>
+where
+    S: Solver<O, IterState<P, G, J, H, F>> + Clone,
+    P: Clone + SerializeAlias + DeserializeOwnedAlias,
+    G: Clone + SerializeAlias + DeserializeOwnedAlias,
+    J: Clone + SerializeAlias + DeserializeOwnedAlias,
+    H: Clone + SerializeAlias + DeserializeOwnedAlias,
+    F: ArgminFloat,
+{
+    let OptimizationResult {
+        problem: inner_problem,
+        state: mut inner_state,
+        ..
+    } = Executor::new(problem.take_problem().unwrap(), inner.clone())
+        .configure(|config| config.param(start).max_iters(max_iters))
+        .ctrlc(false)
+        .run()?;
+    problem.consume_problem(inner_problem);
+
+    let iters = inner_state.get_iter();
+    let cost = inner_state.get_best_cost();
+    let param = inner_state
+        .take_best_param()
+        .or_else(|| inner_state.take_param())
+        .ok_or_else(argmin_error_closure!(
+            PotentialBug,
+            "Inner solver did not return a parameter vector."
+        ))?;
+    Ok((param, cost, iters))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_ranked() {
+        let mut archive: MinimaArchive<Vec<f64>, f64> = MinimaArchive::new();
+        assert!(archive.insert(vec![1.0], 3.0));
+        assert!(archive.insert(vec![2.0], 1.0));
+        assert!(archive.insert(vec![3.0], 2.0));
+        let costs: Vec<f64> = archive.minima.iter().map(|m| m.cost).collect();
+        assert_eq!(costs, vec![1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_insert_known() {
+        let mut archive: MinimaArchive<Vec<f64>, f64> = MinimaArchive::new();
+        assert!(archive.insert(vec![1.0], 3.0));
+        assert!(archive.insert(vec![2.0], 2.0));
+        // Within tolerance of the first minimum and better than both
+        assert!(!archive.insert(vec![1.0 + 1e-5], 1.0));
+        assert_eq!(archive.minima.len(), 2);
+        assert_eq!(archive.minima[0].param, vec![1.0 + 1e-5]);
+        assert_eq!(archive.minima[0].hits, 2);
+        assert_eq!(archive.minima[1].param, vec![2.0]);
+        // Worse than the stored one: only the hit count changes
+        assert!(!archive.insert(vec![2.0 - 1e-5], 4.0));
+        assert_eq!(archive.minima[1].param, vec![2.0]);
+        assert_eq!(archive.minima[1].hits, 2);
+    }
+
+    #[test]
+    fn test_insert_truncate() {
+        let mut archive: MinimaArchive<Vec<f64>, f64> = MinimaArchive::new();
+        archive.size = 2;
+        archive.insert(vec![1.0], 3.0);
+        archive.insert(vec![2.0], 1.0);
+        archive.insert(vec![3.0], 2.0);
+        let params: Vec<Vec<f64>> = archive.minima.iter().map(|m| m.param.clone()).collect();
+        assert_eq!(params, vec![vec![2.0], vec![3.0]]);
+    }
+}
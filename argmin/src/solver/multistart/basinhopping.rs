@@ -0,0 +1,543 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::core::{
+    ArgminFloat, DeserializeOwnedAlias, Error, IterState, Problem, SerializeAlias, Solver, State,
+    KV,
+};
+use crate::solver::multistart::archive::{local_search, LocalMinimum, MinimaArchive};
+use argmin_math::{ArgminAdd, ArgminL2Norm, ArgminMinMax, ArgminRandom, ArgminSub};
+use rand::{Rng, SeedableRng};
+use rand_xoshiro::Xoshiro256PlusPlus;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// # Basin-hopping
+///
+/// Repeatedly perturbs the best parameter vector found so far and runs a local search with an
+/// inner solver from the perturbed parameter vector. Each iteration performs one local search.
+/// The perturbation is drawn uniformly from `[-step_size, step_size]` in each dimension, where the
+/// step size defaults to `0.5` and can be set with
+/// [`with_step_size`](`BasinHopping::with_step_size`). If bounds are set with
+/// [`with_bounds`](`BasinHopping::with_bounds`), the perturbed parameter vectors are limited to
+/// them.
+///
+/// Any solver which operates on an [`IterState`] and accepts an initial parameter vector, such as
+/// [`LBFGS`](`crate::solver::quasinewton::LBFGS`) or
+/// [`NelderMead`](`crate::solver::neldermead::NelderMead`), can be used as inner solver. Each local
+/// search starts with a fresh copy of the inner solver passed to [`BasinHopping::new`]. The number
+/// of iterations of each local search is limited by
+/// [`with_inner_max_iters`](`BasinHopping::with_inner_max_iters`). The function evaluations of the
+/// local searches are added to the function evaluation counts of the outer problem.
+///
+/// The best parameter vector of the state is the best local minimum found so far. In addition, a
+/// ranked archive of the distinct local minima found is kept, which is available via
+/// [`minima`](`BasinHopping::minima`). Two minima are considered to be the same if the L2 norm of
+/// their difference is below the tolerance set with
+/// [`with_minima_tolerance`](`BasinHopping::with_minima_tolerance`).
+///
+/// An initial parameter vector is required, which is to be provided via the
+/// [`configure`](`crate::core::Executor::configure`) method of the
+/// [`Executor`](`crate::core::Executor`). The method does not have a convergence criterion of its
+/// own. The number of local searches is therefore to be limited via `max_iters`.
+///
+/// The random perturbations are generated by the RNG of the solver. With a seeded RNG passed to
+/// [`new_with_rng`](`BasinHopping::new_with_rng`), the sequence of hops is the same in each run,
+/// provided that the inner solver is deterministic.
+///
+/// ## Requirements on the optimization problem
+///
+/// The requirements are those of the inner solver.
+///
+/// ## Reference
+///
+/// David J. Wales and Jonathan P. K. Doye (1997). Global Optimization by Basin-Hopping and the
+/// Lowest Energy Structures of Lennard-Jones Clusters Containing up to 110 Atoms. The Journal of
+/// Physical Chemistry A 101(28), pp. 5111-5116. <https://doi.org/10.1021/jp970984n>
+#[derive(Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct BasinHopping<S, P, F, R = Xoshiro256PlusPlus> {
+    /// Inner solver
+    inner: S,
+    /// Maximum perturbation in each dimension
+    step_size: F,
+    /// Optional bounds on parameter space
+    bounds: Option<(P, P)>,
+    /// Maximum number of iterations of each local search
+    inner_max_iters: u64,
+    /// Archive of distinct local minima
+    archive: MinimaArchive<P, F>,
+    /// Random number generator
+    rng: R,
+}
+
+impl<S, P, F> BasinHopping<S, P, F, Xoshiro256PlusPlus>
+where
+    P: ArgminSub<P, P> + ArgminL2Norm<F>,
+    F: ArgminFloat,
+{
+    /// Construct a new instance of [`BasinHopping`]
+    ///
+    /// Takes the inner solver as input.
+    ///
+    /// Uses the `Xoshiro256PlusPlus` RNG internally. For use of another RNG, consider using
+    /// [`BasinHopping::new_with_rng`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::multistart::BasinHopping;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// # let inner: NelderMead<Vec<f64>, f64> = NelderMead::new(vec![vec![0.0], vec![1.0]]);
+    /// let solver: BasinHopping<_, Vec<f64>, f64> = BasinHopping::new(inner);
+    /// ```
+    pub fn new(inner: S) -> Self {
+        BasinHopping::new_with_rng(inner, Xoshiro256PlusPlus::from_entropy())
+    }
+}
+
+impl<S, P, F, R> BasinHopping<S, P, F, R>
+where
+    P: ArgminSub<P, P> + ArgminL2Norm<F>,
+    F: ArgminFloat,
+{
+    /// Construct a new instance of [`BasinHopping`] with a given random number generator
+    ///
+    /// Same as [`BasinHopping::new`], but requires a RNG which must implement `rand::Rng` (and
+    /// `serde::Serialize` if the `serde1` feature is enabled).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::multistart::BasinHopping;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// use rand::SeedableRng;
+    /// use rand_xoshiro::Xoshiro256PlusPlus;
+    /// # let inner: NelderMead<Vec<f64>, f64> = NelderMead::new(vec![vec![0.0], vec![1.0]]);
+    /// let rng = Xoshiro256PlusPlus::seed_from_u64(42);
+    /// let solver: BasinHopping<_, Vec<f64>, f64, _> = BasinHopping::new_with_rng(inner, rng);
+    /// ```
+    pub fn new_with_rng(inner: S, rng: R) -> Self {
+        BasinHopping {
+            inner,
+            step_size: float!(0.5),
+            bounds: None,
+            inner_max_iters: 1000,
+            archive: MinimaArchive::new(),
+            rng,
+        }
+    }
+
+    /// Set the maximum perturbation in each dimension
+    ///
+    /// Defaults to `0.5`. Must be larger than 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::multistart::BasinHopping;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// # let inner: NelderMead<Vec<f64>, f64> = NelderMead::new(vec![vec![0.0], vec![1.0]]);
+    /// let solver: BasinHopping<_, Vec<f64>, f64> = BasinHopping::new(inner).with_step_size(1.0)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_step_size(mut self, step_size: F) -> Result<Self, Error> {
+        if step_size <= float!(0.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`BasinHopping`: step size must be > 0."
+            ));
+        }
+        self.step_size = step_size;
+        Ok(self)
+    }
+
+    /// Limit the perturbed parameter vectors to the given bounds
+    ///
+    /// `bounds` is a tuple `(lower_bound, upper_bound)`, where `lower_bound` and `upper_bound` are
+    /// of the same type as the parameter vector (`P`) and of the same length as the problem as
+    /// dimensions. The local searches themselves are not restricted to the bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::multistart::BasinHopping;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// # let inner: NelderMead<Vec<f64>, f64> = NelderMead::new(vec![vec![0.0], vec![1.0]]);
+    /// let solver: BasinHopping<_, Vec<f64>, f64> =
+    ///     BasinHopping::new(inner).with_bounds((vec![-1.0], vec![1.0]));
+    /// ```
+    #[must_use]
+    pub fn with_bounds(mut self, bounds: (P, P)) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    /// Set maximum number of iterations of each local search
+    ///
+    /// Defaults to `1000`. Must be larger than 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::multistart::BasinHopping;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// # let inner: NelderMead<Vec<f64>, f64> = NelderMead::new(vec![vec![0.0], vec![1.0]]);
+    /// let solver: BasinHopping<_, Vec<f64>, f64> =
+    ///     BasinHopping::new(inner).with_inner_max_iters(100)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_inner_max_iters(mut self, iters: u64) -> Result<Self, Error> {
+        if iters == 0 {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`BasinHopping`: inner max iters must be > 0."
+            ));
+        }
+        self.inner_max_iters = iters;
+        Ok(self)
+    }
+
+    /// Set the tolerance below which two local minima are considered to be the same
+    ///
+    /// Defaults to `1e-4`. Must be non-negative.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::multistart::BasinHopping;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// # let inner: NelderMead<Vec<f64>, f64> = NelderMead::new(vec![vec![0.0], vec![1.0]]);
+    /// let solver: BasinHopping<_, Vec<f64>, f64> =
+    ///     BasinHopping::new(inner).with_minima_tolerance(1e-3)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_minima_tolerance(mut self, tol: F) -> Result<Self, Error> {
+        if tol < float!(0.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`BasinHopping`: minima tolerance must be >= 0."
+            ));
+        }
+        self.archive.tolerance = tol;
+        Ok(self)
+    }
+
+    /// Set the maximum number of distinct local minima kept in the archive
+    ///
+    /// Defaults to `10`. Must be larger than 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::multistart::BasinHopping;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// # let inner: NelderMead<Vec<f64>, f64> = NelderMead::new(vec![vec![0.0], vec![1.0]]);
+    /// let solver: BasinHopping<_, Vec<f64>, f64> =
+    ///     BasinHopping::new(inner).with_archive_size(20)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_archive_size(mut self, size: usize) -> Result<Self, Error> {
+        if size == 0 {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`BasinHopping`: archive size must be > 0."
+            ));
+        }
+        self.archive.size = size;
+        Ok(self)
+    }
+}
+
+impl<S, P, F, R> BasinHopping<S, P, F, R> {
+    /// Returns the distinct local minima found so far, best first
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::multistart::BasinHopping;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// # let inner: NelderMead<Vec<f64>, f64> = NelderMead::new(vec![vec![0.0], vec![1.0]]);
+    /// # let solver: BasinHopping<_, Vec<f64>, f64> = BasinHopping::new(inner);
+    /// let minima = solver.minima();
+    /// # assert!(minima.is_empty());
+    /// ```
+    pub fn minima(&self) -> &[LocalMinimum<P, F>] {
+        &self.archive.minima
+    }
+}
+
+impl<S, P, F, R> BasinHopping<S, P, F, R>
+where
+    P: ArgminAdd<F, P> + ArgminSub<F, P> + ArgminMinMax + ArgminRandom,
+    F: ArgminFloat,
+    R: Rng,
+{
+    /// Perturbs `param` randomly and limits the result to the bounds (if any)
+    fn perturb(&mut self, param: &P) -> P {
+        let param = P::rand_from_range(
+            &param.sub(&self.step_size),
+            &param.add(&self.step_size),
+            &mut self.rng,
+        );
+        match self.bounds.as_ref() {
+            Some((lower, upper)) => P::min(&P::max(&param, lower), upper),
+            None => param,
+        }
+    }
+}
+
+impl<O, S, P, G, J, H, F, R> Solver<O, IterState<P, G, J, H, F>> for BasinHopping<S, P, F, R>
+where
+    S: Solver<O, IterState<P, G, J, H, F>> + Clone + SerializeAlias,
+    P: Clone
+        + SerializeAlias
+        + DeserializeOwnedAlias
+        + ArgminSub<P, P>
+        + ArgminL2Norm<F>
+        + ArgminAdd<F, P>
+        + ArgminSub<F, P>
+        + ArgminMinMax
+        + ArgminRandom,
+    G: Clone + SerializeAlias + DeserializeOwnedAlias,
+    J: Clone + SerializeAlias + DeserializeOwnedAlias,
+    H: Clone + SerializeAlias + DeserializeOwnedAlias,
+    F: ArgminFloat,
+    R: Rng + SerializeAlias,
+{
+    const NAME: &'static str = "Basin-hopping";
+
+    fn init(
+        &mut self,
+        problem: &mut Problem<O>,
+        mut state: IterState<P, G, J, H, F>,
+    ) -> Result<(IterState<P, G, J, H, F>, Option<KV>), Error> {
+        let start = state.take_param().ok_or_else(argmin_error_closure!(
+            NotInitialized,
+            concat!(
+                "`BasinHopping` requires an initial parameter vector. ",
+                "Please provide an initial guess via `Executor`s `configure` method."
+            )
+        ))?;
+        self.archive.minima.clear();
+        let (param, cost, inner_iters) =
+            local_search(problem, &self.inner, start, self.inner_max_iters)?;
+        self.archive.insert(param.clone(), cost);
+        Ok((
+            state.param(param).cost(cost),
+            Some(kv!(
+                "inner_iters" => inner_iters;
+                "num_minima" => self.archive.minima.len() as u64;
+            )),
+        ))
+    }
+
+    fn next_iter(
+        &mut self,
+        problem: &mut Problem<O>,
+        state: IterState<P, G, J, H, F>,
+    ) -> Result<(IterState<P, G, J, H, F>, Option<KV>), Error> {
+        let best = state.get_best_param().ok_or_else(argmin_error_closure!(
+            PotentialBug,
+            "`BasinHopping`: Best parameter vector in state not set."
+        ))?;
+        let start = self.perturb(best);
+        let (param, cost, inner_iters) =
+            local_search(problem, &self.inner, start, self.inner_max_iters)?;
+        self.archive.insert(param.clone(), cost);
+        Ok((
+            state.param(param).cost(cost),
+            Some(kv!(
+                "inner_iters" => inner_iters;
+                "num_minima" => self.archive.minima.len() as u64;
+            )),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ArgminError, CostFunction, Executor};
+    use crate::solver::neldermead::NelderMead;
+    use approx::assert_relative_eq;
+
+    type Inner = NelderMead<Vec<f64>, f64>;
+
+    test_trait_impl!(
+        basinhopping,
+        BasinHopping<Inner, Vec<f64>, f64>
+    );
+
+    /// `f(x) = (x^2 - 1)^2 + 0.2 x` has a global minimum at `x ~ -1.02` and a local minimum at
+    /// `x ~ 0.97`
+    #[derive(Clone)]
+    struct DoubleWell {}
+
+    impl CostFunction for DoubleWell {
+        type Param = Vec<f64>;
+        type Output = f64;
+
+        fn cost(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+            Ok((p[0].powi(2) - 1.0).powi(2) + 0.2 * p[0])
+        }
+    }
+
+    fn nelder_mead() -> Inner {
+        NelderMead::new(vec![vec![0.0], vec![0.1]])
+            .with_sd_tolerance(1e-10)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_new() {
+        let solver: BasinHopping<Inner, Vec<f64>, f64> = BasinHopping::new(nelder_mead());
+        let BasinHopping {
+            inner: _,
+            step_size,
+            bounds,
+            inner_max_iters,
+            archive,
+            rng: _,
+        } = solver;
+        assert_eq!(step_size.to_ne_bytes(), 0.5f64.to_ne_bytes());
+        assert!(bounds.is_none());
+        assert_eq!(inner_max_iters, 1000);
+        assert!(archive.minima.is_empty());
+        assert_eq!(archive.size, 10);
+        assert_eq!(archive.tolerance.to_ne_bytes(), 1e-4f64.to_ne_bytes());
+    }
+
+    #[test]
+    fn test_builders() {
+        let solver: BasinHopping<Inner, Vec<f64>, f64> = BasinHopping::new(nelder_mead())
+            .with_step_size(2.0)
+            .unwrap()
+            .with_bounds((vec![-1.0], vec![1.0]))
+            .with_inner_max_iters(20)
+            .unwrap()
+            .with_minima_tolerance(0.0)
+            .unwrap()
+            .with_archive_size(3)
+            .unwrap();
+        assert_eq!(solver.step_size.to_ne_bytes(), 2.0f64.to_ne_bytes());
+        assert_eq!(solver.bounds, Some((vec![-1.0], vec![1.0])));
+        assert_eq!(solver.inner_max_iters, 20);
+        assert_eq!(solver.archive.tolerance.to_ne_bytes(), 0.0f64.to_ne_bytes());
+        assert_eq!(solver.archive.size, 3);
+    }
+
+    #[test]
+    fn test_builder_errors() {
+        let solver = || -> BasinHopping<Inner, Vec<f64>, f64> { BasinHopping::new(nelder_mead()) };
+        assert_error!(
+            solver().with_step_size(0.0),
+            ArgminError,
+            "Invalid parameter: \"`BasinHopping`: step size must be > 0.\""
+        );
+        assert_error!(
+            solver().with_inner_max_iters(0),
+            ArgminError,
+            "Invalid parameter: \"`BasinHopping`: inner max iters must be > 0.\""
+        );
+        assert_error!(
+            solver().with_minima_tolerance(-1.0),
+            ArgminError,
+            "Invalid parameter: \"`BasinHopping`: minima tolerance must be >= 0.\""
+        );
+        assert_error!(
+            solver().with_archive_size(0),
+            ArgminError,
+            "Invalid parameter: \"`BasinHopping`: archive size must be > 0.\""
+        );
+    }
+
+    #[test]
+    fn test_init_param_not_initialized() {
+        let mut solver: BasinHopping<Inner, Vec<f64>, f64> = BasinHopping::new(nelder_mead());
+        let res = solver.init(&mut Problem::new(DoubleWell {}), IterState::new());
+        assert_error!(
+            res,
+            ArgminError,
+            concat!(
+                "Not initialized: \"`BasinHopping` requires an initial parameter vector. ",
+                "Please provide an initial guess via `Executor`s `configure` method.\""
+            )
+        );
+    }
+
+    #[test]
+    fn test_perturb() {
+        let mut solver: BasinHopping<Inner, Vec<f64>, f64> = BasinHopping::new(nelder_mead())
+            .with_step_size(1.0)
+            .unwrap()
+            .with_bounds((vec![-0.5, -0.5], vec![0.5, 0.5]));
+        for _ in 0..100 {
+            let p = solver.perturb(&vec![0.0, 0.4]);
+            assert!((-0.5..=0.5).contains(&p[0]));
+            assert!((-0.5..=0.5).contains(&p[1]));
+        }
+    }
+
+    #[test]
+    fn test_nelder_mead() {
+        let solver = BasinHopping::new(nelder_mead())
+            .with_step_size(1.5)
+            .unwrap()
+            .with_minima_tolerance(1e-2)
+            .unwrap();
+        let res = Executor::new(DoubleWell {}, solver)
+            .configure(|state| state.param(vec![1.5]).max_iters(50))
+            .run()
+            .unwrap();
+        let minima = res.solver.minima();
+        assert_eq!(minima.len(), 2);
+        assert_relative_eq!(minima[0].param[0], -1.02, epsilon = 1e-2);
+        assert_relative_eq!(minima[1].param[0], 0.97, epsilon = 1e-2);
+        assert_relative_eq!(
+            res.state.get_best_param().unwrap()[0],
+            -1.02,
+            epsilon = 1e-2
+        );
+        assert!(res.problem.counts["cost_count"] > 0);
+    }
+
+    #[test]
+    fn test_seeded_hops() {
+        let run = |seed: u64| -> (Vec<u64>, u64) {
+            let solver =
+                BasinHopping::new_with_rng(nelder_mead(), Xoshiro256PlusPlus::seed_from_u64(seed));
+            let res = Executor::new(DoubleWell {}, solver)
+                .configure(|state| state.param(vec![1.5]).max_iters(5))
+                .run()
+                .unwrap();
+            let minima = res
+                .solver
+                .minima()
+                .iter()
+                .map(|m| m.param[0].to_bits())
+                .collect();
+            (minima, res.state.get_func_counts()["cost_count"])
+        };
+        let hops = run(42);
+        assert_eq!(hops, run(42));
+        // Other perturbations lead to other local searches
+        assert_ne!(hops, run(43));
+    }
+}
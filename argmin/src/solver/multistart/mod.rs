@@ -0,0 +1,28 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Multi-start methods
+//!
+//! Meta-solvers which repeatedly run a local search with an inner solver from different starting
+//! points and keep a ranked archive of the distinct local minima found.
+//!
+//! * [`MultiStart`]
+//! * [`BasinHopping`]
+//!
+//! ## Reference
+//!
+//! David J. Wales and Jonathan P. K. Doye (1997). Global Optimization by Basin-Hopping and the
+//! Lowest Energy Structures of Lennard-Jones Clusters Containing up to 110 Atoms. The Journal of
+//! Physical Chemistry A 101(28), pp. 5111-5116. <https://doi.org/10.1021/jp970984n>
+
+mod archive;
+mod basinhopping;
+mod multistart_method;
+
+pub use self::archive::LocalMinimum;
+pub use self::basinhopping::BasinHopping;
+pub use self::multistart_method::MultiStart;
@@ -0,0 +1,482 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::core::{
+    ArgminFloat, DeserializeOwnedAlias, Error, IterState, Problem, SerializeAlias, Solver, KV,
+};
+use crate::solver::multistart::archive::{local_search, LocalMinimum, MinimaArchive};
+use argmin_math::{ArgminL2Norm, ArgminRandom, ArgminSub};
+use rand::{Rng, SeedableRng};
+use rand_xoshiro::Xoshiro256PlusPlus;
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// # Multi-start method
+///
+/// Runs a local search with an inner solver from a number of starting points sampled uniformly
+/// within the given bounds. Each iteration performs one local search. If an initial parameter
+/// vector is provided via the [`configure`](`crate::core::Executor::configure`) method of the
+/// [`Executor`](`crate::core::Executor`), the first local search starts from it.
+///
+/// Any solver which operates on an [`IterState`] and accepts an initial parameter vector, such as
+/// [`LBFGS`](`crate::solver::quasinewton::LBFGS`) or
+/// [`NelderMead`](`crate::solver::neldermead::NelderMead`), can be used as inner solver. Each local
+/// search starts with a fresh copy of the inner solver passed to [`MultiStart::new`]. The number of
+/// iterations of each local search is limited by
+/// [`with_inner_max_iters`](`MultiStart::with_inner_max_iters`). The function evaluations of the
+/// local searches are added to the function evaluation counts of the outer problem.
+///
+/// The best parameter vector of the state is the best local minimum found so far. In addition, a
+/// ranked archive of the distinct local minima found is kept, which is available via
+/// [`minima`](`MultiStart::minima`). Two minima are considered to be the same if the L2 norm of
+/// their difference is below the tolerance set with
+/// [`with_minima_tolerance`](`MultiStart::with_minima_tolerance`).
+///
+/// The method does not have a convergence criterion of its own. The number of local searches is
+/// therefore to be limited via `max_iters`.
+///
+/// Start points are sampled uniformly within the bounds using the RNG of the solver. It is
+/// serialized with the solver, so resuming from a checkpoint does not repeat start points, and
+/// seeding it via [`new_with_rng`](`MultiStart::new_with_rng`) reproduces them exactly.
+///
+/// ## Requirements on the optimization problem
+///
+/// The requirements are those of the inner solver.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct MultiStart<S, P, F, R = Xoshiro256PlusPlus> {
+    /// Inner solver
+    inner: S,
+    /// Bounds on parameter space
+    bounds: (P, P),
+    /// Maximum number of iterations of each local search
+    inner_max_iters: u64,
+    /// Archive of distinct local minima
+    archive: MinimaArchive<P, F>,
+    /// Random number generator
+    rng: R,
+}
+
+impl<S, P, F> MultiStart<S, P, F, Xoshiro256PlusPlus>
+where
+    P: ArgminSub<P, P> + ArgminL2Norm<F>,
+    F: ArgminFloat,
+{
+    /// Construct a new instance of [`MultiStart`]
+    ///
+    /// Takes the inner solver and the bounds on the search space as inputs. `bounds` is a tuple
+    /// `(lower_bound, upper_bound)`, where `lower_bound` and `upper_bound` are of the same type as
+    /// the parameter vector (`P`) and of the same length as the problem as dimensions.
+    ///
+    /// Uses the `Xoshiro256PlusPlus` RNG internally. For use of another RNG, consider using
+    /// [`MultiStart::new_with_rng`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::multistart::MultiStart;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// # let inner: NelderMead<Vec<f64>, f64> = NelderMead::new(vec![vec![0.0], vec![1.0]]);
+    /// # let lower_bound: Vec<f64> = vec![-1.0];
+    /// # let upper_bound: Vec<f64> = vec![1.0];
+    /// let solver: MultiStart<_, _, f64> = MultiStart::new(inner, (lower_bound, upper_bound));
+    /// ```
+    pub fn new(inner: S, bounds: (P, P)) -> Self {
+        MultiStart::new_with_rng(inner, bounds, Xoshiro256PlusPlus::from_entropy())
+    }
+}
+
+impl<S, P, F, R> MultiStart<S, P, F, R>
+where
+    P: ArgminSub<P, P> + ArgminL2Norm<F>,
+    F: ArgminFloat,
+{
+    /// Construct a new instance of [`MultiStart`] with a given random number generator
+    ///
+    /// Same as [`MultiStart::new`], but requires a RNG which must implement `rand::Rng` (and
+    /// `serde::Serialize` if the `serde1` feature is enabled).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::multistart::MultiStart;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// use rand::SeedableRng;
+    /// use rand_xoshiro::Xoshiro256PlusPlus;
+    /// # let inner: NelderMead<Vec<f64>, f64> = NelderMead::new(vec![vec![0.0], vec![1.0]]);
+    /// # let lower_bound: Vec<f64> = vec![-1.0];
+    /// # let upper_bound: Vec<f64> = vec![1.0];
+    /// let rng = Xoshiro256PlusPlus::seed_from_u64(42);
+    /// let solver: MultiStart<_, _, f64, _> =
+    ///     MultiStart::new_with_rng(inner, (lower_bound, upper_bound), rng);
+    /// ```
+    pub fn new_with_rng(inner: S, bounds: (P, P), rng: R) -> Self {
+        MultiStart {
+            inner,
+            bounds,
+            inner_max_iters: 1000,
+            archive: MinimaArchive::new(),
+            rng,
+        }
+    }
+
+    /// Set maximum number of iterations of each local search
+    ///
+    /// Defaults to `1000`. Must be larger than 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::multistart::MultiStart;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// # let inner: NelderMead<Vec<f64>, f64> = NelderMead::new(vec![vec![0.0], vec![1.0]]);
+    /// let solver: MultiStart<_, _, f64> =
+    ///     MultiStart::new(inner, (vec![-1.0], vec![1.0])).with_inner_max_iters(100)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_inner_max_iters(mut self, iters: u64) -> Result<Self, Error> {
+        if iters == 0 {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`MultiStart`: inner max iters must be > 0."
+            ));
+        }
+        self.inner_max_iters = iters;
+        Ok(self)
+    }
+
+    /// Set the tolerance below which two local minima are considered to be the same
+    ///
+    /// Defaults to `1e-4`. Must be non-negative.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::multistart::MultiStart;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// # let inner: NelderMead<Vec<f64>, f64> = NelderMead::new(vec![vec![0.0], vec![1.0]]);
+    /// let solver: MultiStart<_, _, f64> =
+    ///     MultiStart::new(inner, (vec![-1.0], vec![1.0])).with_minima_tolerance(1e-3)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_minima_tolerance(mut self, tol: F) -> Result<Self, Error> {
+        if tol < float!(0.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`MultiStart`: minima tolerance must be >= 0."
+            ));
+        }
+        self.archive.tolerance = tol;
+        Ok(self)
+    }
+
+    /// Set the maximum number of distinct local minima kept in the archive
+    ///
+    /// Defaults to `10`. Must be larger than 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::multistart::MultiStart;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// # let inner: NelderMead<Vec<f64>, f64> = NelderMead::new(vec![vec![0.0], vec![1.0]]);
+    /// let solver: MultiStart<_, _, f64> =
+    ///     MultiStart::new(inner, (vec![-1.0], vec![1.0])).with_archive_size(20)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_archive_size(mut self, size: usize) -> Result<Self, Error> {
+        if size == 0 {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`MultiStart`: archive size must be > 0."
+            ));
+        }
+        self.archive.size = size;
+        Ok(self)
+    }
+}
+
+impl<S, P, F, R> MultiStart<S, P, F, R> {
+    /// Returns the distinct local minima found so far, best first
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::multistart::MultiStart;
+    /// # use argmin::solver::neldermead::NelderMead;
+    /// # let inner: NelderMead<Vec<f64>, f64> = NelderMead::new(vec![vec![0.0], vec![1.0]]);
+    /// # let solver: MultiStart<_, _, f64> = MultiStart::new(inner, (vec![-1.0], vec![1.0]));
+    /// let minima = solver.minima();
+    /// # assert!(minima.is_empty());
+    /// ```
+    pub fn minima(&self) -> &[LocalMinimum<P, F>] {
+        &self.archive.minima
+    }
+}
+
+impl<S, P, F, R> MultiStart<S, P, F, R>
+where
+    P: ArgminRandom,
+    R: Rng,
+{
+    /// Samples a starting point uniformly within the bounds
+    fn sample(&mut self) -> P {
+        P::rand_from_range(&self.bounds.0, &self.bounds.1, &mut self.rng)
+    }
+}
+
+impl<O, S, P, G, J, H, F, R> Solver<O, IterState<P, G, J, H, F>> for MultiStart<S, P, F, R>
+where
+    S: Solver<O, IterState<P, G, J, H, F>> + Clone + SerializeAlias,
+    P: Clone
+        + SerializeAlias
+        + DeserializeOwnedAlias
+        + ArgminSub<P, P>
+        + ArgminL2Norm<F>
+        + ArgminRandom,
+    G: Clone + SerializeAlias + DeserializeOwnedAlias,
+    J: Clone + SerializeAlias + DeserializeOwnedAlias,
+    H: Clone + SerializeAlias + DeserializeOwnedAlias,
+    F: ArgminFloat,
+    R: Rng + SerializeAlias,
+{
+    const NAME: &'static str = "Multi-start";
+
+    fn init(
+        &mut self,
+        problem: &mut Problem<O>,
+        mut state: IterState<P, G, J, H, F>,
+    ) -> Result<(IterState<P, G, J, H, F>, Option<KV>), Error> {
+        let start = state.take_param().unwrap_or_else(|| self.sample());
+        self.archive.minima.clear();
+        let (param, cost, inner_iters) =
+            local_search(problem, &self.inner, start, self.inner_max_iters)?;
+        self.archive.insert(param.clone(), cost);
+        Ok((
+            state.param(param).cost(cost),
+            Some(kv!(
+                "inner_iters" => inner_iters;
+                "num_minima" => self.archive.minima.len() as u64;
+            )),
+        ))
+    }
+
+    fn next_iter(
+        &mut self,
+        problem: &mut Problem<O>,
+        state: IterState<P, G, J, H, F>,
+    ) -> Result<(IterState<P, G, J, H, F>, Option<KV>), Error> {
+        let start = self.sample();
+        let (param, cost, inner_iters) =
+            local_search(problem, &self.inner, start, self.inner_max_iters)?;
+        self.archive.insert(param.clone(), cost);
+        Ok((
+            state.param(param).cost(cost),
+            Some(kv!(
+                "inner_iters" => inner_iters;
+                "num_minima" => self.archive.minima.len() as u64;
+            )),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ArgminError, CostFunction, Executor, Gradient, State};
+    use crate::solver::linesearch::MoreThuenteLineSearch;
+    use crate::solver::neldermead::NelderMead;
+    use crate::solver::quasinewton::LBFGS;
+    use approx::assert_relative_eq;
+
+    type Inner = NelderMead<Vec<f64>, f64>;
+
+    test_trait_impl!(
+        multistart,
+        MultiStart<Inner, Vec<f64>, f64>
+    );
+
+    /// `f(x) = (x^2 - 1)^2 + 0.2 x` has a global minimum at `x ~ -1.02` and a local minimum at
+    /// `x ~ 0.97`
+    #[derive(Clone)]
+    struct DoubleWell {}
+
+    impl CostFunction for DoubleWell {
+        type Param = Vec<f64>;
+        type Output = f64;
+
+        fn cost(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+            Ok((p[0].powi(2) - 1.0).powi(2) + 0.2 * p[0])
+        }
+    }
+
+    impl Gradient for DoubleWell {
+        type Param = Vec<f64>;
+        type Gradient = Vec<f64>;
+
+        fn gradient(&self, p: &Self::Param) -> Result<Self::Gradient, Error> {
+            Ok(vec![4.0 * p[0] * (p[0].powi(2) - 1.0) + 0.2])
+        }
+    }
+
+    fn nelder_mead() -> Inner {
+        NelderMead::new(vec![vec![0.0], vec![0.1]])
+            .with_sd_tolerance(1e-10)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_new() {
+        let solver: MultiStart<Inner, Vec<f64>, f64> =
+            MultiStart::new(nelder_mead(), (vec![-2.0], vec![2.0]));
+        let MultiStart {
+            inner: _,
+            bounds,
+            inner_max_iters,
+            archive,
+            rng: _,
+        } = solver;
+        assert_eq!(bounds, (vec![-2.0], vec![2.0]));
+        assert_eq!(inner_max_iters, 1000);
+        assert!(archive.minima.is_empty());
+        assert_eq!(archive.size, 10);
+        assert_eq!(archive.tolerance.to_ne_bytes(), 1e-4f64.to_ne_bytes());
+    }
+
+    #[test]
+    fn test_builders() {
+        let solver: MultiStart<Inner, Vec<f64>, f64> =
+            MultiStart::new(nelder_mead(), (vec![-2.0], vec![2.0]))
+                .with_inner_max_iters(20)
+                .unwrap()
+                .with_minima_tolerance(0.0)
+                .unwrap()
+                .with_archive_size(3)
+                .unwrap();
+        assert_eq!(solver.inner_max_iters, 20);
+        assert_eq!(solver.archive.tolerance.to_ne_bytes(), 0.0f64.to_ne_bytes());
+        assert_eq!(solver.archive.size, 3);
+    }
+
+    #[test]
+    fn test_builder_errors() {
+        let solver = || -> MultiStart<Inner, Vec<f64>, f64> {
+            MultiStart::new(nelder_mead(), (vec![-2.0], vec![2.0]))
+        };
+        assert_error!(
+            solver().with_inner_max_iters(0),
+            ArgminError,
+            "Invalid parameter: \"`MultiStart`: inner max iters must be > 0.\""
+        );
+        assert_error!(
+            solver().with_minima_tolerance(-1.0),
+            ArgminError,
+            "Invalid parameter: \"`MultiStart`: minima tolerance must be >= 0.\""
+        );
+        assert_error!(
+            solver().with_archive_size(0),
+            ArgminError,
+            "Invalid parameter: \"`MultiStart`: archive size must be > 0.\""
+        );
+    }
+
+    #[test]
+    fn test_init_param() {
+        let mut solver: MultiStart<Inner, Vec<f64>, f64> =
+            MultiStart::new(nelder_mead(), (vec![-2.0], vec![2.0]));
+        let mut problem = Problem::new(DoubleWell {});
+        let (state, kv) = solver
+            .init(&mut problem, IterState::new().param(vec![1.5]))
+            .unwrap();
+        // Starting from 1.5 the local search ends in the local minimum
+        assert_relative_eq!(state.get_param().unwrap()[0], 0.97, epsilon = 1e-2);
+        assert_eq!(solver.minima().len(), 1);
+        assert!(kv.unwrap().get("inner_iters").unwrap().get_uint().unwrap() > 0);
+        assert!(problem.counts["cost_count"] > 0);
+    }
+
+    #[test]
+    fn test_nelder_mead() {
+        let solver = MultiStart::new(nelder_mead(), (vec![-2.0], vec![2.0]))
+            .with_minima_tolerance(1e-2)
+            .unwrap();
+        let res = Executor::new(DoubleWell {}, solver)
+            .configure(|state| state.param(vec![1.5]).max_iters(30))
+            .run()
+            .unwrap();
+        let minima = res.solver.minima();
+        assert_eq!(minima.len(), 2);
+        assert_relative_eq!(minima[0].param[0], -1.02, epsilon = 1e-2);
+        assert_relative_eq!(minima[1].param[0], 0.97, epsilon = 1e-2);
+        assert_eq!(minima.iter().map(|m| m.hits).sum::<u64>(), 31);
+        assert_relative_eq!(
+            res.state.get_best_param().unwrap()[0],
+            -1.02,
+            epsilon = 1e-2
+        );
+    }
+
+    #[test]
+    fn test_lbfgs() {
+        let linesearch = MoreThuenteLineSearch::new();
+        let inner: LBFGS<_, Vec<f64>, Vec<f64>, f64> = LBFGS::new(linesearch, 5);
+        let solver = MultiStart::new(inner, (vec![-2.0], vec![2.0]));
+        let res = Executor::new(DoubleWell {}, solver)
+            .configure(|state| state.max_iters(20))
+            .run()
+            .unwrap();
+        assert_relative_eq!(
+            res.state.get_best_param().unwrap()[0],
+            -1.02,
+            epsilon = 1e-2
+        );
+        assert!(res.problem.counts["cost_count"] > 0);
+        assert!(res.problem.counts["gradient_count"] > 0);
+    }
+
+    #[test]
+    fn test_seeded_start_points() {
+        let solver = |seed: u64| -> MultiStart<Inner, Vec<f64>, f64, Xoshiro256PlusPlus> {
+            MultiStart::new_with_rng(
+                nelder_mead(),
+                (vec![-2.0], vec![2.0]),
+                Xoshiro256PlusPlus::seed_from_u64(seed),
+            )
+        };
+        let start_points = |seed: u64| -> Vec<f64> {
+            let mut solver = solver(seed);
+            (0..5).map(|_| solver.sample()[0]).collect()
+        };
+        let points = start_points(42);
+        assert!(points.iter().all(|x| (-2.0..=2.0).contains(x)));
+        assert_eq!(points, start_points(42));
+        assert_ne!(points, start_points(43));
+
+        // The local minima found from other start points differ as well
+        let minima = |seed: u64| -> Vec<u64> {
+            let res = Executor::new(DoubleWell {}, solver(seed))
+                .configure(|state| state.max_iters(5))
+                .run()
+                .unwrap();
+            res.solver
+                .minima()
+                .iter()
+                .map(|m| m.param[0].to_bits())
+                .collect()
+        };
+        assert_ne!(minima(42), minima(43));
+    }
+}
@@ -49,6 +49,16 @@ use std::fmt;
 /// 4) Shrink (Parameter `sigma`, defaults to `0.5`, configurable via
 ///    [`with_sigma`](`NelderMead::with_sigma`))
 ///
+/// ## Initial parameter vector
+///
+/// An initial parameter vector provided via `Executor`s `configure` method takes precedence over
+/// the position of the simplex: All vertices are translated by the same offset such that the first
+/// vertex coincides with the initial parameter vector. The simplex passed to
+/// [`new`](`NelderMead::new`) then only determines the shape and size of the simplex. This allows
+/// restarting the method from different points, for instance in
+/// [`MultiStart`](`crate::solver::multistart::MultiStart`). Without an initial parameter vector,
+/// the simplex is used as is.
+///
 /// ## Requirements on the optimization problem
 ///
 /// The optimization problem is required to implement [`CostFunction`].
@@ -83,7 +93,9 @@ where
     /// Construct a new instance of `NelderMead`
     ///
     /// Takes a vector of parameter vectors. The number of parameter vectors must be `n + 1` where
-    /// `n` is the number of optimization parameters.
+    /// `n` is the number of optimization parameters. If an initial parameter vector is configured
+    /// in the `Executor`, the simplex is moved such that its first vertex is located at the initial
+    /// parameter vector.
     ///
     /// # Example
     ///
@@ -327,11 +339,19 @@ where
     fn init(
         &mut self,
         problem: &mut Problem<O>,
-        state: IterState<P, (), (), (), F>,
+        mut state: IterState<P, (), (), (), F>,
     ) -> Result<(IterState<P, (), (), (), F>, Option<KV>), Error> {
+        if let Some(param) = state.take_param() {
+            let shift = param.sub(&self.params[0].0);
+            self.params.iter_mut().for_each(|(p, _)| *p = p.add(&shift));
+        }
+
         self.params
             .iter_mut()
-            .for_each(|(p, c)| *c = problem.cost(p).unwrap());
+            .try_for_each(|(p, c)| -> Result<(), Error> {
+                *c = problem.cost(p)?;
+                Ok(())
+            })?;
 
         self.sort_param_vecs();
 
@@ -717,6 +737,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_init_translate() {
+        let params: Vec<Vec<f64>> = vec![vec![-1.0, 1.0], vec![-0.5, 2.0], vec![0.7, -1.0]];
+        let mut nm: NelderMead<_, f64> = NelderMead::new(params);
+        let state: IterState<Vec<f64>, (), (), (), f64> = IterState::new().param(vec![1.0, 1.0]);
+        let problem = MwProblem {};
+        let (state_out, _) = nm.init(&mut Problem::new(problem), state).unwrap();
+
+        // Simplex is translated by (2, 0)
+        let params_sorted: Vec<Vec<f64>> = vec![vec![1.0, 1.0], vec![1.5, 2.0], vec![2.7, -1.0]];
+        for ((p, c), ps) in nm.params.iter().zip(params_sorted.iter()) {
+            assert_relative_eq!(p[0], ps[0], epsilon = f64::EPSILON);
+            assert_relative_eq!(p[1], ps[1], epsilon = f64::EPSILON);
+            assert_relative_eq!(*c, ps[0].powi(2) + ps[1].powi(2), epsilon = f64::EPSILON);
+        }
+        assert_eq!(*state_out.get_param().unwrap(), vec![1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_init_translate_noop() {
+        let params: Vec<Vec<f64>> = vec![vec![-1.0, 1.0], vec![-0.5, 2.0], vec![0.7, -1.0]];
+        let mut nm: NelderMead<_, f64> = NelderMead::new(params.clone());
+        let state: IterState<Vec<f64>, (), (), (), f64> = IterState::new().param(vec![-1.0, 1.0]);
+        let problem = MwProblem {};
+        nm.init(&mut Problem::new(problem), state).unwrap();
+
+        // Configuring the first vertex as initial parameter vector leaves the simplex unchanged
+        for p in params.iter() {
+            assert!(nm.params.iter().any(|(q, _)| q == p));
+        }
+    }
+
     #[test]
     fn test_next_iter_reflection() {
         let params: Vec<Vec<f64>> = vec![vec![-1.0, 0.0], vec![-0.1, 0.65], vec![-0.1, -0.95]];
@@ -25,6 +25,8 @@ use crate::core::{
     KV,
 };
 use argmin_math::{ArgminAdd, ArgminMinMax, ArgminMul, ArgminRandom, ArgminSub, ArgminZeroLike};
+use rand::{Rng, SeedableRng};
+use rand_xoshiro::Xoshiro256PlusPlus;
 #[cfg(feature = "serde1")]
 use serde::{Deserialize, Serialize};
 
@@ -37,6 +39,11 @@ use serde::{Deserialize, Serialize};
 /// for expensive cost functions, but may cause a drop in performance for cheap cost functions. Be
 /// sure to benchmark both parallel and sequential computation.
 ///
+/// The initial positions and velocities of the particles and the random pull towards the best
+/// positions in each velocity update are drawn from an RNG owned by the solver, which can be
+/// seeded via [`new_with_rng`](`ParticleSwarm::new_with_rng`). As it is saved in checkpoints along
+/// with the particles, a resumed run continues with the same random sequence.
+///
 /// ## Requirements on the optimization problem
 ///
 /// The optimization problem is required to implement [`CostFunction`].
@@ -50,7 +57,7 @@ use serde::{Deserialize, Serialize};
 /// \[1\] <https://en.wikipedia.org/wiki/Particle_swarm_optimization>
 #[derive(Clone)]
 #[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
-pub struct ParticleSwarm<P, F> {
+pub struct ParticleSwarm<P, F, R = Xoshiro256PlusPlus> {
     /// Inertia weight
     weight_inertia: F,
     /// Cognitive acceleration coefficient
@@ -61,9 +68,11 @@ pub struct ParticleSwarm<P, F> {
     bounds: (P, P),
     /// Number of particles
     num_particles: usize,
+    /// Random number generator
+    rng: R,
 }
 
-impl<P, F> ParticleSwarm<P, F>
+impl<P, F> ParticleSwarm<P, F, Xoshiro256PlusPlus>
 where
     P: Clone + SyncAlias + ArgminSub<P, P> + ArgminMul<F, P> + ArgminRandom + ArgminZeroLike,
     F: ArgminFloat,
@@ -74,6 +83,9 @@ where
     /// `(lower_bound, upper_bound)`, where `lower_bound` and `upper_bound` are of the same type as
     /// the position of a particle (`P`) and of the same length as the problem as dimensions.
     ///
+    /// Uses the `Xoshiro256PlusPlus` RNG internally. For use of another RNG, consider using
+    /// [`ParticleSwarm::new_with_rng`].
+    ///
     /// The inertia weight on velocity and the social and cognitive acceleration factors can be
     /// adapted with [`with_inertia_factor`](`ParticleSwarm::with_inertia_factor`),
     /// [`with_cognitive_factor`](`ParticleSwarm::with_cognitive_factor`) and
@@ -94,12 +106,40 @@ where
     /// let pso: ParticleSwarm<_, f64> = ParticleSwarm::new((lower_bound, upper_bound), 40);
     /// ```
     pub fn new(bounds: (P, P), num_particles: usize) -> Self {
+        ParticleSwarm::new_with_rng(bounds, num_particles, Xoshiro256PlusPlus::from_entropy())
+    }
+}
+
+impl<P, F, R> ParticleSwarm<P, F, R>
+where
+    P: Clone + SyncAlias + ArgminSub<P, P> + ArgminMul<F, P> + ArgminRandom + ArgminZeroLike,
+    F: ArgminFloat,
+{
+    /// Construct a new instance of `ParticleSwarm` with a given random number generator
+    ///
+    /// Same as [`ParticleSwarm::new`], but requires a RNG which must implement `rand::Rng` (and
+    /// `serde::Serialize` if the `serde1` feature is enabled).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::particleswarm::ParticleSwarm;
+    /// use rand::SeedableRng;
+    /// use rand_xoshiro::Xoshiro256PlusPlus;
+    /// # let lower_bound: Vec<f64> = vec![-1.0, -1.0];
+    /// # let upper_bound: Vec<f64> = vec![1.0, 1.0];
+    /// let rng = Xoshiro256PlusPlus::seed_from_u64(42);
+    /// let pso: ParticleSwarm<_, f64, _> =
+    ///     ParticleSwarm::new_with_rng((lower_bound, upper_bound), 40, rng);
+    /// ```
+    pub fn new_with_rng(bounds: (P, P), num_particles: usize, rng: R) -> Self {
         ParticleSwarm {
             weight_inertia: float!(1.0f64 / (2.0 * 2.0f64.ln())),
             weight_cognitive: float!(0.5 + 2.0f64.ln()),
             weight_social: float!(0.5 + 2.0f64.ln()),
             bounds,
             num_particles,
+            rng,
         }
     }
 
@@ -186,7 +226,14 @@ where
         self.weight_social = factor;
         Ok(self)
     }
+}
 
+impl<P, F, R> ParticleSwarm<P, F, R>
+where
+    P: Clone + SyncAlias + ArgminSub<P, P> + ArgminMul<F, P> + ArgminRandom + ArgminZeroLike,
+    F: ArgminFloat,
+    R: Rng,
+{
     /// Initializes all particles randomly and sorts them by their cost function values
     fn initialize_particles<O: CostFunction<Param = P, Output = F> + SyncAlias>(
         &mut self,
@@ -214,23 +261,24 @@ where
     }
 
     /// Initializes positions and velocities for all particles
-    fn initialize_positions_and_velocities(&self) -> (Vec<P>, Vec<P>) {
+    fn initialize_positions_and_velocities(&mut self) -> (Vec<P>, Vec<P>) {
         let (min, max) = &self.bounds;
         let delta = max.sub(min);
         let delta_neg = delta.mul(&float!(-1.0));
+        let rng = &mut self.rng;
 
         (
             (0..self.num_particles)
-                .map(|_| P::rand_from_range(min, max))
+                .map(|_| P::rand_from_range(min, max, rng))
                 .collect(),
             (0..self.num_particles)
-                .map(|_| P::rand_from_range(&delta_neg, &delta))
+                .map(|_| P::rand_from_range(&delta_neg, &delta, rng))
                 .collect(),
         )
     }
 }
 
-impl<O, P, F> Solver<O, PopulationState<Particle<P, F>, F>> for ParticleSwarm<P, F>
+impl<O, P, F, R> Solver<O, PopulationState<Particle<P, F>, F>> for ParticleSwarm<P, F, R>
 where
     O: CostFunction<Param = P, Output = F> + SyncAlias,
     P: SerializeAlias
@@ -243,6 +291,7 @@ where
         + ArgminRandom
         + ArgminMinMax,
     F: ArgminFloat,
+    R: Rng + SerializeAlias,
 {
     const NAME: &'static str = "Particle Swarm Optimization";
 
@@ -315,13 +364,14 @@ where
 
                 // ad 2)
                 let to_optimum = p.best_position.sub(&p.position);
-                let pull_to_optimum = P::rand_from_range(&zero, &to_optimum);
+                let pull_to_optimum = P::rand_from_range(&zero, &to_optimum, &mut self.rng);
                 let pull_to_optimum = pull_to_optimum.mul(&self.weight_cognitive);
 
                 // ad 3)
                 let to_global_optimum = best_particle.position.sub(&p.position);
                 let pull_to_global_optimum =
-                    P::rand_from_range(&zero, &to_global_optimum).mul(&self.weight_social);
+                    P::rand_from_range(&zero, &to_global_optimum, &mut self.rng)
+                        .mul(&self.weight_social);
 
                 p.velocity = momentum.add(&pull_to_optimum).add(&pull_to_global_optimum);
                 let new_position = p.position.add(&p.velocity);
@@ -422,6 +472,7 @@ mod tests {
             weight_social,
             bounds,
             num_particles,
+            rng: _,
         } = pso;
 
         assert_relative_eq!(
@@ -538,7 +589,7 @@ mod tests {
         let lower_bound: Vec<f64> = vec![-1.0, -1.0];
         let upper_bound: Vec<f64> = vec![1.0, 1.0];
         let num_particles = 100;
-        let pso: ParticleSwarm<_, f64> =
+        let mut pso: ParticleSwarm<_, f64> =
             ParticleSwarm::new((lower_bound, upper_bound), num_particles);
 
         let (positions, velocities) = pso.initialize_positions_and_velocities();
@@ -727,4 +778,29 @@ mod tests {
             assert_eq!(state.get_cost().to_ne_bytes(), (-3.0f64).to_ne_bytes());
         }
     }
+
+    #[test]
+    fn test_seeded_swarm() {
+        let run = |seed: u64| -> Vec<u64> {
+            let mut problem = Problem::new(TestProblem::new());
+            let mut pso: ParticleSwarm<_, f64, _> = ParticleSwarm::new_with_rng(
+                (vec![-1.0, -1.0], vec![1.0, 1.0]),
+                10,
+                Xoshiro256PlusPlus::seed_from_u64(seed),
+            );
+            let (mut state, _) = pso.init(&mut problem, PopulationState::new()).unwrap();
+            for _ in 0..10 {
+                (state, _) = pso.next_iter(&mut problem, state).unwrap();
+            }
+            state
+                .get_population()
+                .unwrap()
+                .iter()
+                .flat_map(|p| p.position.iter().chain(p.velocity.iter()))
+                .map(|x| x.to_bits())
+                .collect()
+        };
+        assert_eq!(run(42), run(42));
+        assert_ne!(run(42), run(43));
+    }
 }
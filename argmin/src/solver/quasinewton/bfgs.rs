@@ -261,28 +261,13 @@ where
 
         let sk = xk1.sub(&param);
 
-        let yksk: F = yk.dot(&sk);
-        let rhok = float!(1.0) / yksk;
-
-        let e = inv_hessian.eye_like();
-        let mat1: H = sk.dot(&yk);
-        let mat1 = mat1.mul(&rhok);
-
-        let tmp1 = e.sub(&mat1);
-
-        let mat2 = mat1.t();
-        let tmp2 = e.sub(&mat2);
-
-        let sksk: H = sk.dot(&sk);
-        let sksk = sksk.mul(&rhok);
-
         // if state.get_iter() == 0 {
         //     let ykyk: f64 = yk.dot(&yk);
         //     self.inv_hessian = self.inv_hessian.eye_like().mul(&(yksk / ykyk));
         //     println!("{:?}", self.inv_hessian);
         // }
 
-        let inv_hessian = tmp1.dot(&inv_hessian.dot(&tmp2)).add(&sksk);
+        let inv_hessian = bfgs_update(&inv_hessian, &sk, &yk);
 
         Ok((
             state
@@ -305,6 +290,38 @@ where
     }
 }
 
+/// BFGS update of the inverse Hessian approximation `inv_hessian` with the step `sk` and the
+/// corresponding change of the gradient `yk`
+pub(crate) fn bfgs_update<P, G, H, F>(inv_hessian: &H, sk: &P, yk: &G) -> H
+where
+    P: ArgminDot<G, H> + ArgminDot<P, H>,
+    G: ArgminDot<P, F>,
+    H: ArgminSub<H, H>
+        + ArgminDot<H, H>
+        + ArgminAdd<H, H>
+        + ArgminMul<F, H>
+        + ArgminTranspose<H>
+        + ArgminEye,
+    F: ArgminFloat,
+{
+    let yksk: F = yk.dot(sk);
+    let rhok = float!(1.0) / yksk;
+
+    let e = inv_hessian.eye_like();
+    let mat1: H = sk.dot(yk);
+    let mat1 = mat1.mul(&rhok);
+
+    let tmp1 = e.sub(&mat1);
+
+    let mat2 = mat1.t();
+    let tmp2 = e.sub(&mat2);
+
+    let sksk: H = sk.dot(sk);
+    let sksk = sksk.mul(&rhok);
+
+    tmp1.dot(&inv_hessian.dot(&tmp2)).add(&sksk)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
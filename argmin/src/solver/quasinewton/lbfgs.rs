@@ -32,6 +32,39 @@ where
     G::max(&gradient.add(&coeff_n), &zeros).add(&G::min(&gradient.add(&coeff_p), &zeros))
 }
 
+/// L-BFGS two-loop recursion.
+///
+/// Computes `H grad`, where `H` is the limited-memory approximation of the inverse Hessian
+/// implicitly given by the pairs `s` and `y` (oldest first) and the initial approximation
+/// `gamma * I`.
+pub(super) fn two_loop_recursion<P, G, F>(s: &VecDeque<P>, y: &VecDeque<G>, grad: &G, gamma: F) -> P
+where
+    P: ArgminAdd<P, P> + ArgminDot<G, F> + ArgminMul<F, P>,
+    G: Clone + ArgminSub<G, G> + ArgminDot<P, F> + ArgminMul<F, G> + ArgminMul<F, P>,
+    F: ArgminFloat,
+{
+    let mut q = grad.clone();
+    let cur_m = s.len();
+    let mut alpha: Vec<F> = vec![float!(0.0); cur_m];
+    let mut rho: Vec<F> = vec![float!(0.0); cur_m];
+    for (i, (sk, yk)) in s.iter().rev().zip(y.iter().rev()).enumerate() {
+        let yksk: F = yk.dot(sk);
+        let rho_t = float!(1.0) / yksk;
+        let skq: F = sk.dot(&q);
+        let alpha_t = skq.mul(rho_t);
+        q = q.sub(&yk.mul(&alpha_t));
+        rho[cur_m - i - 1] = rho_t;
+        alpha[cur_m - i - 1] = alpha_t;
+    }
+    let mut r: P = q.mul(&gamma);
+    for (i, (sk, yk)) in s.iter().zip(y.iter()).enumerate() {
+        let beta: F = yk.dot(&r);
+        let beta = beta.mul(rho[i]);
+        r = r.add(&sk.mul(&(alpha[i] - beta)));
+    }
+    r
+}
+
 /// # Limited-memory BFGS (L-BFGS) method
 ///
 /// L-BFGS is an approximation to BFGS which requires a limited amount of memory. Instead of
@@ -401,27 +434,7 @@ where
             float!(1.0)
         };
 
-        // L-BFGS two-loop recursion
-        #[allow(clippy::redundant_clone)]
-        let mut q = prev_grad.clone();
-        let cur_m = self.s.len();
-        let mut alpha: Vec<F> = vec![float!(0.0); cur_m];
-        let mut rho: Vec<F> = vec![float!(0.0); cur_m];
-        for (i, (sk, yk)) in self.s.iter().rev().zip(self.y.iter().rev()).enumerate() {
-            let yksk: F = yk.dot(sk);
-            let rho_t = float!(1.0) / yksk;
-            let skq: F = sk.dot(&q);
-            let alpha_t = skq.mul(rho_t);
-            q = q.sub(&yk.mul(&alpha_t));
-            rho[cur_m - i - 1] = rho_t;
-            alpha[cur_m - i - 1] = alpha_t;
-        }
-        let mut r: P = q.mul(&gamma);
-        for (i, (sk, yk)) in self.s.iter().zip(self.y.iter()).enumerate() {
-            let beta: F = yk.dot(&r);
-            let beta = beta.mul(rho[i]);
-            r = r.add(&sk.mul(&(alpha[i] - beta)));
-        }
+        let r = two_loop_recursion(&self.s, &self.y, &prev_grad, gamma);
 
         let mut line_problem = LineSearchProblem::new(problem.take_problem().unwrap());
         let d = if let Some(l1_coeff) = self.l1_coeff {
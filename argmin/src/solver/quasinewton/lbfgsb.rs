@@ -0,0 +1,842 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::lbfgs::two_loop_recursion;
+use crate::core::{
+    ArgminFloat, CostFunction, DeserializeOwnedAlias, Error, Gradient, IterState, Problem,
+    SerializeAlias, Solver, State, TerminationReason, TerminationStatus, KV,
+};
+use crate::solver::utils::{cmp_nan_last, dot, identity, solve};
+use argmin_math::{ArgminAdd, ArgminDot, ArgminMul, ArgminSub};
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Sufficient decrease parameter of the backtracking line search
+const ARMIJO_C1: f64 = 1e-4;
+/// Maximum number of step length reductions in the backtracking line search
+const MAX_BACKTRACKING: usize = 30;
+
+/// # Limited-memory BFGS method with bound constraints (L-BFGS-B)
+///
+/// L-BFGS-B minimizes a function subject to simple lower and upper bounds on the parameters. The
+/// bounds are passed to [`LBFGSB::new`] as a tuple `(lower_bound, upper_bound)` of the same type
+/// as the parameter vector. Infinite bounds are allowed and mark unbounded parameters.
+///
+/// Each iteration consists of three steps:
+///
+/// 1. The generalized Cauchy point is computed, which is the first local minimizer of the
+///    quadratic model along the projected steepest descent path. This determines the set of
+///    parameters which are fixed at their bounds.
+/// 2. The quadratic model is minimized over the remaining free parameters (subspace minimization).
+///    The limited-memory Hessian approximation is used in its compact representation. If no
+///    parameter is at its bound, the step is computed with the L-BFGS two-loop recursion instead.
+/// 3. A backtracking line search along the resulting feasible direction determines the next
+///    iterate.
+///
+/// The number of stored correction pairs (history size `m`) must be set. Additionally an initial
+/// guess for the parameter vector is required, which is to be provided via the
+/// [`configure`](`crate::core::Executor::configure`) method of the
+/// [`Executor`](`crate::core::Executor`) (See [`IterState`], in particular [`IterState::param`]).
+/// An initial guess which violates the bounds is projected onto the feasible set.
+///
+/// The algorithm stops if the infinity norm of the projected gradient is below the tolerance set
+/// with [`with_tolerance_grad`](`LBFGSB::with_tolerance_grad`) (default: `sqrt(EPSILON)`) or if
+/// the relative change of the cost function is below the tolerance set with
+/// [`with_tolerance_cost`](`LBFGSB::with_tolerance_cost`) (default: `EPSILON`).
+///
+/// Parameter and gradient vectors need to be convertible from `Vec<F>` and iterable by reference,
+/// because the bounds are handled per parameter.
+///
+/// ## Requirements on the optimization problem
+///
+/// The optimization problem is required to implement [`CostFunction`] and [`Gradient`].
+///
+/// ## Reference
+///
+/// Richard H. Byrd, Peihuang Lu, Jorge Nocedal and Ciyou Zhu (1995). A limited memory algorithm
+/// for bound constrained optimization. SIAM Journal on Scientific Computing 16(5),
+/// pp. 1190-1208.
+///
+/// Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct LBFGSB<P, G, F> {
+    /// Lower bound
+    lower: P,
+    /// Upper bound
+    upper: P,
+    /// m
+    m: usize,
+    /// s_{k-1}
+    s: VecDeque<P>,
+    /// y_{k-1}
+    y: VecDeque<G>,
+    /// Tolerance for the stopping criterion based on the projected gradient
+    tol_grad: F,
+    /// Tolerance for the stopping criterion based on the relative change of the cost function
+    tol_cost: F,
+}
+
+impl<P, G, F> LBFGSB<P, G, F>
+where
+    F: ArgminFloat,
+{
+    /// Construct a new instance of [`LBFGSB`]
+    ///
+    /// Takes the bounds and the history size `m` as inputs. `bounds` is a tuple
+    /// `(lower_bound, upper_bound)`, where `lower_bound` and `upper_bound` are of the same type and
+    /// length as the parameter vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::quasinewton::LBFGSB;
+    /// # let lower_bound: Vec<f64> = vec![-1.0, -1.0];
+    /// # let upper_bound: Vec<f64> = vec![1.0, 1.0];
+    /// let lbfgsb: LBFGSB<Vec<f64>, Vec<f64>, f64> = LBFGSB::new((lower_bound, upper_bound), 5);
+    /// ```
+    pub fn new(bounds: (P, P), m: usize) -> Self {
+        let (lower, upper) = bounds;
+        LBFGSB {
+            lower,
+            upper,
+            m,
+            s: VecDeque::with_capacity(m),
+            y: VecDeque::with_capacity(m),
+            tol_grad: F::epsilon().sqrt(),
+            tol_cost: F::epsilon(),
+        }
+    }
+
+    /// The algorithm stops if the infinity norm of the projected gradient is below `tol_grad`.
+    ///
+    /// The provided value must be non-negative. Defaults to `sqrt(EPSILON)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::quasinewton::LBFGSB;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// # let bounds = (vec![-1.0f64, -1.0], vec![1.0, 1.0]);
+    /// let lbfgsb: LBFGSB<Vec<f64>, Vec<f64>, f64> = LBFGSB::new(bounds, 3).with_tolerance_grad(1e-6)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_tolerance_grad(mut self, tol_grad: F) -> Result<Self, Error> {
+        if tol_grad < float!(0.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`L-BFGS-B`: gradient tolerance must be >= 0."
+            ));
+        }
+        self.tol_grad = tol_grad;
+        Ok(self)
+    }
+
+    /// The algorithm stops if the relative change of the cost function from one iteration to the
+    /// next is below `tol_cost`.
+    ///
+    /// The provided value must be non-negative. Defaults to `EPSILON`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::quasinewton::LBFGSB;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// # let bounds = (vec![-1.0f64, -1.0], vec![1.0, 1.0]);
+    /// let lbfgsb: LBFGSB<Vec<f64>, Vec<f64>, f64> = LBFGSB::new(bounds, 3).with_tolerance_cost(1e-6)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_tolerance_cost(mut self, tol_cost: F) -> Result<Self, Error> {
+        if tol_cost < float!(0.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`L-BFGS-B`: cost tolerance must be >= 0."
+            ));
+        }
+        self.tol_cost = tol_cost;
+        Ok(self)
+    }
+}
+
+/// Copies the elements of a vector type into a `Vec`
+fn to_vec<T, F>(v: &T) -> Vec<F>
+where
+    for<'a> &'a T: IntoIterator<Item = &'a F>,
+    F: ArgminFloat,
+{
+    v.into_iter().copied().collect()
+}
+
+fn mat_vec<F: ArgminFloat>(m: &[Vec<F>], v: &[F]) -> Vec<F> {
+    m.iter().map(|row| dot(row, v)).collect()
+}
+
+/// Projects `x` onto the box `[l, u]`
+fn project<F: ArgminFloat>(x: &[F], l: &[F], u: &[F]) -> Vec<F> {
+    x.iter()
+        .zip(l.iter().zip(u.iter()))
+        .map(|(&xi, (&li, &ui))| xi.max(li).min(ui))
+        .collect()
+}
+
+/// Compact representation `B = theta I - W M W^T` of the limited-memory BFGS matrix
+struct CompactRepresentation<F> {
+    theta: F,
+    /// Rows of the `n x 2k` matrix `W = [Y, theta S]`
+    w: Vec<Vec<F>>,
+    /// `2k x 2k` middle matrix
+    m: Vec<Vec<F>>,
+}
+
+impl<F: ArgminFloat> CompactRepresentation<F> {
+    /// Builds the compact representation from the correction pairs (oldest first). Returns `None`
+    /// if the middle matrix is singular.
+    fn new(s: &[Vec<F>], y: &[Vec<F>], n: usize) -> Option<Self> {
+        let k = s.len();
+        let theta = match (s.last(), y.last()) {
+            (Some(sk), Some(yk)) => dot(yk, yk) / dot(sk, yk),
+            _ => float!(1.0),
+        };
+        let w = (0..n)
+            .map(|i| {
+                y.iter()
+                    .map(|yj| yj[i])
+                    .chain(s.iter().map(|sj| theta * sj[i]))
+                    .collect()
+            })
+            .collect();
+
+        // K = [[-D, L^T], [L, theta S^T S]] with D = diag(s_i^T y_i) and L_ij = s_i^T y_j, i > j
+        let mut kmat = vec![vec![float!(0.0); 2 * k]; 2 * k];
+        for i in 0..k {
+            for j in 0..k {
+                if i == j {
+                    kmat[i][j] = -dot(&s[i], &y[i]);
+                } else if i > j {
+                    let l = dot(&s[i], &y[j]);
+                    kmat[k + i][j] = l;
+                    kmat[j][k + i] = l;
+                }
+                kmat[k + i][k + j] = theta * dot(&s[i], &s[j]);
+            }
+        }
+        let m = solve(kmat, identity(2 * k))?;
+        Some(CompactRepresentation { theta, w, m })
+    }
+
+    /// Computes the generalized Cauchy point of the quadratic model at `x`. Returns the Cauchy
+    /// point `xc` and `c = W^T (xc - x)`.
+    fn cauchy_point(&self, x: &[F], g: &[F], l: &[F], u: &[F]) -> (Vec<F>, Vec<F>) {
+        let n = x.len();
+        let theta = self.theta;
+        let inf = F::infinity();
+
+        // Breakpoints of the projected steepest descent path
+        let t: Vec<F> = (0..n)
+            .map(|i| {
+                if g[i] < float!(0.0) {
+                    (x[i] - u[i]) / g[i]
+                } else if g[i] > float!(0.0) {
+                    (x[i] - l[i]) / g[i]
+                } else {
+                    inf
+                }
+            })
+            .collect();
+        let mut d: Vec<F> = (0..n)
+            .map(|i| {
+                if t[i] == float!(0.0) {
+                    float!(0.0)
+                } else {
+                    -g[i]
+                }
+            })
+            .collect();
+        let mut order: Vec<usize> = (0..n).filter(|&i| t[i] > float!(0.0)).collect();
+        order.sort_by(|&i, &j| cmp_nan_last(&t[i], &t[j]));
+
+        let mut xc = x.to_vec();
+        let two_k = self.m.len();
+        // p = W^T d
+        let mut p: Vec<F> = (0..two_k)
+            .map(|j| {
+                self.w
+                    .iter()
+                    .zip(d.iter())
+                    .fold(float!(0.0), |acc, (wi, &di)| acc + wi[j] * di)
+            })
+            .collect();
+        let mut c = vec![float!(0.0); two_k];
+
+        let mut fp = -dot(&d, &d);
+        if fp == float!(0.0) {
+            return (xc, c);
+        }
+        let fpp0 = -theta * fp;
+        let mut fpp = (fpp0 - dot(&p, &mat_vec(&self.m, &p))).max(F::epsilon() * fpp0);
+        let mut dt_min = -fp / fpp;
+        let mut t_old = float!(0.0);
+
+        for &b in order.iter() {
+            let dt = t[b] - t_old;
+            if dt_min < dt {
+                break;
+            }
+            // Fix variable `b` at its bound
+            xc[b] = if d[b] > float!(0.0) { u[b] } else { l[b] };
+            let zb = xc[b] - x[b];
+            for (ci, &pi) in c.iter_mut().zip(p.iter()) {
+                *ci = *ci + dt * pi;
+            }
+            let gb = g[b];
+            let wb = &self.w[b];
+            let mwb = mat_vec(&self.m, wb);
+            fp = fp + dt * fpp + gb * gb + theta * gb * zb - gb * dot(&mwb, &c);
+            fpp = (fpp
+                - theta * gb * gb
+                - float!(2.0) * gb * dot(&mwb, &p)
+                - gb * gb * dot(wb, &mwb))
+            .max(F::epsilon() * fpp0);
+            for (pi, &wbi) in p.iter_mut().zip(wb.iter()) {
+                *pi = *pi + gb * wbi;
+            }
+            d[b] = float!(0.0);
+            dt_min = -fp / fpp;
+            t_old = t[b];
+        }
+
+        let dt_min = dt_min.max(float!(0.0));
+        let t_old = t_old + dt_min;
+        for i in 0..n {
+            if d[i] != float!(0.0) {
+                xc[i] = (x[i] + t_old * d[i]).max(l[i]).min(u[i]);
+            }
+        }
+        for (ci, &pi) in c.iter_mut().zip(p.iter()) {
+            *ci = *ci + dt_min * pi;
+        }
+        (xc, c)
+    }
+
+    /// Minimizes the quadratic model over the `free` variables starting from the Cauchy point
+    /// (direct primal method). `r` is the reduced gradient of the model at the Cauchy point.
+    /// Returns the step of the free variables or `None` if the reduced system is singular.
+    fn subspace_step(&self, free: &[usize], r: &[F]) -> Option<Vec<F>> {
+        let theta = self.theta;
+        let two_k = self.m.len();
+        // W_F^T r_F
+        let wtr: Vec<F> = (0..two_k)
+            .map(|j| {
+                free.iter()
+                    .zip(r.iter())
+                    .fold(float!(0.0), |acc, (&i, &ri)| acc + self.w[i][j] * ri)
+            })
+            .collect();
+        let v = mat_vec(&self.m, &wtr);
+        // N = I - 1/theta M W_F^T W_F
+        let wtw: Vec<Vec<F>> = (0..two_k)
+            .map(|a| {
+                (0..two_k)
+                    .map(|b| {
+                        free.iter()
+                            .fold(float!(0.0), |acc, &i| acc + self.w[i][a] * self.w[i][b])
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut nmat = identity(two_k);
+        for (a, row) in nmat.iter_mut().enumerate() {
+            for (b, nab) in row.iter_mut().enumerate() {
+                *nab =
+                    *nab - dot(&self.m[a], &wtw.iter().map(|r| r[b]).collect::<Vec<F>>()) / theta;
+            }
+        }
+        let v = solve(nmat, v.into_iter().map(|vi| vec![vi]).collect())?;
+        let v: Vec<F> = v.into_iter().map(|vi| vi[0]).collect();
+        Some(
+            free.iter()
+                .zip(r.iter())
+                .map(|(&i, &ri)| -ri / theta - dot(&self.w[i], &v) / (theta * theta))
+                .collect(),
+        )
+    }
+}
+
+impl<P, G, F> LBFGSB<P, G, F>
+where
+    P: Clone + From<Vec<F>> + ArgminAdd<P, P> + ArgminDot<G, F> + ArgminMul<F, P>,
+    G: Clone + From<Vec<F>> + ArgminSub<G, G> + ArgminDot<P, F> + ArgminMul<F, G> + ArgminMul<F, P>,
+    for<'a> &'a P: IntoIterator<Item = &'a F>,
+    for<'a> &'a G: IntoIterator<Item = &'a F>,
+    F: ArgminFloat,
+{
+    /// Computes a feasible descent direction at `x`. Returns the direction and the number of free
+    /// variables at the generalized Cauchy point.
+    fn search_direction(&mut self, x: &[F], g: &[F], l: &[F], u: &[F]) -> (Vec<F>, usize) {
+        let n = x.len();
+        let s: Vec<Vec<F>> = self.s.iter().map(to_vec).collect();
+        let y: Vec<Vec<F>> = self.y.iter().map(to_vec).collect();
+        let compact = match CompactRepresentation::new(&s, &y, n) {
+            Some(compact) => compact,
+            None => {
+                self.s.clear();
+                self.y.clear();
+                CompactRepresentation::new(&[], &[], n).unwrap()
+            }
+        };
+
+        let (xc, c) = compact.cauchy_point(x, g, l, u);
+        let free: Vec<usize> = (0..n).filter(|&i| xc[i] > l[i] && xc[i] < u[i]).collect();
+
+        // Gradient of the model at the Cauchy point: g + theta (xc - x) - W M c
+        let mc = mat_vec(&compact.m, &c);
+        let r: Vec<F> = (0..n)
+            .map(|i| g[i] + compact.theta * (xc[i] - x[i]) - dot(&compact.w[i], &mc))
+            .collect();
+
+        let du: Option<Vec<F>> = if free.len() == n {
+            // No variable at its bound: The minimizer of the model is given by the L-BFGS step.
+            let hr: P =
+                two_loop_recursion(&self.s, &self.y, &G::from(r), float!(1.0) / compact.theta);
+            Some(to_vec(&hr).into_iter().map(|v| -v).collect())
+        } else {
+            let r_free: Vec<F> = free.iter().map(|&i| r[i]).collect();
+            compact.subspace_step(&free, &r_free)
+        };
+
+        let mut xbar = xc.clone();
+        if let Some(du) = du {
+            // Largest step along `du` which keeps the free variables feasible
+            let alpha = free
+                .iter()
+                .zip(du.iter())
+                .fold(float!(1.0), |alpha: F, (&i, &dui)| {
+                    if dui > float!(0.0) {
+                        alpha.min((u[i] - xc[i]) / dui)
+                    } else if dui < float!(0.0) {
+                        alpha.min((l[i] - xc[i]) / dui)
+                    } else {
+                        alpha
+                    }
+                });
+            for (&i, &dui) in free.iter().zip(du.iter()) {
+                xbar[i] = xc[i] + alpha * dui;
+            }
+        }
+
+        let d: Vec<F> = xbar.iter().zip(x.iter()).map(|(&b, &a)| b - a).collect();
+        if dot(&d, g) < float!(0.0) {
+            (d, free.len())
+        } else {
+            // Fall back to the Cauchy point
+            (
+                xc.iter().zip(x.iter()).map(|(&b, &a)| b - a).collect(),
+                free.len(),
+            )
+        }
+    }
+}
+
+impl<O, P, G, F> Solver<O, IterState<P, G, (), (), F>> for LBFGSB<P, G, F>
+where
+    O: CostFunction<Param = P, Output = F> + Gradient<Param = P, Gradient = G>,
+    P: Clone
+        + SerializeAlias
+        + DeserializeOwnedAlias
+        + From<Vec<F>>
+        + ArgminSub<P, P>
+        + ArgminAdd<P, P>
+        + ArgminDot<G, F>
+        + ArgminMul<F, P>,
+    G: Clone
+        + SerializeAlias
+        + DeserializeOwnedAlias
+        + From<Vec<F>>
+        + ArgminSub<G, G>
+        + ArgminDot<G, F>
+        + ArgminDot<P, F>
+        + ArgminMul<F, G>
+        + ArgminMul<F, P>,
+    for<'a> &'a P: IntoIterator<Item = &'a F>,
+    for<'a> &'a G: IntoIterator<Item = &'a F>,
+    F: ArgminFloat,
+{
+    const NAME: &'static str = "L-BFGS-B";
+
+    fn init(
+        &mut self,
+        problem: &mut Problem<O>,
+        mut state: IterState<P, G, (), (), F>,
+    ) -> Result<(IterState<P, G, (), (), F>, Option<KV>), Error> {
+        let param = state.take_param().ok_or_else(argmin_error_closure!(
+            NotInitialized,
+            concat!(
+                "`L-BFGS-B` requires an initial parameter vector. ",
+                "Please provide an initial guess via `Executor`s `configure` method."
+            )
+        ))?;
+
+        let l = to_vec(&self.lower);
+        let u = to_vec(&self.upper);
+        let x = to_vec(&param);
+        if l.len() != x.len() || u.len() != x.len() {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`L-BFGS-B`: bounds must be of the same length as the parameter vector."
+            ));
+        }
+        if l.iter().zip(u.iter()).any(|(li, ui)| li > ui) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`L-BFGS-B`: lower bound must be lower than or equal to upper bound."
+            ));
+        }
+
+        let projected = project(&x, &l, &u);
+        let (param, cost, grad) = if projected != x {
+            // Cost and gradient provided by the user do not belong to the projected parameters.
+            let param = P::from(projected);
+            let cost = problem.cost(&param)?;
+            let grad = problem.gradient(&param)?;
+            (param, cost, grad)
+        } else {
+            let cost = state.get_cost();
+            let cost = if cost.is_infinite() {
+                problem.cost(&param)?
+            } else {
+                cost
+            };
+            let grad = state
+                .take_gradient()
+                .map(Result::Ok)
+                .unwrap_or_else(|| problem.gradient(&param))?;
+            (param, cost, grad)
+        };
+
+        Ok((state.param(param).cost(cost).gradient(grad), None))
+    }
+
+    fn next_iter(
+        &mut self,
+        problem: &mut Problem<O>,
+        mut state: IterState<P, G, (), (), F>,
+    ) -> Result<(IterState<P, G, (), (), F>, Option<KV>), Error> {
+        let param = state.take_param().ok_or_else(argmin_error_closure!(
+            PotentialBug,
+            "`L-BFGS-B`: Parameter vector in state not set."
+        ))?;
+        let grad = state.take_gradient().ok_or_else(argmin_error_closure!(
+            PotentialBug,
+            "`L-BFGS-B`: Gradient in state not set."
+        ))?;
+        let cost = state.get_cost();
+
+        let l = to_vec(&self.lower);
+        let u = to_vec(&self.upper);
+        let x = to_vec(&param);
+        let g = to_vec(&grad);
+
+        // Backtracking line search along a feasible descent direction. If it fails, the history
+        // is discarded and the search is repeated along the direction towards the Cauchy point of
+        // the steepest descent model.
+        let mut next = None;
+        let mut free_vars = 0;
+        let mut step_length = float!(0.0);
+        for _ in 0..2 {
+            let (d, free) = self.search_direction(&x, &g, &l, &u);
+            free_vars = free;
+            let slope = dot(&d, &g);
+            if slope >= float!(0.0) {
+                break;
+            }
+            let mut alpha = float!(1.0);
+            for _ in 0..MAX_BACKTRACKING {
+                let candidate: Vec<F> = x
+                    .iter()
+                    .zip(d.iter())
+                    .map(|(&xi, &di)| xi + alpha * di)
+                    .collect();
+                let candidate = P::from(project(&candidate, &l, &u));
+                let candidate_cost = problem.cost(&candidate)?;
+                if candidate_cost <= cost + float!(ARMIJO_C1) * alpha * slope {
+                    next = Some((candidate, candidate_cost));
+                    step_length = alpha;
+                    break;
+                }
+                alpha = alpha * float!(0.5);
+            }
+            if next.is_some() || self.s.is_empty() {
+                break;
+            }
+            self.s.clear();
+            self.y.clear();
+        }
+
+        let kv = kv!(
+            "free_vars" => free_vars as u64;
+            "step_length" => step_length;
+        );
+
+        let (new_param, new_cost) = match next {
+            Some(next) => next,
+            // No progress possible: Keep the current iterate, the cost based stopping criterion
+            // terminates the solver.
+            None => return Ok((state.param(param).cost(cost).gradient(grad), Some(kv))),
+        };
+        let new_grad = problem.gradient(&new_param)?;
+
+        let sk = new_param.sub(&param);
+        let yk = new_grad.sub(&grad);
+        // Only store pairs which keep the Hessian approximation positive definite
+        if sk.dot(&yk) > F::epsilon() * yk.dot(&yk) {
+            if self.s.len() >= self.m {
+                self.s.pop_front();
+                self.y.pop_front();
+            }
+            self.s.push_back(sk);
+            self.y.push_back(yk);
+        }
+
+        Ok((
+            state.param(new_param).cost(new_cost).gradient(new_grad),
+            Some(kv),
+        ))
+    }
+
+    fn terminate(&mut self, state: &IterState<P, G, (), (), F>) -> TerminationStatus {
+        if let (Some(param), Some(grad)) = (state.get_param(), state.get_gradient()) {
+            // Infinity norm of the projected gradient P(x - g) - x
+            let pg_norm = param
+                .into_iter()
+                .zip(grad)
+                .zip((&self.lower).into_iter().zip(&self.upper))
+                .fold(float!(0.0), |acc: F, ((&xi, &gi), (&li, &ui))| {
+                    acc.max(((xi - gi).max(li).min(ui) - xi).abs())
+                });
+            if pg_norm <= self.tol_grad {
+                return TerminationStatus::Terminated(TerminationReason::SolverConverged);
+            }
+        }
+        let prev_cost = state.get_prev_cost();
+        let cost = state.get_cost();
+        if prev_cost.is_finite()
+            && (prev_cost - cost).abs()
+                <= self.tol_cost * prev_cost.abs().max(cost.abs()).max(float!(1.0))
+        {
+            return TerminationStatus::Terminated(TerminationReason::SolverConverged);
+        }
+        TerminationStatus::NotTerminated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{test_utils::TestProblem, ArgminError, Executor};
+    use approx::assert_relative_eq;
+
+    test_trait_impl!(lbfgsb, LBFGSB<Vec<f64>, Vec<f64>, f64>);
+
+    /// Rosenbrock function in `n` dimensions
+    struct Rosenbrock {}
+
+    impl CostFunction for Rosenbrock {
+        type Param = Vec<f64>;
+        type Output = f64;
+
+        fn cost(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+            Ok(p.windows(2)
+                .map(|w| 100.0 * (w[1] - w[0].powi(2)).powi(2) + (1.0 - w[0]).powi(2))
+                .sum())
+        }
+    }
+
+    impl Gradient for Rosenbrock {
+        type Param = Vec<f64>;
+        type Gradient = Vec<f64>;
+
+        fn gradient(&self, p: &Self::Param) -> Result<Self::Gradient, Error> {
+            let n = p.len();
+            let mut g = vec![0.0; n];
+            for i in 0..n - 1 {
+                g[i] += -400.0 * p[i] * (p[i + 1] - p[i].powi(2)) - 2.0 * (1.0 - p[i]);
+                g[i + 1] += 200.0 * (p[i + 1] - p[i].powi(2));
+            }
+            Ok(g)
+        }
+    }
+
+    fn run(
+        bounds: (Vec<f64>, Vec<f64>),
+        init: Vec<f64>,
+    ) -> IterState<Vec<f64>, Vec<f64>, (), (), f64> {
+        let solver = LBFGSB::new(bounds, 7).with_tolerance_grad(1e-8).unwrap();
+        Executor::new(Rosenbrock {}, solver)
+            .configure(|state| state.param(init).max_iters(500))
+            .run()
+            .unwrap()
+            .state
+    }
+
+    #[test]
+    fn test_new() {
+        let LBFGSB {
+            lower,
+            upper,
+            m,
+            s,
+            y,
+            tol_grad,
+            tol_cost,
+        }: LBFGSB<Vec<f64>, Vec<f64>, f64> = LBFGSB::new((vec![-1.0], vec![1.0]), 5);
+
+        assert_eq!(lower, vec![-1.0]);
+        assert_eq!(upper, vec![1.0]);
+        assert_eq!(m, 5);
+        assert!(s.is_empty());
+        assert!(y.is_empty());
+        assert_eq!(tol_grad.to_ne_bytes(), f64::EPSILON.sqrt().to_ne_bytes());
+        assert_eq!(tol_cost.to_ne_bytes(), f64::EPSILON.to_ne_bytes());
+    }
+
+    #[test]
+    fn test_tolerances() {
+        let lbfgsb: LBFGSB<Vec<f64>, Vec<f64>, f64> = LBFGSB::new((vec![-1.0], vec![1.0]), 5)
+            .with_tolerance_grad(1e-4)
+            .unwrap()
+            .with_tolerance_cost(1e-5)
+            .unwrap();
+        assert_eq!(lbfgsb.tol_grad.to_ne_bytes(), 1e-4f64.to_ne_bytes());
+        assert_eq!(lbfgsb.tol_cost.to_ne_bytes(), 1e-5f64.to_ne_bytes());
+
+        assert_error!(
+            LBFGSB::<Vec<f64>, Vec<f64>, f64>::new((vec![-1.0], vec![1.0]), 5)
+                .with_tolerance_grad(-1.0),
+            ArgminError,
+            "Invalid parameter: \"`L-BFGS-B`: gradient tolerance must be >= 0.\""
+        );
+        assert_error!(
+            LBFGSB::<Vec<f64>, Vec<f64>, f64>::new((vec![-1.0], vec![1.0]), 5)
+                .with_tolerance_cost(-1.0),
+            ArgminError,
+            "Invalid parameter: \"`L-BFGS-B`: cost tolerance must be >= 0.\""
+        );
+    }
+
+    #[test]
+    fn test_init_errors() {
+        let mut lbfgsb: LBFGSB<Vec<f64>, Vec<f64>, f64> =
+            LBFGSB::new((vec![-1.0, -1.0], vec![1.0, 1.0]), 5);
+        assert_error!(
+            lbfgsb.init(&mut Problem::new(TestProblem::new()), IterState::new()),
+            ArgminError,
+            concat!(
+                "Not initialized: \"`L-BFGS-B` requires an initial parameter vector. ",
+                "Please provide an initial guess via `Executor`s `configure` method.\""
+            )
+        );
+
+        assert_error!(
+            lbfgsb.init(
+                &mut Problem::new(TestProblem::new()),
+                IterState::new().param(vec![0.0])
+            ),
+            ArgminError,
+            concat!(
+                "Invalid parameter: \"`L-BFGS-B`: bounds must be of the same length as the ",
+                "parameter vector.\""
+            )
+        );
+
+        let mut lbfgsb: LBFGSB<Vec<f64>, Vec<f64>, f64> =
+            LBFGSB::new((vec![-1.0, 1.0], vec![1.0, -1.0]), 5);
+        assert_error!(
+            lbfgsb.init(
+                &mut Problem::new(TestProblem::new()),
+                IterState::new().param(vec![0.0, 0.0])
+            ),
+            ArgminError,
+            concat!(
+                "Invalid parameter: \"`L-BFGS-B`: lower bound must be lower than or equal to ",
+                "upper bound.\""
+            )
+        );
+    }
+
+    #[test]
+    fn test_init_projects_param() {
+        let mut lbfgsb: LBFGSB<Vec<f64>, Vec<f64>, f64> =
+            LBFGSB::new((vec![-1.0, -1.0], vec![1.0, 1.0]), 5);
+        let (state, _) = lbfgsb
+            .init(
+                &mut Problem::new(Rosenbrock {}),
+                IterState::new().param(vec![2.0, -3.0]).cost(0.0),
+            )
+            .unwrap();
+        assert_eq!(state.param.unwrap(), vec![1.0, -1.0]);
+        assert_relative_eq!(state.cost, 400.0);
+    }
+
+    #[test]
+    fn test_unconstrained() {
+        let inf = f64::INFINITY;
+        let state = run((vec![-inf; 4], vec![inf; 4]), vec![-1.2, 1.0, -1.2, 1.0]);
+        assert_eq!(
+            state.termination_status,
+            TerminationStatus::Terminated(TerminationReason::SolverConverged)
+        );
+        for xi in state.best_param.unwrap() {
+            assert_relative_eq!(xi, 1.0, epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_active_bounds() {
+        // The unconstrained minimum (1, 1, 1) violates the upper bound of the second parameter.
+        let state = run(
+            (vec![-2.0, -2.0, -2.0], vec![2.0, 0.5, 2.0]),
+            vec![-1.0, 0.0, 1.5],
+        );
+        assert_eq!(
+            state.termination_status,
+            TerminationStatus::Terminated(TerminationReason::SolverConverged)
+        );
+        let x = state.best_param.unwrap();
+        assert_relative_eq!(x[1], 0.5, epsilon = 1e-10);
+        // With x[1] fixed, the remaining parameters minimize the decoupled terms.
+        assert_relative_eq!(x[2], 0.25, epsilon = 1e-5);
+        let g = Rosenbrock {}.gradient(&x).unwrap();
+        assert!(g[1] < 0.0);
+        assert_relative_eq!(g[0], 0.0, epsilon = 1e-5);
+        assert_relative_eq!(g[2], 0.0, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn test_nan() {
+        let x = vec![0.0, 0.0, 0.0];
+        let l = vec![-1.0; 3];
+        let u = vec![1.0; 3];
+        let compact = CompactRepresentation::new(&[], &[], 3).unwrap();
+        let (xc, _) = compact.cauchy_point(&x, &[1.0, f64::NAN, -2.0], &l, &u);
+        assert_eq!(xc.len(), 3);
+    }
+
+    #[test]
+    fn test_start_at_bound() {
+        // Minimum of the Rosenbrock function restricted to x >= 1.5 in the first dimension
+        let state = run((vec![1.5, -5.0], vec![5.0, 5.0]), vec![1.5, 0.0]);
+        let x = state.best_param.unwrap();
+        assert_relative_eq!(x[0], 1.5, epsilon = 1e-10);
+        assert_relative_eq!(x[1], 2.25, epsilon = 1e-6);
+    }
+}
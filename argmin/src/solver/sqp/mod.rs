@@ -0,0 +1,912 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! # Sequential Quadratic Programming
+//!
+//! Solves smooth nonlinearly constrained problems of the form
+//!
+//! ```text
+//! minimize    f(x)
+//! subject to  c_E(x) = 0
+//!             c_I(x) >= 0
+//! ```
+//!
+//! For details see [`SQP`].
+//!
+//! ## Reference
+//!
+//! Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+//! Springer. ISBN 0-387-30303-0.
+
+use crate::core::{
+    ArgminFloat, CostFunction, DeserializeOwnedAlias, EqualityConstraints, Error, Executor,
+    Gradient, InequalityConstraints, IterState, LineSearch, OptimizationResult, Problem,
+    SerializeAlias, Solver, TerminationReason, TerminationStatus, KV,
+};
+use crate::solver::augmentedlagrangian::{violation, AugmentedLagrangianProblem};
+use crate::solver::quasinewton::bfgs_update;
+use crate::solver::utils::{dot, solve};
+use argmin_math::{
+    ArgminAdd, ArgminDot, ArgminEye, ArgminL2Norm, ArgminMul, ArgminScaledAdd, ArgminSub,
+    ArgminTranspose,
+};
+#[cfg(feature = "serde1")]
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of sweeps of the QP subproblem solver
+const QP_MAX_SWEEPS: usize = 10000;
+
+/// # Sequential Quadratic Programming
+///
+/// SQP method for smooth problems with equality constraints `c_E(x) = 0` and inequality
+/// constraints `c_I(x) >= 0`. In each iteration, the quadratic program
+///
+/// ```text
+/// minimize    g^T d + 1/2 d^T B d
+/// subject to  c_E + A_E d = 0
+///             c_I + A_I d >= 0
+/// ```
+///
+/// is solved, where `g` is the gradient of the objective, `A_E` and `A_I` are the Jacobians of
+/// the constraints and `B` is a BFGS approximation of the Hessian of the Lagrangian. The
+/// approximation of the inverse of `B` is updated with the same BFGS update as used by
+/// [`BFGS`](`crate::solver::quasinewton::BFGS`), where the change of the gradient of the
+/// Lagrangian takes the role of the change of the gradient. Updates which would destroy positive
+/// definiteness are skipped. The quadratic program is solved internally via its dual, which only
+/// requires the inverse Hessian approximation.
+///
+/// The step length along the solution `d` of the quadratic program is determined by a line
+/// search on the augmented Lagrangian merit function
+///
+/// ```text
+/// L(x) = f(x) - sum_i lambda_i c_i(x) + mu/2 sum_i c_i(x)^2 + sum_j psi(c_j(x), sigma_j, mu)
+/// ```
+///
+/// (see [`AugmentedLagrangian`](`crate::solver::augmentedlagrangian::AugmentedLagrangian`)) with
+/// the multipliers of the quadratic program and the penalty parameter `mu` set via
+/// [`with_penalty`](`SQP::with_penalty`). The line search is provided via the constructor and
+/// may be any solver implementing the [`LineSearch`] trait.
+///
+/// An initial guess for the parameter vector and an initial inverse Hessian are required, which
+/// are to be provided via the [`configure`](`crate::core::Executor::configure`) method of the
+/// [`Executor`](`crate::core::Executor`) (See [`IterState`], in particular [`IterState::param`]
+/// and [`IterState::inv_hessian`]).
+///
+/// The algorithm terminates once the norm of the gradient of the Lagrangian is below the
+/// tolerance set with [`with_tolerance_grad`](`SQP::with_tolerance_grad`) and the infinity norm
+/// of the constraint violation is below the tolerance set with
+/// [`with_tolerance_constraints`](`SQP::with_tolerance_constraints`). The cost reported in the
+/// state is the value of the objective function `f` if the constraint violation is within the
+/// tolerance and infinity otherwise. The Lagrange multipliers of the last quadratic program are
+/// available via [`IterState::get_equality_multipliers`] and
+/// [`IterState::get_inequality_multipliers`].
+///
+/// ## Requirements on the optimization problem
+///
+/// The optimization problem is required to implement [`CostFunction`], [`Gradient`],
+/// [`EqualityConstraints`] and [`InequalityConstraints`], including the Jacobians of the
+/// constraints. Problems without equality or inequality constraints return empty vectors.
+///
+/// ## Reference
+///
+/// Jorge Nocedal and Stephen J. Wright (2006). Numerical Optimization.
+/// Springer. ISBN 0-387-30303-0.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+pub struct SQP<L, G, F> {
+    /// line search
+    linesearch: L,
+    /// Penalty parameter of the merit function
+    penalty: F,
+    /// Tolerance on the norm of the gradient of the Lagrangian
+    tol_grad: F,
+    /// Tolerance on the constraint violation
+    tol_constraints: F,
+    /// Constraints and their Jacobians at the current iterate
+    linearization: Option<Linearization<G, F>>,
+    /// Norm of the gradient of the Lagrangian at the current iterate
+    grad_norm: F,
+    /// Constraint violation of the current iterate
+    violation: F,
+}
+
+/// Values and Jacobians of the constraints at a given parameter vector
+#[derive(Clone)]
+#[cfg_attr(feature = "serde1", derive(Serialize, Deserialize))]
+struct Linearization<G, F> {
+    /// Values of the equality constraints
+    ceq: Vec<F>,
+    /// Values of the inequality constraints
+    cineq: Vec<F>,
+    /// Jacobian of the equality constraints
+    jeq: Vec<G>,
+    /// Jacobian of the inequality constraints
+    jineq: Vec<G>,
+}
+
+impl<G, F: ArgminFloat> Linearization<G, F> {
+    /// Evaluate constraints and their Jacobians at `param`
+    fn new<O, P>(problem: &mut Problem<O>, param: &P) -> Result<Self, Error>
+    where
+        O: EqualityConstraints<Param = P, Gradient = G, Float = F>
+            + InequalityConstraints<Param = P, Gradient = G, Float = F>,
+    {
+        Ok(Linearization {
+            ceq: problem.equality_constraints(param)?,
+            cineq: problem.inequality_constraints(param)?,
+            jeq: problem.equality_jacobian(param)?,
+            jineq: problem.inequality_jacobian(param)?,
+        })
+    }
+
+    /// Values of all constraints, equality constraints first
+    fn constraints(&self) -> impl Iterator<Item = &F> {
+        self.ceq.iter().chain(self.cineq.iter())
+    }
+
+    /// Gradients of all constraints, equality constraints first
+    fn jacobian(&self) -> impl Iterator<Item = &G> {
+        self.jeq.iter().chain(self.jineq.iter())
+    }
+
+    /// Gradient of the Lagrangian `grad - sum_i multipliers_i grad c_i`
+    fn lagrangian_gradient(&self, grad: &G, multipliers: &[F]) -> G
+    where
+        G: Clone + ArgminScaledAdd<G, F, G>,
+    {
+        self.jacobian()
+            .zip(multipliers.iter())
+            .fold(grad.clone(), |acc, (a, &m)| acc.scaled_add(&-m, a))
+    }
+}
+
+impl<L, G, F> SQP<L, G, F>
+where
+    F: ArgminFloat,
+{
+    /// Construct a new instance of [`SQP`]
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::sqp::SQP;
+    /// # let linesearch = ();
+    /// let sqp: SQP<_, Vec<f64>, f64> = SQP::new(linesearch);
+    /// ```
+    pub fn new(linesearch: L) -> Self {
+        SQP {
+            linesearch,
+            penalty: float!(10.0),
+            tol_grad: F::epsilon().sqrt(),
+            tol_constraints: F::epsilon().sqrt(),
+            linearization: None,
+            grad_norm: F::infinity(),
+            violation: F::infinity(),
+        }
+    }
+
+    /// Set penalty parameter of the merit function
+    ///
+    /// Must be larger than 0. Defaults to `10`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::sqp::SQP;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// # let linesearch = ();
+    /// let sqp: SQP<_, Vec<f64>, f64> = SQP::new(linesearch).with_penalty(100.0)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_penalty(mut self, penalty: F) -> Result<Self, Error> {
+        if penalty <= float!(0.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`SQP`: penalty must be > 0."
+            ));
+        }
+        self.penalty = penalty;
+        Ok(self)
+    }
+
+    /// Set tolerance on the norm of the gradient of the Lagrangian
+    ///
+    /// Must be non-negative. Defaults to `sqrt(EPSILON)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::sqp::SQP;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// # let linesearch = ();
+    /// let sqp: SQP<_, Vec<f64>, f64> = SQP::new(linesearch).with_tolerance_grad(1e-6)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_tolerance_grad(mut self, tol: F) -> Result<Self, Error> {
+        if tol < float!(0.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`SQP`: gradient tolerance must be >= 0."
+            ));
+        }
+        self.tol_grad = tol;
+        Ok(self)
+    }
+
+    /// Set tolerance on the infinity norm of the constraint violation
+    ///
+    /// Must be non-negative. Defaults to `sqrt(EPSILON)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use argmin::solver::sqp::SQP;
+    /// # use argmin::core::Error;
+    /// # fn main() -> Result<(), Error> {
+    /// # let linesearch = ();
+    /// let sqp: SQP<_, Vec<f64>, f64> = SQP::new(linesearch).with_tolerance_constraints(1e-6)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_tolerance_constraints(mut self, tol: F) -> Result<Self, Error> {
+        if tol < float!(0.0) {
+            return Err(argmin_error!(
+                InvalidParameter,
+                "`SQP`: constraint tolerance must be >= 0."
+            ));
+        }
+        self.tol_constraints = tol;
+        Ok(self)
+    }
+
+    /// Objective function value if the current constraint violation is within the tolerance,
+    /// infinity otherwise
+    fn cost<O, P>(&self, problem: &mut Problem<O>, param: &P) -> Result<F, Error>
+    where
+        O: CostFunction<Param = P, Output = F>,
+    {
+        if self.violation <= self.tol_constraints {
+            problem.cost(param)
+        } else {
+            Ok(F::infinity())
+        }
+    }
+}
+
+/// Solves the dual of the quadratic program
+///
+/// ```text
+/// minimize    1/2 mu^T m mu + mu^T r
+/// subject to  mu_i >= 0 for i >= n_eq
+/// ```
+///
+/// where `m` is positive semidefinite. The gradient `m mu + r` of the dual objective is the
+/// residual of the linearized constraints. The problem is solved with projected Gauss-Seidel
+/// iterations. Every few sweeps, an exact solve on the current set of active constraints is
+/// attempted, which is accepted if it satisfies the optimality conditions. An error is returned
+/// if the optimality conditions are not met after `QP_MAX_SWEEPS` sweeps.
+fn solve_dual_qp<F: ArgminFloat>(m: &[Vec<F>], r: &[F], n_eq: usize) -> Result<Vec<F>, Error> {
+    let n = r.len();
+    let zero = float!(0.0);
+    let tol = float!(1e3) * F::epsilon() * r.iter().fold(float!(1.0), |acc, x| acc.max(x.abs()));
+
+    // Violation of the optimality conditions of the dual problem
+    let max_kkt = |mu: &[F]| {
+        (0..n).fold(zero, |acc, i| {
+            let grad = r[i] + dot(&m[i], mu);
+            if i < n_eq || mu[i] > zero {
+                acc.max(grad.abs())
+            } else {
+                acc.max((-grad).max(zero)).max(-mu[i])
+            }
+        })
+    };
+
+    // Exact solution of the equality constrained problem on the active set
+    let refine = |mu: &[F]| {
+        let active: Vec<usize> = (0..n).filter(|&i| i < n_eq || mu[i] > zero).collect();
+        let a = active
+            .iter()
+            .map(|&i| active.iter().map(|&j| m[i][j]).collect())
+            .collect();
+        let b = active.iter().map(|&i| vec![-r[i]]).collect();
+        solve(a, b).map(|x| {
+            let mut refined = vec![zero; n];
+            for (&i, xi) in active.iter().zip(x) {
+                refined[i] = xi[0];
+            }
+            refined
+        })
+    };
+
+    let mut mu = vec![zero; n];
+    for sweep in 0..QP_MAX_SWEEPS {
+        for i in 0..n {
+            if m[i][i] > zero {
+                let new = mu[i] - (r[i] + dot(&m[i], &mu)) / m[i][i];
+                mu[i] = if i < n_eq { new } else { new.max(zero) };
+            }
+        }
+        if max_kkt(&mu) <= tol {
+            return Ok(mu);
+        }
+        if sweep % 10 == 0 {
+            if let Some(refined) = refine(&mu) {
+                if max_kkt(&refined) <= tol {
+                    return Ok(refined);
+                }
+            }
+        }
+    }
+    Err(argmin_error!(
+        ConditionViolated,
+        format!("`SQP`: Quadratic subproblem did not converge within {QP_MAX_SWEEPS} sweeps.")
+    ))
+}
+
+impl<O, L, P, G, H, F> Solver<O, IterState<P, G, (), H, F>> for SQP<L, G, F>
+where
+    O: CostFunction<Param = P, Output = F>
+        + Gradient<Param = P, Gradient = G>
+        + EqualityConstraints<Param = P, Gradient = G, Float = F>
+        + InequalityConstraints<Param = P, Gradient = G, Float = F>,
+    P: Clone
+        + SerializeAlias
+        + DeserializeOwnedAlias
+        + ArgminSub<P, P>
+        + ArgminL2Norm<F>
+        + ArgminDot<G, H>
+        + ArgminDot<P, H>,
+    G: Clone
+        + SerializeAlias
+        + DeserializeOwnedAlias
+        + ArgminL2Norm<F>
+        + ArgminMul<F, P>
+        + ArgminDot<P, F>
+        + ArgminDot<G, F>
+        + ArgminSub<G, G>
+        + ArgminScaledAdd<G, F, G>,
+    H: SerializeAlias
+        + DeserializeOwnedAlias
+        + ArgminSub<H, H>
+        + ArgminDot<G, G>
+        + ArgminDot<H, H>
+        + ArgminAdd<H, H>
+        + ArgminMul<F, H>
+        + ArgminTranspose<H>
+        + ArgminEye,
+    L: Clone
+        + LineSearch<P, F>
+        + Solver<AugmentedLagrangianProblem<O, F>, IterState<P, G, (), (), F>>,
+    F: ArgminFloat,
+{
+    const NAME: &'static str = "SQP";
+
+    fn init(
+        &mut self,
+        problem: &mut Problem<O>,
+        mut state: IterState<P, G, (), H, F>,
+    ) -> Result<(IterState<P, G, (), H, F>, Option<KV>), Error> {
+        let param = state.take_param().ok_or_else(argmin_error_closure!(
+            NotInitialized,
+            concat!(
+                "`SQP` requires an initial parameter vector. ",
+                "Please provide an initial guess via `Executor`s `configure` method."
+            )
+        ))?;
+
+        let inv_hessian = state.take_inv_hessian().ok_or_else(argmin_error_closure!(
+            NotInitialized,
+            concat!(
+                "`SQP` requires an initial inverse Hessian. ",
+                "Please provide an initial guess via `Executor`s `configure` method."
+            )
+        ))?;
+
+        let grad = state
+            .take_gradient()
+            .map(Result::Ok)
+            .unwrap_or_else(|| problem.gradient(&param))?;
+
+        let linearization = Linearization::new(problem, &param)?;
+        self.violation = violation(&linearization.ceq, &linearization.cineq);
+        self.grad_norm = F::infinity();
+        self.linearization = Some(linearization);
+
+        let cost = self.cost(problem, &param)?;
+        Ok((
+            state
+                .param(param)
+                .cost(cost)
+                .gradient(grad)
+                .inv_hessian(inv_hessian),
+            Some(kv!("constraint_violation" => self.violation;)),
+        ))
+    }
+
+    fn next_iter(
+        &mut self,
+        problem: &mut Problem<O>,
+        mut state: IterState<P, G, (), H, F>,
+    ) -> Result<(IterState<P, G, (), H, F>, Option<KV>), Error> {
+        let param = state.take_param().ok_or_else(argmin_error_closure!(
+            PotentialBug,
+            "`SQP`: Parameter vector in state not set."
+        ))?;
+
+        let grad = state.take_gradient().ok_or_else(argmin_error_closure!(
+            PotentialBug,
+            "`SQP`: Gradient in state not set."
+        ))?;
+
+        let inv_hessian = state.take_inv_hessian().ok_or_else(argmin_error_closure!(
+            PotentialBug,
+            "`SQP`: Inverse Hessian in state not set."
+        ))?;
+
+        let lin = self.linearization.take().ok_or_else(argmin_error_closure!(
+            PotentialBug,
+            "`SQP`: Constraints not evaluated."
+        ))?;
+
+        // Dual of the QP subproblem: The solution of the QP for given multipliers `mu` is
+        // `d = H (A^T mu - g)`, which leads to `1/2 mu^T (A H A^T) mu + mu^T (c - A H g)`.
+        let inv_hessian_jacobian: Vec<G> = lin.jacobian().map(|a| inv_hessian.dot(a)).collect();
+        let inv_hessian_grad: G = inv_hessian.dot(&grad);
+        let m: Vec<Vec<F>> = lin
+            .jacobian()
+            .map(|a| inv_hessian_jacobian.iter().map(|ha| a.dot(ha)).collect())
+            .collect();
+        let r: Vec<F> = lin
+            .constraints()
+            .zip(lin.jacobian())
+            .map(|(&c, a)| c - a.dot(&inv_hessian_grad))
+            .collect();
+        let multipliers = solve_dual_qp(&m, &r, lin.ceq.len())?;
+
+        let lagrangian_grad = lin.lagrangian_gradient(&grad, &multipliers);
+        let d: P = inv_hessian.dot(&lagrangian_grad).mul(&float!(-1.0));
+
+        self.linesearch.search_direction(d);
+
+        let (multipliers_eq, multipliers_ineq) = multipliers.split_at(lin.ceq.len());
+        let merit = AugmentedLagrangianProblem {
+            problem: problem.take_problem().unwrap(),
+            multipliers_eq: multipliers_eq.to_vec(),
+            multipliers_ineq: multipliers_ineq.to_vec(),
+            penalty: self.penalty,
+        };
+
+        // Run line search on merit function
+        let OptimizationResult {
+            problem: line_problem,
+            state: mut sub_state,
+            ..
+        } = Executor::new(merit, self.linesearch.clone())
+            .configure(|config| config.param(param.clone()))
+            .ctrlc(false)
+            .run()?;
+
+        let xk1 = sub_state.take_param().ok_or_else(argmin_error_closure!(
+            PotentialBug,
+            "`SQP`: No parameters returned by line search."
+        ))?;
+
+        // take care of function eval counts
+        AugmentedLagrangianProblem::restore(problem, line_problem);
+
+        let grad1 = problem.gradient(&xk1)?;
+        let lin1 = Linearization::new(problem, &xk1)?;
+
+        // BFGS update with the change of the gradient of the Lagrangian. Updates with
+        // insufficient curvature are skipped to keep the approximation positive definite.
+        let lagrangian_grad1 = lin1.lagrangian_gradient(&grad1, &multipliers);
+        let yk = lagrangian_grad1.sub(&lagrangian_grad);
+        let sk = xk1.sub(&param);
+        let yksk: F = yk.dot(&sk);
+        let inv_hessian = if yksk > F::epsilon().sqrt() * yk.l2_norm() * sk.l2_norm() {
+            bfgs_update(&inv_hessian, &sk, &yk)
+        } else {
+            inv_hessian
+        };
+
+        self.grad_norm = lagrangian_grad1.l2_norm();
+        self.violation = violation(&lin1.ceq, &lin1.cineq);
+        self.linearization = Some(lin1);
+
+        let cost = self.cost(problem, &xk1)?;
+        Ok((
+            state
+                .param(xk1)
+                .cost(cost)
+                .gradient(grad1)
+                .inv_hessian(inv_hessian)
+                .equality_multipliers(multipliers_eq.to_vec())
+                .inequality_multipliers(multipliers_ineq.to_vec()),
+            Some(kv!(
+                "constraint_violation" => self.violation;
+                "lagrangian_grad_norm" => self.grad_norm;
+            )),
+        ))
+    }
+
+    fn terminate(&mut self, _state: &IterState<P, G, (), H, F>) -> TerminationStatus {
+        if self.grad_norm <= self.tol_grad && self.violation <= self.tol_constraints {
+            return TerminationStatus::Terminated(TerminationReason::SolverConverged);
+        }
+        TerminationStatus::NotTerminated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ArgminError, State};
+    use crate::solver::linesearch::{
+        condition::ArmijoCondition, BacktrackingLineSearch, MoreThuenteLineSearch,
+    };
+    use approx::assert_relative_eq;
+
+    type MoreThuente = MoreThuenteLineSearch<Vec<f64>, Vec<f64>, f64>;
+
+    test_trait_impl!(sqp, SQP<MoreThuente, Vec<f64>, f64>);
+
+    /// Defines a test problem with objective `$cost`, equality constraints `$eq` and inequality
+    /// constraints `$ineq` together with their derivatives
+    macro_rules! constrained_problem {
+        ($name:ident, $cost:expr, $grad:expr, $eq:expr, $jeq:expr, $ineq:expr, $jineq:expr) => {
+            #[derive(Clone)]
+            struct $name {}
+
+            impl CostFunction for $name {
+                type Param = Vec<f64>;
+                type Output = f64;
+
+                fn cost(&self, p: &Self::Param) -> Result<Self::Output, Error> {
+                    Ok($cost(p))
+                }
+            }
+
+            impl Gradient for $name {
+                type Param = Vec<f64>;
+                type Gradient = Vec<f64>;
+
+                fn gradient(&self, p: &Self::Param) -> Result<Self::Gradient, Error> {
+                    Ok($grad(p))
+                }
+            }
+
+            impl EqualityConstraints for $name {
+                type Param = Vec<f64>;
+                type Gradient = Vec<f64>;
+                type Float = f64;
+
+                fn equality_constraints(&self, p: &Self::Param) -> Result<Vec<f64>, Error> {
+                    Ok($eq(p))
+                }
+
+                fn equality_jacobian(&self, p: &Self::Param) -> Result<Vec<Vec<f64>>, Error> {
+                    Ok($jeq(p))
+                }
+            }
+
+            impl InequalityConstraints for $name {
+                type Param = Vec<f64>;
+                type Gradient = Vec<f64>;
+                type Float = f64;
+
+                fn inequality_constraints(&self, p: &Self::Param) -> Result<Vec<f64>, Error> {
+                    Ok($ineq(p))
+                }
+
+                fn inequality_jacobian(&self, p: &Self::Param) -> Result<Vec<Vec<f64>>, Error> {
+                    Ok($jineq(p))
+                }
+            }
+        };
+    }
+
+    // `min (x - 2)^2 + (y - 1)^2` s.t. `x + y = 1` with solution `(1, 0)` and multiplier `-2`
+    constrained_problem!(
+        LinearEquality,
+        |p: &Vec<f64>| (p[0] - 2.0).powi(2) + (p[1] - 1.0).powi(2),
+        |p: &Vec<f64>| vec![2.0 * (p[0] - 2.0), 2.0 * (p[1] - 1.0)],
+        |p: &Vec<f64>| vec![p[0] + p[1] - 1.0],
+        |_p: &Vec<f64>| vec![vec![1.0, 1.0]],
+        |_p: &Vec<f64>| vec![],
+        |_p: &Vec<f64>| vec![]
+    );
+
+    // `min x^2 + y^2` s.t. `x + y >= 1` and `y >= -1` with solution `(0.5, 0.5)` and multipliers
+    // `(1, 0)`
+    constrained_problem!(
+        LinearInequality,
+        |p: &Vec<f64>| p[0].powi(2) + p[1].powi(2),
+        |p: &Vec<f64>| vec![2.0 * p[0], 2.0 * p[1]],
+        |_p: &Vec<f64>| vec![],
+        |_p: &Vec<f64>| vec![],
+        |p: &Vec<f64>| vec![p[0] + p[1] - 1.0, p[1] + 1.0],
+        |_p: &Vec<f64>| vec![vec![1.0, 1.0], vec![0.0, 1.0]]
+    );
+
+    // `min x + y` s.t. `x^2 + y^2 = 2` with solution `(-1, -1)` and multiplier `-0.5`
+    constrained_problem!(
+        Circle,
+        |p: &Vec<f64>| p[0] + p[1],
+        |_p: &Vec<f64>| vec![1.0, 1.0],
+        |p: &Vec<f64>| vec![p[0].powi(2) + p[1].powi(2) - 2.0],
+        |p: &Vec<f64>| vec![vec![2.0 * p[0], 2.0 * p[1]]],
+        |_p: &Vec<f64>| vec![],
+        |_p: &Vec<f64>| vec![]
+    );
+
+    // `min -x - y` s.t. `x^2 + y^2 <= 1` and `x <= 2` with solution `(1/sqrt(2), 1/sqrt(2))` and
+    // multipliers `(1/sqrt(2), 0)`
+    constrained_problem!(
+        Disk,
+        |p: &Vec<f64>| -p[0] - p[1],
+        |_p: &Vec<f64>| vec![-1.0, -1.0],
+        |_p: &Vec<f64>| vec![],
+        |_p: &Vec<f64>| vec![],
+        |p: &Vec<f64>| vec![1.0 - p[0].powi(2) - p[1].powi(2), 2.0 - p[0]],
+        |p: &Vec<f64>| vec![vec![-2.0 * p[0], -2.0 * p[1]], vec![-1.0, 0.0]]
+    );
+
+    fn identity() -> Vec<Vec<f64>> {
+        vec![vec![1.0, 0.0], vec![0.0, 1.0]]
+    }
+
+    #[test]
+    fn test_new() {
+        let sqp: SQP<_, Vec<f64>, f64> = SQP::new(MoreThuente::new());
+        assert_eq!(sqp.penalty.to_ne_bytes(), 10.0f64.to_ne_bytes());
+        assert_eq!(
+            sqp.tol_grad.to_ne_bytes(),
+            f64::EPSILON.sqrt().to_ne_bytes()
+        );
+        assert_eq!(
+            sqp.tol_constraints.to_ne_bytes(),
+            f64::EPSILON.sqrt().to_ne_bytes()
+        );
+        assert!(sqp.linearization.is_none());
+        assert!(sqp.grad_norm.is_infinite());
+        assert!(sqp.violation.is_infinite());
+    }
+
+    #[test]
+    fn test_builders() {
+        let sqp: SQP<_, Vec<f64>, f64> = SQP::new(MoreThuente::new())
+            .with_penalty(2.0)
+            .unwrap()
+            .with_tolerance_grad(1e-4)
+            .unwrap()
+            .with_tolerance_constraints(0.0)
+            .unwrap();
+        assert_eq!(sqp.penalty.to_ne_bytes(), 2.0f64.to_ne_bytes());
+        assert_eq!(sqp.tol_grad.to_ne_bytes(), 1e-4f64.to_ne_bytes());
+        assert_eq!(sqp.tol_constraints.to_ne_bytes(), 0.0f64.to_ne_bytes());
+    }
+
+    #[test]
+    fn test_builder_errors() {
+        let new = || SQP::<_, Vec<f64>, f64>::new(MoreThuente::new());
+        for penalty in [0.0, -1.0] {
+            assert_error!(
+                new().with_penalty(penalty),
+                ArgminError,
+                "Invalid parameter: \"`SQP`: penalty must be > 0.\""
+            );
+        }
+        assert_error!(
+            new().with_tolerance_grad(-1.0),
+            ArgminError,
+            "Invalid parameter: \"`SQP`: gradient tolerance must be >= 0.\""
+        );
+        assert_error!(
+            new().with_tolerance_constraints(-1.0),
+            ArgminError,
+            "Invalid parameter: \"`SQP`: constraint tolerance must be >= 0.\""
+        );
+    }
+
+    #[test]
+    fn test_init_param_not_initialized() {
+        let mut sqp: SQP<_, Vec<f64>, f64> = SQP::new(MoreThuente::new());
+        let res = sqp.init(
+            &mut Problem::new(LinearEquality {}),
+            IterState::<Vec<f64>, Vec<f64>, (), Vec<Vec<f64>>, f64>::new().inv_hessian(identity()),
+        );
+        assert_error!(
+            res,
+            ArgminError,
+            concat!(
+                "Not initialized: \"`SQP` requires an initial parameter vector. ",
+                "Please provide an initial guess via `Executor`s `configure` method.\""
+            )
+        );
+    }
+
+    #[test]
+    fn test_init_inv_hessian_not_initialized() {
+        let mut sqp: SQP<_, Vec<f64>, f64> = SQP::new(MoreThuente::new());
+        let res = sqp.init(
+            &mut Problem::new(LinearEquality {}),
+            IterState::<Vec<f64>, Vec<f64>, (), Vec<Vec<f64>>, f64>::new().param(vec![0.0, 0.0]),
+        );
+        assert_error!(
+            res,
+            ArgminError,
+            concat!(
+                "Not initialized: \"`SQP` requires an initial inverse Hessian. ",
+                "Please provide an initial guess via `Executor`s `configure` method.\""
+            )
+        );
+    }
+
+    #[test]
+    fn test_init() {
+        let mut sqp: SQP<_, Vec<f64>, f64> = SQP::new(MoreThuente::new());
+        let mut problem = Problem::new(LinearInequality {});
+        let (mut state, kv) = sqp
+            .init(
+                &mut problem,
+                IterState::new()
+                    .param(vec![0.0, -2.0])
+                    .inv_hessian(identity()),
+            )
+            .unwrap();
+        assert_eq!(state.take_gradient().unwrap(), vec![0.0, -4.0]);
+        assert!(state.get_cost().is_infinite());
+        assert_relative_eq!(sqp.violation, 3.0);
+        assert_relative_eq!(
+            kv.unwrap()
+                .get("constraint_violation")
+                .unwrap()
+                .get_float()
+                .unwrap(),
+            3.0
+        );
+        let lin = sqp.linearization.as_ref().unwrap();
+        assert_eq!(lin.cineq, vec![-3.0, -1.0]);
+        assert_eq!(lin.jineq, vec![vec![1.0, 1.0], vec![0.0, 1.0]]);
+        assert!(lin.ceq.is_empty());
+        assert!(lin.jeq.is_empty());
+        assert_eq!(problem.counts["gradient_count"], 1);
+        assert_eq!(problem.counts["inequality_jacobian_count"], 1);
+        assert!(!problem.counts.contains_key("cost_count"));
+    }
+
+    #[test]
+    fn test_solve_dual_qp() {
+        // Equality constrained: unconstrained minimizer
+        let m = vec![vec![2.0, 1.0], vec![1.0, 2.0]];
+        let mu = solve_dual_qp(&m, &[1.0, -1.0], 2).unwrap();
+        assert_relative_eq!(mu[0], -1.0, epsilon = 1e-12);
+        assert_relative_eq!(mu[1], 1.0, epsilon = 1e-12);
+        // Inequality constrained: first multiplier is at its bound
+        let mu = solve_dual_qp(&m, &[1.0, -1.0], 0).unwrap();
+        assert_relative_eq!(mu[0], 0.0);
+        assert_relative_eq!(mu[1], 0.5, epsilon = 1e-12);
+        // No constraints
+        assert!(solve_dual_qp::<f64>(&[], &[], 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_solve_dual_qp_errors() {
+        // Singular `m` and inconsistent `r`: the dual problem is unbounded
+        let m = vec![vec![1.0, 1.0], vec![1.0, 1.0]];
+        assert_error!(
+            solve_dual_qp(&m, &[1.0, -1.0], 2),
+            ArgminError,
+            concat!(
+                "Condition violated: \"`SQP`: Quadratic subproblem did not converge within ",
+                "10000 sweeps.\""
+            )
+        );
+    }
+
+    #[test]
+    fn test_linear_equality() {
+        let sqp = SQP::new(MoreThuente::new());
+        let res = Executor::new(LinearEquality {}, sqp)
+            .configure(|state| {
+                state
+                    .param(vec![0.0, 0.0])
+                    .inv_hessian(identity())
+                    .max_iters(50)
+            })
+            .run()
+            .unwrap();
+        assert_eq!(
+            res.state.get_termination_reason(),
+            Some(&TerminationReason::SolverConverged)
+        );
+        let param = res.state.get_best_param().unwrap();
+        assert_relative_eq!(param[0], 1.0, epsilon = 1e-6);
+        assert_relative_eq!(param[1], 0.0, epsilon = 1e-6);
+        assert_relative_eq!(res.state.get_best_cost(), 2.0, epsilon = 1e-6);
+        let multipliers = res.state.get_equality_multipliers().unwrap();
+        assert_relative_eq!(multipliers[0], -2.0, epsilon = 1e-6);
+        assert!(res.state.get_inequality_multipliers().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_linear_inequality() {
+        let sqp = SQP::new(MoreThuente::new());
+        let res = Executor::new(LinearInequality {}, sqp)
+            .configure(|state| {
+                state
+                    .param(vec![3.0, -2.0])
+                    .inv_hessian(identity())
+                    .max_iters(50)
+            })
+            .run()
+            .unwrap();
+        assert_eq!(
+            res.state.get_termination_reason(),
+            Some(&TerminationReason::SolverConverged)
+        );
+        let param = res.state.get_best_param().unwrap();
+        assert_relative_eq!(param[0], 0.5, epsilon = 1e-6);
+        assert_relative_eq!(param[1], 0.5, epsilon = 1e-6);
+        let multipliers = res.state.get_inequality_multipliers().unwrap();
+        assert_relative_eq!(multipliers[0], 1.0, epsilon = 1e-6);
+        assert_relative_eq!(multipliers[1], 0.0);
+    }
+
+    #[test]
+    fn test_nonlinear_equality() {
+        let sqp = SQP::new(MoreThuente::new());
+        let res = Executor::new(Circle {}, sqp)
+            .configure(|state| {
+                state
+                    .param(vec![-1.5, -0.5])
+                    .inv_hessian(identity())
+                    .max_iters(100)
+            })
+            .run()
+            .unwrap();
+        assert_eq!(
+            res.state.get_termination_reason(),
+            Some(&TerminationReason::SolverConverged)
+        );
+        let param = res.state.get_best_param().unwrap();
+        assert_relative_eq!(param[0], -1.0, epsilon = 1e-6);
+        assert_relative_eq!(param[1], -1.0, epsilon = 1e-6);
+        let multipliers = res.state.get_equality_multipliers().unwrap();
+        assert_relative_eq!(multipliers[0], -0.5, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_nonlinear_inequality_backtracking() {
+        let linesearch = BacktrackingLineSearch::new(ArmijoCondition::new(1e-4).unwrap());
+        let sqp = SQP::new(linesearch);
+        let res = Executor::new(Disk {}, sqp)
+            .configure(|state| {
+                state
+                    .param(vec![0.5, 0.0])
+                    .inv_hessian(identity())
+                    .max_iters(100)
+            })
+            .run()
+            .unwrap();
+        assert_eq!(
+            res.state.get_termination_reason(),
+            Some(&TerminationReason::SolverConverged)
+        );
+        let param = res.state.get_best_param().unwrap();
+        assert_relative_eq!(param[0], 0.5f64.sqrt(), epsilon = 1e-6);
+        assert_relative_eq!(param[1], 0.5f64.sqrt(), epsilon = 1e-6);
+        let multipliers = res.state.get_inequality_multipliers().unwrap();
+        assert_relative_eq!(multipliers[0], 0.5f64.sqrt(), epsilon = 1e-6);
+        assert_relative_eq!(multipliers[1], 0.0);
+    }
+}
@@ -0,0 +1,118 @@
+// Copyright 2018-2022 argmin developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Linear algebra on `Vec`s and float ordering shared by the solvers which work on the
+//! components of their parameter vectors.
+
+use crate::core::ArgminFloat;
+use std::cmp::Ordering;
+
+/// Dot product of two slices
+pub(crate) fn dot<F: ArgminFloat>(a: &[F], b: &[F]) -> F {
+    a.iter()
+        .zip(b.iter())
+        .fold(float!(0.0), |acc, (&x, &y)| acc + x * y)
+}
+
+/// Identity matrix of size `n`
+pub(crate) fn identity<F: ArgminFloat>(n: usize) -> Vec<Vec<F>> {
+    (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| if i == j { float!(1.0) } else { float!(0.0) })
+                .collect()
+        })
+        .collect()
+}
+
+/// Total order on floats which places NaN above all other values
+pub(crate) fn cmp_nan_last<F: ArgminFloat>(a: &F, b: &F) -> Ordering {
+    a.partial_cmp(b)
+        .unwrap_or_else(|| a.is_nan().cmp(&b.is_nan()))
+}
+
+/// Solves `a x = b` for all columns of `b` via Gaussian elimination with partial pivoting. Returns
+/// `None` if `a` is (numerically) singular or contains NaN.
+pub(crate) fn solve<F: ArgminFloat>(mut a: Vec<Vec<F>>, mut b: Vec<Vec<F>>) -> Option<Vec<Vec<F>>> {
+    let n = a.len();
+    for k in 0..n {
+        let pivot = (k..n).max_by(|&i, &j| cmp_nan_last(&a[i][k].abs(), &a[j][k].abs()))?;
+        if a[pivot][k].is_nan() || a[pivot][k].abs() <= F::epsilon() {
+            return None;
+        }
+        a.swap(k, pivot);
+        b.swap(k, pivot);
+        for i in k + 1..n {
+            let factor = a[i][k] / a[k][k];
+            let (upper, lower) = a.split_at_mut(i);
+            for (aij, &akj) in lower[0].iter_mut().zip(upper[k].iter()).skip(k) {
+                *aij = *aij - factor * akj;
+            }
+            let (upper, lower) = b.split_at_mut(i);
+            for (bij, &bkj) in lower[0].iter_mut().zip(upper[k].iter()) {
+                *bij = *bij - factor * bkj;
+            }
+        }
+    }
+    for k in (0..n).rev() {
+        for i in 0..k {
+            let factor = a[i][k] / a[k][k];
+            let (upper, lower) = b.split_at_mut(k);
+            for (bij, &bkj) in upper[i].iter_mut().zip(lower[0].iter()) {
+                *bij = *bij - factor * bkj;
+            }
+        }
+        let akk = a[k][k];
+        for bkj in b[k].iter_mut() {
+            *bkj = *bkj / akk;
+        }
+    }
+    Some(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_dot() {
+        assert_relative_eq!(dot(&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]), 32.0);
+        assert_relative_eq!(dot::<f64>(&[], &[]), 0.0);
+    }
+
+    #[test]
+    fn test_cmp_nan_last() {
+        let mut values = [2.0, f64::NAN, -1.0, f64::INFINITY, 0.0];
+        values.sort_by(cmp_nan_last);
+        assert_eq!(values[..4], [-1.0, 0.0, 2.0, f64::INFINITY]);
+        assert!(values[4].is_nan());
+    }
+
+    #[test]
+    fn test_solve() {
+        let a = vec![vec![0.0, 2.0], vec![1.0, 1.0]];
+        let x = solve(a, vec![vec![4.0, 2.0], vec![3.0, 1.0]]).unwrap();
+        assert_eq!(x, vec![vec![1.0, 0.0], vec![2.0, 1.0]]);
+        assert_eq!(
+            solve(vec![vec![2.0]], identity::<f64>(1)).unwrap(),
+            vec![vec![0.5]]
+        );
+        // Singular or NaN
+        assert!(solve(vec![vec![1.0, 1.0], vec![1.0, 1.0]], identity::<f64>(2)).is_none());
+        assert!(solve(
+            vec![vec![1.0, 0.0], vec![f64::NAN, 1.0]],
+            identity::<f64>(2)
+        )
+        .is_none());
+        assert!(solve(
+            vec![vec![f64::NAN, 1.0], vec![1.0, 1.0]],
+            identity::<f64>(2)
+        )
+        .is_none());
+    }
+}